@@ -195,6 +195,10 @@ from shinqlx import (
     NewGameDispatcher,
     KillDispatcher,
     DeathDispatcher,
+    MedalDispatcher,
+    PlayerStatsDispatcher,
+    StatsPlayerConnectDispatcher,
+    StatsPlayerDisconnectDispatcher,
     UserinfoDispatcher,
     KamikazeUseDispatcher,
     KamikazeExplodeDispatcher,
@@ -464,6 +468,10 @@ __all__ = [
     "NewGameDispatcher",
     "KillDispatcher",
     "DeathDispatcher",
+    "MedalDispatcher",
+    "PlayerStatsDispatcher",
+    "StatsPlayerConnectDispatcher",
+    "StatsPlayerDisconnectDispatcher",
     "UserinfoDispatcher",
     "KamikazeUseDispatcher",
     "KamikazeExplodeDispatcher",
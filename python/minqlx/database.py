@@ -1,6 +1,8 @@
-from shinqlx.database import AbstractDatabase, Redis
+from shinqlx.database import AbstractDatabase, InMemory, Redis, Sqlite
 
 __all__ = [
     "AbstractDatabase",
+    "InMemory",
     "Redis",
+    "Sqlite",
 ]
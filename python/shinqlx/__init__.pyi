@@ -90,6 +90,10 @@ from ._events import (
     NewGameDispatcher,
     KillDispatcher,
     DeathDispatcher,
+    MedalDispatcher,
+    PlayerStatsDispatcher,
+    StatsPlayerConnectDispatcher,
+    StatsPlayerDisconnectDispatcher,
     UserinfoDispatcher,
     KamikazeUseDispatcher,
     KamikazeExplodeDispatcher,
@@ -258,6 +262,23 @@ from ._shinqlx import (
     set_cvar_limit_once,
 )
 from ._zmq import StatsListener
+from ._stats import (
+    PlayerSummary,
+    MatchStarted,
+    MatchReport,
+    RoundOver,
+    Coordinates,
+    KillParticipant,
+    PlayerDeath,
+    PlayerMedal,
+    PlayerConnection,
+    TeamSwitchPlayer,
+    TeamSwitch,
+    DamageStats,
+    WeaponStats,
+    PlayerStatsReport,
+    parse_stats,
+)
 
 import database
 
@@ -810,6 +831,10 @@ __all__ = [
     "NewGameDispatcher",
     "KillDispatcher",
     "DeathDispatcher",
+    "MedalDispatcher",
+    "PlayerStatsDispatcher",
+    "StatsPlayerConnectDispatcher",
+    "StatsPlayerDisconnectDispatcher",
     "UserinfoDispatcher",
     "KamikazeUseDispatcher",
     "KamikazeExplodeDispatcher",
@@ -979,6 +1004,22 @@ __all__ = [
     "get_targetting_entities",
     # from _zmq.pyi
     "StatsListener",
+    # from _stats.pyi
+    "PlayerSummary",
+    "MatchStarted",
+    "MatchReport",
+    "RoundOver",
+    "Coordinates",
+    "KillParticipant",
+    "PlayerDeath",
+    "PlayerMedal",
+    "PlayerConnection",
+    "TeamSwitchPlayer",
+    "TeamSwitch",
+    "DamageStats",
+    "WeaponStats",
+    "PlayerStatsReport",
+    "parse_stats",
     # from database.pyi
     "database",
 ]
@@ -14,8 +14,11 @@ if TYPE_CHECKING:
         GameStartData,
         GameEndData,
         RoundEndData,
-        KillData,
+        PlayerDeath,
         DeathData,
+        PlayerMedal,
+        PlayerStatsReport,
+        PlayerConnection,
         UserinfoEventInput,
     )
 
@@ -100,12 +103,24 @@ class EventDispatcher(Generic[E]):
     @overload
     def dispatch(self: EventDispatcher[NewGameDispatcher]) -> bool: ...
     @overload
-    def dispatch(self: EventDispatcher[KillDispatcher], victim: Player, killer: Player, data: KillData) -> bool: ...
+    def dispatch(self: EventDispatcher[KillDispatcher], victim: Player, killer: Player, data: PlayerDeath) -> bool: ...
     @overload
     def dispatch(
         self: EventDispatcher[DeathDispatcher], victim: Player, killer: Player | None, data: DeathData
     ) -> bool: ...
     @overload
+    def dispatch(self: EventDispatcher[MedalDispatcher], player: Player, data: PlayerMedal) -> bool: ...
+    @overload
+    def dispatch(self: EventDispatcher[PlayerStatsDispatcher], player: Player, data: PlayerStatsReport) -> bool: ...
+    @overload
+    def dispatch(
+        self: EventDispatcher[StatsPlayerConnectDispatcher], player: Player, data: PlayerConnection
+    ) -> bool: ...
+    @overload
+    def dispatch(
+        self: EventDispatcher[StatsPlayerDisconnectDispatcher], player: Player, data: PlayerConnection
+    ) -> bool: ...
+    @overload
     def dispatch(
         self: EventDispatcher[UserinfoDispatcher], player: Player, changed: UserinfoEventInput
     ) -> bool | UserinfoEventInput: ...
@@ -161,6 +176,10 @@ class MapDispatcher(EventDispatcher[MapDispatcher]): ...
 class NewGameDispatcher(EventDispatcher[NewGameDispatcher]): ...
 class KillDispatcher(EventDispatcher[KillDispatcher]): ...
 class DeathDispatcher(EventDispatcher[DeathDispatcher]): ...
+class MedalDispatcher(EventDispatcher[MedalDispatcher]): ...
+class PlayerStatsDispatcher(EventDispatcher[PlayerStatsDispatcher]): ...
+class StatsPlayerConnectDispatcher(EventDispatcher[StatsPlayerConnectDispatcher]): ...
+class StatsPlayerDisconnectDispatcher(EventDispatcher[StatsPlayerDisconnectDispatcher]): ...
 class UserinfoDispatcher(EventDispatcher[UserinfoDispatcher]): ...
 class KamikazeUseDispatcher(EventDispatcher[KamikazeUseDispatcher]): ...
 class KamikazeExplodeDispatcher(EventDispatcher[KamikazeExplodeDispatcher]): ...
@@ -227,6 +246,14 @@ class EventDispatcherManager:
     @overload
     def __getitem__(self, key: Literal["death"]) -> DeathDispatcher: ...
     @overload
+    def __getitem__(self, key: Literal["medal"]) -> MedalDispatcher: ...
+    @overload
+    def __getitem__(self, key: Literal["player_stats"]) -> PlayerStatsDispatcher: ...
+    @overload
+    def __getitem__(self, key: Literal["stats_player_connect"]) -> StatsPlayerConnectDispatcher: ...
+    @overload
+    def __getitem__(self, key: Literal["stats_player_disconnect"]) -> StatsPlayerDisconnectDispatcher: ...
+    @overload
     def __getitem__(self, key: Literal["userinfo"]) -> UserinfoDispatcher: ...
     @overload
     def __getitem__(self, key: Literal["kamikaze_use"]) -> KamikazeUseDispatcher: ...
@@ -16,8 +16,11 @@ if TYPE_CHECKING:
         GameStartData,
         GameEndData,
         RoundEndData,
-        KillData,
+        PlayerDeath,
         DeathData,
+        PlayerMedal,
+        PlayerStatsReport,
+        PlayerConnection,
         UserinfoEventInput,
         UserInfo,
     )
@@ -396,7 +399,7 @@ class Plugin:
     def add_hook(
         self,
         event: Literal["kill"],
-        handler: Callable[[Player, Player, KillData], CancellableEventReturn],
+        handler: Callable[[Player, Player, PlayerDeath], CancellableEventReturn],
         priority: int = ...,
     ) -> None: ...
     @overload
@@ -407,6 +410,34 @@ class Plugin:
         priority: int = ...,
     ) -> None: ...
     @overload
+    def add_hook(
+        self,
+        event: Literal["medal"],
+        handler: Callable[[Player, PlayerMedal], CancellableEventReturn],
+        priority: int = ...,
+    ) -> None: ...
+    @overload
+    def add_hook(
+        self,
+        event: Literal["player_stats"],
+        handler: Callable[[Player, PlayerStatsReport], CancellableEventReturn],
+        priority: int = ...,
+    ) -> None: ...
+    @overload
+    def add_hook(
+        self,
+        event: Literal["stats_player_connect"],
+        handler: Callable[[Player, PlayerConnection], CancellableEventReturn],
+        priority: int = ...,
+    ) -> None: ...
+    @overload
+    def add_hook(
+        self,
+        event: Literal["stats_player_disconnect"],
+        handler: Callable[[Player, PlayerConnection], CancellableEventReturn],
+        priority: int = ...,
+    ) -> None: ...
+    @overload
     def add_hook(
         self,
         event: Literal["userinfo"],
@@ -636,7 +667,7 @@ class Plugin:
     def remove_hook(
         self,
         event: Literal["kill"],
-        handler: Callable[[Player, Player, KillData], CancellableEventReturn],
+        handler: Callable[[Player, Player, PlayerDeath], CancellableEventReturn],
         priority: int = ...,
     ) -> None: ...
     @overload
@@ -647,6 +678,34 @@ class Plugin:
         priority: int = ...,
     ) -> None: ...
     @overload
+    def remove_hook(
+        self,
+        event: Literal["medal"],
+        handler: Callable[[Player, PlayerMedal], CancellableEventReturn],
+        priority: int = ...,
+    ) -> None: ...
+    @overload
+    def remove_hook(
+        self,
+        event: Literal["player_stats"],
+        handler: Callable[[Player, PlayerStatsReport], CancellableEventReturn],
+        priority: int = ...,
+    ) -> None: ...
+    @overload
+    def remove_hook(
+        self,
+        event: Literal["stats_player_connect"],
+        handler: Callable[[Player, PlayerConnection], CancellableEventReturn],
+        priority: int = ...,
+    ) -> None: ...
+    @overload
+    def remove_hook(
+        self,
+        event: Literal["stats_player_disconnect"],
+        handler: Callable[[Player, PlayerConnection], CancellableEventReturn],
+        priority: int = ...,
+    ) -> None: ...
+    @overload
     def remove_hook(
         self,
         event: Literal["userinfo"],
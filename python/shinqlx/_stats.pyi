@@ -0,0 +1,188 @@
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    from shinqlx import StatsData
+
+class PlayerSummary:
+    name: str
+    steam_id: int
+    team: int
+
+class MatchStarted:
+    capture_limit: int
+    factory: str
+    factory_title: str
+    frag_limit: int
+    game_type: str
+    infected: int
+    instagib: int
+    map: str
+    match_guid: str
+    mercy_limit: int
+    players: list[PlayerSummary]
+    quadhog: int
+    round_limit: int
+    score_limit: int
+    server_title: str
+    time_limit: int
+    training: int
+
+class MatchReport:
+    aborted: bool
+    capture_limit: int
+    exit_msg: str
+    factory: str
+    factory_title: str
+    first_scorer: str
+    frag_limit: int
+    game_length: int
+    game_type: str
+    infected: int
+    instagib: int
+    last_lead_change_time: int
+    last_scorer: str
+    last_teamscorer: str
+    map: str
+    match_guid: str
+    mercy_limit: int
+    quadhog: int
+    restarted: int
+    round_limit: int
+    score_limit: int
+    server_title: str
+    time_limit: int
+    training: int
+    tscore0: int
+    tscore1: int
+
+class RoundOver:
+    match_guid: str
+    round: int
+    team_won: str
+    time: int
+    warmup: bool
+
+class Coordinates:
+    x: float
+    y: float
+    z: float
+
+class KillParticipant:
+    airborne: bool
+    ammo: int
+    armor: int
+    bot: bool
+    bot_skill: int | None
+    health: int
+    holdable: str | None
+    name: str
+    position: Coordinates
+    powerups: list[str] | None
+    speed: float
+    steam_id: int
+    submerged: bool
+    team: int
+    view: Coordinates
+    weapon: str
+
+class PlayerDeath:
+    killer: KillParticipant | None
+    victim: KillParticipant
+    match_guid: str
+    means_of_death: str
+    other_team_alive: int
+    other_team_dead: int
+    round: int
+    suicide: bool
+    teamkill: bool
+    team_alive: int
+    team_dead: int
+    time: int
+    warmup: bool
+
+class PlayerMedal:
+    match_guid: str
+    medal: str
+    name: str
+    steam_id: int
+    time: int
+    total: int
+    warmup: bool
+
+class PlayerConnection:
+    match_guid: str
+    name: str
+    steam_id: int
+    time: int
+    warmup: bool
+
+class TeamSwitchPlayer:
+    name: str
+    old_team: str
+    steam_id: int
+    team: str
+
+class TeamSwitch:
+    player: TeamSwitchPlayer
+    match_guid: str
+    time: int
+    warmup: bool
+
+class DamageStats:
+    dealt: int
+    taken: int
+
+class WeaponStats:
+    deaths: int
+    damage_given: int
+    damage_received: int
+    hits: int
+    kills: int
+    pickups: int
+    shots: int
+    time: int
+
+    def accuracy(self) -> float: ...
+
+class PlayerStatsReport:
+    aborted: bool
+    blue_flag_pickups: int
+    damage: DamageStats
+    deaths: int
+    holy_shits: int
+    kills: int
+    lose: int
+    match_guid: str
+    max_streak: int
+    medals: dict[str, int]
+    model: str
+    name: str
+    neutral_flag_pickups: int
+    pickups: dict[str, int]
+    play_time: int
+    quit: int
+    rank: int
+    red_flag_pickups: int
+    score: int
+    steam_id: int
+    team: int
+    team_join_time: int
+    team_rank: int
+    tied_rank: int
+    tied_team_rank: int
+    warmup: bool
+    weapons: dict[str, WeaponStats]
+    win: int
+
+def parse_stats(
+    stats: str | StatsData,
+) -> (
+    MatchStarted
+    | MatchReport
+    | RoundOver
+    | PlayerDeath
+    | PlayerMedal
+    | PlayerStatsReport
+    | PlayerConnection
+    | TeamSwitch
+): ...
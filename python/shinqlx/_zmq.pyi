@@ -2,6 +2,11 @@ class StatsListener:
     done: bool
     address: str
     password: str | None
+    state: str
+    connected: bool
+    messages_received: int
+    reconnects: int
+    handshake_failures: int
 
     def __init__(self) -> None: ...
     def keep_receiving(self) -> None: ...
@@ -2,14 +2,14 @@ from typing import TYPE_CHECKING, overload
 from abc import ABC, abstractmethod
 
 if TYPE_CHECKING:
-    from typing import ClassVar, Mapping
+    from typing import Any, Callable, ClassVar, Mapping
 
     from datetime import timedelta
     from logging import Logger
 
     from shinqlx import Plugin, Player
 
-    from redis import StrictRedis, ConnectionPool
+RedisArg = str | bytes | int | float
 
 class AbstractDatabase(ABC):
     plugin: Plugin
@@ -36,16 +36,15 @@ class AbstractDatabase(ABC):
 class Redis(AbstractDatabase):
     _counter: ClassVar[int]
 
-    _conn: StrictRedis | None
-    _pool: ConnectionPool | None
-
     def __init__(self, plugin: Plugin) -> None: ...
     def __del__(self) -> None: ...
     def __contains__(self, key: str) -> bool: ...
     def __getitem__(self, key: str) -> str: ...
     def __setitem__(self, key: str, item: str | int) -> None: ...
     def __delitem__(self, key: str) -> None: ...
-    def __getattr__(self, attr: str) -> str: ...
+    def __getattr__(self, attr: str) -> RedisCommand: ...
+    @property
+    def r(self) -> Redis: ...
     def set_permission(self, player: Player | int | str, level: int) -> None: ...
     def get_permission(self, player: Player | int | str) -> int: ...
     def has_permission(self, player: Player | int | str, level: int = ...) -> bool: ...
@@ -57,19 +56,87 @@ class Redis(AbstractDatabase):
         database: int = ...,
         unix_socket: bool = ...,
         password: str | None = ...,
-    ) -> None: ...
+    ) -> Redis: ...
     def close(self) -> None: ...
+    def get(self, name: str) -> str | None: ...
+    def set(
+        self,
+        name: str,
+        value: RedisArg,
+        ex: int | timedelta | None = ...,
+        px: int | None = ...,
+        nx: bool = ...,
+        xx: bool = ...,
+    ) -> bool | None: ...
+    def delete(self, *names: str) -> int: ...
+    def exists(self, *names: str) -> int: ...
+    def keys(self, pattern: str = ...) -> list[str]: ...
+    def incr(self, name: str, amount: int = ...) -> int: ...
+    def incrby(self, name: str, amount: int = ...) -> int: ...
+    def decr(self, name: str, amount: int = ...) -> int: ...
+    def expire(self, name: str, time: int | timedelta) -> bool: ...
+    def ttl(self, name: str) -> int: ...
+    def hget(self, name: str, key: str) -> str | None: ...
+    def hset(
+        self,
+        name: str,
+        key: RedisArg | None = ...,
+        value: RedisArg | None = ...,
+        mapping: Mapping[RedisArg, RedisArg] | None = ...,
+    ) -> int: ...
+    def hgetall(self, name: str) -> dict[str, str]: ...
+    def hdel(self, name: str, *keys: str) -> int: ...
+    def hexists(self, name: str, key: str) -> bool: ...
+    def hincrby(self, name: str, key: str, amount: int = ...) -> int: ...
+    def hkeys(self, name: str) -> list[str]: ...
+    def hvals(self, name: str) -> list[str]: ...
+    def hlen(self, name: str) -> int: ...
+    def sadd(self, name: str, *values: RedisArg) -> int: ...
+    def srem(self, name: str, *values: RedisArg) -> int: ...
+    def smembers(self, name: str) -> set[str]: ...
+    def sismember(self, name: str, value: RedisArg) -> bool: ...
+    def scard(self, name: str) -> int: ...
+    def zscore(self, name: str, value: RedisArg) -> float | None: ...
+    @overload
+    def zrange(
+        self, name: str, start: int, end: int, desc: bool = ..., withscores: bool = ...
+    ) -> list[str]: ...
+    @overload
+    def zrange(
+        self, name: str, start: int, end: int, desc: bool = ..., *, withscores: bool
+    ) -> list[tuple[str, float]]: ...
+    def zrevrange(
+        self, name: str, start: int, end: int, withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrangebyscore(
+        self, name: str, min: RedisArg, max: RedisArg, withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrevrangebyscore(
+        self, name: str, max: RedisArg, min: RedisArg, withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrem(self, name: str, *values: RedisArg) -> int: ...
+    def zcard(self, name: str) -> int: ...
+    def zrank(self, name: str, value: RedisArg) -> int | None: ...
+    def zrevrank(self, name: str, value: RedisArg) -> int | None: ...
+    def lpush(self, name: str, *values: RedisArg) -> int: ...
+    def rpush(self, name: str, *values: RedisArg) -> int: ...
+    def lpop(self, name: str) -> str | None: ...
+    def rpop(self, name: str) -> str | None: ...
+    def lrange(self, name: str, start: int, end: int) -> list[str]: ...
+    def llen(self, name: str) -> int: ...
+    def lindex(self, name: str, index: int) -> str | None: ...
+    def ltrim(self, name: str, start: int, end: int) -> bool: ...
     def mset(self, *args: dict, **kwargs: str | int | float | bool) -> bool: ...
     def msetnx(self, *args: dict, **kwargs: str | int | float | bool) -> bool: ...
     @overload
-    async def zadd(
+    def zadd(
         self,
         name: str,
         *args: str | int | float,
         **kwargs: int | float,
     ) -> int: ...
     @overload
-    async def zadd(
+    def zadd(
         self,
         name: str,
         mapping: Mapping[str, int | float],
@@ -82,4 +149,203 @@ class Redis(AbstractDatabase):
     ) -> int: ...
     def zincrby(self, name: str, *, value: str, amount: int | float) -> float: ...
     def setex(self, name: str, *, value: str, time: int | timedelta) -> bool: ...
+    def setx(self, name: str, *, value: str, time: int | timedelta) -> bool: ...
+    def lrem(self, name: str, *, value: str, count: int) -> int: ...
+    def enqueue(self, command: str, *args: RedisArg) -> None: ...
+    def flush(self, timeout: float = ...) -> bool: ...
+    def write_queue_stats(self) -> dict[str, int]: ...
+
+class RedisCommand:
+    command: str
+
+    def __call__(self, *args: RedisArg) -> Any: ...
+
+class RedisError(Exception): ...
+
+class Sqlite(AbstractDatabase):
+    _counter: ClassVar[int]
+
+    def __init__(self, plugin: Plugin) -> None: ...
+    def __del__(self) -> None: ...
+    def __contains__(self, key: str) -> bool: ...
+    def __getitem__(self, key: str) -> str: ...
+    def __setitem__(self, key: str, item: str | int) -> None: ...
+    def __delitem__(self, key: str) -> None: ...
+    def set_permission(self, player: Player | int | str, level: int) -> None: ...
+    def get_permission(self, player: Player | int | str) -> int: ...
+    def has_permission(self, player: Player | int | str, level: int = ...) -> bool: ...
+    def set_flag(self, player: Player | int | str, flag: str, value: bool = ...) -> None: ...
+    def get_flag(self, player: Player | int | str, flag: str, default: bool = False) -> bool: ...
+    def connect(self, path: str | None = ...) -> Sqlite: ...
+    def close(self) -> None: ...
+    def get(self, name: str) -> str | None: ...
+    def set(
+        self,
+        name: str,
+        value: RedisArg,
+        ex: int | timedelta | None = ...,
+        nx: bool = ...,
+        xx: bool = ...,
+    ) -> bool | None: ...
+    def delete(self, *names: str) -> int: ...
+    def exists(self, *names: str) -> int: ...
+    def keys(self, pattern: str = ...) -> list[str]: ...
+    def incr(self, name: str, amount: int = ...) -> int: ...
+    def incrby(self, name: str, amount: int = ...) -> int: ...
+    def decr(self, name: str, amount: int = ...) -> int: ...
+    def expire(self, name: str, time: int | timedelta) -> bool: ...
+    def ttl(self, name: str) -> int: ...
+    def hget(self, name: str, key: str) -> str | None: ...
+    def hset(
+        self,
+        name: str,
+        key: RedisArg | None = ...,
+        value: RedisArg | None = ...,
+        mapping: Mapping[RedisArg, RedisArg] | None = ...,
+    ) -> int: ...
+    def hgetall(self, name: str) -> dict[str, str]: ...
+    def hdel(self, name: str, *keys: str) -> int: ...
+    def hexists(self, name: str, key: str) -> bool: ...
+    def hincrby(self, name: str, key: str, amount: int = ...) -> int: ...
+    def hkeys(self, name: str) -> list[str]: ...
+    def hvals(self, name: str) -> list[str]: ...
+    def hlen(self, name: str) -> int: ...
+    def sadd(self, name: str, *values: RedisArg) -> int: ...
+    def srem(self, name: str, *values: RedisArg) -> int: ...
+    def smembers(self, name: str) -> set[str]: ...
+    def sismember(self, name: str, value: RedisArg) -> bool: ...
+    def scard(self, name: str) -> int: ...
+    def zscore(self, name: str, value: RedisArg) -> float | None: ...
+    def zrange(
+        self, name: str, start: int, end: int, desc: bool = ..., withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrevrange(
+        self, name: str, start: int, end: int, withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrangebyscore(
+        self, name: str, min: RedisArg, max: RedisArg, withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrem(self, name: str, *values: RedisArg) -> int: ...
+    def zcard(self, name: str) -> int: ...
+    def zrank(self, name: str, value: RedisArg) -> int | None: ...
+    def zrevrank(self, name: str, value: RedisArg) -> int | None: ...
+    def mset(self, *args: dict, **kwargs: str | int | float) -> bool: ...
+    def msetnx(self, *args: dict, **kwargs: str | int | float) -> bool: ...
+    def zadd(
+        self,
+        name: str,
+        *args: str | int | float | Mapping[str, int | float],
+        **kwargs: int | float | bool,
+    ) -> int | float | None: ...
+    def zincrby(self, name: str, *, value: str, amount: int | float) -> float: ...
+    def setex(self, name: str, *, value: str, time: int | timedelta) -> bool: ...
+    def setx(self, name: str, *, value: str, time: int | timedelta) -> bool: ...
+    def type(self, name: str) -> str: ...
+
+class SqliteError(Exception): ...
+
+class InMemory(AbstractDatabase):
+    _counter: ClassVar[int]
+
+    def __init__(self, plugin: Plugin) -> None: ...
+    def __del__(self) -> None: ...
+    def __contains__(self, key: str) -> bool: ...
+    def __getitem__(self, key: str) -> str: ...
+    def __setitem__(self, key: str, item: str | int) -> None: ...
+    def __delitem__(self, key: str) -> None: ...
+    def set_permission(self, player: Player | int | str, level: int) -> None: ...
+    def get_permission(self, player: Player | int | str) -> int: ...
+    def has_permission(self, player: Player | int | str, level: int = ...) -> bool: ...
+    def set_flag(self, player: Player | int | str, flag: str, value: bool = ...) -> None: ...
+    def get_flag(self, player: Player | int | str, flag: str, default: bool = False) -> bool: ...
+    def connect(self, snapshot: str | None = ...) -> InMemory: ...
+    def close(self) -> None: ...
+    def save(self, path: str | None = ...) -> int: ...
+    def load(self, path: str | None = ...) -> int: ...
+    def flushdb(self) -> bool: ...
+    def type(self, name: str) -> str: ...
+    def get(self, name: str) -> str | None: ...
+    def set(
+        self,
+        name: str,
+        value: RedisArg,
+        ex: int | timedelta | None = ...,
+        px: int | None = ...,
+        nx: bool = ...,
+        xx: bool = ...,
+    ) -> bool | None: ...
+    def delete(self, *names: str) -> int: ...
+    def exists(self, *names: str) -> int: ...
+    def keys(self, pattern: str = ...) -> list[str]: ...
+    def incr(self, name: str, amount: int = ...) -> int: ...
+    def incrby(self, name: str, amount: int = ...) -> int: ...
+    def decr(self, name: str, amount: int = ...) -> int: ...
+    def expire(self, name: str, time: int | timedelta) -> bool: ...
+    def ttl(self, name: str) -> int: ...
+    def hget(self, name: str, key: str) -> str | None: ...
+    def hset(
+        self,
+        name: str,
+        key: RedisArg | None = ...,
+        value: RedisArg | None = ...,
+        mapping: Mapping[RedisArg, RedisArg] | None = ...,
+    ) -> int: ...
+    def hgetall(self, name: str) -> dict[str, str]: ...
+    def hdel(self, name: str, *keys: str) -> int: ...
+    def hexists(self, name: str, key: str) -> bool: ...
+    def hincrby(self, name: str, key: str, amount: int = ...) -> int: ...
+    def hkeys(self, name: str) -> list[str]: ...
+    def hvals(self, name: str) -> list[str]: ...
+    def hlen(self, name: str) -> int: ...
+    def sadd(self, name: str, *values: RedisArg) -> int: ...
+    def srem(self, name: str, *values: RedisArg) -> int: ...
+    def smembers(self, name: str) -> set[str]: ...
+    def sismember(self, name: str, value: RedisArg) -> bool: ...
+    def scard(self, name: str) -> int: ...
+    def zscore(self, name: str, value: RedisArg) -> float | None: ...
+    def zrange(
+        self, name: str, start: int, end: int, desc: bool = ..., withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrevrange(
+        self, name: str, start: int, end: int, withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrangebyscore(
+        self, name: str, min: RedisArg, max: RedisArg, withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrevrangebyscore(
+        self, name: str, max: RedisArg, min: RedisArg, withscores: bool = ...
+    ) -> list[str] | list[tuple[str, float]]: ...
+    def zrem(self, name: str, *values: RedisArg) -> int: ...
+    def zcard(self, name: str) -> int: ...
+    def zrank(self, name: str, value: RedisArg) -> int | None: ...
+    def zrevrank(self, name: str, value: RedisArg) -> int | None: ...
+    def lpush(self, name: str, *values: RedisArg) -> int: ...
+    def rpush(self, name: str, *values: RedisArg) -> int: ...
+    def lpop(self, name: str) -> str | None: ...
+    def rpop(self, name: str) -> str | None: ...
+    def lrange(self, name: str, start: int, end: int) -> list[str]: ...
+    def llen(self, name: str) -> int: ...
+    def lindex(self, name: str, index: int) -> str | None: ...
+    def ltrim(self, name: str, start: int, end: int) -> bool: ...
+    def mset(self, *args: dict, **kwargs: str | int | float) -> bool: ...
+    def msetnx(self, *args: dict, **kwargs: str | int | float) -> bool: ...
+    def zadd(
+        self,
+        name: str,
+        *args: str | int | float | Mapping[str, int | float],
+        **kwargs: int | float | bool,
+    ) -> int | float | None: ...
+    def zincrby(self, name: str, *, value: str, amount: int | float) -> float: ...
+    def setex(self, name: str, *, value: str, time: int | timedelta) -> bool: ...
+    def setx(self, name: str, *, value: str, time: int | timedelta) -> bool: ...
     def lrem(self, name: str, *, value: str, count: int) -> int: ...
+
+class InMemoryError(Exception): ...
+
+def register_migration(
+    prefix: str, version: int, migration: Callable[[str, Any], tuple[str, Any] | None]
+) -> None: ...
+def export_database(database: AbstractDatabase, path: str, prefix: str = ...) -> int: ...
+def import_database(
+    database: AbstractDatabase, path: str, prefix: str = ..., dry_run: bool = ...
+) -> int: ...
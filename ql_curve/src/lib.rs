@@ -0,0 +1,206 @@
+//! CURVE keys and ZMQ certificates for connecting to servers fronted by a CURVE-capable ZMQ proxy.
+//!
+//! Keys are handled Z85-encoded, just like ZMQ certificate files hold them.
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use arzmq::security::{SecurityMechanism, curve::curve_keypair};
+
+const Z85_ALPHABET: &str =
+    "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+const Z85_KEY_LENGTH: usize = 40;
+
+/// The keys for connecting to a server fronted by a CURVE-capable ZMQ proxy, Z85-encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurveKeys {
+    /// The public key of the server.
+    pub server_key: String,
+    /// The public key of the client.
+    pub public_key: String,
+    /// The secret key of the client.
+    pub secret_key: String,
+}
+
+impl CurveKeys {
+    /// The mechanism for a ZMQ socket connecting as CURVE client with these keys.
+    pub fn security_mechanism(&self) -> SecurityMechanism {
+        SecurityMechanism::CurveClient {
+            server_key: self.server_key.as_bytes().to_vec(),
+            public_key: self.public_key.as_bytes().to_vec(),
+            secret_key: self.secret_key.as_bytes().to_vec(),
+        }
+    }
+}
+
+fn is_z85_key(key: &str) -> bool {
+    key.len() == Z85_KEY_LENGTH && key.chars().all(|c| Z85_ALPHABET.contains(c))
+}
+
+/// Reads the ``public-key`` and ``secret-key`` of a ZMQ certificate file.
+pub fn parse_certificate(contents: &str) -> (Option<String>, Option<String>) {
+    let key = |name: &str| {
+        contents.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == name).then(|| value.trim().trim_matches('"').to_string())
+        })
+    };
+    (key("public-key"), key("secret-key"))
+}
+
+/// A ZMQ certificate file holding *public_key* and *secret_key*, naming *generator* as the tool
+/// that created it.
+pub fn format_certificate(generator: &str, public_key: &str, secret_key: &str) -> String {
+    format!(
+        "#   ****  Generated by {generator}  ****\n\
+         metadata\n\
+         curve\n    \
+         public-key = \"{public_key}\"\n    \
+         secret-key = \"{secret_key}\"\n"
+    )
+}
+
+/// The public key of a server, given either directly or as a certificate file holding it.
+pub fn read_server_key(value: &str) -> Result<String> {
+    if is_z85_key(value) {
+        return Ok(value.to_string());
+    }
+
+    let contents = fs::read_to_string(value)
+        .with_context(|| format!("'{value}' is neither a Z85 key nor a readable certificate"))?;
+    match parse_certificate(&contents) {
+        (Some(public_key), _) if is_z85_key(&public_key) => Ok(public_key),
+        _ => bail!("{value} does not hold a valid public-key"),
+    }
+}
+
+fn generate_keypair() -> Result<(String, String)> {
+    let (public_key, secret_key) = curve_keypair()?;
+    let z85 = |key: Vec<u8>| {
+        String::from_utf8(key)
+            .map(|key| key.trim_end_matches('\0').to_string())
+            .context("the generated key is not Z85-encoded")
+    };
+    Ok((z85(public_key)?, z85(secret_key)?))
+}
+
+/// The keypair of the client from a certificate file, or a new one for this session.
+pub fn read_client_keys(certificate: Option<&Path>) -> Result<(String, String)> {
+    let Some(certificate) = certificate else {
+        return generate_keypair();
+    };
+
+    let contents = fs::read_to_string(certificate)
+        .with_context(|| format!("could not read {}", certificate.display()))?;
+    match parse_certificate(&contents) {
+        (Some(public_key), Some(secret_key))
+            if is_z85_key(&public_key) && is_z85_key(&secret_key) =>
+        {
+            Ok((public_key, secret_key))
+        }
+        _ => bail!(
+            "{} does not hold a valid public-key and secret-key",
+            certificate.display()
+        ),
+    }
+}
+
+/// Creates the certificate file at *path*, readable only by its owner on unix since it holds the
+/// secret key.
+fn create_certificate_file(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options.open(path)
+}
+
+/// Writes a new client certificate to *path*, for the public key to be allowed by the proxy.
+/// *generator* is the name of the tool noted in the certificate.
+pub fn generate_certificate(path: &Path, generator: &str) -> Result<String> {
+    let (public_key, secret_key) = generate_keypair()?;
+    create_certificate_file(path)
+        .and_then(|mut file| {
+            file.write_all(format_certificate(generator, &public_key, &secret_key).as_bytes())
+        })
+        .with_context(|| format!("could not write {}", path.display()))?;
+    Ok(public_key)
+}
+
+#[cfg(test)]
+mod curve_tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        format_certificate, generate_certificate, is_z85_key, parse_certificate, read_client_keys,
+        read_server_key,
+    };
+
+    const PUBLIC_KEY: &str = "rq:rM>}U?@Lns47E1%kR.o@n%FcmmsL/@{H8]yf7";
+    const SECRET_KEY: &str = "JTKVSB%%)wK0E.X)V>+}o?pNmC{O&4W4b!Ni{Lh6";
+
+    #[test]
+    fn keys_are_z85_encoded() {
+        assert!(is_z85_key(PUBLIC_KEY));
+        assert!(!is_z85_key("too short"));
+        assert!(!is_z85_key(&"~".repeat(40)));
+    }
+
+    #[test]
+    fn certificates_can_be_read_back() {
+        assert_eq!(
+            parse_certificate(&format_certificate("ql_rcon", PUBLIC_KEY, SECRET_KEY)),
+            (Some(PUBLIC_KEY.to_string()), Some(SECRET_KEY.to_string()))
+        );
+        assert_eq!(
+            parse_certificate(&format!("curve\n    public-key = \"{PUBLIC_KEY}\"\n")),
+            (Some(PUBLIC_KEY.to_string()), None)
+        );
+    }
+
+    #[test]
+    fn server_keys_are_given_directly() {
+        assert_eq!(
+            read_server_key(PUBLIC_KEY).ok(),
+            Some(PUBLIC_KEY.to_string())
+        );
+        assert!(read_server_key("/nonexistent/server.key").is_err());
+    }
+
+    #[test]
+    fn generated_certificates_can_be_read_back() {
+        let directory = tempfile::tempdir().expect("this should not happen");
+        let path = directory.path().join("client.cert");
+
+        let public_key = generate_certificate(&path, "ql_rcon").expect("this should not happen");
+
+        assert_eq!(
+            read_client_keys(Some(&path)).map(|(key, _)| key).ok(),
+            Some(public_key)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn generated_certificates_are_only_readable_by_their_owner() {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let directory = tempfile::tempdir().expect("this should not happen");
+        let path = directory.path().join("client.cert");
+
+        generate_certificate(&path, "ql_rcon").expect("this should not happen");
+
+        assert_eq!(
+            fs::metadata(&path)
+                .map(|metadata| metadata.permissions().mode() & 0o777)
+                .ok(),
+            Some(0o600)
+        );
+    }
+}
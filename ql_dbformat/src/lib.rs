@@ -0,0 +1,342 @@
+//! The storage formats shared by shinqlx's database backends and `ql_dbtool`.
+//!
+//! [`SQLITE_SCHEMA`] holds the tables of the `Sqlite` database, and [`entries_to_json`] and
+//! [`entries_from_json`] convert between stored entries and the versioned JSON snapshot format
+//! used for `InMemory` snapshots, `qlx_dbexport` and `ql_dbtool export`.
+
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use serde_json::{Map, Value, json};
+
+/// The version of the JSON snapshot format.
+pub const SNAPSHOT_VERSION: u64 = 1;
+
+/// The tables of the `Sqlite` database, created unless they already exist.
+pub const SQLITE_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS kv (
+        key TEXT PRIMARY KEY NOT NULL,
+        value TEXT NOT NULL,
+        expires_at INTEGER
+    );
+    CREATE TABLE IF NOT EXISTS hashes (
+        key TEXT NOT NULL,
+        field TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (key, field)
+    );
+    CREATE TABLE IF NOT EXISTS sets (
+        key TEXT NOT NULL,
+        member TEXT NOT NULL,
+        PRIMARY KEY (key, member)
+    );
+    CREATE TABLE IF NOT EXISTS sorted_sets (
+        key TEXT NOT NULL,
+        member TEXT NOT NULL,
+        score REAL NOT NULL,
+        PRIMARY KEY (key, member)
+    );
+    CREATE INDEX IF NOT EXISTS sorted_sets_by_score ON sorted_sets (key, score);
+";
+
+/// A snapshot that does not match the JSON snapshot format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotError(String);
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// A value stored under a key, modelled after the Redis data types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoredValue {
+    /// A plain string.
+    String(String),
+    /// A hash of fields and their values.
+    Hash(BTreeMap<String, String>),
+    /// A set of members.
+    Set(BTreeSet<String>),
+    /// A sorted set of members and their scores.
+    SortedSet(BTreeMap<String, f64>),
+    /// A list of items.
+    List(VecDeque<String>),
+}
+
+impl StoredValue {
+    /// The name Redis uses for this type, e.g. in replies to `TYPE`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::String(_) => "string",
+            Self::Hash(_) => "hash",
+            Self::Set(_) => "set",
+            Self::SortedSet(_) => "zset",
+            Self::List(_) => "list",
+        }
+    }
+
+    /// Whether this is an empty collection. Redis removes keys holding those.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::String(_) => false,
+            Self::Hash(hash) => hash.is_empty(),
+            Self::Set(set) => set.is_empty(),
+            Self::SortedSet(sorted_set) => sorted_set.is_empty(),
+            Self::List(list) => list.is_empty(),
+        }
+    }
+
+    /// The string, if this is one.
+    pub fn as_string_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The hash, if this is one.
+    pub fn as_hash_mut(&mut self) -> Option<&mut BTreeMap<String, String>> {
+        match self {
+            Self::Hash(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    /// The set, if this is one.
+    pub fn as_set_mut(&mut self) -> Option<&mut BTreeSet<String>> {
+        match self {
+            Self::Set(set) => Some(set),
+            _ => None,
+        }
+    }
+
+    /// The sorted set, if this is one.
+    pub fn as_sorted_set_mut(&mut self) -> Option<&mut BTreeMap<String, f64>> {
+        match self {
+            Self::SortedSet(sorted_set) => Some(sorted_set),
+            _ => None,
+        }
+    }
+
+    /// The list, if this is one.
+    pub fn as_list_mut(&mut self) -> Option<&mut VecDeque<String>> {
+        match self {
+            Self::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Self::String(value) => json!(value),
+            Self::Hash(hash) => json!(hash),
+            Self::Set(set) => json!(set),
+            Self::SortedSet(sorted_set) => json!(sorted_set),
+            Self::List(list) => json!(list),
+        }
+    }
+
+    fn from_json(type_name: &str, value: &Value) -> Result<Self, SnapshotError> {
+        let invalid = || SnapshotError(format!("invalid {type_name} value: {value}"));
+        let strings = |values: &Vec<Value>| {
+            values
+                .iter()
+                .map(|item| item.as_str().map(str::to_string).ok_or_else(invalid))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        match (type_name, value) {
+            ("string", Value::String(string)) => Ok(Self::String(string.clone())),
+            ("hash", Value::Object(fields)) => fields
+                .iter()
+                .map(|(field, item)| {
+                    item.as_str()
+                        .map(|field_value| (field.clone(), field_value.to_string()))
+                        .ok_or_else(invalid)
+                })
+                .collect::<Result<_, _>>()
+                .map(Self::Hash),
+            ("set", Value::Array(members)) => {
+                strings(members).map(|members| Self::Set(members.into_iter().collect()))
+            }
+            ("zset", Value::Object(members)) => members
+                .iter()
+                .map(|(member, score)| {
+                    score
+                        .as_f64()
+                        .map(|score| (member.clone(), score))
+                        .ok_or_else(invalid)
+                })
+                .collect::<Result<_, _>>()
+                .map(Self::SortedSet),
+            ("list", Value::Array(items)) => {
+                strings(items).map(|items| Self::List(items.into_iter().collect()))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A value together with its expiry time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredEntry {
+    /// The stored value.
+    pub value: StoredValue,
+    /// Unix timestamp in seconds after which the entry is gone.
+    pub expires_at: Option<i64>,
+}
+
+impl From<StoredValue> for StoredEntry {
+    fn from(value: StoredValue) -> Self {
+        Self {
+            value,
+            expires_at: None,
+        }
+    }
+}
+
+impl StoredEntry {
+    /// Whether the entry is gone at the Unix timestamp *now*.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Serializes *entries* into the versioned JSON snapshot format.
+pub fn entries_to_json<'a>(
+    entries: impl IntoIterator<Item = (&'a String, &'a StoredEntry)>,
+) -> Value {
+    let keys = entries
+        .into_iter()
+        .map(|(key, entry)| {
+            let mut json_entry = Map::new();
+            json_entry.insert("type".to_string(), json!(entry.value.type_name()));
+            json_entry.insert("value".to_string(), entry.value.to_json());
+            if let Some(expires_at) = entry.expires_at {
+                json_entry.insert("expires_at".to_string(), json!(expires_at));
+            }
+            (key.clone(), Value::Object(json_entry))
+        })
+        .collect::<Map<_, _>>();
+
+    json!({"version": SNAPSHOT_VERSION, "keys": keys})
+}
+
+/// Parses the versioned JSON snapshot format back into entries.
+pub fn entries_from_json<T>(snapshot: &Value) -> Result<T, SnapshotError>
+where
+    T: FromIterator<(String, StoredEntry)>,
+{
+    let version = snapshot.get("version").and_then(Value::as_u64);
+    if version != Some(SNAPSHOT_VERSION) {
+        return Err(SnapshotError(format!(
+            "unsupported snapshot version: {}",
+            version.map_or("none".to_string(), |value| value.to_string())
+        )));
+    }
+
+    let Some(keys) = snapshot.get("keys").and_then(Value::as_object) else {
+        return Err(SnapshotError(
+            "snapshot does not contain any keys".to_string(),
+        ));
+    };
+
+    keys.iter()
+        .map(|(key, json_entry)| {
+            let type_name = json_entry
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let value =
+                StoredValue::from_json(type_name, json_entry.get("value").unwrap_or(&Value::Null))?;
+            let expires_at = json_entry.get("expires_at").and_then(Value::as_i64);
+            Ok((key.clone(), StoredEntry { value, expires_at }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::{StoredEntry, StoredValue, entries_from_json, entries_to_json};
+
+    #[test]
+    fn snapshot_json_roundtrip() {
+        let entries = BTreeMap::from([
+            (
+                "minqlx:string".to_string(),
+                StoredEntry {
+                    value: StoredValue::String("asdf".to_string()),
+                    expires_at: Some(1_700_000_000),
+                },
+            ),
+            (
+                "minqlx:hash".to_string(),
+                StoredValue::Hash([("field".to_string(), "value".to_string())].into()).into(),
+            ),
+            (
+                "minqlx:set".to_string(),
+                StoredValue::Set(["member".to_string()].into()).into(),
+            ),
+            (
+                "minqlx:zset".to_string(),
+                StoredValue::SortedSet([("member".to_string(), 1.5)].into()).into(),
+            ),
+            (
+                "minqlx:list".to_string(),
+                StoredValue::List(["a".to_string(), "b".to_string()].into()).into(),
+            ),
+        ]);
+
+        let snapshot = entries_to_json(&entries);
+
+        assert_eq!(snapshot["version"], json!(1));
+        assert_eq!(snapshot["keys"]["minqlx:zset"]["type"], json!("zset"));
+        assert_eq!(
+            entries_from_json::<BTreeMap<_, _>>(&snapshot).expect("this should not happen"),
+            entries
+        );
+    }
+
+    #[test]
+    fn snapshot_with_unsupported_version() {
+        let result = entries_from_json::<BTreeMap<_, _>>(&json!({"version": 42, "keys": {}}));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn snapshot_without_keys() {
+        let result = entries_from_json::<BTreeMap<_, _>>(&json!({"version": 1}));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn snapshot_with_invalid_value() {
+        let result = entries_from_json::<BTreeMap<_, _>>(&json!({
+            "version": 1,
+            "keys": {"minqlx:key": {"type": "hash", "value": ["not", "a", "hash"]}},
+        }));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn expired_entry() {
+        let entry = StoredEntry {
+            value: StoredValue::String("asdf".to_string()),
+            expires_at: Some(1_700_000_000),
+        };
+
+        assert!(!entry.is_expired(1_699_999_999));
+        assert!(entry.is_expired(1_700_000_000));
+    }
+}
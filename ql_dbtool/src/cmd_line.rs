@@ -0,0 +1,64 @@
+use clap::{Args, Parser, Subcommand};
+
+/// QuakeLive database export and import, for moving shinqlx player data between servers and
+/// database backends while no server is running.
+#[derive(Parser, Debug, Clone)]
+#[command(version, about, long_about = None)]
+pub(crate) struct CommandLineOptions {
+    #[command(subcommand)]
+    pub(crate) command: Command,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum Command {
+    /// Export all keys below the prefix to a versioned JSON file.
+    Export {
+        #[command(flatten)]
+        database: DatabaseOptions,
+        /// The JSON file to write.
+        #[arg(long)]
+        output: String,
+        /// Only export keys starting with this prefix.
+        #[arg(long, default_value = "minqlx:")]
+        prefix: String,
+        /// Record the plugin schema version of a key prefix as PREFIX=VERSION, so that
+        /// qlx_dbimport only runs the plugin migrations the data did not go through yet.
+        #[arg(long = "schema-version", value_parser = parse_schema_version)]
+        schema_versions: Vec<(String, u64)>,
+    },
+    /// Import all keys below the prefix from a JSON file created by export or qlx_dbexport.
+    Import {
+        #[command(flatten)]
+        database: DatabaseOptions,
+        /// The JSON file to read.
+        #[arg(long)]
+        input: String,
+        /// Only import keys starting with this prefix.
+        #[arg(long, default_value = "minqlx:")]
+        prefix: String,
+        /// Only report what would be imported without writing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Args, Debug, Clone)]
+#[group(required = true, multiple = false)]
+pub(crate) struct DatabaseOptions {
+    /// Redis URL to connect to, e.g. redis://127.0.0.1:6379/0
+    #[arg(long)]
+    pub(crate) redis: Option<String>,
+    /// SQLite database file, as configured via qlx_sqlitePath.
+    #[arg(long)]
+    pub(crate) sqlite: Option<String>,
+}
+
+fn parse_schema_version(value: &str) -> Result<(String, u64), String> {
+    let Some((prefix, version)) = value.rsplit_once('=') else {
+        return Err(format!("expected PREFIX=VERSION, got '{value}'"));
+    };
+    version
+        .parse::<u64>()
+        .map(|version| (prefix.to_string(), version))
+        .map_err(|err| format!("invalid version '{version}': {err}"))
+}
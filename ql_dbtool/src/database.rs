@@ -0,0 +1,394 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use ql_dbformat::{SQLITE_SCHEMA, StoredEntry, StoredValue};
+use redis::{Commands, Connection as RedisConnection};
+use rusqlite::{Connection as SqliteConnection, Transaction, params};
+
+pub(crate) trait Database {
+    /// Reads all live entries whose key starts with *prefix*.
+    fn export(&mut self, prefix: &str) -> Result<BTreeMap<String, StoredEntry>>;
+    /// Replaces whatever is stored under the keys of *entries* with their entries, all at once or
+    /// not at all.
+    fn import(&mut self, entries: &[(String, StoredEntry)]) -> Result<()>;
+}
+
+pub(crate) struct RedisDatabase {
+    connection: RedisConnection,
+}
+
+impl RedisDatabase {
+    pub(crate) fn connect(url: &str) -> Result<Self> {
+        let connection = redis::Client::open(url)
+            .and_then(|client| client.get_connection())
+            .with_context(|| format!("could not connect to {url}"))?;
+        Ok(Self { connection })
+    }
+
+    fn read_entry(&mut self, key: &str) -> Result<Option<StoredEntry>> {
+        let type_name: String = redis::cmd("TYPE").arg(key).query(&mut self.connection)?;
+        let value = match type_name.as_str() {
+            "string" => {
+                let Some(string) = self.connection.get::<_, Option<String>>(key)? else {
+                    return Ok(None);
+                };
+                StoredValue::String(string)
+            }
+            "hash" => StoredValue::Hash(self.connection.hgetall(key)?),
+            "set" => StoredValue::Set(self.connection.smembers(key)?),
+            "zset" => StoredValue::SortedSet(
+                self.connection
+                    .zrange_withscores::<_, Vec<(String, f64)>>(key, 0, -1)?
+                    .into_iter()
+                    .collect(),
+            ),
+            "list" => {
+                StoredValue::List(self.connection.lrange::<_, Vec<String>>(key, 0, -1)?.into())
+            }
+            _ => return Ok(None),
+        };
+
+        let ttl: i64 = self.connection.ttl(key)?;
+        let expires_at = (ttl > 0).then(|| Utc::now().timestamp() + ttl);
+        Ok(Some(StoredEntry { value, expires_at }))
+    }
+}
+
+impl Database for RedisDatabase {
+    fn export(&mut self, prefix: &str) -> Result<BTreeMap<String, StoredEntry>> {
+        let keys = self
+            .connection
+            .scan_match::<_, String>(format!("{prefix}*"))?
+            .filter(|key| key.as_ref().map_or(true, |key| key.starts_with(prefix)))
+            .collect::<redis::RedisResult<BTreeSet<_>>>()?;
+
+        let mut entries = BTreeMap::new();
+        for key in keys {
+            if let Some(entry) = self.read_entry(&key)? {
+                entries.insert(key, entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    fn import(&mut self, entries: &[(String, StoredEntry)]) -> Result<()> {
+        let mut pipeline = redis::pipe();
+        pipeline.atomic();
+        for (key, entry) in entries {
+            pipeline.del(key).ignore();
+            match entry.value {
+                StoredValue::String(ref string) => pipeline.set(key, string).ignore(),
+                StoredValue::Hash(ref hash) => pipeline
+                    .hset_multiple(key, &hash.iter().collect::<Vec<_>>())
+                    .ignore(),
+                StoredValue::Set(ref set) => {
+                    pipeline.sadd(key, set.iter().collect::<Vec<_>>()).ignore()
+                }
+                StoredValue::SortedSet(ref sorted_set) => pipeline
+                    .zadd_multiple(
+                        key,
+                        &sorted_set
+                            .iter()
+                            .map(|(member, score)| (*score, member))
+                            .collect::<Vec<_>>(),
+                    )
+                    .ignore(),
+                StoredValue::List(ref list) => pipeline
+                    .rpush(key, list.iter().collect::<Vec<_>>())
+                    .ignore(),
+            };
+            if let Some(expires_at) = entry.expires_at {
+                pipeline.expire_at(key, expires_at).ignore();
+            }
+        }
+
+        pipeline
+            .query::<()>(&mut self.connection)
+            .context("could not import the snapshot")
+    }
+}
+
+fn collection_entry(
+    entries: &mut BTreeMap<String, StoredEntry>,
+    key: String,
+    empty: fn() -> StoredValue,
+) -> &mut StoredEntry {
+    entries.entry(key).or_insert_with(|| StoredEntry {
+        value: empty(),
+        expires_at: None,
+    })
+}
+
+pub(crate) struct SqliteDatabase {
+    connection: SqliteConnection,
+}
+
+impl SqliteDatabase {
+    pub(crate) fn open(path: &str) -> Result<Self> {
+        let connection = SqliteConnection::open(path)
+            .and_then(|connection| connection.execute_batch(SQLITE_SCHEMA).map(|_| connection))
+            .with_context(|| format!("could not open {path}"))?;
+        Ok(Self { connection })
+    }
+}
+
+impl Database for SqliteDatabase {
+    fn export(&mut self, prefix: &str) -> Result<BTreeMap<String, StoredEntry>> {
+        let mut entries = BTreeMap::new();
+
+        let mut statement = self.connection.prepare(
+            "SELECT key, value, expires_at FROM kv WHERE substr(key, 1, length(?1)) = ?1 \
+             AND (expires_at IS NULL OR expires_at > unixepoch())",
+        )?;
+        for row in statement.query_map([prefix], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                StoredEntry {
+                    value: StoredValue::String(row.get(1)?),
+                    expires_at: row.get(2)?,
+                },
+            ))
+        })? {
+            let (key, entry) = row?;
+            entries.insert(key, entry);
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT key, field, value FROM hashes WHERE substr(key, 1, length(?1)) = ?1",
+        )?;
+        for row in statement.query_map([prefix], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })? {
+            let (key, field, value) = row?;
+            if let StoredValue::Hash(ref mut hash) =
+                collection_entry(&mut entries, key, || StoredValue::Hash(BTreeMap::new())).value
+            {
+                hash.insert(field, value);
+            }
+        }
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT key, member FROM sets WHERE substr(key, 1, length(?1)) = ?1")?;
+        for row in statement.query_map([prefix], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })? {
+            let (key, member) = row?;
+            if let StoredValue::Set(ref mut set) =
+                collection_entry(&mut entries, key, || StoredValue::Set(BTreeSet::new())).value
+            {
+                set.insert(member);
+            }
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT key, member, score FROM sorted_sets WHERE substr(key, 1, length(?1)) = ?1",
+        )?;
+        for row in statement.query_map([prefix], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })? {
+            let (key, member, score) = row?;
+            if let StoredValue::SortedSet(ref mut sorted_set) = collection_entry(
+                &mut entries,
+                key,
+                || StoredValue::SortedSet(BTreeMap::new()),
+            )
+            .value
+            {
+                sorted_set.insert(member, score);
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn import(&mut self, entries: &[(String, StoredEntry)]) -> Result<()> {
+        let list_keys = entries
+            .iter()
+            .filter(|(_, entry)| matches!(entry.value, StoredValue::List(_)))
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>();
+        if !list_keys.is_empty() {
+            bail!(
+                "SQLite databases do not support lists, nothing was imported: {}",
+                list_keys.join(", ")
+            );
+        }
+
+        let transaction = self.connection.transaction()?;
+        for (key, entry) in entries {
+            import_sqlite_entry(&transaction, key, entry)
+                .with_context(|| format!("could not import {key}"))?;
+        }
+        transaction
+            .commit()
+            .context("could not import the snapshot")
+    }
+}
+
+fn import_sqlite_entry(
+    transaction: &Transaction<'_>,
+    key: &str,
+    entry: &StoredEntry,
+) -> Result<()> {
+    for table in ["kv", "hashes", "sets", "sorted_sets"] {
+        transaction.execute(&format!("DELETE FROM {table} WHERE key = ?1"), [key])?;
+    }
+
+    match entry.value {
+        StoredValue::String(ref string) => {
+            transaction.execute(
+                "INSERT INTO kv (key, value, expires_at) VALUES (?1, ?2, ?3)",
+                params![key, string, entry.expires_at],
+            )?;
+        }
+        StoredValue::Hash(ref hash) => {
+            for (field, value) in hash {
+                transaction.execute(
+                    "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)",
+                    params![key, field, value],
+                )?;
+            }
+        }
+        StoredValue::Set(ref set) => {
+            for member in set {
+                transaction.execute(
+                    "INSERT INTO sets (key, member) VALUES (?1, ?2)",
+                    params![key, member],
+                )?;
+            }
+        }
+        StoredValue::SortedSet(ref sorted_set) => {
+            for (member, score) in sorted_set {
+                transaction.execute(
+                    "INSERT INTO sorted_sets (key, member, score) VALUES (?1, ?2, ?3)",
+                    params![key, member, score],
+                )?;
+            }
+        }
+        StoredValue::List(_) => (),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod database_tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use pretty_assertions::assert_eq;
+    use ql_dbformat::{StoredEntry, StoredValue};
+    use tempfile::tempdir;
+
+    use super::{Database, SqliteDatabase};
+
+    #[test]
+    fn sqlite_import_and_export_roundtrip() {
+        let database_dir = tempdir().expect("this should not happen");
+        let database_path = database_dir.path().join("shinqlx.sqlite");
+        let mut database =
+            SqliteDatabase::open(&database_path.to_string_lossy()).expect("this should not happen");
+
+        let entries = BTreeMap::from([
+            (
+                "minqlx:players:1234:permission".to_string(),
+                StoredEntry {
+                    value: StoredValue::String("3".to_string()),
+                    expires_at: None,
+                },
+            ),
+            (
+                "minqlx:players:1234".to_string(),
+                StoredEntry {
+                    value: StoredValue::Set(BTreeSet::from(["name".to_string()])),
+                    expires_at: None,
+                },
+            ),
+            (
+                "minqlx:players:1234:last_seen".to_string(),
+                StoredEntry {
+                    value: StoredValue::Hash(BTreeMap::from([(
+                        "map".to_string(),
+                        "campgrounds".to_string(),
+                    )])),
+                    expires_at: None,
+                },
+            ),
+            (
+                "minqlx:scores".to_string(),
+                StoredEntry {
+                    value: StoredValue::SortedSet(BTreeMap::from([("player1".to_string(), 1.5)])),
+                    expires_at: None,
+                },
+            ),
+        ]);
+        let mut imported = entries
+            .iter()
+            .map(|(key, entry)| (key.to_string(), entry.clone()))
+            .collect::<Vec<_>>();
+        imported.push((
+            "other:key".to_string(),
+            StoredEntry {
+                value: StoredValue::String("ignored".to_string()),
+                expires_at: None,
+            },
+        ));
+        database.import(&imported).expect("this should not happen");
+
+        assert_eq!(
+            database.export("minqlx:").expect("this should not happen"),
+            entries
+        );
+    }
+
+    #[test]
+    fn sqlite_import_with_lists_leaves_database_untouched() {
+        let mut database = SqliteDatabase::open(":memory:").expect("this should not happen");
+        let string_entry = |value: &str| StoredEntry {
+            value: StoredValue::String(value.to_string()),
+            expires_at: None,
+        };
+        database
+            .import(&[(
+                "minqlx:players:1234:permission".to_string(),
+                string_entry("3"),
+            )])
+            .expect("this should not happen");
+
+        let result = database.import(&[
+            (
+                "minqlx:a".to_string(),
+                string_entry("written before the list"),
+            ),
+            (
+                "minqlx:queue".to_string(),
+                StoredEntry {
+                    value: StoredValue::List(["a".to_string()].into()),
+                    expires_at: None,
+                },
+            ),
+            (
+                "minqlx:players:1234:permission".to_string(),
+                string_entry("5"),
+            ),
+        ]);
+
+        assert!(result.is_err());
+        assert_eq!(
+            database.export("minqlx:").expect("this should not happen"),
+            BTreeMap::from([(
+                "minqlx:players:1234:permission".to_string(),
+                string_entry("3")
+            )])
+        );
+    }
+}
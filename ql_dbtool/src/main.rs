@@ -0,0 +1,99 @@
+mod cmd_line;
+mod database;
+
+use std::{collections::BTreeMap, fs};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Parser;
+use cmd_line::{Command, CommandLineOptions, DatabaseOptions};
+use database::{Database, RedisDatabase, SqliteDatabase};
+use ql_dbformat::{entries_from_json, entries_to_json};
+use serde_json::{Map, Value};
+
+fn open_database(options: &DatabaseOptions) -> Result<Box<dyn Database>> {
+    match (&options.redis, &options.sqlite) {
+        (Some(url), _) => Ok(Box::new(RedisDatabase::connect(url)?)),
+        (_, Some(path)) => Ok(Box::new(SqliteDatabase::open(path)?)),
+        _ => anyhow::bail!("either --redis or --sqlite is required"),
+    }
+}
+
+fn export(
+    options: &DatabaseOptions,
+    output: &str,
+    prefix: &str,
+    schema_versions: Vec<(String, u64)>,
+) -> Result<()> {
+    let entries = open_database(options)?.export(prefix)?;
+    let schema_versions = schema_versions
+        .into_iter()
+        .map(|(schema_prefix, version)| (schema_prefix, Value::from(version)))
+        .collect::<Map<_, _>>();
+    let mut export = entries_to_json(&entries);
+    export["schema_versions"] = Value::Object(schema_versions);
+    fs::write(output, serde_json::to_string_pretty(&export)?)
+        .with_context(|| format!("could not write {output}"))?;
+
+    println!("Exported {} keys to {output}.", entries.len());
+    Ok(())
+}
+
+fn import(options: &DatabaseOptions, input: &str, prefix: &str, dry_run: bool) -> Result<()> {
+    let contents = fs::read_to_string(input).with_context(|| format!("could not read {input}"))?;
+    let export = serde_json::from_str::<Value>(&contents)
+        .with_context(|| format!("could not parse {input}"))?;
+
+    if export
+        .get("schema_versions")
+        .and_then(Value::as_object)
+        .is_some_and(|versions| !versions.is_empty())
+    {
+        println!(
+            "{input} records plugin schema versions. Plugin migrations only run when importing \
+             with qlx_dbimport on a server."
+        );
+    }
+
+    let now = Utc::now().timestamp();
+    let entries = entries_from_json::<BTreeMap<_, _>>(&export)?
+        .into_iter()
+        .filter(|(key, entry)| {
+            key.starts_with(prefix)
+                && !entry.value.is_empty()
+                && entry.expires_at.is_none_or(|expires_at| expires_at > now)
+        })
+        .collect::<Vec<_>>();
+
+    if dry_run {
+        entries
+            .iter()
+            .for_each(|(key, entry)| println!("{key} ({})", entry.value.type_name()));
+        println!("Dry run: would import {} keys from {input}.", entries.len());
+        return Ok(());
+    }
+
+    open_database(options)?.import(&entries)?;
+
+    println!("Imported {} keys from {input}.", entries.len());
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let args = CommandLineOptions::parse();
+
+    match args.command {
+        Command::Export {
+            database,
+            output,
+            prefix,
+            schema_versions,
+        } => export(&database, &output, &prefix, schema_versions),
+        Command::Import {
+            database,
+            input,
+            prefix,
+            dry_run,
+        } => import(&database, &input, &prefix, dry_run),
+    }
+}
@@ -0,0 +1,189 @@
+use core::{sync::atomic::Ordering, time::Duration};
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    process::ExitCode,
+};
+
+use anyhow::{Context, Result, bail};
+use tokio::{
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    time::{Instant, sleep, timeout},
+};
+
+use crate::{
+    CONTINUE_RUNNING, CommandLineOptions,
+    linefeed_helper::{terminal_buffer, write_display_line},
+    servers::{connected_count, parse_targets},
+    zmq_helper::DisplayLine,
+};
+
+/// Exit status when the server did not know one of the commands.
+const UNKNOWN_COMMAND_EXIT_CODE: u8 = 2;
+
+/// The commands of a script: one per line, skipping empty lines and comments starting with
+/// ``#`` or ``//``.
+pub(crate) fn parse_script(script: &str) -> Vec<String> {
+    script
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_unknown_command(response: &str) -> bool {
+    response
+        .trim_start_matches("broadcast: ")
+        .trim_start_matches("print \"")
+        .trim_start()
+        .to_lowercase()
+        .starts_with("unknown command")
+}
+
+/// The commands to run without the interactive terminal, from ``--command``, ``--script`` or
+/// stdin if it is not a terminal. ``None`` if ``ql_rcon`` runs interactively.
+pub(crate) fn batch_commands(args: &CommandLineOptions) -> Result<Option<Vec<String>>> {
+    let mut commands = args.commands.clone();
+    if let Some(script) = &args.script {
+        let contents = fs::read_to_string(script)
+            .with_context(|| format!("could not read {}", script.display()))?;
+        commands.extend(parse_script(&contents));
+    }
+
+    if commands.is_empty() && args.script.is_none() {
+        if io::stdin().is_terminal() {
+            return Ok(None);
+        }
+        commands.extend(parse_script(&io::read_to_string(io::stdin())?));
+    }
+
+    Ok(Some(commands))
+}
+
+fn server_names(args: &CommandLineOptions) -> String {
+    args.servers
+        .iter()
+        .map(|server| server.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn wait_for_connection(
+    args: &CommandLineOptions,
+    display_receiver: &mut UnboundedReceiver<DisplayLine>,
+) -> Result<()> {
+    let deadline = Instant::now() + Duration::from_secs(args.timeout);
+    let mut last_status = String::new();
+
+    while connected_count() < args.servers.len() {
+        while let Ok(line) = display_receiver.try_recv() {
+            if let (_, DisplayLine::Status(status)) = line.untagged() {
+                last_status = status.to_owned();
+            }
+        }
+        if !CONTINUE_RUNNING.load(Ordering::Acquire) {
+            bail!("could not connect to {}: {last_status}", server_names(args));
+        }
+        if Instant::now() >= deadline {
+            bail!("timed out connecting to {}", server_names(args));
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    Ok(())
+}
+
+fn print_response(args: &CommandLineOptions, line: &DisplayLine) -> Result<()> {
+    let mut buffer = terminal_buffer(&args.color);
+    write_display_line(&mut buffer, line)?;
+    println!("{}", String::from_utf8_lossy(&buffer.into_inner()));
+    Ok(())
+}
+
+/// Collects the responses to the last command until the server stays quiet for
+/// ``--quiet-period``. Returns whether the server did not know the command.
+async fn collect_responses(
+    args: &CommandLineOptions,
+    display_receiver: &mut UnboundedReceiver<DisplayLine>,
+) -> Result<bool> {
+    let quiet_period = Duration::from_millis(args.quiet_period);
+    let mut unknown_command = false;
+
+    while let Ok(Some(line)) = timeout(quiet_period, display_receiver.recv()).await {
+        match line.untagged() {
+            (_, DisplayLine::Response(response)) => {
+                unknown_command |= is_unknown_command(response);
+                print_response(args, &line)?;
+            }
+            (_, DisplayLine::Status(status)) if !CONTINUE_RUNNING.load(Ordering::Acquire) => {
+                bail!("lost connection to {}: {status}", server_names(args));
+            }
+            _ => (),
+        }
+    }
+
+    Ok(unknown_command)
+}
+
+/// Sends *commands* one after another, printing the responses of each before sending the next.
+/// Commands can be addressed to some of the servers with ``@NAME,NAME COMMAND``.
+pub(crate) async fn run_batch(
+    args: CommandLineOptions,
+    commands: Vec<String>,
+    zmq_sender: UnboundedSender<String>,
+    display_receiver: &mut UnboundedReceiver<DisplayLine>,
+) -> Result<ExitCode> {
+    let result = async {
+        if let Some(unknown) = commands
+            .iter()
+            .filter_map(|command| parse_targets(command).0)
+            .flatten()
+            .find(|target| !args.servers.iter().any(|server| server.name == *target))
+        {
+            bail!("unknown server '{unknown}'");
+        }
+
+        wait_for_connection(&args, display_receiver).await?;
+
+        let mut unknown_commands = false;
+        for command in commands {
+            zmq_sender.send(command)?;
+            unknown_commands |= collect_responses(&args, display_receiver).await?;
+        }
+
+        Ok(if unknown_commands {
+            ExitCode::from(UNKNOWN_COMMAND_EXIT_CODE)
+        } else {
+            ExitCode::SUCCESS
+        })
+    }
+    .await;
+
+    drop(zmq_sender);
+    CONTINUE_RUNNING.store(false, Ordering::Release);
+
+    result
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{is_unknown_command, parse_script};
+
+    #[test]
+    fn scripts_skip_comments_and_empty_lines() {
+        assert_eq!(
+            parse_script("# restart the match\nmap campgrounds ca\n\n  // then\n  status  \n"),
+            vec!["map campgrounds ca".to_string(), "status".to_string()]
+        );
+    }
+
+    #[test]
+    fn unknown_commands_are_detected() {
+        assert!(is_unknown_command("Unknown command \"mapp^7\""));
+        assert!(is_unknown_command("print \"unknown command: mapp\n\""));
+        assert!(!is_unknown_command("print \"map: campgrounds\n\""));
+    }
+}
@@ -1,13 +1,24 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 use termcolor::ColorChoice;
 
+use crate::servers::Server;
+
 /// QuakeLive rcon options
 #[derive(Parser, Debug, Clone)]
 #[command(version, about, long_about = None)]
 pub(crate) struct CommandLineOptions {
-    /// ZMQ URI to connect to.
-    #[arg(long, default_value = "tcp://127.0.0.1:27961")]
-    pub(crate) host: String,
+    /// Connect to these profiles from the config file
+    #[arg(value_name = "PROFILE")]
+    pub(crate) profiles: Vec<String>,
+    /// Read the profiles from this file instead of profiles.json in the config directory
+    #[arg(long, value_name = "FILE")]
+    pub(crate) config: Option<PathBuf>,
+    /// ZMQ URI to connect to, or udp://HOST:PORT for the classic UDP rcon protocol. Can be given
+    /// multiple times. [default: tcp://127.0.0.1:27961]
+    #[arg(long = "host", value_name = "HOST")]
+    pub(crate) hosts: Vec<String>,
     /// The ZMQ password.
     #[arg(long, default_value = "")]
     pub(crate) password: String,
@@ -17,4 +28,33 @@ pub(crate) struct CommandLineOptions {
     /// Use color output
     #[arg(long, default_value = "auto")]
     pub(crate) color: ColorChoice,
+    /// Keep the command history in this file instead of one per host or profile
+    #[arg(long, value_name = "FILE")]
+    pub(crate) history: Option<PathBuf>,
+    /// Z85-encoded public key of the CURVE proxy in front of the server, or a certificate file
+    /// holding it. Connects with CURVE instead of PLAIN.
+    #[arg(long, value_name = "KEY")]
+    pub(crate) curve_server_key: Option<String>,
+    /// Certificate file with the CURVE keypair of the client. A new keypair is generated for
+    /// each session if not given
+    #[arg(long, value_name = "FILE")]
+    pub(crate) curve_cert: Option<PathBuf>,
+    /// Write a new CURVE client certificate to this file and exit
+    #[arg(long, value_name = "FILE")]
+    pub(crate) curve_generate_cert: Option<PathBuf>,
+    /// Send this command and exit after printing the responses. Can be given multiple times.
+    #[arg(long = "command", value_name = "COMMAND")]
+    pub(crate) commands: Vec<String>,
+    /// Send the commands in this file, one per line, and exit after printing the responses
+    #[arg(long, value_name = "FILE")]
+    pub(crate) script: Option<PathBuf>,
+    /// Milliseconds without a response after which a command is considered finished
+    #[arg(long, value_name = "MILLISECONDS", default_value_t = 1000)]
+    pub(crate) quiet_period: u64,
+    /// Seconds to wait for the connection to the server when sending commands
+    #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+    pub(crate) timeout: u64,
+    /// The servers from the profiles and ``--host``, resolved after parsing
+    #[arg(skip)]
+    pub(crate) servers: Vec<Server>,
 }
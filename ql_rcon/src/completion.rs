@@ -0,0 +1,503 @@
+use alloc::{collections::VecDeque, sync::Arc};
+use core::time::Duration;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::PathBuf,
+    sync::{LazyLock, RwLock},
+    time::Instant,
+};
+
+use linefeed::{Completer, Completion, DefaultTerminal, Prompter};
+use regex::Regex;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// How long the server may stay quiet before the response to a query is considered complete.
+const QUERY_QUIET_PERIOD: Duration = Duration::from_millis(500);
+/// How often the connected players are fetched again.
+const PLAYERS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// QuakeLive color codes, like ``^1``.
+static COLOR_CODES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\^[0-9]").unwrap());
+
+/// Commands whose first argument is a map.
+const MAP_COMMANDS: [&str; 2] = ["map", "devmap"];
+/// Commands whose first argument is a cvar.
+const CVAR_COMMANDS: [&str; 6] = ["set", "seta", "sets", "setu", "reset", "toggle"];
+
+/// What ``ql_rcon`` asks the server for to complete the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Query {
+    Commands,
+    Cvars,
+    QlxCommands,
+    Players,
+    Maps,
+}
+
+impl Query {
+    const ALL: [Self; 5] = [
+        Self::Commands,
+        Self::Cvars,
+        Self::QlxCommands,
+        Self::Players,
+        Self::Maps,
+    ];
+
+    fn command(self) -> &'static str {
+        match self {
+            Self::Commands => "cmdlist",
+            Self::Cvars => "cvarlist",
+            Self::QlxCommands => "qlx_cmdlist",
+            Self::Players => "players",
+            Self::Maps => "dir maps bsp",
+        }
+    }
+
+    /// Whether *line* is the summary the server ends its response with.
+    fn is_last_line(self, line: &str) -> bool {
+        let suffix = match self {
+            Self::Commands => " commands",
+            Self::Cvars => " cvar indexes",
+            Self::QlxCommands if line.to_lowercase().starts_with("unknown command") => {
+                return true;
+            }
+            Self::QlxCommands => " qlx commands",
+            Self::Players | Self::Maps => return false,
+        };
+        line.strip_suffix(suffix)
+            .is_some_and(|count| !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()))
+    }
+
+    /// Whether *line* is one of the headers or separators the server starts its response with.
+    fn is_header(self, line: &str) -> bool {
+        let is_separator = !line.is_empty() && line.chars().all(|c| c == '-' || c == '=');
+        match self {
+            Self::Players => is_separator || line.to_lowercase().starts_with("map:"),
+            Self::Maps => is_separator || line.to_lowercase().starts_with("directory of"),
+            Self::Commands | Self::Cvars | Self::QlxCommands => false,
+        }
+    }
+}
+
+/// The text of a response, without the ``print "..."`` QuakeLive wraps it in and color codes.
+fn response_text(response: &str) -> String {
+    let text = response
+        .strip_prefix("broadcast: ")
+        .unwrap_or(response)
+        .trim();
+    let text = text
+        .strip_prefix("print \"")
+        .map_or(text, |printed| printed.trim_end_matches(['"', '\n']));
+    COLOR_CODES.replace_all(text, "").trim().to_string()
+}
+
+fn parse_single_word(line: &str) -> Option<String> {
+    (!line.is_empty() && !line.contains(char::is_whitespace)).then(|| line.to_string())
+}
+
+/// Parses a line of ``cvarlist``, i.e. the flags, the name and the quoted value of a cvar.
+fn parse_cvar(line: &str) -> Option<String> {
+    let (flags_and_name, _value) = line.split_once('"')?;
+    flags_and_name.split_whitespace().last().map(str::to_string)
+}
+
+/// Parses a line of ``players``, starting with the client id and ending with the name.
+fn parse_player(line: &str) -> Option<(String, String)> {
+    let mut tokens = line.split_whitespace();
+    let client_id = tokens.next()?.parse::<u8>().ok()?;
+    let name = tokens
+        .filter(|token| {
+            let is_number = token.chars().all(|c| c.is_ascii_digit());
+            let is_tag = token.starts_with('[') && token.ends_with(']');
+            !is_number && !is_tag
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!name.is_empty()).then(|| (client_id.to_string(), name))
+}
+
+fn parse_map(line: &str) -> Option<String> {
+    let file_name = line.rsplit('/').next()?;
+    file_name
+        .strip_suffix(".bsp")
+        .filter(|map| !map.is_empty())
+        .map(str::to_string)
+}
+
+/// Everything ``ql_rcon`` knows about the server to complete the input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Completions {
+    commands: BTreeSet<String>,
+    cvars: BTreeSet<String>,
+    qlx_commands: BTreeSet<String>,
+    players: Vec<(String, String)>,
+    maps: BTreeSet<String>,
+}
+
+impl Completions {
+    /// Reads the completions cached for a server, one ``KIND NAME`` per line.
+    pub(crate) fn from_cache(cache: &str) -> Self {
+        let mut completions = Self::default();
+        cache.lines().for_each(|line| match line.split_once(' ') {
+            Some(("command", name)) => {
+                completions.commands.insert(name.to_string());
+            }
+            Some(("cvar", name)) => {
+                completions.cvars.insert(name.to_string());
+            }
+            Some(("qlx", name)) => {
+                completions.qlx_commands.insert(name.to_string());
+            }
+            Some(("map", name)) => {
+                completions.maps.insert(name.to_string());
+            }
+            _ => (),
+        });
+        completions
+    }
+
+    /// The completions to cache. Players are not cached, since they change all the time.
+    pub(crate) fn to_cache(&self) -> String {
+        [
+            ("command", &self.commands),
+            ("cvar", &self.cvars),
+            ("qlx", &self.qlx_commands),
+            ("map", &self.maps),
+        ]
+        .iter()
+        .flat_map(|(kind, names)| names.iter().map(move |name| format!("{kind} {name}\n")))
+        .collect()
+    }
+
+    fn clear(&mut self, query: Query) {
+        match query {
+            Query::Commands => self.commands.clear(),
+            Query::Cvars => self.cvars.clear(),
+            Query::QlxCommands => self.qlx_commands.clear(),
+            Query::Players => self.players.clear(),
+            Query::Maps => self.maps.clear(),
+        }
+    }
+
+    /// Adds a *line* of the response to *query*. Returns whether the line was part of it.
+    fn add(&mut self, query: Query, line: &str) -> bool {
+        match query {
+            Query::Commands => parse_single_word(line).map(|name| self.commands.insert(name)),
+            Query::Cvars => parse_cvar(line).map(|name| self.cvars.insert(name)),
+            Query::QlxCommands => {
+                parse_single_word(line).map(|name| self.qlx_commands.insert(name))
+            }
+            Query::Players => parse_player(line).map(|player| {
+                self.players.push(player);
+                true
+            }),
+            Query::Maps => parse_map(line).map(|map| self.maps.insert(map)),
+        }
+        .is_some()
+    }
+
+    /// The candidates for *word*, given the input before it.
+    pub(crate) fn candidates(&self, before: &str, word: &str) -> Vec<String> {
+        let previous_words = before.split_whitespace().collect::<Vec<_>>();
+        let lowercase_word = word.to_lowercase();
+        let matches = |candidate: &&String| candidate.to_lowercase().starts_with(&lowercase_word);
+
+        let mut candidates = match previous_words.as_slice() {
+            [] => self
+                .commands
+                .iter()
+                .chain(self.cvars.iter())
+                .filter(matches)
+                .cloned()
+                .collect::<Vec<_>>(),
+            ["qlx"] => self.qlx_commands.iter().filter(matches).cloned().collect(),
+            [command] if MAP_COMMANDS.contains(&command.to_lowercase().as_str()) => {
+                self.maps.iter().filter(matches).cloned().collect()
+            }
+            [command] if CVAR_COMMANDS.contains(&command.to_lowercase().as_str()) => {
+                self.cvars.iter().filter(matches).cloned().collect()
+            }
+            _ => self
+                .players
+                .iter()
+                .flat_map(|(client_id, name)| [client_id, name])
+                .filter(matches)
+                .cloned()
+                .collect(),
+        };
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+}
+
+/// Completes the input from the history, and from the commands, cvars, players and maps of the
+/// server.
+pub(crate) struct RconCompleter {
+    pub(crate) completions: Arc<RwLock<Completions>>,
+}
+
+impl Completer<DefaultTerminal> for RconCompleter {
+    fn complete(
+        &self,
+        word: &str,
+        prompter: &Prompter<DefaultTerminal>,
+        start: usize,
+        _end: usize,
+    ) -> Option<Vec<Completion>> {
+        let current_buffer = prompter.buffer();
+        let mut completions = prompter
+            .history()
+            .rev()
+            .filter(|item| item.starts_with(current_buffer))
+            .map(|item| Completion::simple(item[start..].to_string()))
+            .collect::<Vec<_>>();
+
+        if let Ok(server_completions) = self.completions.read() {
+            completions.extend(
+                server_completions
+                    .candidates(&current_buffer[..start], word)
+                    .into_iter()
+                    .map(Completion::simple),
+            );
+        }
+
+        Some(completions)
+    }
+}
+
+/// Fetches the completions from the server one query after another, hiding the responses from
+/// the display.
+pub(crate) struct CompletionFetcher {
+    completions: Arc<RwLock<Completions>>,
+    cache_file: Option<PathBuf>,
+    /// The server to address the queries to if there are several.
+    target: Option<String>,
+    pending: VecDeque<Query>,
+    current: Option<(Query, Instant)>,
+    next_players_refresh: Option<Instant>,
+}
+
+impl CompletionFetcher {
+    pub(crate) fn new(
+        completions: Arc<RwLock<Completions>>,
+        cache_file: Option<PathBuf>,
+        target: Option<String>,
+    ) -> Self {
+        Self {
+            completions,
+            cache_file,
+            target,
+            pending: VecDeque::new(),
+            current: None,
+            next_players_refresh: None,
+        }
+    }
+
+    /// Fetches everything again, e.g. after connecting to the server.
+    pub(crate) fn start(&mut self) {
+        self.pending = Query::ALL.into_iter().collect();
+    }
+
+    fn start_query(&mut self, query: Query, zmq_sender: &UnboundedSender<String>) {
+        if let Ok(mut completions) = self.completions.write() {
+            completions.clear(query);
+        }
+        self.current = Some((query, Instant::now()));
+        let command = match &self.target {
+            Some(target) => format!("@{target} {}", query.command()),
+            None => query.command().to_string(),
+        };
+        let _ = zmq_sender.send(command);
+    }
+
+    fn finish_query(&mut self) {
+        let Some((query, _)) = self.current.take() else {
+            return;
+        };
+        if query == Query::Players {
+            self.next_players_refresh = Some(Instant::now() + PLAYERS_REFRESH_INTERVAL);
+        }
+        if self.pending.is_empty()
+            && query != Query::Players
+            && let (Some(cache_file), Ok(completions)) = (&self.cache_file, self.completions.read())
+        {
+            let _ = fs::write(cache_file, completions.to_cache());
+        }
+    }
+
+    /// Handles a *response* of the server. Returns whether it answered a query and should not be
+    /// displayed.
+    pub(crate) fn consume(&mut self, response: &str) -> bool {
+        let Some((query, _)) = self.current else {
+            return false;
+        };
+
+        let line = response_text(response);
+        if query.is_last_line(&line) {
+            self.finish_query();
+            return true;
+        }
+
+        let consumed = query.is_header(&line)
+            || self
+                .completions
+                .write()
+                .is_ok_and(|mut completions| completions.add(query, &line));
+        if consumed {
+            self.current = Some((query, Instant::now()));
+        }
+        consumed
+    }
+
+    /// Sends the next query once the current one is complete.
+    pub(crate) fn tick(&mut self, zmq_sender: &UnboundedSender<String>) {
+        if self
+            .current
+            .is_some_and(|(_, last_response)| last_response.elapsed() >= QUERY_QUIET_PERIOD)
+        {
+            self.finish_query();
+        }
+        if self.current.is_some() {
+            return;
+        }
+
+        if let Some(query) = self.pending.pop_front() {
+            self.start_query(query, zmq_sender);
+        } else if self
+            .next_players_refresh
+            .is_some_and(|refresh| Instant::now() >= refresh)
+        {
+            self.start_query(Query::Players, zmq_sender);
+        }
+    }
+}
+
+#[cfg(test)]
+mod completion_tests {
+    use alloc::sync::Arc;
+    use std::sync::RwLock;
+
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::{
+        CompletionFetcher, Completions, Query, parse_cvar, parse_map, parse_player, response_text,
+    };
+
+    fn completions() -> Completions {
+        let mut completions = Completions::default();
+        [
+            (Query::Commands, "map"),
+            (Query::Commands, "kick"),
+            (Query::Commands, "set"),
+            (Query::Commands, "qlx"),
+            (Query::Cvars, "S R     sv_hostname \"my server\""),
+            (Query::Cvars, "        sv_maxclients \"16\""),
+            (Query::QlxCommands, "!kick"),
+            (Query::QlxCommands, "!elo"),
+            (Query::Players, "  3 76561198000000001 ^1Player^7One"),
+            (Query::Players, " 12 [admin] other"),
+            (Query::Maps, "maps/campgrounds.bsp"),
+            (Query::Maps, "bloodrun.bsp"),
+        ]
+        .iter()
+        .for_each(|(query, line)| {
+            assert!(completions.add(*query, &response_text(line)));
+        });
+        completions
+    }
+
+    #[test]
+    fn responses_are_unwrapped() {
+        assert_eq!(
+            response_text("broadcast: print \"^1red ^7text\n\""),
+            "red text"
+        );
+        assert_eq!(response_text("  plain  "), "plain");
+    }
+
+    #[test]
+    fn lines_of_list_commands_are_parsed() {
+        assert_eq!(
+            parse_cvar("S R     sv_hostname \"my server\""),
+            Some("sv_hostname".to_string())
+        );
+        assert_eq!(parse_cvar("1024 total cvars"), None);
+        assert_eq!(
+            parse_player("  3 76561198000000001 Player One"),
+            Some(("3".to_string(), "Player One".to_string()))
+        );
+        assert_eq!(parse_player("Map: campgrounds"), None);
+        assert_eq!(
+            parse_map("maps/campgrounds.bsp"),
+            Some("campgrounds".to_string())
+        );
+        assert_eq!(parse_map("---------------"), None);
+    }
+
+    #[test]
+    fn responses_end_with_their_summary() {
+        assert!(Query::Commands.is_last_line("421 commands"));
+        assert!(!Query::Commands.is_last_line("commands"));
+        assert!(Query::Cvars.is_last_line("1024 cvar indexes"));
+        assert!(Query::QlxCommands.is_last_line("12 qlx commands"));
+        assert!(Query::QlxCommands.is_last_line("Unknown command \"qlx_cmdlist\""));
+        assert!(!Query::Players.is_last_line("12 players"));
+    }
+
+    #[test]
+    fn only_lines_of_the_response_are_consumed() {
+        let completions = Arc::new(RwLock::new(Completions::default()));
+        let mut fetcher = CompletionFetcher::new(completions.clone(), None, None);
+        let (zmq_sender, mut zmq_receiver) = unbounded_channel();
+        fetcher.pending = [Query::Players].into_iter().collect();
+
+        fetcher.tick(&zmq_sender);
+
+        assert_eq!(zmq_receiver.try_recv().ok(), Some("players".to_string()));
+        assert!(fetcher.consume("print \"Map: campgrounds\n\""));
+        assert!(fetcher.consume("print \"------------------\n\""));
+        assert!(fetcher.consume("print \"  3 76561198000000001 Player One\n\""));
+        assert!(!fetcher.consume("broadcast: print \"Player Two connected\n\""));
+        assert!(!fetcher.consume("print \"^7Player Two: ^2hello there\n\""));
+        assert_eq!(
+            completions.read().expect("this should not happen").players,
+            vec![("3".to_string(), "Player One".to_string())]
+        );
+    }
+
+    #[test]
+    fn candidates_depend_on_position() {
+        let completions = completions();
+
+        assert_eq!(
+            completions.candidates("", "s"),
+            vec!["set", "sv_hostname", "sv_maxclients"]
+        );
+        assert_eq!(completions.candidates("map ", "c"), vec!["campgrounds"]);
+        assert_eq!(completions.candidates("qlx ", "!"), vec!["!elo", "!kick"]);
+        assert_eq!(
+            completions.candidates("set ", "SV_M"),
+            vec!["sv_maxclients"]
+        );
+        assert_eq!(completions.candidates("kick ", "p"), vec!["PlayerOne"]);
+        assert_eq!(completions.candidates("kick ", "1"), vec!["12"]);
+    }
+
+    #[test]
+    fn completions_are_cached_without_players() {
+        let completions = completions();
+
+        let cached = Completions::from_cache(&completions.to_cache());
+
+        assert_eq!(
+            cached,
+            Completions {
+                players: vec![],
+                ..completions
+            }
+        );
+    }
+}
@@ -1,39 +1,24 @@
 use alloc::sync::Arc;
 use core::{sync::atomic::Ordering, time::Duration};
 use std::{
+    fs,
     io::{self, IsTerminal, Write},
     path::PathBuf,
+    sync::RwLock,
 };
 
 use anyhow::Result;
 use directories::UserDirs;
-use linefeed::{Completer, Completion, DefaultTerminal, Interface, Prompter, ReadResult, Signal};
+use linefeed::{DefaultTerminal, Interface, ReadResult, Signal};
 use termcolor::{Buffer, Color, ColorChoice, ColorSpec, WriteColor};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
-use crate::{CONTINUE_RUNNING, CommandLineOptions};
-
-struct HistoryCompleter {}
-
-impl Completer<DefaultTerminal> for HistoryCompleter {
-    fn complete(
-        &self,
-        _word: &str,
-        prompter: &Prompter<DefaultTerminal>,
-        start: usize,
-        _end: usize,
-    ) -> Option<Vec<Completion>> {
-        let current_buffer = prompter.buffer();
-        Some(
-            prompter
-                .history()
-                .rev()
-                .filter(|item| item.starts_with(current_buffer))
-                .map(|item| Completion::simple(item[start..].to_string()))
-                .collect(),
-        )
-    }
-}
+use crate::{
+    CONTINUE_RUNNING, CommandLineOptions,
+    completion::{CompletionFetcher, Completions, RconCompleter},
+    servers::{host_address, is_connected},
+    zmq_helper::DisplayLine,
+};
 
 fn split_ql_colors(input: &str) -> Vec<(&str, &str)> {
     let split_regex = regex::Regex::new(r"\^[0-7]").unwrap();
@@ -97,20 +82,61 @@ fn write_formatted_ql_colors(buffer: &mut Buffer, text: &str) -> Result<()> {
     Ok(())
 }
 
-fn get_history_file(args: &CommandLineOptions) -> Option<PathBuf> {
+/// Writes *line*, prefixed with the name of its server if it is tagged with one.
+pub(crate) fn write_display_line(buffer: &mut Buffer, line: &DisplayLine) -> Result<()> {
+    let (server, line) = line.untagged();
+    if let Some(server) = server {
+        buffer.set_color(ColorSpec::new().set_bold(true))?;
+        write!(buffer, "[{server}] ")?;
+        buffer.set_color(ColorSpec::new().set_reset(true))?;
+    }
+    write_formatted_ql_colors(buffer, line.text())
+}
+
+fn get_server_file(args: &CommandLineOptions, extension: &str) -> Option<PathBuf> {
     UserDirs::new().map(|user_dirs| {
         let mut home_dir = user_dirs.home_dir().to_path_buf();
-        match &args.host.strip_prefix("tcp://") {
-            None => home_dir.push(".ql_zmq_rcon.history"),
-            Some(hostname) => home_dir.push(format!(
-                ".ql_zmq_rcon-{}.history",
-                hostname.replace(".", "_").replace(":", "_")
+        let hostnames = args
+            .servers
+            .iter()
+            .map(|server| host_address(&server.host))
+            .collect::<Option<Vec<_>>>();
+        match (args.profiles.as_slice(), hostnames) {
+            ([], None) => home_dir.push(format!(".ql_zmq_rcon.{extension}")),
+            ([], Some(hostnames)) => home_dir.push(format!(
+                ".ql_zmq_rcon-{}.{extension}",
+                hostnames.join("+").replace(".", "_").replace(":", "_")
+            )),
+            (profiles, _) => home_dir.push(format!(
+                ".ql_zmq_rcon-profile-{}.{extension}",
+                profiles.join("+")
             )),
         }
         home_dir
     })
 }
-fn terminal(args: &CommandLineOptions) -> Result<Interface<DefaultTerminal>> {
+
+fn get_history_file(args: &CommandLineOptions) -> Option<PathBuf> {
+    args.history
+        .clone()
+        .or_else(|| get_server_file(args, "history"))
+}
+
+fn get_completions_file(args: &CommandLineOptions) -> Option<PathBuf> {
+    get_server_file(args, "completions")
+}
+
+fn load_completions(args: &CommandLineOptions) -> Completions {
+    get_completions_file(args)
+        .and_then(|completions_file| fs::read_to_string(completions_file).ok())
+        .map(|cache| Completions::from_cache(&cache))
+        .unwrap_or_default()
+}
+
+fn terminal(
+    args: &CommandLineOptions,
+    completions: Arc<RwLock<Completions>>,
+) -> Result<Interface<DefaultTerminal>> {
     let editor = Interface::new(env!("CARGO_PKG_NAME"))?;
 
     editor.set_prompt("")?;
@@ -130,13 +156,12 @@ fn terminal(args: &CommandLineOptions) -> Result<Interface<DefaultTerminal>> {
         editor.load_history(&history_file)?;
     };
 
-    let history_completer = HistoryCompleter {};
-    editor.set_completer(Arc::new(history_completer));
+    editor.set_completer(Arc::new(RconCompleter { completions }));
 
     Ok(editor)
 }
 
-fn terminal_buffer(color_choice: &ColorChoice) -> Buffer {
+pub(crate) fn terminal_buffer(color_choice: &ColorChoice) -> Buffer {
     match color_choice {
         ColorChoice::Always => Buffer::ansi(),
         ColorChoice::AlwaysAnsi => Buffer::ansi(),
@@ -154,14 +179,33 @@ fn terminal_buffer(color_choice: &ColorChoice) -> Buffer {
 pub(crate) async fn run_terminal(
     args: CommandLineOptions,
     zmq_sender: UnboundedSender<String>,
-    mut display_receiver: UnboundedReceiver<String>,
+    mut display_receiver: UnboundedReceiver<DisplayLine>,
 ) -> Result<()> {
-    let terminal = terminal(&args)?;
+    let completions = Arc::new(RwLock::new(load_completions(&args)));
+    let terminal = terminal(&args, completions.clone())?;
+    // With several servers, the completions come from the first one.
+    let completion_server = args.servers.first().map(|server| server.name.to_owned());
+    let completion_target = completion_server
+        .as_ref()
+        .filter(|_| args.servers.len() > 1)
+        .cloned();
+    let mut completion_fetcher = CompletionFetcher::new(
+        completions,
+        get_completions_file(&args),
+        completion_target.clone(),
+    );
+    let mut connected = false;
 
     while CONTINUE_RUNNING.load(Ordering::Acquire) {
         while let Ok(line) = display_receiver.try_recv() {
+            if let (server, DisplayLine::Response(response)) = line.untagged()
+                && server == completion_target.as_deref()
+                && completion_fetcher.consume(response)
+            {
+                continue;
+            }
             let mut buffer = terminal_buffer(&args.color);
-            write_formatted_ql_colors(&mut buffer, &line)?;
+            write_display_line(&mut buffer, &line)?;
             terminal.lock_writer_erase().and_then(|mut writer| {
                 writeln!(
                     writer,
@@ -171,6 +215,13 @@ pub(crate) async fn run_terminal(
             })?;
         }
 
+        let now_connected = completion_server.as_deref().is_some_and(is_connected);
+        if now_connected && !connected {
+            completion_fetcher.start();
+        }
+        connected = now_connected;
+        completion_fetcher.tick(&zmq_sender);
+
         match terminal.read_line_step(Some(Duration::from_millis(250))) {
             Ok(None) => continue,
             Ok(Some(ReadResult::Input(line))) => {
@@ -240,7 +291,7 @@ pub(crate) async fn run_terminal(
 
     while let Ok(line) = display_receiver.try_recv() {
         let mut buffer = terminal_buffer(&args.color);
-        write_formatted_ql_colors(&mut buffer, &line)?;
+        write_display_line(&mut buffer, &line)?;
         terminal.lock_writer_erase().and_then(|mut writer| {
             writeln!(
                 writer,
@@ -1,35 +1,58 @@
+mod batch;
 mod cmd_line;
+mod completion;
 mod linefeed_helper;
+mod profiles;
+mod servers;
+mod udp_rcon;
 mod zmq_helper;
 
 use core::sync::atomic::AtomicBool;
+use std::process::ExitCode;
 
 use anyhow::Result;
-use clap::Parser;
+use batch::{batch_commands, run_batch};
 use cmd_line::CommandLineOptions;
 use linefeed_helper::run_terminal;
+use profiles::parse_args;
+use ql_curve::generate_certificate;
+use servers::run_servers;
 use tokio::{sync::mpsc::unbounded_channel, task, try_join};
-use zmq_helper::run_zmq;
 
 extern crate alloc;
 
 pub(crate) static CONTINUE_RUNNING: AtomicBool = AtomicBool::new(true);
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 5)]
-async fn main() -> Result<()> {
-    let args = CommandLineOptions::parse();
+async fn main() -> Result<ExitCode> {
+    let args = parse_args()?;
+
+    if let Some(certificate) = &args.curve_generate_cert {
+        let public_key = generate_certificate(certificate, env!("CARGO_PKG_NAME"))?;
+        println!("Public key: {public_key}");
+        return Ok(ExitCode::SUCCESS);
+    }
 
     let (zmq_sender, zmq_receiver) = unbounded_channel();
-    let (display_sender, display_receiver) = unbounded_channel();
+    let (display_sender, mut display_receiver) = unbounded_channel();
 
     let cloned_args = args.clone();
 
-    display_sender.send("Ctrl-C or 'exit' to exit rcon session".to_string())?;
+    if let Some(commands) = batch_commands(&args)? {
+        let zmq_task = task::spawn(run_servers(cloned_args, zmq_receiver, display_sender));
+        let result = run_batch(args, commands, zmq_sender, &mut display_receiver).await;
+        let zmq_result = zmq_task.await?;
+        let exit_code = result?;
+        zmq_result?;
+        return Ok(exit_code);
+    }
+
+    display_sender.send("Ctrl-C or 'exit' to exit rcon session".to_string().into())?;
 
-    let zmq_task = task::spawn(run_zmq(cloned_args, zmq_receiver, display_sender));
+    let zmq_task = task::spawn(run_servers(cloned_args, zmq_receiver, display_sender));
     let terminal_task = task::spawn(run_terminal(args, zmq_sender, display_receiver));
     match try_join!(zmq_task, terminal_task)? {
         (Err(e), ..) | (.., Err(e)) => Err(e),
-        _ => Ok(()),
+        _ => Ok(ExitCode::SUCCESS),
     }
 }
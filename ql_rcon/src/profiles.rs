@@ -0,0 +1,327 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::{Context, Result, bail};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, parser::ValueSource};
+use directories::ProjectDirs;
+use ql_curve::{CurveKeys, read_client_keys, read_server_key};
+use serde_json::Value;
+use termcolor::ColorChoice;
+
+use crate::{
+    CommandLineOptions,
+    servers::{DEFAULT_HOST, Server, check_unique_names},
+};
+
+/// A named server from the config file. Everything not given falls back to the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Profile {
+    pub(crate) host: Option<String>,
+    pub(crate) password: Option<String>,
+    pub(crate) identity: Option<String>,
+    pub(crate) color: Option<ColorChoice>,
+    pub(crate) history: Option<PathBuf>,
+    pub(crate) curve_server_key: Option<String>,
+}
+
+fn profile_from_json(name: &str, entry: &Value) -> Result<Profile> {
+    let Some(entry) = entry.as_object() else {
+        bail!("the profile '{name}' is not an object");
+    };
+    let field = |key: &str| -> Result<Option<String>> {
+        match entry.get(key) {
+            None => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value.to_string())),
+            Some(value) => bail!("'{key}' of the profile '{name}' is not a string, got {value}"),
+        }
+    };
+
+    Ok(Profile {
+        host: field("host")?,
+        password: field("password")?,
+        identity: field("identity")?,
+        color: field("color")?
+            .map(|color| color.parse::<ColorChoice>())
+            .transpose()
+            .with_context(|| format!("invalid 'color' in the profile '{name}'"))?,
+        history: field("history")?.map(PathBuf::from),
+        curve_server_key: field("curve_server_key")?,
+    })
+}
+
+/// Parses a config file holding an object that maps profile names to objects with ``host``,
+/// ``password``, ``identity``, ``color``, ``history`` and ``curve_server_key``, all of them
+/// optional.
+pub(crate) fn parse_profiles(contents: &str) -> Result<HashMap<String, Profile>> {
+    let config = serde_json::from_str::<Value>(contents)?;
+    let Some(profiles) = config.as_object() else {
+        bail!("the config does not hold an object of profiles");
+    };
+
+    profiles
+        .iter()
+        .map(|(name, entry)| Ok((name.to_string(), profile_from_json(name, entry)?)))
+        .collect()
+}
+
+/// ``profiles.json`` in the config directory of the user, e.g. ``~/.config/ql_rcon`` on Linux.
+fn default_config_file() -> Option<PathBuf> {
+    ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))
+        .map(|project_dirs| project_dirs.config_dir().join("profiles.json"))
+}
+
+fn load_profile(args: &CommandLineOptions, name: &str) -> Result<Profile> {
+    let Some(config_file) = args.config.clone().or_else(default_config_file) else {
+        bail!("could not determine the config file for the profile '{name}'");
+    };
+    let contents = fs::read_to_string(&config_file)
+        .with_context(|| format!("could not read {}", config_file.display()))?;
+    let mut profiles = parse_profiles(&contents)
+        .with_context(|| format!("could not parse {}", config_file.display()))?;
+
+    profiles
+        .remove(name)
+        .with_context(|| format!("no profile '{name}' in {}", config_file.display()))
+}
+
+/// Resolves the servers from *profiles* and ``--host``. Options given on the command line take
+/// precedence over the ones of the profiles, and color and history are only taken from a profile
+/// if it is the only one.
+pub(crate) fn apply_profiles(
+    mut args: CommandLineOptions,
+    matches: &ArgMatches,
+    profiles: Vec<(String, Profile)>,
+) -> Result<CommandLineOptions> {
+    let from_command_line = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+
+    if let [(_, profile)] = profiles.as_slice() {
+        if let Some(color) = profile.color.filter(|_| !from_command_line("color")) {
+            args.color = color;
+        }
+        if args.history.is_none() {
+            args.history = profile.history.clone();
+        }
+    }
+
+    let mut profile_server_keys = profiles
+        .iter()
+        .map(|(_, profile)| profile.curve_server_key.to_owned())
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    let mut servers = profiles
+        .into_iter()
+        .map(|(name, profile)| Server {
+            name,
+            host: profile.host.unwrap_or_else(|| DEFAULT_HOST.to_string()),
+            password: profile
+                .password
+                .filter(|_| !from_command_line("password"))
+                .unwrap_or_else(|| args.password.to_owned()),
+            identity: profile
+                .identity
+                .filter(|_| !from_command_line("identity"))
+                .unwrap_or_else(|| args.identity.to_owned()),
+            curve: None,
+        })
+        .collect::<Vec<_>>();
+    servers.extend(args.hosts.iter().map(|host| Server::from_host(host, &args)));
+    if servers.is_empty() {
+        servers.push(Server::from_host(DEFAULT_HOST, &args));
+    }
+    check_unique_names(&servers)?;
+
+    let curve_server_keys = servers
+        .iter()
+        .map(|_| {
+            profile_server_keys
+                .next()
+                .flatten()
+                .or_else(|| args.curve_server_key.to_owned())
+                .map(|server_key| read_server_key(&server_key))
+                .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if curve_server_keys.iter().any(Option::is_some) {
+        let (public_key, secret_key) = read_client_keys(args.curve_cert.as_deref())?;
+        servers
+            .iter_mut()
+            .zip(curve_server_keys)
+            .for_each(|(server, server_key)| {
+                server.curve = server_key.map(|server_key| CurveKeys {
+                    server_key,
+                    public_key: public_key.to_owned(),
+                    secret_key: secret_key.to_owned(),
+                });
+            });
+    }
+
+    args.servers = servers;
+    Ok(args)
+}
+
+/// Parses the command line, filling in the options of the selected profiles.
+pub(crate) fn parse_args() -> Result<CommandLineOptions> {
+    let matches = CommandLineOptions::command().get_matches();
+    let args = CommandLineOptions::from_arg_matches(&matches)?;
+
+    let profiles = args
+        .profiles
+        .iter()
+        .map(|name| Ok((name.to_owned(), load_profile(&args, name)?)))
+        .collect::<Result<Vec<_>>>()?;
+    apply_profiles(args, &matches, profiles)
+}
+
+#[cfg(test)]
+mod profiles_tests {
+    use std::path::PathBuf;
+
+    use clap::{CommandFactory, FromArgMatches};
+    use pretty_assertions::assert_eq;
+    use termcolor::ColorChoice;
+
+    use super::{Profile, apply_profiles, parse_profiles};
+    use crate::{CommandLineOptions, servers::Server};
+
+    #[test]
+    fn profiles_are_parsed() {
+        let profiles = parse_profiles(
+            r#"{"eu1": {"host": "tcp://10.0.0.1:28960", "password": "secret", "color": "never"},
+                "local": {}}"#,
+        )
+        .expect("this should not happen");
+
+        assert_eq!(
+            profiles.get("eu1"),
+            Some(&Profile {
+                host: Some("tcp://10.0.0.1:28960".to_string()),
+                password: Some("secret".to_string()),
+                color: Some(ColorChoice::Never),
+                ..Profile::default()
+            })
+        );
+        assert_eq!(profiles.get("local"), Some(&Profile::default()));
+    }
+
+    #[test]
+    fn invalid_profiles_are_rejected() {
+        assert!(parse_profiles(r#"["eu1"]"#).is_err());
+        assert!(parse_profiles(r#"{"eu1": {"host": 27960}}"#).is_err());
+        assert!(parse_profiles(r#"{"eu1": {"color": "purple"}}"#).is_err());
+    }
+
+    #[test]
+    fn command_line_overrides_profile() {
+        let matches = CommandLineOptions::command().get_matches_from([
+            "ql_rcon",
+            "eu1",
+            "--password",
+            "other",
+        ]);
+        let args = CommandLineOptions::from_arg_matches(&matches).expect("this should not happen");
+        let profile = Profile {
+            host: Some("tcp://10.0.0.1:28960".to_string()),
+            password: Some("secret".to_string()),
+            color: Some(ColorChoice::Never),
+            history: Some(PathBuf::from("eu1.history")),
+            ..Profile::default()
+        };
+
+        let args = apply_profiles(args, &matches, vec![("eu1".to_string(), profile)])
+            .expect("this should not happen");
+
+        assert_eq!(
+            args.servers,
+            vec![Server {
+                name: "eu1".to_string(),
+                host: "tcp://10.0.0.1:28960".to_string(),
+                password: "other".to_string(),
+                identity: String::new(),
+                curve: None,
+            }]
+        );
+        assert_eq!(args.color, ColorChoice::Never);
+        assert_eq!(args.history, Some(PathBuf::from("eu1.history")));
+    }
+
+    #[test]
+    fn profiles_and_hosts_are_combined() {
+        let matches = CommandLineOptions::command().get_matches_from([
+            "ql_rcon",
+            "eu1",
+            "eu2",
+            "--host",
+            "tcp://10.0.0.3:28960",
+            "--password",
+            "fallback",
+        ]);
+        let args = CommandLineOptions::from_arg_matches(&matches).expect("this should not happen");
+        let profile = |host: &str, password: Option<&str>| Profile {
+            host: Some(host.to_string()),
+            password: password.map(str::to_string),
+            color: Some(ColorChoice::Never),
+            ..Profile::default()
+        };
+
+        let args = apply_profiles(
+            args,
+            &matches,
+            vec![
+                (
+                    "eu1".to_string(),
+                    profile("tcp://10.0.0.1:28960", Some("secret")),
+                ),
+                ("eu2".to_string(), profile("tcp://10.0.0.2:28960", None)),
+            ],
+        )
+        .expect("this should not happen");
+
+        assert_eq!(
+            args.servers
+                .iter()
+                .map(|server| (server.name.as_str(), server.password.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("eu1", "fallback"),
+                ("eu2", "fallback"),
+                ("10.0.0.3:28960", "fallback")
+            ]
+        );
+        assert_eq!(args.color, ColorChoice::Auto);
+    }
+
+    #[test]
+    fn curve_server_key_per_profile() {
+        let matches = CommandLineOptions::command().get_matches_from([
+            "ql_rcon",
+            "eu1",
+            "--host",
+            "tcp://10.0.0.3:28960",
+            "--curve-cert",
+            "client.cert",
+        ]);
+        let args = CommandLineOptions::from_arg_matches(&matches).expect("this should not happen");
+        let profile = Profile {
+            curve_server_key: Some("not a key".to_string()),
+            ..Profile::default()
+        };
+
+        assert!(apply_profiles(args, &matches, vec![("eu1".to_string(), profile)]).is_err());
+    }
+
+    #[test]
+    fn host_defaults_without_profiles() {
+        let matches = CommandLineOptions::command().get_matches_from(["ql_rcon"]);
+        let args = CommandLineOptions::from_arg_matches(&matches).expect("this should not happen");
+
+        let args = apply_profiles(args, &matches, vec![]).expect("this should not happen");
+
+        assert_eq!(
+            args.servers
+                .iter()
+                .map(|server| server.host.as_str())
+                .collect::<Vec<_>>(),
+            vec!["tcp://127.0.0.1:27961"]
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use core::sync::atomic::Ordering;
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::{LazyLock, Mutex},
+};
+
+use anyhow::{Result, bail};
+use ql_curve::CurveKeys;
+use tokio::{
+    sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+    task,
+};
+
+use crate::{
+    CONTINUE_RUNNING, CommandLineOptions,
+    udp_rcon::run_udp,
+    zmq_helper::{DisplayLine, run_zmq},
+};
+
+pub(crate) const DEFAULT_HOST: &str = "tcp://127.0.0.1:27961";
+
+/// The names of the servers the handshake succeeded with, so that commands can be sent to them.
+pub(crate) static CONNECTED_SERVERS: LazyLock<Mutex<BTreeSet<String>>> =
+    LazyLock::new(|| Mutex::new(BTreeSet::new()));
+
+pub(crate) fn is_connected(name: &str) -> bool {
+    CONNECTED_SERVERS
+        .lock()
+        .is_ok_and(|connected| connected.contains(name))
+}
+
+pub(crate) fn connected_count() -> usize {
+    CONNECTED_SERVERS
+        .lock()
+        .map_or(0, |connected| connected.len())
+}
+
+/// A server to send the commands to, from a profile or ``--host``.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Server {
+    pub(crate) name: String,
+    pub(crate) host: String,
+    pub(crate) password: String,
+    pub(crate) identity: String,
+    /// Connect with CURVE instead of PLAIN.
+    pub(crate) curve: Option<CurveKeys>,
+}
+
+impl Server {
+    /// A server given by ``--host``, named after its address.
+    pub(crate) fn from_host(host: &str, args: &CommandLineOptions) -> Self {
+        Self {
+            name: host_address(host).unwrap_or(host).to_string(),
+            host: host.to_string(),
+            password: args.password.to_owned(),
+            identity: args.identity.to_owned(),
+            curve: None,
+        }
+    }
+}
+
+/// The address of *host* without its ``tcp://`` or ``udp://`` scheme.
+pub(crate) fn host_address(host: &str) -> Option<&str> {
+    host.strip_prefix("tcp://")
+        .or_else(|| host.strip_prefix("udp://"))
+}
+
+pub(crate) fn check_unique_names(servers: &[Server]) -> Result<()> {
+    let mut names = HashSet::new();
+    if let Some(duplicate) = servers
+        .iter()
+        .map(|server| server.name.as_str())
+        .find(|name| !names.insert(*name))
+    {
+        bail!("the server '{duplicate}' is given more than once");
+    }
+    Ok(())
+}
+
+/// Splits a command like ``@eu1,eu2 map campgrounds`` into the servers it is meant for and the
+/// command itself. Commands without ``@`` are meant for all servers.
+pub(crate) fn parse_targets(line: &str) -> (Option<Vec<&str>>, &str) {
+    let Some(addressed) = line.trim_start().strip_prefix('@') else {
+        return (None, line);
+    };
+    let (targets, command) = addressed
+        .split_once(char::is_whitespace)
+        .unwrap_or((addressed, ""));
+    (
+        Some(
+            targets
+                .split(',')
+                .filter(|target| !target.is_empty())
+                .collect(),
+        ),
+        command.trim_start(),
+    )
+}
+
+fn route(
+    line: &str,
+    server_senders: &[(String, UnboundedSender<String>)],
+    display_sender: &UnboundedSender<DisplayLine>,
+) -> Result<()> {
+    let (targets, command) = parse_targets(line);
+    if let Some(unknown) = targets.as_ref().and_then(|targets| {
+        targets
+            .iter()
+            .find(|target| !server_senders.iter().any(|(name, _)| name == *target))
+    }) {
+        display_sender.send(format!("Unknown server '{unknown}'.").into())?;
+        return Ok(());
+    }
+    if command.is_empty() {
+        return Ok(());
+    }
+
+    server_senders
+        .iter()
+        .filter(|(name, _)| {
+            targets
+                .as_ref()
+                .is_none_or(|targets| targets.contains(&name.as_str()))
+        })
+        .for_each(|(_, server_sender)| {
+            let _ = server_sender.send(command.to_string());
+        });
+    Ok(())
+}
+
+/// Connects to all configured servers and sends each command to the servers it is meant for.
+/// With more than one server, the lines for the display are tagged with the name of the server.
+pub(crate) async fn run_servers(
+    args: CommandLineOptions,
+    mut zmq_receiver: UnboundedReceiver<String>,
+    display_sender: UnboundedSender<DisplayLine>,
+) -> Result<()> {
+    let tagged = args.servers.len() > 1;
+
+    let mut server_senders = vec![];
+    let mut server_tasks = vec![];
+    for server in &args.servers {
+        let (server_sender, server_receiver) = unbounded_channel();
+        server_senders.push((server.name.to_owned(), server_sender));
+        let server_task = if server.host.starts_with("udp://") {
+            task::spawn(run_udp(
+                server.clone(),
+                tagged,
+                server_receiver,
+                display_sender.clone(),
+            ))
+        } else {
+            task::spawn(run_zmq(
+                server.clone(),
+                tagged,
+                server_receiver,
+                display_sender.clone(),
+            ))
+        };
+        server_tasks.push(server_task);
+    }
+
+    while CONTINUE_RUNNING.load(Ordering::Acquire) {
+        let Some(line) = zmq_receiver.recv().await else {
+            break;
+        };
+        route(&line, &server_senders, &display_sender)?;
+    }
+
+    drop(server_senders);
+    drop(display_sender);
+
+    for server_task in server_tasks {
+        server_task.await??;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod servers_tests {
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::{Server, check_unique_names, parse_targets, route};
+
+    #[test]
+    fn commands_are_addressed_with_at() {
+        assert_eq!(
+            parse_targets("@eu1,eu2 map campgrounds"),
+            (Some(vec!["eu1", "eu2"]), "map campgrounds")
+        );
+        assert_eq!(
+            parse_targets("@eu1   status"),
+            (Some(vec!["eu1"]), "status")
+        );
+        assert_eq!(parse_targets("status"), (None, "status"));
+    }
+
+    #[test]
+    fn commands_are_routed_to_their_servers() {
+        let (display_sender, mut display_receiver) = unbounded_channel();
+        let (eu1_sender, mut eu1_receiver) = unbounded_channel();
+        let (eu2_sender, mut eu2_receiver) = unbounded_channel();
+        let server_senders = [
+            ("eu1".to_string(), eu1_sender),
+            ("eu2".to_string(), eu2_sender),
+        ];
+
+        route("status", &server_senders, &display_sender).expect("this should not happen");
+        route("@eu2 map campgrounds", &server_senders, &display_sender)
+            .expect("this should not happen");
+        route("@us1 status", &server_senders, &display_sender).expect("this should not happen");
+
+        assert_eq!(eu1_receiver.try_recv().ok(), Some("status".to_string()));
+        assert!(eu1_receiver.try_recv().is_err());
+        assert_eq!(eu2_receiver.try_recv().ok(), Some("status".to_string()));
+        assert_eq!(
+            eu2_receiver.try_recv().ok(),
+            Some("map campgrounds".to_string())
+        );
+        assert_eq!(
+            display_receiver
+                .try_recv()
+                .map(|line| line.text().to_string())
+                .ok(),
+            Some("Unknown server 'us1'.".to_string())
+        );
+    }
+
+    #[test]
+    fn server_names_have_to_be_unique() {
+        let server = |name: &str| Server {
+            name: name.to_string(),
+            host: "tcp://127.0.0.1:27961".to_string(),
+            password: String::new(),
+            identity: String::new(),
+            curve: None,
+        };
+
+        assert!(check_unique_names(&[server("eu1"), server("eu2")]).is_ok());
+        assert!(check_unique_names(&[server("eu1"), server("eu1")]).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use core::{
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::atomic::Ordering,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use tokio::{
+    net::{UdpSocket, lookup_host},
+    select,
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    time::sleep,
+};
+
+use crate::{
+    CONTINUE_RUNNING,
+    servers::{CONNECTED_SERVERS, Server},
+    zmq_helper::{DisplayLine, ServerDisplay},
+};
+
+/// Every connectionless packet of the Quake 3 protocol starts with this header.
+const OUT_OF_BAND_HEADER: &[u8] = b"\xff\xff\xff\xff";
+const PRINT_PREFIX: &[u8] = b"print\n";
+const MAX_PACKET_SIZE: usize = 16384;
+/// How long to wait for the next packet of a response before displaying an incomplete line.
+const REASSEMBLY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// The out-of-band packet running *command* on the server.
+pub(crate) fn rcon_packet(password: &str, command: &str) -> Vec<u8> {
+    [
+        OUT_OF_BAND_HEADER,
+        format!("rcon {password} {command}").as_bytes(),
+    ]
+    .concat()
+}
+
+/// The text of a ``print`` packet the server answers with.
+pub(crate) fn parse_print_packet(packet: &[u8]) -> Option<String> {
+    let text = packet
+        .strip_prefix(OUT_OF_BAND_HEADER)?
+        .strip_prefix(PRINT_PREFIX)?;
+    Some(String::from_utf8_lossy(text).to_string())
+}
+
+/// Puts the lines of a response back together. Servers split long responses into several
+/// ``print`` packets, without regard for where a line ends.
+#[derive(Debug, Default)]
+pub(crate) struct ResponseAssembler {
+    pending: String,
+}
+
+impl ResponseAssembler {
+    /// Adds the text of a packet, returning the lines it completes.
+    pub(crate) fn push(&mut self, text: &str) -> Vec<String> {
+        self.pending.push_str(text);
+        let Some(last_newline) = self.pending.rfind('\n') else {
+            return vec![];
+        };
+
+        let rest = self.pending.split_off(last_newline + 1);
+        let complete = core::mem::replace(&mut self.pending, rest);
+        complete.lines().map(str::to_string).collect()
+    }
+
+    /// The incomplete last line, once no further packets are coming.
+    pub(crate) fn flush(&mut self) -> Option<String> {
+        (!self.pending.is_empty()).then(|| core::mem::take(&mut self.pending))
+    }
+}
+
+/// Sends the commands for *server* with the classic Quake 3 UDP rcon protocol, for servers given
+/// as ``udp://HOST:PORT``.
+pub(crate) async fn run_udp(
+    server: Server,
+    tagged: bool,
+    mut zmq_receiver: UnboundedReceiver<String>,
+    display_sender: UnboundedSender<DisplayLine>,
+) -> Result<()> {
+    let display = ServerDisplay {
+        server: &server,
+        tagged,
+        sender: display_sender,
+    };
+    let address = server.host.strip_prefix("udp://").unwrap_or(&server.host);
+
+    let remote = lookup_host(address)
+        .await?
+        .next()
+        .with_context(|| format!("could not resolve {address}"))?;
+    let local: SocketAddr = if remote.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(remote).await?;
+    if let Ok(mut connected) = CONNECTED_SERVERS.lock() {
+        connected.insert(server.name.to_owned());
+    }
+    display.send(format!("UDP rcon to {address}."))?;
+
+    let mut buffer = vec![0; MAX_PACKET_SIZE];
+    let mut assembler = ResponseAssembler::default();
+
+    while CONTINUE_RUNNING.load(Ordering::Acquire) && !zmq_receiver.is_closed() {
+        select!(
+            result = socket.recv(&mut buffer) => match result {
+                Ok(size) => {
+                    if let Some(text) = parse_print_packet(&buffer[..size]) {
+                        for line in assembler.push(&text) {
+                            display.send(DisplayLine::Response(line))?;
+                        }
+                    }
+                }
+                Err(e) => display.send(format!("UDP error: {e}."))?,
+            },
+
+            Some(command) = zmq_receiver.recv() => {
+                socket.send(&rcon_packet(&server.password, &command)).await?;
+            },
+
+            () = sleep(REASSEMBLY_TIMEOUT) => {
+                if let Some(line) = assembler.flush() {
+                    display.send(DisplayLine::Response(line))?;
+                }
+            },
+        );
+    }
+
+    if let Some(line) = assembler.flush() {
+        display.send(DisplayLine::Response(line))?;
+    }
+    if let Ok(mut connected) = CONNECTED_SERVERS.lock() {
+        connected.remove(&server.name);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod udp_rcon_tests {
+    use pretty_assertions::assert_eq;
+    use tokio::{net::UdpSocket, sync::mpsc::unbounded_channel, task};
+
+    use super::{ResponseAssembler, parse_print_packet, rcon_packet, run_udp};
+    use crate::{servers::Server, zmq_helper::DisplayLine};
+
+    #[test]
+    fn rcon_packets_are_out_of_band() {
+        assert_eq!(
+            rcon_packet("secret", "map campgrounds"),
+            b"\xff\xff\xff\xffrcon secret map campgrounds".to_vec()
+        );
+    }
+
+    #[test]
+    fn only_print_packets_are_responses() {
+        assert_eq!(
+            parse_print_packet(b"\xff\xff\xff\xffprint\n^1red\n"),
+            Some("^1red\n".to_string())
+        );
+        assert_eq!(
+            parse_print_packet(b"\xff\xff\xff\xffstatusResponse\n"),
+            None
+        );
+        assert_eq!(parse_print_packet(b"print\nno header\n"), None);
+    }
+
+    #[test]
+    fn lines_split_across_packets_are_reassembled() {
+        let mut assembler = ResponseAssembler::default();
+
+        assert_eq!(
+            assembler.push("map: campgrounds\nnum sc"),
+            vec!["map: campgrounds"]
+        );
+        assert_eq!(
+            assembler.push("ore ping name\n  0"),
+            vec!["num score ping name"]
+        );
+        assert_eq!(assembler.flush(), Some("  0".to_string()));
+        assert_eq!(assembler.flush(), None);
+    }
+
+    #[tokio::test]
+    async fn responses_of_a_local_stand_in_are_displayed() {
+        let stand_in = UdpSocket::bind(("127.0.0.1", 0))
+            .await
+            .expect("this should not happen");
+        let address = stand_in.local_addr().expect("this should not happen");
+        let server = Server {
+            name: "local".to_string(),
+            host: format!("udp://{address}"),
+            password: "secret".to_string(),
+            identity: String::new(),
+            curve: None,
+        };
+
+        let (zmq_sender, zmq_receiver) = unbounded_channel();
+        let (display_sender, mut display_receiver) = unbounded_channel();
+        let udp_task = task::spawn(run_udp(server, false, zmq_receiver, display_sender));
+
+        zmq_sender
+            .send("status".to_string())
+            .expect("this should not happen");
+        let mut buffer = [0; 1024];
+        let (size, client) = stand_in
+            .recv_from(&mut buffer)
+            .await
+            .expect("this should not happen");
+        assert_eq!(&buffer[..size], b"\xff\xff\xff\xffrcon secret status");
+
+        for packet in [
+            b"\xff\xff\xff\xffprint\nmap: ^1camp".as_slice(),
+            b"\xff\xff\xff\xffprint\ngrounds^7\nnum score\n".as_slice(),
+        ] {
+            stand_in
+                .send_to(packet, client)
+                .await
+                .expect("this should not happen");
+        }
+
+        let mut responses = vec![];
+        while responses.len() < 2 {
+            match display_receiver.recv().await {
+                Some(DisplayLine::Response(response)) => responses.push(response),
+                Some(_) => (),
+                None => break,
+            }
+        }
+        drop(zmq_sender);
+        udp_task
+            .await
+            .expect("this should not happen")
+            .expect("this should not happen");
+
+        assert_eq!(
+            responses,
+            vec!["map: ^1campgrounds^7".to_string(), "num score".to_string()]
+        );
+    }
+}
@@ -8,6 +8,7 @@ use arzmq::{
     },
     security::SecurityMechanism,
 };
+use ql_curve::CurveKeys;
 use tokio::{
     select,
     sync::{
@@ -17,7 +18,10 @@ use tokio::{
 };
 use uuid::Uuid;
 
-use crate::{CONTINUE_RUNNING, cmd_line::CommandLineOptions};
+use crate::{
+    CONTINUE_RUNNING,
+    servers::{CONNECTED_SERVERS, Server},
+};
 
 struct MonitoredDealer {
     dealer: RwLock<DealerSocket>,
@@ -53,7 +57,8 @@ impl MonitoredDealer {
         })
     }
 
-    async fn configure(&self, password: &str, identity: &str) -> Result<()> {
+    async fn configure(&self, server: &Server) -> Result<()> {
+        let identity = &server.identity;
         let identity_str = if identity.is_empty() {
             let identity = Uuid::new_v4();
             identity.to_string().replace("-", "")
@@ -62,10 +67,13 @@ impl MonitoredDealer {
         };
 
         let socket_builder = SocketBuilder::default()
-            .security_mechanism(SecurityMechanism::Plain {
-                username: "rcon".into(),
-                password: password.into(),
-            })
+            .security_mechanism(server.curve.as_ref().map_or_else(
+                || SecurityMechanism::Plain {
+                    username: "rcon".into(),
+                    password: server.password.to_owned(),
+                },
+                CurveKeys::security_mechanism,
+            ))
             .immediate(true)
             .receive_timeout(0)
             .receive_highwater_mark(0)
@@ -127,24 +135,76 @@ fn trim_ql_msg(msg: &str) -> String {
         .replace('\u{0019}', "")
 }
 
-static FIRST_TIME: AtomicBool = AtomicBool::new(true);
+/// A line for the display: either the status of the connection or a response of the server.
+pub(crate) enum DisplayLine {
+    Status(String),
+    Response(String),
+    /// A line of one of several servers, displayed with the name of the server.
+    Server(String, Box<DisplayLine>),
+}
+
+impl DisplayLine {
+    pub(crate) fn text(&self) -> &str {
+        match self {
+            Self::Status(text) | Self::Response(text) => text,
+            Self::Server(_, line) => line.text(),
+        }
+    }
+
+    /// The name of the server the line is tagged with, if any, and the line itself.
+    pub(crate) fn untagged(&self) -> (Option<&str>, &Self) {
+        match self {
+            Self::Server(name, line) => (Some(name), line.untagged().1),
+            line => (None, line),
+        }
+    }
+}
+
+impl From<String> for DisplayLine {
+    fn from(status: String) -> Self {
+        Self::Status(status)
+    }
+}
+
+/// Sends the lines of one server to the display, tagging them if there are several servers.
+pub(crate) struct ServerDisplay<'a> {
+    pub(crate) server: &'a Server,
+    pub(crate) tagged: bool,
+    pub(crate) sender: UnboundedSender<DisplayLine>,
+}
+
+impl ServerDisplay<'_> {
+    pub(crate) fn send<T: Into<DisplayLine>>(&self, line: T) -> Result<()> {
+        let line = line.into();
+        self.sender.send(if self.tagged {
+            DisplayLine::Server(self.server.name.to_owned(), Box::new(line))
+        } else {
+            line
+        })?;
+        Ok(())
+    }
+}
 
 async fn check_monitor(
     monitored_dealer: &MonitoredDealer,
-    sender: &UnboundedSender<String>,
-    endpoint: &str,
+    sender: &ServerDisplay<'_>,
+    first_time: &AtomicBool,
 ) -> Result<()> {
+    let endpoint = &sender.server.host;
     match monitored_dealer.check_monitor().await {
         Some(MonitorSocketEvent::Connected) => {
-            if FIRST_TIME.load(Ordering::Acquire) {
-                FIRST_TIME.store(false, Ordering::Release);
+            if first_time.load(Ordering::Acquire) {
+                first_time.store(false, Ordering::Release);
                 sender.send("ZMQ registering with the server.".to_string())?;
             }
         }
 
         Some(MonitorSocketEvent::HandshakeSucceeded) => {
-            FIRST_TIME.store(true, Ordering::Release);
-            sender.send(format!("ZMQ connected to {}.", &endpoint))?;
+            first_time.store(true, Ordering::Release);
+            if let Ok(mut connected) = CONNECTED_SERVERS.lock() {
+                connected.insert(sender.server.name.to_owned());
+            }
+            sender.send(format!("ZMQ connected to {}.", endpoint))?;
         }
 
         Some(
@@ -158,8 +218,11 @@ async fn check_monitor(
         }
 
         Some(MonitorSocketEvent::Disconnected | MonitorSocketEvent::Closed) => {
-            if FIRST_TIME.load(Ordering::Acquire) {
-                FIRST_TIME.store(false, Ordering::Release);
+            if let Ok(mut connected) = CONNECTED_SERVERS.lock() {
+                connected.remove(&sender.server.name);
+            }
+            if first_time.load(Ordering::Acquire) {
+                first_time.store(false, Ordering::Release);
                 sender.send("Reconnecting ZMQ...".to_string())?;
             }
             if let Err(e) = monitored_dealer.connect(endpoint).await {
@@ -178,18 +241,24 @@ async fn check_monitor(
 }
 
 pub(crate) async fn run_zmq(
-    args: CommandLineOptions,
+    server: Server,
+    tagged: bool,
     mut zmq_receiver: UnboundedReceiver<String>,
-    display_sender: UnboundedSender<String>,
+    display_sender: UnboundedSender<DisplayLine>,
 ) -> Result<()> {
-    display_sender.send(format!("ZMQ connecting to {}...", &args.host))?;
+    let display = ServerDisplay {
+        server: &server,
+        tagged,
+        sender: display_sender,
+    };
+    let first_time = AtomicBool::new(true);
+
+    display.send(format!("ZMQ connecting to {}...", server.host))?;
 
     let monitored_dealer = MonitoredDealer::new()?;
-    monitored_dealer
-        .configure(&args.password, &args.identity)
-        .await?;
+    monitored_dealer.configure(&server).await?;
 
-    monitored_dealer.connect(&args.host).await?;
+    monitored_dealer.connect(&server.host).await?;
 
     while CONTINUE_RUNNING.load(Ordering::Acquire) && !zmq_receiver.is_closed() {
         select!(
@@ -197,14 +266,14 @@ pub(crate) async fn run_zmq(
 
             Some(zmq_msg) = monitored_dealer.recv_msg() => {
                 let zmq_str = zmq_msg.to_string();
-                display_sender.send(trim_ql_msg(&zmq_str))?;
+                display.send(DisplayLine::Response(trim_ql_msg(&zmq_str)))?;
             }
 
             Some(line) = zmq_receiver.recv(), if !zmq_receiver.is_empty() => {
                 monitored_dealer.send(&line, SendFlags::DONT_WAIT).await;
             },
 
-            Ok(()) = check_monitor(&monitored_dealer, &display_sender, &args.host) => (),
+            Ok(()) = check_monitor(&monitored_dealer, &display, &first_time) => (),
 
             else => ()
         );
@@ -214,7 +283,7 @@ pub(crate) async fn run_zmq(
 
     monitored_dealer.disconnect().await?;
 
-    drop(display_sender);
+    drop(display);
 
     Ok(())
 }
@@ -0,0 +1,322 @@
+use core::{sync::atomic::Ordering, time::Duration};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+    thread,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use arzmq::prelude::{
+    ContextBuilder, Message, MultipartSender, PublishSocket, SendFlags, Socket, SocketBuilder,
+};
+use ql_zap::{PlainCredentials, ZapHandler};
+use serde_json::{Value, json};
+
+use crate::{CONTINUE_RUNNING, cmd_line::CommandLineOptions};
+
+const ZAP_DOMAIN: &str = "stats";
+const ZAP_USERNAME: &str = "stats";
+
+/// A message as received from a server, with the time it was received at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CapturedMessage {
+    /// Milliseconds since the unix epoch.
+    pub(crate) timestamp: u64,
+    pub(crate) server: Option<String>,
+    pub(crate) message: String,
+}
+
+impl CapturedMessage {
+    fn to_json(&self) -> Value {
+        match &self.server {
+            Some(server) => json!({
+                "timestamp": self.timestamp,
+                "server": server,
+                "message": self.message,
+            }),
+            None => json!({"timestamp": self.timestamp, "message": self.message}),
+        }
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            timestamp: value.get("timestamp")?.as_u64()?,
+            server: value
+                .get("server")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            message: value.get("message")?.as_str()?.to_string(),
+        })
+    }
+}
+
+/// Saves the raw stream as one JSON line per message.
+pub(crate) struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    pub(crate) fn create(path: &Path) -> Result<Self> {
+        Ok(Self {
+            file: File::create(path)
+                .with_context(|| format!("could not create {}", path.display()))?,
+        })
+    }
+
+    pub(crate) fn record(&mut self, server: Option<&str>, message: &str) -> Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| {
+                u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+            });
+        let captured = CapturedMessage {
+            timestamp,
+            server: server.map(str::to_string),
+            message: message.to_string(),
+        };
+        writeln!(self.file, "{}", captured.to_json())?;
+        Ok(())
+    }
+}
+
+pub(crate) fn read_capture(path: &Path) -> Result<Vec<CapturedMessage>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str::<Value>(line)
+                .ok()
+                .as_ref()
+                .and_then(CapturedMessage::from_json)
+                .with_context(|| {
+                    format!("{}:{}: not a captured message", path.display(), index + 1)
+                })
+        })
+        .collect()
+}
+
+/// Parses the ``--replay-speed`` factor, which has to be a finite positive number, or zero to
+/// replay without any delay.
+pub(crate) fn parse_replay_speed(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(speed) if speed == 0.0 || (speed.is_finite() && speed > 0.0) => Ok(speed),
+        _ => Err(format!(
+            "expected a finite positive factor or 0, got '{value}'"
+        )),
+    }
+}
+
+/// How long to wait before publishing each message, keeping the original gaps between them,
+/// divided by *speed*. A speed of zero publishes everything without delay.
+pub(crate) fn replay_delays(messages: &[CapturedMessage], speed: f64) -> Vec<Duration> {
+    messages
+        .iter()
+        .scan(None, |previous: &mut Option<u64>, captured| {
+            let gap = previous.map_or(0, |previous| captured.timestamp.saturating_sub(previous));
+            *previous = Some(captured.timestamp);
+            Some(if speed.is_finite() && speed > 0.0 {
+                Duration::from_millis(gap).div_f64(speed)
+            } else {
+                Duration::ZERO
+            })
+        })
+        .collect()
+}
+
+/// A stand-in for the stats socket of a QuakeLive server.
+struct ReplayPublisher {
+    publisher: PublishSocket,
+    authenticator: Option<ZapHandler>,
+}
+
+impl ReplayPublisher {
+    fn new(address: &str, password: &str) -> Result<Self> {
+        let context = ContextBuilder::default()
+            .blocky(false)
+            .max_sockets(10)
+            .io_threads(1)
+            .build()?;
+
+        let authenticator = if password.is_empty() {
+            None
+        } else {
+            Some(ZapHandler::bind(
+                &context,
+                PlainCredentials {
+                    domain: ZAP_DOMAIN.to_string(),
+                    username: ZAP_USERNAME.to_string(),
+                    password: password.to_string(),
+                },
+            )?)
+        };
+
+        let publisher = Socket::from_context(&context)?;
+        SocketBuilder::default()
+            .send_highwater_mark(0)
+            .apply(&publisher)?;
+        if let Some(ref authenticator) = authenticator {
+            authenticator.secure(&publisher)?;
+        }
+        publisher.bind(address)?;
+
+        Ok(Self {
+            publisher,
+            authenticator,
+        })
+    }
+
+    fn handle_authentication(&self) {
+        if let Some(ref authenticator) = self.authenticator {
+            authenticator.handle_requests();
+        }
+    }
+
+    /// Waits for *delay*, answering authentication requests in the meantime.
+    fn wait(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        loop {
+            self.handle_authentication();
+            let remaining = until.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !CONTINUE_RUNNING.load(Ordering::Acquire) {
+                return;
+            }
+            thread::sleep(remaining.min(Duration::from_millis(10)));
+        }
+    }
+}
+
+fn replay(args: &CommandLineOptions, path: &Path) -> Result<()> {
+    let messages = read_capture(path)?;
+    let delays = replay_delays(&messages, args.replay_speed);
+
+    let publisher = ReplayPublisher::new(&args.replay_bind, &args.password)?;
+    println!(
+        "Replaying {} messages on {} in {}s...",
+        messages.len(),
+        args.replay_bind,
+        args.replay_wait
+    );
+    publisher.wait(Duration::from_secs(args.replay_wait));
+
+    for (captured, delay) in messages.iter().zip(delays) {
+        publisher.wait(delay);
+        if !CONTINUE_RUNNING.load(Ordering::Acquire) {
+            break;
+        }
+        publisher.publisher.send_multipart(
+            Message::from(captured.message.as_bytes()),
+            SendFlags::empty(),
+        )?;
+    }
+
+    println!("Replay finished.");
+    Ok(())
+}
+
+/// Republishes a capture made with ``--record`` on a local ZMQ PUB socket.
+pub(crate) async fn run_replay(args: CommandLineOptions, path: &Path) -> Result<()> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || replay(&args, &path)).await?
+}
+
+#[cfg(test)]
+mod capture_tests {
+    use core::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::{CapturedMessage, Recorder, parse_replay_speed, read_capture, replay_delays};
+
+    fn captured(timestamp: u64) -> CapturedMessage {
+        CapturedMessage {
+            timestamp,
+            server: None,
+            message: "{}".to_string(),
+        }
+    }
+
+    #[test]
+    fn recorded_messages_can_be_read_back() {
+        let directory = tempdir().expect("this should not happen");
+        let path = directory.path().join("capture.jsonl");
+
+        let mut recorder = Recorder::create(&path).expect("this should not happen");
+        recorder
+            .record(None, r#"{"TYPE": "ROUND_OVER"}"#)
+            .expect("this should not happen");
+        recorder
+            .record(Some("ca1"), r#"{"TYPE": "PLAYER_KILL"}"#)
+            .expect("this should not happen");
+
+        let messages = read_capture(&path).expect("this should not happen");
+        assert_eq!(
+            messages
+                .iter()
+                .map(|captured| (captured.server.as_deref(), captured.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (None, r#"{"TYPE": "ROUND_OVER"}"#),
+                (Some("ca1"), r#"{"TYPE": "PLAYER_KILL"}"#)
+            ]
+        );
+        assert!(messages[0].timestamp <= messages[1].timestamp);
+    }
+
+    #[test]
+    fn malformed_captures_are_rejected() {
+        let directory = tempdir().expect("this should not happen");
+        let path = directory.path().join("capture.jsonl");
+        std::fs::write(&path, "{\"timestamp\": 1}\n").expect("this should not happen");
+
+        assert!(read_capture(&path).is_err());
+    }
+
+    #[test]
+    fn replay_keeps_original_timing() {
+        let messages = [captured(1_000), captured(1_500), captured(3_500)];
+
+        assert_eq!(
+            replay_delays(&messages, 1.0),
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_secs(2)
+            ]
+        );
+        assert_eq!(
+            replay_delays(&messages, 2.0),
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(250),
+                Duration::from_secs(1)
+            ]
+        );
+        assert_eq!(replay_delays(&messages, 0.0), vec![Duration::ZERO; 3]);
+    }
+
+    #[test]
+    fn replay_speed_has_to_be_finite_and_not_negative() {
+        assert_eq!(parse_replay_speed("2.5"), Ok(2.5));
+        assert_eq!(parse_replay_speed("0"), Ok(0.0));
+        ["-1", "NaN", "inf", "-inf", "fast"]
+            .iter()
+            .for_each(|value| assert!(parse_replay_speed(value).is_err(), "{value}"));
+    }
+
+    #[test]
+    fn replay_without_finite_speed_does_not_delay() {
+        let messages = [captured(1_000), captured(1_500)];
+
+        assert_eq!(replay_delays(&messages, f64::NAN), vec![Duration::ZERO; 2]);
+        assert_eq!(
+            replay_delays(&messages, f64::INFINITY),
+            vec![Duration::ZERO; 2]
+        );
+    }
+}
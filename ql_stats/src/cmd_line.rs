@@ -1,4 +1,11 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+
+use crate::{
+    capture::parse_replay_speed,
+    servers::{ServerConfig, parse_server},
+};
 
 /// QuakeLive server stats
 #[derive(Parser, Debug, Clone)]
@@ -10,7 +17,136 @@ pub(crate) struct CommandLineOptions {
     /// The ZMQ password.
     #[arg(long, default_value = "")]
     pub(crate) password: String,
+    /// Subscribe to this server instead of --host, tagging its output with NAME. Can be given
+    /// multiple times. Servers without a password use --password.
+    #[arg(long = "server", value_name = "NAME=URI[=PASSWORD]", value_parser = parse_server)]
+    pub(crate) servers: Vec<ServerConfig>,
+    /// JSON file listing servers to subscribe to, as objects with name, address, password and
+    /// curve_server_key
+    #[arg(long, value_name = "FILE")]
+    pub(crate) servers_file: Option<PathBuf>,
+    /// Z85-encoded public key of the CURVE proxy in front of the servers, or a certificate file
+    /// holding it. Subscribes with CURVE instead of PLAIN.
+    #[arg(long, value_name = "KEY")]
+    pub(crate) curve_server_key: Option<String>,
+    /// Certificate file with the CURVE keypair of the client. A new keypair is generated for
+    /// each connection if not given
+    #[arg(long, value_name = "FILE")]
+    pub(crate) curve_cert: Option<PathBuf>,
+    /// Write a new CURVE client certificate to this file and exit
+    #[arg(long, value_name = "FILE")]
+    pub(crate) curve_generate_cert: Option<PathBuf>,
     /// Pretty print received json data
     #[arg(long)]
     pub(crate) pretty_print: bool,
+    /// Report received messages that do not match the stats schema
+    #[arg(long)]
+    pub(crate) validate: bool,
+    /// Only handle messages of these types, e.g. PLAYER_KILL,MATCH_REPORT
+    #[arg(long = "type", value_name = "TYPE", value_delimiter = ',')]
+    pub(crate) types: Vec<String>,
+    /// Only output these fields, given as path into the message, e.g. .DATA.KILLER.NAME
+    #[arg(long, value_name = "PATH", value_delimiter = ',')]
+    pub(crate) select: Vec<String>,
+    /// Write messages to this file instead of stdout, or into this directory with --match
+    #[arg(long)]
+    pub(crate) output: Option<PathBuf>,
+    /// Format of the output
+    #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+    pub(crate) format: OutputFormat,
+    /// Rotate output files once they grow beyond this many bytes
+    #[arg(long, value_name = "BYTES", requires = "output")]
+    pub(crate) rotate_size: Option<u64>,
+    /// Number of rotated output files to keep
+    #[arg(
+        long,
+        value_name = "FILES",
+        default_value_t = 5,
+        requires = "rotate_size"
+    )]
+    pub(crate) rotate_keep: usize,
+    /// Save one file per match GUID into the --output directory
+    #[arg(long = "match", requires = "output")]
+    pub(crate) per_match: bool,
+    /// Read the messages from a file saved with --record instead of subscribing to servers
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["servers", "servers_file", "record", "replay", "tui"]
+    )]
+    pub(crate) from: Option<PathBuf>,
+    /// Write a summary of every match that ends into this directory
+    #[arg(long, value_name = "DIRECTORY")]
+    pub(crate) report: Option<PathBuf>,
+    /// Formats of the match summaries
+    #[arg(
+        long = "report-format",
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [ReportFormat::Markdown, ReportFormat::Html],
+        requires = "report"
+    )]
+    pub(crate) report_formats: Vec<ReportFormat>,
+    /// Save the raw stream with the time each message was received to this file
+    #[arg(long, value_name = "FILE")]
+    pub(crate) record: Option<PathBuf>,
+    /// Republish a stream saved with --record on a local ZMQ PUB socket instead of subscribing
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["servers", "servers_file", "record", "tui", "output"]
+    )]
+    pub(crate) replay: Option<PathBuf>,
+    /// ZMQ URI to publish replayed messages on. Subscribers authenticate with --password.
+    #[arg(long, default_value = "tcp://127.0.0.1:27961", requires = "replay")]
+    pub(crate) replay_bind: String,
+    /// Speed up (or slow down) the replay by this factor, 0 replays without any delay
+    #[arg(
+        long,
+        value_name = "FACTOR",
+        default_value_t = 1.0,
+        value_parser = parse_replay_speed,
+        requires = "replay"
+    )]
+    pub(crate) replay_speed: f64,
+    /// Seconds to wait for subscribers to connect before replaying the first message
+    #[arg(long, value_name = "SECONDS", default_value_t = 3, requires = "replay")]
+    pub(crate) replay_wait: u64,
+    /// Show a live scoreboard, kill feed and round results instead of the received messages
+    #[arg(long, conflicts_with_all = ["pretty_print", "select", "output", "format"])]
+    pub(crate) tui: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    /// One JSON document per line
+    Json,
+    /// Comma-separated values of the --select fields
+    Csv,
+}
+
+impl OutputFormat {
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "jsonl",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReportFormat {
+    /// Markdown, e.g. for pasting into forum posts
+    Markdown,
+    /// A standalone HTML page
+    Html,
+}
+
+impl ReportFormat {
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
 }
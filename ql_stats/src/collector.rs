@@ -0,0 +1,643 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+
+use crate::{
+    capture::Recorder,
+    cmd_line::{CommandLineOptions, OutputFormat},
+    report::Reporter,
+};
+
+/// The field messages are tagged with the name of the server they were received from.
+pub(crate) const SERVER_FIELD: &str = "SERVER";
+
+/// How many match files are kept open with ``--match``. Matches that never send a
+/// ``MATCH_REPORT`` are closed once this many newer matches were opened.
+const MAX_OPEN_MATCH_FILES: usize = 64;
+
+/// A path into a stats message, e.g. ``.DATA.KILLER.NAME``. Numeric segments index into arrays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FieldPath {
+    name: String,
+    segments: Vec<String>,
+}
+
+impl FieldPath {
+    pub(crate) fn parse(path: &str) -> Result<Self> {
+        let name = path.trim_start_matches('.');
+        if name.is_empty() {
+            bail!("invalid field path '{path}'");
+        }
+        let segments = name.split('.').map(str::to_string).collect::<Vec<_>>();
+        if segments.iter().any(String::is_empty) {
+            bail!("invalid field path '{path}'");
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            segments,
+        })
+    }
+
+    fn lookup<'a>(&self, message: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(message, |value, segment| match value {
+                Value::Object(fields) => fields.get(segment),
+                Value::Array(items) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get(index)),
+                _ => None,
+            })
+    }
+}
+
+/// Decides which messages are collected, and which of their fields.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MessageFilter {
+    types: Vec<String>,
+    fields: Vec<FieldPath>,
+}
+
+impl MessageFilter {
+    pub(crate) fn new(types: &[String], select: &[String]) -> Result<Self> {
+        Ok(Self {
+            types: types
+                .iter()
+                .map(|message_type| message_type.to_uppercase())
+                .collect(),
+            fields: select
+                .iter()
+                .map(|path| FieldPath::parse(path))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Whether *message* is of one of the requested types. All messages are accepted without
+    /// type filters.
+    pub(crate) fn accepts(&self, message: &Value) -> bool {
+        self.types.is_empty()
+            || message["TYPE"]
+                .as_str()
+                .is_some_and(|message_type| self.types.iter().any(|t| t == message_type))
+    }
+
+    /// The selected fields of *message*, keyed by their path, or the whole message without a
+    /// selection. Fields missing from the message are ``null``.
+    pub(crate) fn select(&self, message: &Value) -> Value {
+        if self.fields.is_empty() {
+            return message.clone();
+        }
+
+        Value::Object(
+            self.fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.name.to_owned(),
+                        field.lookup(message).cloned().unwrap_or(Value::Null),
+                    )
+                })
+                .collect::<Map<_, _>>(),
+        )
+    }
+
+    fn csv_header(&self) -> String {
+        csv_record(
+            self.fields
+                .iter()
+                .map(|field| Value::String(field.name.to_owned())),
+        )
+    }
+
+    fn csv_line(&self, message: &Value) -> String {
+        csv_record(
+            self.fields
+                .iter()
+                .map(|field| field.lookup(message).cloned().unwrap_or(Value::Null)),
+        )
+    }
+}
+
+fn csv_field(value: Value) -> String {
+    let field = match value {
+        Value::Null => return String::new(),
+        Value::String(text) => text,
+        other => other.to_string(),
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+fn csv_record(values: impl Iterator<Item = Value>) -> String {
+    values.map(csv_field).collect::<Vec<_>>().join(",")
+}
+
+/// How output files are rotated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Rotation {
+    pub(crate) max_size: Option<u64>,
+    pub(crate) keep: usize,
+}
+
+/// A file receiving one line per message, moved to ``<path>.1`` and so on once it grows beyond
+/// the maximum size.
+pub(crate) struct RotatingFile {
+    path: PathBuf,
+    rotation: Rotation,
+    header: Option<String>,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    pub(crate) fn open(path: &Path, rotation: Rotation, header: Option<String>) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("could not open {}", path.display()))?;
+        let size = file.metadata()?.len();
+        let mut rotating_file = Self {
+            path: path.to_path_buf(),
+            rotation,
+            header,
+            file,
+            size,
+        };
+        if size == 0 {
+            rotating_file.write_header()?;
+        }
+
+        Ok(rotating_file)
+    }
+
+    fn rotated_path(&self, generation: usize) -> PathBuf {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(format!(".{generation}"));
+        rotated.into()
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        if let Some(header) = self.header.clone() {
+            self.write_raw(&header)?;
+        }
+        Ok(())
+    }
+
+    fn write_raw(&mut self, line: &str) -> Result<()> {
+        self.file
+            .write_all(format!("{line}\n").as_bytes())
+            .with_context(|| format!("could not write {}", self.path.display()))?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        if self.rotation.keep > 0 {
+            let _ = fs::remove_file(self.rotated_path(self.rotation.keep));
+            for generation in (1..self.rotation.keep).rev() {
+                let rotated = self.rotated_path(generation);
+                if rotated.exists() {
+                    fs::rename(&rotated, self.rotated_path(generation + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+
+        self.file = File::create(&self.path)
+            .with_context(|| format!("could not open {}", self.path.display()))?;
+        self.size = 0;
+        self.write_header()
+    }
+
+    pub(crate) fn write_line(&mut self, line: &str) -> Result<()> {
+        if self
+            .rotation
+            .max_size
+            .is_some_and(|max_size| self.size > 0 && self.size + line.len() as u64 + 1 > max_size)
+        {
+            self.rotate()?;
+        }
+        self.write_raw(line)
+    }
+}
+
+enum Destination {
+    Display {
+        header_pending: bool,
+    },
+    File(RotatingFile),
+    PerMatch {
+        directory: PathBuf,
+        rotation: Rotation,
+        /// The open match files, oldest first.
+        files: VecDeque<(String, RotatingFile)>,
+    },
+}
+
+/// Filters received messages and writes them to the display, a file, or one file per match.
+pub(crate) struct Collector {
+    filter: MessageFilter,
+    format: OutputFormat,
+    pretty_print: bool,
+    destination: Destination,
+    recorder: Option<Recorder>,
+    reporter: Option<Reporter>,
+}
+
+/// The match GUID of *message*, if it is safe to use as file name.
+pub(crate) fn match_guid(message: &Value) -> Option<String> {
+    message["DATA"]["MATCH_GUID"]
+        .as_str()
+        .filter(|guid| {
+            !guid.is_empty()
+                && guid
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .map(str::to_string)
+}
+
+impl Collector {
+    /// Creates the collector for *args*. With *tagged* servers, the ``SERVER`` field messages
+    /// are tagged with is always selected.
+    pub(crate) fn new(args: &CommandLineOptions, tagged: bool) -> Result<Self> {
+        let mut filter = MessageFilter::new(&args.types, &args.select)?;
+        if tagged
+            && !filter.fields.is_empty()
+            && !filter.fields.iter().any(|field| field.name == SERVER_FIELD)
+        {
+            filter.fields.insert(0, FieldPath::parse(SERVER_FIELD)?);
+        }
+        if args.format == OutputFormat::Csv && filter.fields.is_empty() {
+            bail!("--format csv needs --select to choose the columns");
+        }
+
+        let rotation = Rotation {
+            max_size: args.rotate_size,
+            keep: args.rotate_keep,
+        };
+        let header = (args.format == OutputFormat::Csv).then(|| filter.csv_header());
+        let destination = match &args.output {
+            None => Destination::Display {
+                header_pending: header.is_some(),
+            },
+            Some(directory) if args.per_match => {
+                fs::create_dir_all(directory)
+                    .with_context(|| format!("could not create {}", directory.display()))?;
+                Destination::PerMatch {
+                    directory: directory.to_path_buf(),
+                    rotation,
+                    files: VecDeque::new(),
+                }
+            }
+            Some(path) => Destination::File(RotatingFile::open(path, rotation, header)?),
+        };
+        let recorder = args.record.as_deref().map(Recorder::create).transpose()?;
+        let reporter = args
+            .report
+            .as_deref()
+            .map(|directory| Reporter::new(directory, &args.report_formats))
+            .transpose()?;
+
+        Ok(Self {
+            filter,
+            format: args.format,
+            pretty_print: args.pretty_print,
+            destination,
+            recorder,
+            reporter,
+        })
+    }
+
+    /// Saves the raw *message* received from *server* when recording with ``--record``.
+    pub(crate) fn record(&mut self, server: Option<&str>, message: &str) -> Result<()> {
+        match &mut self.recorder {
+            Some(recorder) => recorder.record(server, message),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds *message* to the match reports of ``--report``, regardless of ``--type``. Returns the
+    /// reports written when the match ended.
+    pub(crate) fn report(&mut self, message: &Value) -> Result<Vec<PathBuf>> {
+        match &mut self.reporter {
+            Some(reporter) => reporter.update(message),
+            None => Ok(vec![]),
+        }
+    }
+
+    pub(crate) fn accepts(&self, message: &Value) -> bool {
+        self.filter.accepts(message)
+    }
+
+    /// Collects an accepted *message*. Returns the lines to display when writing to the display.
+    pub(crate) fn collect(&mut self, message: &Value) -> Result<Option<String>> {
+        let Self {
+            filter,
+            format,
+            pretty_print,
+            destination,
+            ..
+        } = self;
+
+        match destination {
+            Destination::Display { header_pending } => {
+                let line = format_line(filter, *format, message, *pretty_print)?;
+                if *header_pending {
+                    *header_pending = false;
+                    return Ok(Some(format!("{}\n{line}", filter.csv_header())));
+                }
+                Ok(Some(line))
+            }
+            Destination::File(file) => {
+                file.write_line(&format_line(filter, *format, message, false)?)?;
+                Ok(None)
+            }
+            Destination::PerMatch {
+                directory,
+                rotation,
+                files,
+            } => {
+                let Some(guid) = match_guid(message) else {
+                    return Ok(None);
+                };
+                let index = match files.iter().position(|(open_guid, _)| *open_guid == guid) {
+                    Some(index) => index,
+                    None => {
+                        if files.len() >= MAX_OPEN_MATCH_FILES {
+                            files.pop_front();
+                        }
+                        let path = directory.join(format!("{guid}.{}", format.extension()));
+                        let header = (*format == OutputFormat::Csv).then(|| filter.csv_header());
+                        files.push_back((guid, RotatingFile::open(&path, *rotation, header)?));
+                        files.len() - 1
+                    }
+                };
+                if let Some((_, file)) = files.get_mut(index) {
+                    file.write_line(&format_line(filter, *format, message, false)?)?;
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Closes the file of the match *message* ends with ``--match``, whether the message was
+    /// accepted or not.
+    pub(crate) fn finish_match(&mut self, message: &Value) {
+        let Destination::PerMatch { files, .. } = &mut self.destination else {
+            return;
+        };
+        if message["TYPE"] != "MATCH_REPORT" {
+            return;
+        }
+        if let Some(guid) = match_guid(message) {
+            files.retain(|(open_guid, _)| *open_guid != guid);
+        }
+    }
+}
+
+fn format_line(
+    filter: &MessageFilter,
+    format: OutputFormat,
+    message: &Value,
+    pretty_print: bool,
+) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Csv => filter.csv_line(message),
+        OutputFormat::Json if pretty_print => {
+            serde_json::to_string_pretty(&filter.select(message))?
+        }
+        OutputFormat::Json => serde_json::to_string(&filter.select(message))?,
+    })
+}
+
+#[cfg(test)]
+mod collector_tests {
+    use std::{fs, path::Path};
+
+    use clap::Parser;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::{
+        Collector, Destination, FieldPath, MAX_OPEN_MATCH_FILES, MessageFilter, RotatingFile,
+        Rotation, csv_record,
+    };
+    use crate::cmd_line::CommandLineOptions;
+
+    fn kill_message() -> serde_json::Value {
+        json!({
+            "TYPE": "PLAYER_KILL",
+            "DATA": {
+                "MATCH_GUID": "asdf",
+                "KILLER": {"NAME": "player1", "STEAM_ID": "1234"},
+                "VICTIM": {"NAME": "player, \"two\"", "STEAM_ID": "5678"},
+                "ROUND": null
+            }
+        })
+    }
+
+    #[test]
+    fn filter_by_type() {
+        let filter = MessageFilter::new(
+            &["player_kill".to_string(), "MATCH_REPORT".to_string()],
+            &[],
+        )
+        .expect("this should not happen");
+
+        assert!(filter.accepts(&kill_message()));
+        assert!(!filter.accepts(&json!({"TYPE": "PLAYER_STATS", "DATA": {}})));
+        assert!(MessageFilter::default().accepts(&json!({"TYPE": "PLAYER_STATS"})));
+    }
+
+    #[test]
+    fn select_fields() {
+        let filter = MessageFilter::new(
+            &[],
+            &[
+                "TYPE".to_string(),
+                ".DATA.KILLER.NAME".to_string(),
+                ".DATA.WEAPON".to_string(),
+            ],
+        )
+        .expect("this should not happen");
+
+        assert_eq!(
+            filter.select(&kill_message()),
+            json!({"TYPE": "PLAYER_KILL", "DATA.KILLER.NAME": "player1", "DATA.WEAPON": null})
+        );
+    }
+
+    #[test]
+    fn select_array_elements() {
+        let path = FieldPath::parse(".DATA.SCORES.1").expect("this should not happen");
+
+        assert_eq!(
+            path.lookup(&json!({"DATA": {"SCORES": [3, 5]}})),
+            Some(&json!(5))
+        );
+    }
+
+    #[test]
+    fn invalid_field_paths_are_rejected() {
+        assert!(FieldPath::parse(".").is_err());
+        assert!(FieldPath::parse(".DATA..NAME").is_err());
+    }
+
+    #[test]
+    fn csv_lines_are_quoted() {
+        let filter = MessageFilter::new(
+            &[],
+            &[
+                ".DATA.KILLER.STEAM_ID".to_string(),
+                ".DATA.VICTIM.NAME".to_string(),
+                ".DATA.ROUND".to_string(),
+            ],
+        )
+        .expect("this should not happen");
+
+        assert_eq!(
+            filter.csv_header(),
+            "DATA.KILLER.STEAM_ID,DATA.VICTIM.NAME,DATA.ROUND"
+        );
+        assert_eq!(
+            filter.csv_line(&kill_message()),
+            "1234,\"player, \"\"two\"\"\","
+        );
+        assert_eq!(
+            csv_record([json!(1.5), json!(true)].into_iter()),
+            "1.5,true"
+        );
+    }
+
+    #[test]
+    fn rotate_files_beyond_max_size() {
+        let directory = tempdir().expect("this should not happen");
+        let path = directory.path().join("stats.csv");
+        let rotation = Rotation {
+            max_size: Some(20),
+            keep: 2,
+        };
+
+        let mut file = RotatingFile::open(&path, rotation, Some("header".to_string()))
+            .expect("this should not happen");
+        ["first line", "second line", "third line", "fourth line"]
+            .iter()
+            .for_each(|line| file.write_line(line).expect("this should not happen"));
+
+        let read =
+            |path: &std::path::Path| fs::read_to_string(path).expect("this should not happen");
+        assert_eq!(read(&path), "header\nfourth line\n");
+        assert_eq!(
+            read(&directory.path().join("stats.csv.1")),
+            "header\nthird line\n"
+        );
+        assert_eq!(
+            read(&directory.path().join("stats.csv.2")),
+            "header\nsecond line\n"
+        );
+        assert!(!directory.path().join("stats.csv.3").exists());
+    }
+
+    #[test]
+    fn append_to_existing_files_without_header() {
+        let directory = tempdir().expect("this should not happen");
+        let path = directory.path().join("stats.csv");
+        fs::write(&path, "header\nold line\n").expect("this should not happen");
+        let rotation = Rotation {
+            max_size: None,
+            keep: 0,
+        };
+
+        let mut file = RotatingFile::open(&path, rotation, Some("header".to_string()))
+            .expect("this should not happen");
+        file.write_line("new line").expect("this should not happen");
+
+        assert_eq!(
+            fs::read_to_string(&path).expect("this should not happen"),
+            "header\nold line\nnew line\n"
+        );
+    }
+
+    fn per_match_collector(directory: &Path, types: &str) -> Collector {
+        let args = CommandLineOptions::parse_from([
+            "ql_stats",
+            "--output",
+            directory.to_str().expect("this should not happen"),
+            "--match",
+            "--type",
+            types,
+        ]);
+        Collector::new(&args, false).expect("this should not happen")
+    }
+
+    fn open_match_files(collector: &Collector) -> Vec<String> {
+        match &collector.destination {
+            Destination::PerMatch { files, .. } => {
+                files.iter().map(|(guid, _)| guid.clone()).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    fn match_message(message_type: &str, guid: &str) -> serde_json::Value {
+        json!({"TYPE": message_type, "DATA": {"MATCH_GUID": guid}})
+    }
+
+    #[test]
+    fn match_files_are_closed_on_filtered_match_report() {
+        let directory = tempdir().expect("this should not happen");
+        let mut collector = per_match_collector(directory.path(), "PLAYER_KILL");
+
+        collector
+            .collect(&kill_message())
+            .expect("this should not happen");
+        assert_eq!(open_match_files(&collector), vec!["asdf".to_string()]);
+
+        let report = match_message("MATCH_REPORT", "asdf");
+        assert!(!collector.accepts(&report));
+        collector.finish_match(&report);
+
+        assert!(open_match_files(&collector).is_empty());
+    }
+
+    #[test]
+    fn oldest_match_files_are_closed_beyond_limit() {
+        let directory = tempdir().expect("this should not happen");
+        let mut collector = per_match_collector(directory.path(), "PLAYER_KILL");
+
+        (0..=MAX_OPEN_MATCH_FILES).for_each(|index| {
+            collector
+                .collect(&match_message("PLAYER_KILL", &format!("match{index}")))
+                .expect("this should not happen");
+        });
+
+        let open_files = open_match_files(&collector);
+        assert_eq!(open_files.len(), MAX_OPEN_MATCH_FILES);
+        assert!(!open_files.contains(&"match0".to_string()));
+
+        collector
+            .collect(&match_message("PLAYER_KILL", "match0"))
+            .expect("this should not happen");
+        assert_eq!(
+            fs::read_to_string(directory.path().join("match0.jsonl"))
+                .expect("this should not happen")
+                .lines()
+                .count(),
+            2
+        );
+    }
+}
@@ -1,19 +1,31 @@
+mod capture;
 mod cmd_line;
+mod collector;
+mod report;
+mod scoreboard;
+mod servers;
+mod tui;
 mod zmq_helper;
 
-use core::{
-    sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
-};
+use alloc::sync::Arc;
+use core::sync::atomic::AtomicBool;
 
 use anyhow::Result;
+use capture::{read_capture, run_replay};
 use clap::Parser;
 use cmd_line::CommandLineOptions;
+use collector::Collector;
+use ql_curve::generate_certificate;
+use servers::configured_servers;
 use tokio::{
-    sync::mpsc::{UnboundedReceiver, unbounded_channel},
-    try_join,
+    sync::{
+        Mutex,
+        mpsc::{UnboundedReceiver, unbounded_channel},
+    },
+    task::JoinSet,
 };
-use zmq_helper::run_zmq;
+use tui::run_tui;
+use zmq_helper::{run_capture, run_zmq};
 
 extern crate alloc;
 
@@ -23,27 +35,80 @@ pub(crate) static CONTINUE_RUNNING: AtomicBool = AtomicBool::new(true);
 async fn main() -> Result<()> {
     let args = CommandLineOptions::parse();
 
+    if let Some(certificate) = &args.curve_generate_cert {
+        let public_key = generate_certificate(certificate, env!("CARGO_PKG_NAME"))?;
+        println!("Public key: {public_key}");
+        return Ok(());
+    }
+
+    if let Some(path) = args.replay.clone() {
+        return run_replay(args, &path).await;
+    }
+
+    let capture = args.from.as_deref().map(read_capture).transpose()?;
+    let servers = match &capture {
+        Some(_) => vec![],
+        None => configured_servers(&args)?,
+    };
+    let tagged = servers.iter().any(|server| server.name.is_some())
+        || capture
+            .iter()
+            .flatten()
+            .any(|captured| captured.server.is_some());
+    let collector = Arc::new(Mutex::new(Collector::new(&args, tagged)?));
+
     let (display_sender, display_receiver) = unbounded_channel();
 
-    let cloned_args = args.clone();
+    if !args.tui && capture.is_none() {
+        display_sender.send("Ctrl-C to exit zmq-stats session".to_string())?;
+    }
+
+    let mut zmq_tasks = JoinSet::new();
+    if let Some(messages) = capture {
+        zmq_tasks.spawn(run_capture(
+            messages,
+            args.clone(),
+            collector.clone(),
+            display_sender.clone(),
+        ));
+    }
+    servers.into_iter().for_each(|server| {
+        zmq_tasks.spawn(run_zmq(
+            server,
+            args.clone(),
+            collector.clone(),
+            display_sender.clone(),
+        ));
+    });
+    drop(display_sender);
 
-    display_sender.send("Ctrl-C to exit zmq-stats session".to_string())?;
+    if args.tui {
+        let result = run_tui(display_receiver).await;
+        zmq_tasks.abort_all();
+        return result;
+    }
 
-    let zmq_task = tokio::spawn(run_zmq(cloned_args, display_sender));
     let terminal_task = tokio::spawn(receive_json(display_receiver));
-    match try_join!(zmq_task, terminal_task)? {
-        (Err(e), ..) => Err(e),
+
+    let mut last_error = None;
+    let mut any_succeeded = false;
+    while let Some(result) = zmq_tasks.join_next().await {
+        match result? {
+            Ok(()) => any_succeeded = true,
+            Err(e) => last_error = Some(e),
+        }
+    }
+    terminal_task.await??;
+
+    match last_error {
+        Some(e) if !any_succeeded => Err(e),
         _ => Ok(()),
     }
 }
 
 async fn receive_json(mut receiver: UnboundedReceiver<String>) -> Result<()> {
-    while CONTINUE_RUNNING.load(Ordering::Acquire) && !receiver.is_closed() {
-        while let Ok(line) = receiver.try_recv() {
-            println!("{line}");
-        }
-
-        tokio::time::sleep(Duration::from_millis(100)).await;
+    while let Some(line) = receiver.recv().await {
+        println!("{line}");
     }
 
     Ok(())
@@ -0,0 +1,430 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use ql_stats_schema::{MatchReport, RoundOver, StatsMessage};
+use serde_json::Value;
+
+use crate::{
+    cmd_line::ReportFormat,
+    collector::{SERVER_FIELD, match_guid},
+    scoreboard::{PlayerLine, Scoreboard, TEAM_BLUE, TEAM_RED, TEAM_SPECTATOR, team_name},
+};
+
+/// Removes the color codes like ``^1`` QuakeLive uses in names and titles.
+fn strip_colors(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '^' && chars.peek().is_some_and(char::is_ascii_digit) {
+            chars.next();
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+fn format_length(seconds: i64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A table of the report.
+struct Section {
+    title: &'static str,
+    header: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+/// Everything that happened in a match, collected until its ``MATCH_REPORT`` arrives.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MatchSummary {
+    scoreboard: Scoreboard,
+    rounds: Vec<RoundOver>,
+    report: Option<MatchReport>,
+}
+
+impl MatchSummary {
+    pub(crate) fn update(&mut self, message: StatsMessage) {
+        match &message {
+            StatsMessage::MatchStarted(_) => {
+                self.rounds.clear();
+                self.report = None;
+            }
+            StatsMessage::RoundOver(round) if !round.warmup => self.rounds.push(round.clone()),
+            StatsMessage::MatchReport(report) => self.report = Some(report.clone()),
+            _ => (),
+        }
+        self.scoreboard.update(message);
+    }
+
+    fn players(&self) -> Vec<&PlayerLine> {
+        self.scoreboard
+            .ranked_players()
+            .into_iter()
+            .filter(|player| player.team != TEAM_SPECTATOR)
+            .collect()
+    }
+
+    fn is_team_game(&self) -> bool {
+        self.players()
+            .iter()
+            .any(|player| player.team == TEAM_RED || player.team == TEAM_BLUE)
+    }
+
+    fn title(&self) -> String {
+        match &self.report {
+            Some(report) => format!("{} on {}", strip_colors(&report.factory_title), report.map),
+            None => format!("{} on {}", self.scoreboard.game_type, self.scoreboard.map),
+        }
+    }
+
+    fn result(&self, report: &MatchReport) -> String {
+        if report.aborted {
+            return "aborted".to_string();
+        }
+        if self.is_team_game() {
+            return format!("red {} : {} blue", report.tscore0, report.tscore1);
+        }
+        self.players()
+            .first()
+            .map(|winner| format!("{} wins with {}", strip_colors(&winner.name), winner.score))
+            .unwrap_or_default()
+    }
+
+    fn facts(&self) -> Vec<(&'static str, String)> {
+        let Some(report) = &self.report else {
+            return vec![];
+        };
+
+        vec![
+            ("Server", strip_colors(&report.server_title)),
+            ("Game type", report.game_type.to_owned()),
+            ("Match", report.match_guid.to_owned()),
+            ("Length", format_length(report.game_length)),
+            ("Result", self.result(report)),
+            ("Ended", report.exit_msg.to_owned()),
+        ]
+    }
+
+    fn sections(&self) -> Vec<Section> {
+        let players = self.players();
+        let scores = players
+            .iter()
+            .map(|player| {
+                vec![
+                    strip_colors(&player.name),
+                    team_name(player.team).to_string(),
+                    player.score.to_string(),
+                    player.kills.to_string(),
+                    player.deaths.to_string(),
+                    format!("{:.2}", player.kill_death_ratio()),
+                    player.damage_dealt.to_string(),
+                    player.damage_taken.to_string(),
+                ]
+            })
+            .collect();
+        let weapons = players
+            .iter()
+            .flat_map(|player| {
+                player
+                    .weapons
+                    .iter()
+                    .filter(|(_, stats)| stats.shots > 0 || stats.damage_given > 0)
+                    .map(|(weapon, stats)| {
+                        vec![
+                            strip_colors(&player.name),
+                            weapon.to_lowercase(),
+                            format!("{:.1}%", stats.accuracy() * 100.0),
+                            stats.hits.to_string(),
+                            stats.shots.to_string(),
+                            stats.kills.to_string(),
+                            stats.damage_given.to_string(),
+                        ]
+                    })
+            })
+            .collect();
+        let medals = players
+            .iter()
+            .map(|player| vec![strip_colors(&player.name), player.medal_summary()])
+            .filter(|row| !row[1].is_empty())
+            .collect();
+        let rounds = self
+            .rounds
+            .iter()
+            .map(|round| {
+                vec![
+                    round.round.to_string(),
+                    round.team_won.to_lowercase(),
+                    format_length(round.time),
+                ]
+            })
+            .collect();
+
+        vec![
+            Section {
+                title: "Scores",
+                header: &[
+                    "Player", "Team", "Score", "Kills", "Deaths", "K/D", "Damage", "Taken",
+                ],
+                rows: scores,
+            },
+            Section {
+                title: "Weapons",
+                header: &[
+                    "Player", "Weapon", "Accuracy", "Hits", "Shots", "Kills", "Damage",
+                ],
+                rows: weapons,
+            },
+            Section {
+                title: "Medals",
+                header: &["Player", "Medals"],
+                rows: medals,
+            },
+            Section {
+                title: "Rounds",
+                header: &["Round", "Winner", "Time"],
+                rows: rounds,
+            },
+        ]
+        .into_iter()
+        .filter(|section| !section.rows.is_empty())
+        .collect()
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n", escape_markdown(&self.title()));
+        self.facts().iter().for_each(|(name, value)| {
+            markdown.push_str(&format!("* **{name}:** {}\n", escape_markdown(value)));
+        });
+
+        self.sections().iter().for_each(|section| {
+            markdown.push_str(&format!("\n## {}\n\n", section.title));
+            markdown.push_str(&format!("| {} |\n", section.header.join(" | ")));
+            markdown.push_str(&format!("|{}\n", " --- |".repeat(section.header.len())));
+            section.rows.iter().for_each(|row| {
+                let cells = row
+                    .iter()
+                    .map(|cell| escape_markdown(cell))
+                    .collect::<Vec<_>>();
+                markdown.push_str(&format!("| {} |\n", cells.join(" | ")));
+            });
+        });
+
+        markdown
+    }
+
+    fn to_html(&self) -> String {
+        let title = escape_html(&self.title());
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #999; \
+             padding: 2px 8px; }}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+
+        html.push_str("<ul>\n");
+        self.facts().iter().for_each(|(name, value)| {
+            html.push_str(&format!("<li><b>{name}:</b> {}</li>\n", escape_html(value)));
+        });
+        html.push_str("</ul>\n");
+
+        self.sections().iter().for_each(|section| {
+            html.push_str(&format!("<h2>{}</h2>\n<table>\n<tr>", section.title));
+            section.header.iter().for_each(|column| {
+                html.push_str(&format!("<th>{column}</th>"));
+            });
+            html.push_str("</tr>\n");
+            section.rows.iter().for_each(|row| {
+                html.push_str("<tr>");
+                row.iter().for_each(|cell| {
+                    html.push_str(&format!("<td>{}</td>", escape_html(cell)));
+                });
+                html.push_str("</tr>\n");
+            });
+            html.push_str("</table>\n");
+        });
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    pub(crate) fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+}
+
+/// Writes a report for every match that ends into a directory.
+pub(crate) struct Reporter {
+    directory: PathBuf,
+    formats: Vec<ReportFormat>,
+    matches: HashMap<String, MatchSummary>,
+}
+
+impl Reporter {
+    pub(crate) fn new(directory: &Path, formats: &[ReportFormat]) -> Result<Self> {
+        fs::create_dir_all(directory)
+            .with_context(|| format!("could not create {}", directory.display()))?;
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            formats: formats.to_vec(),
+            matches: HashMap::new(),
+        })
+    }
+
+    /// Adds *message* to the match of its server. Returns the reports written when the match
+    /// ended with it.
+    pub(crate) fn update(&mut self, message: &Value) -> Result<Vec<PathBuf>> {
+        let Ok(stats_message) = StatsMessage::from_value_ref(message) else {
+            return Ok(vec![]);
+        };
+        let server = message[SERVER_FIELD]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let summary = self.matches.entry(server.to_owned()).or_default();
+        summary.update(stats_message);
+        if summary.report.is_none() {
+            return Ok(vec![]);
+        }
+
+        let Some(summary) = self.matches.remove(&server) else {
+            return Ok(vec![]);
+        };
+        let name = match_guid(message).unwrap_or_else(|| "match".to_string());
+        self.formats
+            .iter()
+            .map(|format| {
+                let path = self
+                    .directory
+                    .join(format!("{name}.{}", format.extension()));
+                fs::write(&path, summary.render(*format))
+                    .with_context(|| format!("could not write {}", path.display()))?;
+                Ok(path)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod report_tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::Value;
+    use tempfile::tempdir;
+
+    use super::{MatchSummary, Reporter, strip_colors};
+    use crate::cmd_line::ReportFormat;
+
+    fn player_stats(name: &str, steam_id: &str, team: i64, score: i64) -> String {
+        format!(
+            r#"{{"TYPE": "PLAYER_STATS", "DATA": {{"ABORTED": false, "BLUE_FLAG_PICKUPS": 0, "DAMAGE": {{"DEALT": 2500, "TAKEN": 1800}}, "DEATHS": 4, "HOLY_SHITS": 0, "KILLS": 9, "LOSE": 0, "MATCH_GUID": "asdf", "MAX_STREAK": 3, "MEDALS": {{"EXCELLENT": 2, "IMPRESSIVE": 0}}, "MODEL": "sarge", "NAME": "{name}", "NEUTRAL_FLAG_PICKUPS": 0, "PICKUPS": {{}}, "PLAY_TIME": 600, "QUIT": 0, "RANK": 1, "RED_FLAG_PICKUPS": 0, "SCORE": {score}, "STEAM_ID": "{steam_id}", "TEAM": {team}, "TEAM_JOIN_TIME": 0, "TEAM_RANK": 1, "TIED_RANK": 0, "TIED_TEAM_RANK": 0, "WARMUP": false, "WEAPONS": {{"ROCKET": {{"D": 3, "DG": 1500, "DR": 900, "H": 20, "K": 6, "P": 4, "S": 40, "T": 300}}}}, "WIN": 1}}}}"#
+        )
+    }
+
+    fn match_report(tscore0: i64, tscore1: i64) -> String {
+        format!(
+            r#"{{"TYPE": "MATCH_REPORT", "DATA": {{"ABORTED": false, "CAPTURE_LIMIT": 8, "EXIT_MSG": "Roundlimit hit.", "FACTORY": "ca", "FACTORY_TITLE": "Clan Arena", "FIRST_SCORER": "player1", "FRAG_LIMIT": 50, "GAME_LENGTH": 754, "GAME_TYPE": "CA", "INFECTED": 0, "INSTAGIB": 0, "LAST_LEAD_CHANGE_TIME": 0, "LAST_SCORER": "player2", "LAST_TEAMSCORER": "none", "MAP": "campgrounds", "MATCH_GUID": "asdf", "MERCY_LIMIT": 0, "QUADHOG": 0, "RESTARTED": 0, "ROUND_LIMIT": 10, "SCORE_LIMIT": 150, "SERVER_TITLE": "^1my ^7server", "TIME_LIMIT": 0, "TRAINING": 0, "TSCORE0": {tscore0}, "TSCORE1": {tscore1}}}}}"#
+        )
+    }
+
+    fn summary(messages: &[String]) -> MatchSummary {
+        let mut summary = MatchSummary::default();
+        messages.iter().for_each(|message| {
+            summary.update(message.parse().expect("this should not happen"));
+        });
+        summary
+    }
+
+    #[test]
+    fn color_codes_are_stripped() {
+        assert_eq!(strip_colors("^1my ^7server^"), "my server^");
+    }
+
+    #[test]
+    fn markdown_report_of_team_match() {
+        let summary = summary(&[
+            r#"{"TYPE": "ROUND_OVER", "DATA": {"MATCH_GUID": "asdf", "ROUND": 1, "TEAM_WON": "RED", "TIME": 65, "WARMUP": false}}"#.to_string(),
+            player_stats("player|1", "1234", 1, 30),
+            player_stats("player2", "5678", 2, 20),
+            match_report(10, 7),
+        ]);
+
+        let markdown = summary.render(ReportFormat::Markdown);
+
+        assert!(markdown.starts_with("# Clan Arena on campgrounds\n"));
+        assert!(markdown.contains("* **Server:** my server\n"));
+        assert!(markdown.contains("* **Length:** 12:34\n"));
+        assert!(markdown.contains("* **Result:** red 10 : 7 blue\n"));
+        assert!(markdown.contains("| player\\|1 | red | 30 | 9 | 4 | 2.25 | 2500 | 1800 |\n"));
+        assert!(markdown.contains("| player2 | rocket | 50.0% | 20 | 40 | 6 | 1500 |\n"));
+        assert!(markdown.contains("| player2 | excellent 2 |\n"));
+        assert!(markdown.contains(
+            "## Rounds\n\n| Round | Winner | Time |\n| --- | --- | --- |\n| 1 | red | 1:05 |\n"
+        ));
+    }
+
+    #[test]
+    fn html_report_escapes_names() {
+        let summary = summary(&[
+            player_stats("<b>player</b>", "1234", 0, 30),
+            match_report(0, 0),
+        ]);
+
+        let html = summary.render(ReportFormat::Html);
+
+        assert!(html.contains("<td>&lt;b&gt;player&lt;/b&gt;</td>"));
+        assert!(html.contains("<li><b>Result:</b> &lt;b&gt;player&lt;/b&gt; wins with 30</li>"));
+        assert!(!html.contains("<h2>Rounds</h2>"));
+    }
+
+    #[test]
+    fn reports_are_written_when_match_ends() {
+        let directory = tempdir().expect("this should not happen");
+        let mut reporter = Reporter::new(
+            directory.path(),
+            &[ReportFormat::Markdown, ReportFormat::Html],
+        )
+        .expect("this should not happen");
+
+        let written = reporter
+            .update(
+                &serde_json::from_str::<Value>(&player_stats("player1", "1234", 1, 30))
+                    .expect("this should not happen"),
+            )
+            .expect("this should not happen");
+        assert!(written.is_empty());
+
+        let written = reporter
+            .update(
+                &serde_json::from_str::<Value>(&match_report(10, 7))
+                    .expect("this should not happen"),
+            )
+            .expect("this should not happen");
+        assert_eq!(
+            written,
+            vec![
+                directory.path().join("asdf.md"),
+                directory.path().join("asdf.html")
+            ]
+        );
+        assert!(written.iter().all(|path| path.exists()));
+    }
+}
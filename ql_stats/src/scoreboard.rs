@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use ql_stats_schema::{PlayerDeath, StatsMessage, WeaponStats};
+
+/// How many kills the kill feed keeps.
+const KILL_FEED_SIZE: usize = 10;
+/// How many round results are kept.
+const ROUND_RESULTS_SIZE: usize = 10;
+
+pub(crate) const TEAM_FREE: i64 = 0;
+pub(crate) const TEAM_RED: i64 = 1;
+pub(crate) const TEAM_BLUE: i64 = 2;
+pub(crate) const TEAM_SPECTATOR: i64 = 3;
+
+fn team_from_name(team: &str) -> i64 {
+    match team.to_uppercase().as_str() {
+        "FREE" => TEAM_FREE,
+        "RED" => TEAM_RED,
+        "BLUE" => TEAM_BLUE,
+        _ => TEAM_SPECTATOR,
+    }
+}
+
+pub(crate) fn team_name(team: i64) -> &'static str {
+    match team {
+        TEAM_FREE => "free",
+        TEAM_RED => "red",
+        TEAM_BLUE => "blue",
+        _ => "spec",
+    }
+}
+
+/// The abbreviation QuakeLive players know a weapon by.
+pub(crate) fn weapon_short_name(weapon: &str) -> &str {
+    match weapon {
+        "GAUNTLET" => "GT",
+        "MACHINEGUN" => "MG",
+        "SHOTGUN" => "SG",
+        "GRENADE" => "GL",
+        "ROCKET" => "RL",
+        "LIGHTNING" => "LG",
+        "RAILGUN" => "RG",
+        "PLASMA" => "PG",
+        "BFG" => "BFG",
+        "NAILGUN" => "NG",
+        "PROXMINE" => "PM",
+        "CHAINGUN" => "CG",
+        "HMG" => "HMG",
+        other => other,
+    }
+}
+
+/// A player's line on the scoreboard.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct PlayerLine {
+    pub(crate) name: String,
+    pub(crate) team: i64,
+    pub(crate) score: i64,
+    pub(crate) kills: i64,
+    pub(crate) deaths: i64,
+    pub(crate) damage_dealt: i64,
+    pub(crate) damage_taken: i64,
+    pub(crate) weapons: BTreeMap<String, WeaponStats>,
+    pub(crate) medals: BTreeMap<String, i64>,
+}
+
+impl PlayerLine {
+    pub(crate) fn kill_death_ratio(&self) -> f64 {
+        self.kills as f64 / self.deaths.max(1) as f64
+    }
+
+    /// The accuracy of every weapon the player fired, e.g. ``RL 45% RG 52%``.
+    pub(crate) fn accuracies(&self) -> String {
+        self.weapons
+            .iter()
+            .filter(|(_, stats)| stats.shots > 0)
+            .map(|(weapon, stats)| {
+                format!(
+                    "{} {:.0}%",
+                    weapon_short_name(weapon),
+                    stats.accuracy() * 100.0
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The medals the player earned, e.g. ``excellent 3 impressive 1``.
+    pub(crate) fn medal_summary(&self) -> String {
+        self.medals
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(medal, count)| format!("{} {count}", medal.to_lowercase()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// The state of the current match, built from the stats messages received.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Scoreboard {
+    pub(crate) server_title: String,
+    pub(crate) game_type: String,
+    pub(crate) map: String,
+    pub(crate) match_guid: String,
+    pub(crate) players: BTreeMap<i64, PlayerLine>,
+    pub(crate) red_rounds: i64,
+    pub(crate) blue_rounds: i64,
+    pub(crate) kill_feed: VecDeque<String>,
+    pub(crate) round_results: VecDeque<String>,
+    pub(crate) result: Option<String>,
+}
+
+fn push_limited(entries: &mut VecDeque<String>, entry: String, limit: usize) {
+    entries.push_front(entry);
+    entries.truncate(limit);
+}
+
+fn kill_feed_entry(death: &PlayerDeath) -> String {
+    match &death.killer {
+        Some(killer) if !death.suicide && killer.steam_id != death.victim.steam_id => format!(
+            "{} [{}] {}{}",
+            killer.name,
+            weapon_short_name(&killer.weapon),
+            death.victim.name,
+            if death.teamkill { " (teamkill)" } else { "" }
+        ),
+        _ => format!(
+            "{} died ({})",
+            death.victim.name,
+            death.means_of_death.to_lowercase()
+        ),
+    }
+}
+
+impl Scoreboard {
+    fn player(&mut self, steam_id: i64, name: &str) -> &mut PlayerLine {
+        let player = self.players.entry(steam_id).or_insert_with(|| PlayerLine {
+            team: TEAM_SPECTATOR,
+            ..PlayerLine::default()
+        });
+        name.clone_into(&mut player.name);
+        player
+    }
+
+    /// Updates the scoreboard with a received message. Kills and deaths count live, while
+    /// ``PLAYER_STATS`` replaces a player's line with the stats reported by the server.
+    pub(crate) fn update(&mut self, message: StatsMessage) {
+        match message {
+            StatsMessage::MatchStarted(started) => {
+                *self = Self {
+                    server_title: started.server_title,
+                    game_type: started.game_type,
+                    map: started.map,
+                    match_guid: started.match_guid,
+                    ..Self::default()
+                };
+                started.players.iter().for_each(|summary| {
+                    self.player(summary.steam_id, &summary.name).team = summary.team;
+                });
+            }
+            StatsMessage::MatchReport(report) => {
+                self.result = Some(if report.aborted {
+                    "match aborted".to_string()
+                } else if report.tscore0 != 0 || report.tscore1 != 0 {
+                    format!("red {} : {} blue", report.tscore0, report.tscore1)
+                } else {
+                    report.exit_msg
+                });
+            }
+            StatsMessage::RoundOver(round) if !round.warmup => {
+                match team_from_name(&round.team_won) {
+                    TEAM_RED => self.red_rounds += 1,
+                    TEAM_BLUE => self.blue_rounds += 1,
+                    _ => (),
+                }
+                push_limited(
+                    &mut self.round_results,
+                    format!(
+                        "round {}: {} won after {}s",
+                        round.round,
+                        round.team_won.to_lowercase(),
+                        round.time
+                    ),
+                    ROUND_RESULTS_SIZE,
+                );
+            }
+            StatsMessage::PlayerKill(kill) if !kill.warmup && !kill.suicide => {
+                if let Some(killer) = &kill.killer {
+                    let player = self.player(killer.steam_id, &killer.name);
+                    player.team = killer.team;
+                    player.kills += 1;
+                }
+            }
+            StatsMessage::PlayerDeath(death) if !death.warmup => {
+                push_limited(&mut self.kill_feed, kill_feed_entry(&death), KILL_FEED_SIZE);
+                let victim = self.player(death.victim.steam_id, &death.victim.name);
+                victim.team = death.victim.team;
+                victim.deaths += 1;
+            }
+            StatsMessage::PlayerMedal(medal) if !medal.warmup => {
+                self.player(medal.steam_id, &medal.name)
+                    .medals
+                    .insert(medal.medal, medal.total);
+            }
+            StatsMessage::PlayerStats(stats) if !stats.warmup => {
+                let player = self.player(stats.steam_id, &stats.name);
+                player.team = stats.team;
+                player.score = stats.score;
+                player.kills = stats.kills;
+                player.deaths = stats.deaths;
+                player.damage_dealt = stats.damage.dealt;
+                player.damage_taken = stats.damage.taken;
+                player.weapons = stats.weapons;
+                player.medals = stats.medals;
+            }
+            StatsMessage::PlayerConnect(connection) => {
+                self.player(connection.steam_id, &connection.name);
+            }
+            StatsMessage::PlayerSwitchTeam(switch) => {
+                self.player(switch.player.steam_id, &switch.player.name)
+                    .team = team_from_name(&switch.player.team);
+            }
+            _ => (),
+        }
+    }
+
+    /// The players ordered by team, and by score and kills within their team.
+    pub(crate) fn ranked_players(&self) -> Vec<&PlayerLine> {
+        let mut players = self.players.values().collect::<Vec<_>>();
+        players.sort_by(|left, right| {
+            left.team
+                .cmp(&right.team)
+                .then(right.score.cmp(&left.score))
+                .then(right.kills.cmp(&left.kills))
+                .then(left.name.cmp(&right.name))
+        });
+        players
+    }
+}
+
+#[cfg(test)]
+mod scoreboard_tests {
+    use pretty_assertions::assert_eq;
+    use ql_stats_schema::StatsMessage;
+
+    use super::{Scoreboard, TEAM_BLUE, TEAM_RED};
+
+    fn message(json: &str) -> StatsMessage {
+        json.parse::<StatsMessage>()
+            .expect("this should not happen")
+    }
+
+    fn participant(name: &str, steam_id: &str, team: i64, weapon: &str) -> String {
+        format!(
+            r#"{{"AIRBORNE": false, "AMMO": 5, "ARMOR": 0, "BOT": false, "BOT_SKILL": null, "HEALTH": 100, "HOLDABLE": null, "NAME": "{name}", "POSITION": {{"x": 0.0, "y": 0.0, "z": 0.0}}, "POWERUPS": null, "SPEED": 0, "STEAM_ID": "{steam_id}", "SUBMERGED": false, "TEAM": {team}, "VIEW": {{"x": 0.0, "y": 0.0, "z": 0.0}}, "WEAPON": "{weapon}"}}"#
+        )
+    }
+
+    fn kill(message_type: &str) -> StatsMessage {
+        message(&format!(
+            r#"{{"TYPE": "{message_type}", "DATA": {{"KILLER": {}, "VICTIM": {}, "MATCH_GUID": "asdf", "MOD": "ROCKET", "OTHER_TEAM_ALIVE": 3, "OTHER_TEAM_DEAD": 1, "ROUND": 2, "SUICIDE": false, "TEAMKILL": false, "TEAM_ALIVE": 4, "TEAM_DEAD": 0, "TIME": 42, "WARMUP": false}}}}"#,
+            participant("player1", "1234", 1, "ROCKET"),
+            participant("player2", "5678", 2, "RAILGUN"),
+        ))
+    }
+
+    #[test]
+    fn kills_update_scoreboard_and_kill_feed() {
+        let mut scoreboard = Scoreboard::default();
+
+        scoreboard.update(kill("PLAYER_KILL"));
+        scoreboard.update(kill("PLAYER_DEATH"));
+
+        assert_eq!(scoreboard.players[&1234].kills, 1);
+        assert_eq!(scoreboard.players[&1234].team, TEAM_RED);
+        assert_eq!(scoreboard.players[&5678].deaths, 1);
+        assert_eq!(scoreboard.players[&5678].team, TEAM_BLUE);
+        assert_eq!(
+            scoreboard.kill_feed.front().map(String::as_str),
+            Some("player1 [RL] player2")
+        );
+    }
+
+    #[test]
+    fn round_results_are_counted() {
+        let mut scoreboard = Scoreboard::default();
+
+        scoreboard.update(message(
+            r#"{"TYPE": "ROUND_OVER", "DATA": {"MATCH_GUID": "asdf", "ROUND": 3, "TEAM_WON": "BLUE", "TIME": 65, "WARMUP": false}}"#,
+        ));
+        scoreboard.update(message(
+            r#"{"TYPE": "ROUND_OVER", "DATA": {"MATCH_GUID": "asdf", "ROUND": 4, "TEAM_WON": "RED", "TIME": 40, "WARMUP": true}}"#,
+        ));
+
+        assert_eq!((scoreboard.red_rounds, scoreboard.blue_rounds), (0, 1));
+        assert_eq!(
+            scoreboard.round_results.front().map(String::as_str),
+            Some("round 3: blue won after 65s")
+        );
+    }
+
+    #[test]
+    fn player_stats_replace_live_counts() {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.update(kill("PLAYER_KILL"));
+
+        scoreboard.update(message(
+            r#"{"TYPE": "PLAYER_STATS", "DATA": {"ABORTED": false, "BLUE_FLAG_PICKUPS": 0, "DAMAGE": {"DEALT": 2500, "TAKEN": 1800}, "DEATHS": 4, "HOLY_SHITS": 0, "KILLS": 9, "LOSE": 0, "MATCH_GUID": "asdf", "MAX_STREAK": 3, "MEDALS": {"EXCELLENT": 2, "IMPRESSIVE": 0}, "MODEL": "sarge", "NAME": "player1", "NEUTRAL_FLAG_PICKUPS": 0, "PICKUPS": {}, "PLAY_TIME": 600, "QUIT": 0, "RANK": 1, "RED_FLAG_PICKUPS": 0, "SCORE": 30, "STEAM_ID": "1234", "TEAM": 1, "TEAM_JOIN_TIME": 0, "TEAM_RANK": 1, "TIED_RANK": 0, "TIED_TEAM_RANK": 0, "WARMUP": false, "WEAPONS": {"ROCKET": {"D": 3, "DG": 1500, "DR": 900, "H": 20, "K": 6, "P": 4, "S": 40, "T": 300}, "RAILGUN": {"D": 1, "DG": 1000, "DR": 900, "H": 0, "K": 0, "P": 1, "S": 0, "T": 10}}, "WIN": 1}}"#,
+        ));
+
+        let player = &scoreboard.players[&1234];
+        assert_eq!((player.score, player.kills, player.deaths), (30, 9, 4));
+        assert_eq!(player.damage_dealt, 2500);
+        assert_eq!(player.accuracies(), "RL 50%");
+        assert_eq!(player.medal_summary(), "excellent 2");
+    }
+
+    #[test]
+    fn match_start_resets_scoreboard() {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.update(kill("PLAYER_DEATH"));
+
+        scoreboard.update(message(
+            r#"{"TYPE": "MATCH_STARTED", "DATA": {"CAPTURE_LIMIT": 8, "FACTORY": "ca", "FACTORY_TITLE": "Clan Arena", "FRAG_LIMIT": 50, "GAME_TYPE": "CA", "INFECTED": 0, "INSTAGIB": 0, "MAP": "campgrounds", "MATCH_GUID": "qwertz", "MERCY_LIMIT": 0, "PLAYERS": [{"NAME": "player3", "STEAM_ID": "9012", "TEAM": 2}], "QUADHOG": 0, "ROUND_LIMIT": 10, "SCORE_LIMIT": 150, "SERVER_TITLE": "my server", "TIME_LIMIT": 0, "TRAINING": 0}}"#,
+        ));
+
+        assert_eq!(scoreboard.map, "campgrounds");
+        assert!(scoreboard.kill_feed.is_empty());
+        assert_eq!(
+            scoreboard
+                .ranked_players()
+                .iter()
+                .map(|player| (player.name.as_str(), player.team))
+                .collect::<Vec<_>>(),
+            vec![("player3", TEAM_BLUE)]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+use ql_curve::read_server_key;
+use serde_json::Value;
+
+use crate::cmd_line::CommandLineOptions;
+
+/// A server to subscribe to. Servers without a name are not tagged in the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ServerConfig {
+    pub(crate) name: Option<String>,
+    pub(crate) address: String,
+    pub(crate) password: Option<String>,
+    /// The Z85-encoded public key to subscribe with CURVE instead of PLAIN.
+    pub(crate) curve_server_key: Option<String>,
+}
+
+/// Parses a server given as ``NAME=URI`` or ``NAME=URI=PASSWORD`` on the command line.
+pub(crate) fn parse_server(value: &str) -> Result<ServerConfig, String> {
+    let mut parts = value.splitn(3, '=');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(name), Some(address), password) if !name.is_empty() && !address.is_empty() => {
+            Ok(ServerConfig {
+                name: Some(name.to_string()),
+                address: address.to_string(),
+                password: password.map(str::to_string),
+                curve_server_key: None,
+            })
+        }
+        _ => Err(format!(
+            "expected NAME=URI or NAME=URI=PASSWORD, got '{value}'"
+        )),
+    }
+}
+
+fn server_from_json(entry: &Value) -> Result<ServerConfig> {
+    let field = |key: &str| entry.get(key).and_then(Value::as_str).map(str::to_string);
+    let (Some(name), Some(address)) = (field("name"), field("address")) else {
+        bail!("every server needs a name and an address, got {entry}");
+    };
+
+    Ok(ServerConfig {
+        name: Some(name),
+        address,
+        password: field("password"),
+        curve_server_key: field("curve_server_key"),
+    })
+}
+
+/// Reads the servers from a JSON file holding a list of objects with ``name``, ``address``, and
+/// an optional ``password`` and ``curve_server_key``.
+pub(crate) fn load_servers_file(path: &Path) -> Result<Vec<ServerConfig>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    let servers = serde_json::from_str::<Value>(&contents)
+        .with_context(|| format!("could not parse {}", path.display()))?;
+    let Some(entries) = servers.as_array() else {
+        bail!("{} does not hold a list of servers", path.display());
+    };
+
+    entries.iter().map(server_from_json).collect()
+}
+
+/// The servers to subscribe to: the ones from ``--server`` and ``--servers-file``, or
+/// ``--host`` if neither is given. Servers without their own password use ``--password``, and
+/// servers without their own CURVE server key use ``--curve-server-key``.
+pub(crate) fn configured_servers(args: &CommandLineOptions) -> Result<Vec<ServerConfig>> {
+    let mut servers = args.servers.clone();
+    if let Some(path) = &args.servers_file {
+        servers.extend(load_servers_file(path)?);
+    }
+
+    if servers.is_empty() {
+        return Ok(vec![ServerConfig {
+            name: None,
+            address: args.host.to_owned(),
+            password: Some(args.password.to_owned()),
+            curve_server_key: args
+                .curve_server_key
+                .as_deref()
+                .map(read_server_key)
+                .transpose()?,
+        }]);
+    }
+
+    let mut names = HashSet::new();
+    if let Some(duplicate) = servers
+        .iter()
+        .filter_map(|server| server.name.as_deref())
+        .find(|name| !names.insert(*name))
+    {
+        bail!("the server name '{duplicate}' is used more than once");
+    }
+
+    servers
+        .into_iter()
+        .map(|server| {
+            Ok(ServerConfig {
+                password: server.password.or_else(|| Some(args.password.to_owned())),
+                curve_server_key: server
+                    .curve_server_key
+                    .or_else(|| args.curve_server_key.to_owned())
+                    .as_deref()
+                    .map(read_server_key)
+                    .transpose()?,
+                ..server
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod servers_tests {
+    use std::fs;
+
+    use clap::Parser;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::{ServerConfig, configured_servers, load_servers_file, parse_server};
+    use crate::cmd_line::CommandLineOptions;
+
+    #[test]
+    fn parse_server_from_command_line() {
+        assert_eq!(
+            parse_server("ca1=tcp://127.0.0.1:27961"),
+            Ok(ServerConfig {
+                name: Some("ca1".to_string()),
+                address: "tcp://127.0.0.1:27961".to_string(),
+                password: None,
+                curve_server_key: None,
+            })
+        );
+        assert_eq!(
+            parse_server("ca2=tcp://127.0.0.1:27962=pass=word").map(|server| server.password),
+            Ok(Some("pass=word".to_string()))
+        );
+        assert!(parse_server("tcp://127.0.0.1:27961").is_err());
+        assert!(parse_server("=tcp://127.0.0.1:27961").is_err());
+    }
+
+    #[test]
+    fn load_servers_from_file() {
+        let directory = tempdir().expect("this should not happen");
+        let path = directory.path().join("servers.json");
+        fs::write(
+            &path,
+            r#"[{"name": "ffa", "address": "tcp://10.0.0.1:27961", "password": "secret"},
+                {"name": "duel", "address": "tcp://10.0.0.2:27961"}]"#,
+        )
+        .expect("this should not happen");
+
+        let servers = load_servers_file(&path).expect("this should not happen");
+
+        assert_eq!(
+            servers
+                .iter()
+                .map(|server| (server.name.as_deref(), server.password.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(Some("ffa"), Some("secret")), (Some("duel"), None)]
+        );
+    }
+
+    #[test]
+    fn curve_server_keys_fall_back_to_command_line() {
+        let server_key = "rq:rM>}U?@Lns47E1%kR.o@n%FcmmsL/@{H8]yf7";
+        let directory = tempdir().expect("this should not happen");
+        let path = directory.path().join("servers.json");
+        fs::write(
+            &path,
+            format!(
+                r#"[{{"name": "ffa", "address": "tcp://10.0.0.1:27961", "curve_server_key": "{}"}},
+                    {{"name": "duel", "address": "tcp://10.0.0.2:27961"}}]"#,
+                "Yne@$w-vo<fVvi]a<NY6T1ed:M$fCG*[IaLV{hID"
+            ),
+        )
+        .expect("this should not happen");
+        let args = CommandLineOptions::parse_from([
+            "ql_stats",
+            "--servers-file",
+            path.to_str().expect("this should not happen"),
+            "--curve-server-key",
+            server_key,
+        ]);
+
+        assert_eq!(
+            configured_servers(&args)
+                .expect("this should not happen")
+                .iter()
+                .map(|server| server.curve_server_key.as_deref())
+                .collect::<Vec<_>>(),
+            vec![
+                Some("Yne@$w-vo<fVvi]a<NY6T1ed:M$fCG*[IaLV{hID"),
+                Some(server_key)
+            ]
+        );
+    }
+
+    #[test]
+    fn servers_file_with_missing_address() {
+        let directory = tempdir().expect("this should not happen");
+        let path = directory.path().join("servers.json");
+        fs::write(&path, r#"[{"name": "ffa"}]"#).expect("this should not happen");
+
+        assert!(load_servers_file(&path).is_err());
+    }
+
+    #[test]
+    fn servers_fall_back_to_host_and_password() {
+        let args = CommandLineOptions::parse_from(["ql_stats", "--password", "secret"]);
+
+        assert_eq!(
+            configured_servers(&args).expect("this should not happen"),
+            vec![ServerConfig {
+                name: None,
+                address: "tcp://127.0.0.1:27961".to_string(),
+                password: Some("secret".to_string()),
+                curve_server_key: None,
+            }]
+        );
+
+        let args = CommandLineOptions::parse_from([
+            "ql_stats",
+            "--password",
+            "secret",
+            "--server",
+            "ca=tcp://10.0.0.1:27961",
+            "--server",
+            "duel=tcp://10.0.0.2:27961=other",
+        ]);
+
+        assert_eq!(
+            configured_servers(&args)
+                .expect("this should not happen")
+                .iter()
+                .map(|server| server.password.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("secret"), Some("other")]
+        );
+    }
+
+    #[test]
+    fn server_names_have_to_be_unique() {
+        let args = CommandLineOptions::parse_from([
+            "ql_stats",
+            "--server",
+            "ca=tcp://10.0.0.1:27961",
+            "--server",
+            "ca=tcp://10.0.0.2:27961",
+        ]);
+
+        assert!(configured_servers(&args).is_err());
+    }
+}
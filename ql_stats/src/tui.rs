@@ -0,0 +1,241 @@
+use core::{sync::atomic::Ordering, time::Duration};
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use ql_stats_schema::StatsMessage;
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, List, Paragraph, Row, Table, Tabs},
+};
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::{
+    CONTINUE_RUNNING,
+    collector::SERVER_FIELD,
+    scoreboard::{Scoreboard, TEAM_BLUE, TEAM_RED, team_name},
+};
+
+fn team_style(team: i64) -> Style {
+    match team {
+        TEAM_RED => Style::default().fg(Color::Red),
+        TEAM_BLUE => Style::default().fg(Color::Blue),
+        _ => Style::default(),
+    }
+}
+
+fn render_header(frame: &mut Frame, area: Rect, scoreboard: &Scoreboard) {
+    let mut header = if scoreboard.map.is_empty() {
+        "waiting for the next match to start".to_string()
+    } else {
+        format!(
+            "{} | {} on {}",
+            scoreboard.server_title, scoreboard.game_type, scoreboard.map
+        )
+    };
+    if scoreboard.red_rounds > 0 || scoreboard.blue_rounds > 0 {
+        header.push_str(&format!(
+            " | rounds: red {} : {} blue",
+            scoreboard.red_rounds, scoreboard.blue_rounds
+        ));
+    }
+    if let Some(result) = &scoreboard.result {
+        header.push_str(&format!(" | final: {result}"));
+    }
+
+    frame.render_widget(
+        Paragraph::new(header).style(Style::default().add_modifier(Modifier::BOLD)),
+        area,
+    );
+}
+
+fn render_scores(frame: &mut Frame, area: Rect, scoreboard: &Scoreboard) {
+    let header = Row::new([
+        "Name", "Team", "Score", "K", "D", "K/D", "Dmg", "Taken", "Accuracy", "Medals",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let rows = scoreboard.ranked_players().into_iter().map(|player| {
+        Row::new([
+            player.name.to_owned(),
+            team_name(player.team).to_string(),
+            player.score.to_string(),
+            player.kills.to_string(),
+            player.deaths.to_string(),
+            format!("{:.2}", player.kill_death_ratio()),
+            player.damage_dealt.to_string(),
+            player.damage_taken.to_string(),
+            player.accuracies(),
+            player.medal_summary(),
+        ])
+        .style(team_style(player.team))
+    });
+    let widths = [
+        Constraint::Length(20),
+        Constraint::Length(5),
+        Constraint::Length(6),
+        Constraint::Length(4),
+        Constraint::Length(4),
+        Constraint::Length(5),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Fill(2),
+        Constraint::Fill(1),
+    ];
+
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title("Scoreboard")),
+        area,
+    );
+}
+
+fn render_servers(frame: &mut Frame, area: Rect, servers: &[&str], selected: usize) {
+    frame.render_widget(
+        Tabs::new(servers.iter().copied())
+            .select(selected)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        area,
+    );
+}
+
+fn render(
+    frame: &mut Frame,
+    servers: &[&str],
+    selected: usize,
+    scoreboard: &Scoreboard,
+    status: &str,
+) {
+    let server_tabs = u16::from(servers.iter().any(|server| !server.is_empty()));
+    let [
+        servers_area,
+        header_area,
+        scores_area,
+        feed_area,
+        status_area,
+    ] = Layout::vertical([
+        Constraint::Length(server_tabs),
+        Constraint::Length(1),
+        Constraint::Min(5),
+        Constraint::Length(12),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [kills_area, rounds_area] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .areas(feed_area);
+
+    if server_tabs > 0 {
+        render_servers(frame, servers_area, servers, selected);
+    }
+    render_header(frame, header_area, scoreboard);
+    render_scores(frame, scores_area, scoreboard);
+    frame.render_widget(
+        List::new(scoreboard.kill_feed.iter().map(String::as_str))
+            .block(Block::bordered().title("Kills")),
+        kills_area,
+    );
+    frame.render_widget(
+        List::new(scoreboard.round_results.iter().map(String::as_str))
+            .block(Block::bordered().title("Rounds")),
+        rounds_area,
+    );
+    frame.render_widget(
+        Line::from(format!("{status} | tab to switch servers, q to exit"))
+            .style(Style::default().fg(Color::DarkGray)),
+        status_area,
+    );
+}
+
+enum Input {
+    Quit,
+    NextServer,
+    PreviousServer,
+}
+
+fn read_input() -> Result<Option<Input>> {
+    while event::poll(Duration::ZERO)? {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Some(Input::Quit)),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(Some(Input::Quit));
+            }
+            KeyCode::Tab | KeyCode::Right => return Ok(Some(Input::NextServer)),
+            KeyCode::BackTab | KeyCode::Left => return Ok(Some(Input::PreviousServer)),
+            _ => (),
+        }
+    }
+
+    Ok(None)
+}
+
+async fn tui_loop(
+    terminal: &mut DefaultTerminal,
+    receiver: &mut UnboundedReceiver<String>,
+) -> Result<String> {
+    let mut scoreboards = BTreeMap::<String, Scoreboard>::new();
+    let mut selected = 0;
+    let no_match = Scoreboard::default();
+    let mut status = String::new();
+
+    while CONTINUE_RUNNING.load(Ordering::Acquire) && !receiver.is_closed() {
+        while let Ok(line) = receiver.try_recv() {
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                status = line;
+                continue;
+            };
+            let server = message[SERVER_FIELD]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            match StatsMessage::from_value(message) {
+                Ok(message) => scoreboards.entry(server).or_default().update(message),
+                Err(err) => status = format!("schema violation: {err}"),
+            }
+        }
+
+        let servers = scoreboards.keys().map(String::as_str).collect::<Vec<_>>();
+        let selected_server = servers.get(selected).copied().unwrap_or_default();
+        let scoreboard = scoreboards.get(selected_server).unwrap_or(&no_match);
+        terminal.draw(|frame| render(frame, &servers, selected, scoreboard, &status))?;
+
+        match read_input()? {
+            Some(Input::Quit) => CONTINUE_RUNNING.store(false, Ordering::Release),
+            Some(Input::NextServer) if !servers.is_empty() => {
+                selected = (selected + 1) % servers.len();
+            }
+            Some(Input::PreviousServer) if !servers.is_empty() => {
+                selected = (selected + servers.len() - 1) % servers.len();
+            }
+            _ => (),
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    Ok(status)
+}
+
+/// Renders the messages received from ``run_zmq`` as live scoreboard until the user exits.
+pub(crate) async fn run_tui(mut receiver: UnboundedReceiver<String>) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = tui_loop(&mut terminal, &mut receiver).await;
+    ratatui::restore();
+
+    let last_status = result?;
+    if !last_status.is_empty() {
+        println!("{last_status}");
+    }
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use alloc::sync::Arc;
 use core::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::Result;
@@ -8,17 +9,27 @@ use arzmq::{
     },
     security::SecurityMechanism,
 };
+use ql_curve::{CurveKeys, read_client_keys};
+use ql_stats_schema::StatsMessage;
 use serde_json::Value;
 use tokio::{
     select,
-    sync::{RwLock, mpsc::UnboundedSender},
+    sync::{Mutex, RwLock, mpsc::UnboundedSender},
 };
 
-use crate::{CONTINUE_RUNNING, cmd_line::CommandLineOptions};
+use crate::{
+    CONTINUE_RUNNING,
+    capture::CapturedMessage,
+    cmd_line::CommandLineOptions,
+    collector::{Collector, SERVER_FIELD},
+    servers::ServerConfig,
+};
 
 struct MonitoredSubscriber {
     subscriber: RwLock<SubscribeSocket>,
     monitor: RwLock<MonitorSocket>,
+    first_time: AtomicBool,
+    running: AtomicBool,
 }
 
 unsafe impl Send for MonitoredSubscriber {}
@@ -46,15 +57,20 @@ impl MonitoredSubscriber {
         Ok(Self {
             subscriber: subscriber.into(),
             monitor: monitor.into(),
+            first_time: AtomicBool::new(true),
+            running: AtomicBool::new(true),
         })
     }
 
-    async fn configure(&self, password: &str) -> Result<()> {
+    async fn configure(&self, password: &str, curve: Option<&CurveKeys>) -> Result<()> {
         let socket_builder = SocketBuilder::default()
-            .security_mechanism(SecurityMechanism::Plain {
-                username: "stats".into(),
-                password: password.into(),
-            })
+            .security_mechanism(curve.map_or_else(
+                || SecurityMechanism::Plain {
+                    username: "stats".into(),
+                    password: password.into(),
+                },
+                CurveKeys::security_mechanism,
+            ))
             .receive_timeout(0)
             .receive_highwater_mark(0)
             .send_timeout(0)
@@ -97,29 +113,70 @@ impl MonitoredSubscriber {
     }
 }
 
-fn format_ql_json(msg: &str, args: &CommandLineOptions) -> String {
-    serde_json::from_str::<Value>(msg)
-        .and_then(|parsed_json| {
-            if args.pretty_print {
-                serde_json::to_string_pretty(&parsed_json)
-            } else {
-                serde_json::to_string(&parsed_json)
-            }
-        })
-        .unwrap_or(msg.to_string())
+/// Sends the output of a single server to the display, tagged with the server's name.
+struct ServerOutput {
+    name: Option<String>,
+    sender: UnboundedSender<String>,
+}
+
+impl ServerOutput {
+    fn status(&self, status: &str) -> Result<()> {
+        match &self.name {
+            Some(name) => self.sender.send(format!("[{name}] {status}"))?,
+            None => self.sender.send(status.to_string())?,
+        }
+        Ok(())
+    }
 }
 
-static FIRST_TIME: AtomicBool = AtomicBool::new(true);
+async fn handle_stats_message(
+    msg: &str,
+    collector: &Mutex<Collector>,
+    args: &CommandLineOptions,
+    output: &ServerOutput,
+) -> Result<()> {
+    let mut collector = collector.lock().await;
+    collector.record(output.name.as_deref(), msg)?;
+
+    let Ok(mut message) = serde_json::from_str::<Value>(msg) else {
+        return output.status(msg);
+    };
+
+    if let (Some(name), Some(fields)) = (&output.name, message.as_object_mut()) {
+        fields.insert(SERVER_FIELD.to_string(), Value::from(name.as_str()));
+    }
+
+    for path in collector.report(&message)? {
+        output.status(&format!("Match report saved to {}.", path.display()))?;
+    }
+
+    let accepted = collector.accepts(&message);
+    if accepted && let Some(line) = collector.collect(&message)? {
+        output.sender.send(line)?;
+    }
+    collector.finish_match(&message);
+    if !accepted {
+        return Ok(());
+    }
+
+    if args.validate
+        && let Err(err) = StatsMessage::from_value(message)
+    {
+        output.status(&format!("schema violation: {err}"))?;
+    }
+
+    Ok(())
+}
 
 async fn check_monitor(
     monitored_dealer: &MonitoredSubscriber,
-    sender: &UnboundedSender<String>,
+    output: &ServerOutput,
     endpoint: &str,
 ) -> Result<()> {
     match monitored_dealer.check_monitor().await {
         Some(MonitorSocketEvent::HandshakeSucceeded) => {
-            FIRST_TIME.store(true, Ordering::Release);
-            sender.send(format!("ZMQ connected to {}.", &endpoint))?;
+            monitored_dealer.first_time.store(true, Ordering::Release);
+            output.status(&format!("ZMQ connected to {}.", endpoint))?;
         }
 
         Some(
@@ -128,17 +185,17 @@ async fn check_monitor(
             | MonitorSocketEvent::HandshakeFailedNoDetail(_)
             | MonitorSocketEvent::MonitorStopped),
         ) => {
-            sender.send(format!("ZMQ socket error: {event:?}"))?;
-            CONTINUE_RUNNING.store(false, Ordering::Release);
+            output.status(&format!("ZMQ socket error: {event:?}"))?;
+            monitored_dealer.running.store(false, Ordering::Release);
         }
 
         Some(MonitorSocketEvent::Disconnected | MonitorSocketEvent::Closed) => {
-            if FIRST_TIME.load(Ordering::Acquire) {
-                FIRST_TIME.store(false, Ordering::Release);
-                sender.send("Reconnecting ZMQ...".to_string())?;
+            if monitored_dealer.first_time.load(Ordering::Acquire) {
+                monitored_dealer.first_time.store(false, Ordering::Release);
+                output.status("Reconnecting ZMQ...")?;
             }
             if let Err(e) = monitored_dealer.connect(endpoint).await {
-                sender.send(format!("error reconnecting: {e:?}."))?;
+                output.status(&format!("error reconnecting: {e:?}."))?;
             }
         }
 
@@ -149,7 +206,7 @@ async fn check_monitor(
         ) => (),
 
         Some(event) => {
-            sender.send(format!("ZMQ socket error: {event:?}",))?;
+            output.status(&format!("ZMQ socket error: {event:?}",))?;
         }
 
         _ => (),
@@ -158,27 +215,49 @@ async fn check_monitor(
     Ok(())
 }
 
-pub(crate) async fn run_zmq(
-    args: CommandLineOptions,
-    display_sender: UnboundedSender<String>,
+async fn subscribe(
+    server: &ServerConfig,
+    args: &CommandLineOptions,
+    collector: &Mutex<Collector>,
+    output: &ServerOutput,
 ) -> Result<()> {
-    display_sender.send(format!("ZMQ connecting to {}...", &args.host))?;
+    output.status(&format!("ZMQ connecting to {}...", server.address))?;
+
+    let curve = server
+        .curve_server_key
+        .as_ref()
+        .map(|server_key| {
+            let (public_key, secret_key) = read_client_keys(args.curve_cert.as_deref())?;
+            Ok::<_, anyhow::Error>(CurveKeys {
+                server_key: server_key.to_owned(),
+                public_key,
+                secret_key,
+            })
+        })
+        .transpose()?;
 
     let monitored_dealer = MonitoredSubscriber::new()?;
-    monitored_dealer.configure(&args.password).await?;
-
-    monitored_dealer.connect(&args.host).await?;
-
-    while CONTINUE_RUNNING.load(Ordering::Acquire) {
+    monitored_dealer
+        .configure(
+            server.password.as_deref().unwrap_or_default(),
+            curve.as_ref(),
+        )
+        .await?;
+
+    monitored_dealer.connect(&server.address).await?;
+
+    while CONTINUE_RUNNING.load(Ordering::Acquire)
+        && monitored_dealer.running.load(Ordering::Acquire)
+    {
         select!(
             biased;
 
             Some(zmq_msg) = monitored_dealer.recv_msg() => {
                 let zmq_str = zmq_msg.to_string();
-                display_sender.send(format_ql_json(&zmq_str, &args))?;
+                handle_stats_message(&zmq_str, collector, args, output).await?;
             }
 
-            Ok(()) = check_monitor(&monitored_dealer, &display_sender, &args.host) => (),
+            Ok(()) = check_monitor(&monitored_dealer, output, &server.address) => (),
 
             else => ()
         );
@@ -186,7 +265,45 @@ pub(crate) async fn run_zmq(
 
     monitored_dealer.disconnect().await?;
 
-    drop(display_sender);
+    Ok(())
+}
+
+/// Subscribes to the stats of a single *server* until it fails or the user exits. Errors are
+/// reported on the display, so they do not affect the other servers.
+pub(crate) async fn run_zmq(
+    server: ServerConfig,
+    args: CommandLineOptions,
+    collector: Arc<Mutex<Collector>>,
+    display_sender: UnboundedSender<String>,
+) -> Result<()> {
+    let output = ServerOutput {
+        name: server.name.clone(),
+        sender: display_sender,
+    };
+
+    let result = subscribe(&server, &args, &collector, &output).await;
+    if let Err(e) = &result {
+        output.status(&format!("error: {e:?}"))?;
+    }
+
+    result
+}
+
+/// Handles the messages of a file saved with ``--record`` as if they were received from their
+/// servers.
+pub(crate) async fn run_capture(
+    messages: Vec<CapturedMessage>,
+    args: CommandLineOptions,
+    collector: Arc<Mutex<Collector>>,
+    display_sender: UnboundedSender<String>,
+) -> Result<()> {
+    for captured in messages {
+        let output = ServerOutput {
+            name: captured.server,
+            sender: display_sender.clone(),
+        };
+        handle_stats_message(&captured.message, &collector, &args, &output).await?;
+    }
 
     Ok(())
 }
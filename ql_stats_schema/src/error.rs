@@ -0,0 +1,44 @@
+use core::fmt;
+
+/// Errors that can occur while parsing a stats message.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The message is not valid JSON.
+    Json(serde_json::Error),
+    /// The message has no `TYPE` field.
+    MissingType,
+    /// The message has a `TYPE` this schema does not know about.
+    UnknownType(String),
+    /// The `DATA` of the message does not match the schema of its `TYPE`.
+    InvalidData {
+        /// The `TYPE` of the malformed message.
+        message_type: &'static str,
+        /// The underlying deserialization error, naming the offending field.
+        source: serde_json::Error,
+    },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "stats message is not valid json: {err}"),
+            Self::MissingType => write!(f, "stats message has no TYPE"),
+            Self::UnknownType(message_type) => {
+                write!(f, "unknown stats message TYPE: {message_type}")
+            }
+            Self::InvalidData {
+                message_type,
+                source,
+            } => write!(f, "malformed {message_type} DATA: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(err) | Self::InvalidData { source: err, .. } => Some(err),
+            Self::MissingType | Self::UnknownType(_) => None,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Typed schema for the JSON messages QuakeLive publishes on its ZMQ stats socket.
+//!
+//! Every message is an object with a `TYPE` and a `DATA` field. [`StatsMessage`] parses such a
+//! message into the matching typed payload, reporting which message type and which field were
+//! malformed when the payload does not match the schema.
+//!
+//! With the `python` feature enabled, all payload types are also exposed as frozen Python
+//! classes.
+
+mod error;
+mod messages;
+mod steam_id;
+
+pub use error::SchemaError;
+pub use messages::{
+    Coordinates, DamageStats, KillParticipant, MatchReport, MatchStarted, PlayerConnection,
+    PlayerDeath, PlayerMedal, PlayerStatsReport, PlayerSummary, RoundOver, StatsMessage,
+    TeamSwitch, TeamSwitchPlayer, WeaponStats,
+};
@@ -0,0 +1,773 @@
+use core::str::FromStr;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::error::SchemaError;
+
+/// A player as listed in the `PLAYERS` of a `MATCH_STARTED` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "PlayerSummary",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct PlayerSummary {
+    /// The player's name.
+    pub name: String,
+    /// The player's steam id.
+    #[serde(with = "crate::steam_id")]
+    pub steam_id: i64,
+    /// The player's team.
+    pub team: i64,
+}
+
+/// Payload of a `MATCH_STARTED` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "MatchStarted",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct MatchStarted {
+    /// The capture limit of the match.
+    pub capture_limit: i64,
+    /// The short name of the factory, i.e. `ca`.
+    pub factory: String,
+    /// The title of the factory, i.e. `Clan Arena`.
+    pub factory_title: String,
+    /// The frag limit of the match.
+    pub frag_limit: i64,
+    /// The game type, i.e. `CA`.
+    pub game_type: String,
+    /// Whether infection is enabled.
+    pub infected: i64,
+    /// Whether instagib is enabled.
+    pub instagib: i64,
+    /// The map the match is played on.
+    pub map: String,
+    /// The unique id of the match.
+    pub match_guid: String,
+    /// The mercy limit of the match.
+    pub mercy_limit: i64,
+    /// The players on the server when the match started.
+    pub players: Vec<PlayerSummary>,
+    /// Whether quad hog is enabled.
+    pub quadhog: i64,
+    /// The round limit of the match.
+    pub round_limit: i64,
+    /// The score limit of the match.
+    pub score_limit: i64,
+    /// The title of the server.
+    pub server_title: String,
+    /// The time limit of the match.
+    pub time_limit: i64,
+    /// Whether this is a training match.
+    pub training: i64,
+}
+
+/// Payload of a `MATCH_REPORT` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "MatchReport",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct MatchReport {
+    /// Whether the match was aborted.
+    pub aborted: bool,
+    /// The capture limit of the match.
+    pub capture_limit: i64,
+    /// The reason the match ended.
+    pub exit_msg: String,
+    /// The short name of the factory, i.e. `ca`.
+    pub factory: String,
+    /// The title of the factory, i.e. `Clan Arena`.
+    pub factory_title: String,
+    /// The name of the player that scored first.
+    pub first_scorer: String,
+    /// The frag limit of the match.
+    pub frag_limit: i64,
+    /// The length of the match in seconds.
+    pub game_length: i64,
+    /// The game type, i.e. `CA`.
+    pub game_type: String,
+    /// Whether infection is enabled.
+    pub infected: i64,
+    /// Whether instagib is enabled.
+    pub instagib: i64,
+    /// The time of the last lead change.
+    pub last_lead_change_time: i64,
+    /// The name of the player that scored last.
+    pub last_scorer: String,
+    /// The team that scored last.
+    pub last_teamscorer: String,
+    /// The map the match was played on.
+    pub map: String,
+    /// The unique id of the match.
+    pub match_guid: String,
+    /// The mercy limit of the match.
+    pub mercy_limit: i64,
+    /// Whether quad hog is enabled.
+    pub quadhog: i64,
+    /// Whether the match was restarted.
+    pub restarted: i64,
+    /// The round limit of the match.
+    pub round_limit: i64,
+    /// The score limit of the match.
+    pub score_limit: i64,
+    /// The title of the server.
+    pub server_title: String,
+    /// The time limit of the match.
+    pub time_limit: i64,
+    /// Whether this was a training match.
+    pub training: i64,
+    /// The final score of the red team.
+    pub tscore0: i64,
+    /// The final score of the blue team.
+    pub tscore1: i64,
+}
+
+/// Payload of a `ROUND_OVER` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "RoundOver",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct RoundOver {
+    /// The unique id of the match.
+    pub match_guid: String,
+    /// The number of the round that ended.
+    pub round: i64,
+    /// The team that won the round, i.e. `RED`, `BLUE` or `DRAW`.
+    pub team_won: String,
+    /// The time the round ended.
+    pub time: i64,
+    /// Whether the round was played during warmup.
+    pub warmup: bool,
+}
+
+/// A position or view angle of a player.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "Coordinates",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coordinates {
+    /// The x coordinate.
+    pub x: f64,
+    /// The y coordinate.
+    pub y: f64,
+    /// The z coordinate.
+    pub z: f64,
+}
+
+/// The state of the killer or the victim in a `PLAYER_KILL` or `PLAYER_DEATH` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "KillParticipant",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct KillParticipant {
+    /// Whether the player was in the air.
+    pub airborne: bool,
+    /// The ammo left for the held weapon.
+    pub ammo: i64,
+    /// The player's armor.
+    pub armor: i64,
+    /// Whether the player is a bot.
+    pub bot: bool,
+    /// The skill of the bot, if the player is one.
+    pub bot_skill: Option<i64>,
+    /// The player's health.
+    pub health: i64,
+    /// The holdable item the player carried, if any.
+    pub holdable: Option<String>,
+    /// The player's name.
+    pub name: String,
+    /// The player's position.
+    pub position: Coordinates,
+    /// The powerups the player had, if any.
+    pub powerups: Option<Vec<String>>,
+    /// The player's speed.
+    pub speed: f64,
+    /// The player's steam id.
+    #[serde(with = "crate::steam_id")]
+    pub steam_id: i64,
+    /// Whether the player was under water.
+    pub submerged: bool,
+    /// The player's team.
+    pub team: i64,
+    /// The player's view angles.
+    pub view: Coordinates,
+    /// The weapon the player held.
+    pub weapon: String,
+}
+
+/// Payload of a `PLAYER_KILL` or `PLAYER_DEATH` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "PlayerDeath",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct PlayerDeath {
+    /// The killer, or `None` if the victim died from an environmental hazard.
+    pub killer: Option<KillParticipant>,
+    /// The player that died.
+    pub victim: KillParticipant,
+    /// The unique id of the match.
+    pub match_guid: String,
+    /// The means of death, i.e. `ROCKET_SPLASH`.
+    #[serde(rename = "MOD")]
+    pub means_of_death: String,
+    /// The number of players alive on the other team.
+    pub other_team_alive: i64,
+    /// The number of dead players on the other team.
+    pub other_team_dead: i64,
+    /// The current round.
+    pub round: i64,
+    /// Whether the victim killed themselves.
+    pub suicide: bool,
+    /// Whether the victim was killed by a teammate.
+    pub teamkill: bool,
+    /// The number of players alive on the victim's team.
+    pub team_alive: i64,
+    /// The number of dead players on the victim's team.
+    pub team_dead: i64,
+    /// The time of the death.
+    pub time: i64,
+    /// Whether the death happened during warmup.
+    pub warmup: bool,
+}
+
+/// Payload of a `PLAYER_MEDAL` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "PlayerMedal",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct PlayerMedal {
+    /// The unique id of the match.
+    pub match_guid: String,
+    /// The medal that was earned, i.e. `EXCELLENT`.
+    pub medal: String,
+    /// The player's name.
+    pub name: String,
+    /// The player's steam id.
+    #[serde(with = "crate::steam_id")]
+    pub steam_id: i64,
+    /// The time the medal was earned.
+    pub time: i64,
+    /// How many medals of this kind the player earned in the match so far.
+    pub total: i64,
+    /// Whether the medal was earned during warmup.
+    pub warmup: bool,
+}
+
+/// Payload of a `PLAYER_CONNECT` or `PLAYER_DISCONNECT` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "PlayerConnection",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct PlayerConnection {
+    /// The unique id of the match.
+    pub match_guid: String,
+    /// The player's name.
+    pub name: String,
+    /// The player's steam id.
+    #[serde(with = "crate::steam_id")]
+    pub steam_id: i64,
+    /// The time the player connected or disconnected.
+    pub time: i64,
+    /// Whether this happened during warmup.
+    pub warmup: bool,
+}
+
+/// The player in a `PLAYER_SWITCHTEAM` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "TeamSwitchPlayer",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct TeamSwitchPlayer {
+    /// The player's name.
+    pub name: String,
+    /// The team the player left.
+    pub old_team: String,
+    /// The player's steam id.
+    #[serde(with = "crate::steam_id")]
+    pub steam_id: i64,
+    /// The team the player joined.
+    pub team: String,
+}
+
+/// Payload of a `PLAYER_SWITCHTEAM` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "TeamSwitch",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct TeamSwitch {
+    /// The player that switched teams. QuakeLive sends it as `KILLER`.
+    #[serde(rename = "KILLER")]
+    pub player: TeamSwitchPlayer,
+    /// The unique id of the match.
+    pub match_guid: String,
+    /// The time of the team switch.
+    pub time: i64,
+    /// Whether the switch happened during warmup.
+    pub warmup: bool,
+}
+
+/// The damage a player dealt and took in a match.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "DamageStats",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct DamageStats {
+    /// The damage dealt.
+    pub dealt: i64,
+    /// The damage taken.
+    pub taken: i64,
+}
+
+/// The stats of a player with a single weapon.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "WeaponStats",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeaponStats {
+    /// How often the player died while holding the weapon.
+    #[serde(rename = "D")]
+    pub deaths: i64,
+    /// The damage given with the weapon.
+    #[serde(rename = "DG")]
+    pub damage_given: i64,
+    /// The damage received from the weapon.
+    #[serde(rename = "DR")]
+    pub damage_received: i64,
+    /// The number of shots that hit.
+    #[serde(rename = "H")]
+    pub hits: i64,
+    /// The number of kills with the weapon.
+    #[serde(rename = "K")]
+    pub kills: i64,
+    /// How often the weapon was picked up.
+    #[serde(rename = "P")]
+    pub pickups: i64,
+    /// The number of shots fired.
+    #[serde(rename = "S")]
+    pub shots: i64,
+    /// How long the weapon was held.
+    #[serde(rename = "T")]
+    pub time: i64,
+}
+
+#[cfg_attr(feature = "python", pymethods)]
+impl WeaponStats {
+    /// The share of shots that hit, between 0 and 1.
+    pub fn accuracy(&self) -> f64 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.shots as f64
+        }
+    }
+}
+
+/// Payload of a `PLAYER_STATS` message.
+#[cfg_attr(
+    feature = "python",
+    pyclass(
+        module = "_stats",
+        name = "PlayerStatsReport",
+        frozen,
+        eq,
+        get_all,
+        skip_from_py_object
+    )
+)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct PlayerStatsReport {
+    /// Whether the match was aborted.
+    pub aborted: bool,
+    /// The number of blue flags the player picked up.
+    pub blue_flag_pickups: i64,
+    /// The damage the player dealt and took.
+    pub damage: DamageStats,
+    /// The player's number of deaths.
+    pub deaths: i64,
+    /// The player's number of holy shits.
+    pub holy_shits: i64,
+    /// The player's number of kills.
+    pub kills: i64,
+    /// Whether the player lost the match.
+    pub lose: i64,
+    /// The unique id of the match.
+    pub match_guid: String,
+    /// The player's longest killing streak.
+    pub max_streak: i64,
+    /// The medals the player earned, by medal.
+    pub medals: BTreeMap<String, i64>,
+    /// The player's model.
+    pub model: String,
+    /// The player's name.
+    pub name: String,
+    /// The number of neutral flags the player picked up.
+    pub neutral_flag_pickups: i64,
+    /// The items the player picked up, by item.
+    pub pickups: BTreeMap<String, i64>,
+    /// The time the player played in seconds.
+    pub play_time: i64,
+    /// Whether the player quit the match.
+    pub quit: i64,
+    /// The player's rank.
+    pub rank: i64,
+    /// The number of red flags the player picked up.
+    pub red_flag_pickups: i64,
+    /// The player's score.
+    pub score: i64,
+    /// The player's steam id.
+    #[serde(with = "crate::steam_id")]
+    pub steam_id: i64,
+    /// The player's team.
+    pub team: i64,
+    /// The time the player joined the team.
+    pub team_join_time: i64,
+    /// The player's rank within the team.
+    pub team_rank: i64,
+    /// The player's tied rank.
+    pub tied_rank: i64,
+    /// The player's tied rank within the team.
+    pub tied_team_rank: i64,
+    /// Whether the stats are from warmup.
+    pub warmup: bool,
+    /// The player's stats per weapon.
+    pub weapons: BTreeMap<String, WeaponStats>,
+    /// Whether the player won the match.
+    pub win: i64,
+}
+
+/// A message received from the QuakeLive stats socket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "TYPE", content = "DATA")]
+pub enum StatsMessage {
+    /// A match started.
+    #[serde(rename = "MATCH_STARTED")]
+    MatchStarted(MatchStarted),
+    /// A match ended.
+    #[serde(rename = "MATCH_REPORT")]
+    MatchReport(MatchReport),
+    /// A round ended.
+    #[serde(rename = "ROUND_OVER")]
+    RoundOver(RoundOver),
+    /// A player was killed by another player.
+    #[serde(rename = "PLAYER_KILL")]
+    PlayerKill(PlayerDeath),
+    /// A player died.
+    #[serde(rename = "PLAYER_DEATH")]
+    PlayerDeath(PlayerDeath),
+    /// A player earned a medal.
+    #[serde(rename = "PLAYER_MEDAL")]
+    PlayerMedal(PlayerMedal),
+    /// The stats of a player were reported.
+    #[serde(rename = "PLAYER_STATS")]
+    PlayerStats(PlayerStatsReport),
+    /// A player connected.
+    #[serde(rename = "PLAYER_CONNECT")]
+    PlayerConnect(PlayerConnection),
+    /// A player disconnected.
+    #[serde(rename = "PLAYER_DISCONNECT")]
+    PlayerDisconnect(PlayerConnection),
+    /// A player switched teams.
+    #[serde(rename = "PLAYER_SWITCHTEAM")]
+    PlayerSwitchTeam(TeamSwitch),
+}
+
+fn parse_data<T: DeserializeOwned>(
+    message_type: &'static str,
+    data: &Value,
+) -> Result<T, SchemaError> {
+    T::deserialize(data).map_err(|source| SchemaError::InvalidData {
+        message_type,
+        source,
+    })
+}
+
+impl StatsMessage {
+    /// Parses an already decoded json message.
+    pub fn from_value(value: Value) -> Result<Self, SchemaError> {
+        Self::from_value_ref(&value)
+    }
+
+    /// Parses an already decoded json message, leaving it to the caller for further use.
+    pub fn from_value_ref(value: &Value) -> Result<Self, SchemaError> {
+        let Some(message_type) = value.get("TYPE").and_then(Value::as_str) else {
+            return Err(SchemaError::MissingType);
+        };
+        let data = value.get("DATA").unwrap_or(&Value::Null);
+
+        match message_type {
+            "MATCH_STARTED" => parse_data("MATCH_STARTED", data).map(Self::MatchStarted),
+            "MATCH_REPORT" => parse_data("MATCH_REPORT", data).map(Self::MatchReport),
+            "ROUND_OVER" => parse_data("ROUND_OVER", data).map(Self::RoundOver),
+            "PLAYER_KILL" => parse_data("PLAYER_KILL", data).map(Self::PlayerKill),
+            "PLAYER_DEATH" => parse_data("PLAYER_DEATH", data).map(Self::PlayerDeath),
+            "PLAYER_MEDAL" => parse_data("PLAYER_MEDAL", data).map(Self::PlayerMedal),
+            "PLAYER_STATS" => parse_data("PLAYER_STATS", data).map(Self::PlayerStats),
+            "PLAYER_CONNECT" => parse_data("PLAYER_CONNECT", data).map(Self::PlayerConnect),
+            "PLAYER_DISCONNECT" => {
+                parse_data("PLAYER_DISCONNECT", data).map(Self::PlayerDisconnect)
+            }
+            "PLAYER_SWITCHTEAM" => {
+                parse_data("PLAYER_SWITCHTEAM", data).map(Self::PlayerSwitchTeam)
+            }
+            _ => Err(SchemaError::UnknownType(message_type.to_string())),
+        }
+    }
+
+    /// The `TYPE` of the message as sent by QuakeLive.
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            Self::MatchStarted(_) => "MATCH_STARTED",
+            Self::MatchReport(_) => "MATCH_REPORT",
+            Self::RoundOver(_) => "ROUND_OVER",
+            Self::PlayerKill(_) => "PLAYER_KILL",
+            Self::PlayerDeath(_) => "PLAYER_DEATH",
+            Self::PlayerMedal(_) => "PLAYER_MEDAL",
+            Self::PlayerStats(_) => "PLAYER_STATS",
+            Self::PlayerConnect(_) => "PLAYER_CONNECT",
+            Self::PlayerDisconnect(_) => "PLAYER_DISCONNECT",
+            Self::PlayerSwitchTeam(_) => "PLAYER_SWITCHTEAM",
+        }
+    }
+
+    /// The unique id of the match the message belongs to.
+    pub fn match_guid(&self) -> &str {
+        match self {
+            Self::MatchStarted(data) => &data.match_guid,
+            Self::MatchReport(data) => &data.match_guid,
+            Self::RoundOver(data) => &data.match_guid,
+            Self::PlayerKill(data) | Self::PlayerDeath(data) => &data.match_guid,
+            Self::PlayerMedal(data) => &data.match_guid,
+            Self::PlayerStats(data) => &data.match_guid,
+            Self::PlayerConnect(data) | Self::PlayerDisconnect(data) => &data.match_guid,
+            Self::PlayerSwitchTeam(data) => &data.match_guid,
+        }
+    }
+}
+
+impl FromStr for StatsMessage {
+    type Err = SchemaError;
+
+    fn from_str(msg: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(msg)
+            .map_err(SchemaError::Json)
+            .and_then(Self::from_value)
+    }
+}
+
+#[cfg(test)]
+mod stats_message_tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{StatsMessage, WeaponStats};
+    use crate::SchemaError;
+
+    const PLAYER_STATS: &str = r#"{"DATA": {"ABORTED": false, "BLUE_FLAG_PICKUPS": 0, "DAMAGE": {"DEALT": 1500, "TAKEN": 900}, "DEATHS": 2, "HOLY_SHITS": 0, "KILLS": 5, "LOSE": 0, "MATCH_GUID": "asdf", "MAX_STREAK": 3, "MEDALS": {"EXCELLENT": 1}, "MODEL": "sarge", "NAME": "player1", "NEUTRAL_FLAG_PICKUPS": 0, "PICKUPS": {"MEGA_HEALTH": 2}, "PLAY_TIME": 590, "QUIT": 0, "RANK": 1, "RED_FLAG_PICKUPS": 0, "SCORE": 42, "STEAM_ID": "76561198000000000", "TEAM": 1, "TEAM_JOIN_TIME": 0, "TEAM_RANK": 1, "TIED_RANK": 0, "TIED_TEAM_RANK": 0, "WARMUP": false, "WEAPONS": {"ROCKET": {"D": 1, "DG": 800, "DR": 300, "H": 8, "K": 3, "P": 2, "S": 20, "T": 120}}, "WIN": 1}, "TYPE": "PLAYER_STATS"}"#;
+
+    #[test]
+    fn parses_player_stats_with_weapon_breakdown() {
+        let Ok(StatsMessage::PlayerStats(stats)) = PLAYER_STATS.parse::<StatsMessage>() else {
+            panic!("expected player stats");
+        };
+
+        assert_eq!(stats.steam_id, 76561198000000000);
+        assert_eq!(stats.damage.dealt, 1500);
+        assert_eq!(
+            stats.weapons.get("ROCKET"),
+            Some(&WeaponStats {
+                deaths: 1,
+                damage_given: 800,
+                damage_received: 300,
+                hits: 8,
+                kills: 3,
+                pickups: 2,
+                shots: 20,
+                time: 120,
+            })
+        );
+        assert_eq!(stats.weapons["ROCKET"].accuracy(), 0.4);
+    }
+
+    #[test]
+    fn serializes_back_to_the_wire_format() {
+        let message = PLAYER_STATS
+            .parse::<StatsMessage>()
+            .expect("this should not happen");
+        let reparsed = serde_json::to_string(&message)
+            .expect("this should not happen")
+            .parse::<StatsMessage>()
+            .expect("this should not happen");
+
+        assert_eq!(reparsed, message);
+        assert_eq!(message.message_type(), "PLAYER_STATS");
+        assert_eq!(message.match_guid(), "asdf");
+    }
+
+    #[test]
+    fn parses_medal_and_death_without_killer() {
+        let medal = r#"{"DATA": {"MATCH_GUID": "asdf", "MEDAL": "EXCELLENT", "NAME": "player1", "STEAM_ID": "1234", "TIME": 42, "TOTAL": 3, "WARMUP": false}, "TYPE": "PLAYER_MEDAL"}"#;
+        assert!(matches!(
+            medal.parse::<StatsMessage>(),
+            Ok(StatsMessage::PlayerMedal(data)) if data.total == 3 && data.steam_id == 1234
+        ));
+
+        let death = r#"{"DATA": {"KILLER": null, "MATCH_GUID": "asdf", "MOD": "HURT", "OTHER_TEAM_ALIVE": 4, "OTHER_TEAM_DEAD": 0, "ROUND": 3, "SUICIDE": false, "TEAMKILL": false, "TEAM_ALIVE": 3, "TEAM_DEAD": 1, "TIME": 120, "VICTIM": {"AIRBORNE": false, "AMMO": 5, "ARMOR": 0, "BOT": false, "BOT_SKILL": null, "HEALTH": 0, "HOLDABLE": null, "NAME": "player1", "POSITION": {"x": 1.0, "y": 2.0, "z": 3.0}, "POWERUPS": null, "SPEED": 320.5, "STEAM_ID": "1234", "SUBMERGED": false, "TEAM": 1, "VIEW": {"x": 0.0, "y": 90.0, "z": 0.0}, "WEAPON": "ROCKET"}, "WARMUP": false}, "TYPE": "PLAYER_DEATH"}"#;
+        assert!(matches!(
+            death.parse::<StatsMessage>(),
+            Ok(StatsMessage::PlayerDeath(data))
+                if data.killer.is_none() && data.victim.position.z == 3.0
+        ));
+    }
+
+    #[test]
+    fn reports_the_malformed_field() {
+        let medal = r#"{"DATA": {"MATCH_GUID": "asdf", "MEDAL": "EXCELLENT", "NAME": "player1", "STEAM_ID": "1234", "TIME": 42, "WARMUP": false}, "TYPE": "PLAYER_MEDAL"}"#;
+        let result = medal.parse::<StatsMessage>();
+
+        assert!(matches!(
+            result,
+            Err(SchemaError::InvalidData {
+                message_type: "PLAYER_MEDAL",
+                ..
+            })
+        ));
+        assert_eq!(
+            result.map(|_| ()).unwrap_err().to_string(),
+            "malformed PLAYER_MEDAL DATA: missing field `TOTAL`"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_missing_types() {
+        assert!(matches!(
+            r#"{"DATA": {}, "TYPE": "PLAYER_JUMP"}"#.parse::<StatsMessage>(),
+            Err(SchemaError::UnknownType(message_type)) if message_type == "PLAYER_JUMP"
+        ));
+        assert!(matches!(
+            r#"{"DATA": {}}"#.parse::<StatsMessage>(),
+            Err(SchemaError::MissingType)
+        ));
+        assert!(matches!(
+            "not json".parse::<StatsMessage>(),
+            Err(SchemaError::Json(_))
+        ));
+    }
+}
@@ -0,0 +1,41 @@
+//! QuakeLive sends steam ids as strings. These helpers read them into an `i64`, also accepting
+//! plain numbers, and write them back as strings.
+
+use core::fmt;
+
+use serde::{
+    Deserializer, Serializer,
+    de::{self, Visitor},
+};
+
+struct SteamIdVisitor;
+
+impl Visitor<'_> for SteamIdVisitor {
+    type Value = i64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a steam id as string or integer")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(value)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        i64::try_from(value).map_err(|_| E::custom(format!("steam id {value} out of range")))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        value
+            .parse()
+            .map_err(|_| E::custom(format!("invalid steam id {value:?}")))
+    }
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    deserializer.deserialize_any(SteamIdVisitor)
+}
+
+pub(crate) fn serialize<S: Serializer>(steam_id: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(steam_id)
+}
@@ -0,0 +1,191 @@
+//! A ZAP handler authenticating PLAIN clients of ZMQ sockets, like QuakeLive does for its stats
+//! socket.
+//!
+//! libzmq sends the authentication requests of sockets with a ZAP domain to [`ZAP_ENDPOINT`] of
+//! their context. [`ZapHandler`] binds that endpoint and answers the pending requests whenever
+//! [`ZapHandler::handle_requests`] is called.
+
+use arzmq::{
+    auth::ZapDomain,
+    prelude::{
+        Context, Message, MultipartReceiver, MultipartSender, PublishSocket, RecvFlags,
+        ReplySocket, SendFlags, Socket, ZmqResult,
+    },
+    socket::{PollEvents, SocketOption},
+};
+
+/// The endpoint libzmq sends authentication requests to.
+pub const ZAP_ENDPOINT: &str = "inproc://zeromq.zap.01";
+
+/// The PLAIN credentials clients have to present to connect to sockets of a ZAP domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlainCredentials {
+    /// The ZAP domain of the authenticated sockets.
+    pub domain: String,
+    /// The username clients have to present.
+    pub username: String,
+    /// The password clients have to present.
+    pub password: String,
+}
+
+impl PlainCredentials {
+    /// Builds the reply to a ZAP *request*, accepting PLAIN clients that present these
+    /// credentials for their domain.
+    pub fn zap_reply(&self, request: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let version = request.first().cloned().unwrap_or_default();
+        let request_id = request.get(1).cloned().unwrap_or_default();
+
+        let authenticated = version == b"1.0"
+            && request
+                .get(2)
+                .is_some_and(|domain| domain == self.domain.as_bytes())
+            && request
+                .get(5)
+                .is_some_and(|mechanism| mechanism == b"PLAIN")
+            && request
+                .get(6)
+                .is_some_and(|username| username == self.username.as_bytes())
+            && request
+                .get(7)
+                .is_some_and(|password| password == self.password.as_bytes());
+
+        let (status_code, status_text, user_id): (&[u8], &[u8], &[u8]) = if authenticated {
+            (b"200", b"OK", self.username.as_bytes())
+        } else {
+            (b"400", b"Invalid credentials", b"")
+        };
+
+        vec![
+            version,
+            request_id,
+            status_code.to_vec(),
+            status_text.to_vec(),
+            user_id.to_vec(),
+            vec![],
+        ]
+    }
+}
+
+/// The ZAP handler of a context, answering authentication requests with [`PlainCredentials`].
+pub struct ZapHandler {
+    socket: ReplySocket,
+    credentials: PlainCredentials,
+}
+
+impl ZapHandler {
+    /// Binds the ZAP endpoint of *context*. Only one handler can be bound per context.
+    pub fn bind(context: &Context, credentials: PlainCredentials) -> ZmqResult<Self> {
+        let socket: ReplySocket = Socket::from_context(context)?;
+        socket.bind(ZAP_ENDPOINT)?;
+        Ok(Self {
+            socket,
+            credentials,
+        })
+    }
+
+    /// Makes *socket* a PLAIN server whose clients are authenticated by this handler. Call this
+    /// before binding the socket.
+    pub fn secure(&self, socket: &PublishSocket) -> ZmqResult<()> {
+        socket.set_sockopt_bool(SocketOption::PlainServer, true)?;
+        socket.set_zap_domain(&ZapDomain::new(self.credentials.domain.clone()))
+    }
+
+    /// Answers the pending authentication requests without blocking.
+    pub fn handle_requests(&self) {
+        while self
+            .socket
+            .poll(PollEvents::POLL_IN, 0)
+            .is_ok_and(|value| value == PollEvents::POLL_IN)
+        {
+            let Ok(request) = self.socket.recv_multipart(RecvFlags::DONT_WAIT) else {
+                return;
+            };
+            let request = request
+                .iter()
+                .map(|frame| frame.bytes())
+                .collect::<Vec<_>>();
+            let reply = self
+                .credentials
+                .zap_reply(&request)
+                .into_iter()
+                .map(Message::from)
+                .collect::<Vec<_>>();
+            let _ = self.socket.send_multipart(reply, SendFlags::empty());
+        }
+    }
+}
+
+#[cfg(test)]
+mod zap_tests {
+    use pretty_assertions::assert_eq;
+
+    use super::PlainCredentials;
+
+    fn credentials() -> PlainCredentials {
+        PlainCredentials {
+            domain: "stats".to_string(),
+            username: "stats".to_string(),
+            password: "secret".to_string(),
+        }
+    }
+
+    fn zap_request(version: &str, domain: &str, username: &str, password: &str) -> Vec<Vec<u8>> {
+        [
+            version,
+            "1",
+            domain,
+            "127.0.0.1",
+            "",
+            "PLAIN",
+            username,
+            password,
+        ]
+        .iter()
+        .map(|frame| frame.as_bytes().to_vec())
+        .collect()
+    }
+
+    #[test]
+    fn zap_accepts_correct_credentials() {
+        let reply = credentials().zap_reply(&zap_request("1.0", "stats", "stats", "secret"));
+
+        assert_eq!(
+            reply,
+            [
+                b"1.0".to_vec(),
+                b"1".to_vec(),
+                b"200".to_vec(),
+                b"OK".to_vec(),
+                b"stats".to_vec(),
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn zap_rejects_wrong_credentials() {
+        for request in [
+            zap_request("1.0", "stats", "stats", "wrong"),
+            zap_request("1.0", "stats", "events", "secret"),
+            zap_request("1.0", "events", "stats", "secret"),
+            zap_request("0.9", "stats", "stats", "secret"),
+        ] {
+            let reply = credentials().zap_reply(&request);
+
+            assert_eq!(reply[2], b"400");
+            assert_eq!(reply[4], b"");
+        }
+    }
+
+    #[test]
+    fn zap_rejects_truncated_requests() {
+        let request = zap_request("1.0", "stats", "stats", "secret");
+
+        let reply = credentials().zap_reply(&request[..7]);
+
+        assert_eq!(
+            reply[..3],
+            [b"1.0".to_vec(), b"1".to_vec(), b"400".to_vec()]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use core::{borrow::BorrowMut, hint::cold_path};
+use std::path::Path;
 
 use pyo3::types::PyBool;
 use rand::RngExt;
@@ -8,7 +9,9 @@ use crate::{
     MAIN_ENGINE,
     ffi::{c::prelude::*, python::prelude::*},
     prelude::*,
-    quake_live_engine::{CmdArgc, CmdArgs, CmdArgv, ComPrintf, GameAddEvent, SendServerCommand},
+    quake_live_engine::{
+        CmdArgc, CmdArgs, CmdArgv, ComPrintf, FindCVar, GameAddEvent, SendServerCommand,
+    },
 };
 
 #[unsafe(no_mangle)]
@@ -258,6 +261,23 @@ pub extern "C" fn cmd_py_command() {
         });
 }
 
+#[unsafe(no_mangle)]
+// Lists the commands of the loaded plugins, e.g. for tab completion in rcon clients.
+pub extern "C" fn cmd_list_python_commands() {
+    MAIN_ENGINE.load().as_ref().tap_some(|&main_engine| {
+        let prefix = main_engine
+            .find_cvar("qlx_commandPrefix")
+            .map(|cvar| cvar.get_string())
+            .unwrap_or_default();
+        let names = Python::attach(|py| registered_command_names(py, &prefix));
+
+        names
+            .iter()
+            .for_each(|name| main_engine.com_printf(&format!("{name}\n")));
+        main_engine.com_printf(&format!("{} qlx commands\n", names.len()));
+    });
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn cmd_restart_python() {
     MAIN_ENGINE.load().as_ref().tap_some(|&main_engine| {
@@ -279,20 +299,130 @@ pub extern "C" fn cmd_restart_python() {
     });
 }
 
+#[unsafe(no_mangle)]
+// Export all database keys below a prefix to a versioned JSON file.
+pub extern "C" fn cmd_db_export() {
+    MAIN_ENGINE.load().as_ref().tap_some(|&main_engine| {
+        if main_engine.cmd_argc() < 2 {
+            let Some(command_name) = main_engine.cmd_argv(0) else {
+                cold_path();
+                return;
+            };
+
+            main_engine.com_printf(&format!("Usage: {command_name} <file> [prefix]\n"));
+            return;
+        }
+
+        let Some(path) = main_engine.cmd_argv(1) else {
+            cold_path();
+            return;
+        };
+        let prefix = main_engine
+            .cmd_argv(2)
+            .unwrap_or_else(|| DEFAULT_TRANSFER_PREFIX.to_string());
+
+        if !pyshinqlx_is_initialized() {
+            cold_path();
+            main_engine.com_printf("Python is not initialized. The database is not available.\n");
+            return;
+        }
+
+        let result = Python::attach(|py| {
+            let database = configured_database(py)?;
+            let snapshot = export_database(&database, &prefix)?;
+            py.detach(|| write_export_file(Path::new(&path), &snapshot))
+        });
+
+        match result {
+            Ok(exported) => {
+                main_engine.com_printf(&format!("Exported {exported} keys to {path}.\n"));
+            }
+            Err(err) => {
+                cold_path();
+                main_engine.com_printf(&format!("Database export failed: {err}\n"));
+            }
+        }
+    });
+}
+
+#[unsafe(no_mangle)]
+// Import database keys below a prefix from a JSON file created by cmd_db_export, running the
+// migrations registered by plugins. With "dryrun", nothing is written to the database.
+pub extern "C" fn cmd_db_import() {
+    MAIN_ENGINE.load().as_ref().tap_some(|&main_engine| {
+        let argc = main_engine.cmd_argc();
+        if argc < 2 {
+            let Some(command_name) = main_engine.cmd_argv(0) else {
+                cold_path();
+                return;
+            };
+
+            main_engine.com_printf(&format!("Usage: {command_name} <file> [prefix] [dryrun]\n"));
+            return;
+        }
+
+        let Some(path) = main_engine.cmd_argv(1) else {
+            cold_path();
+            return;
+        };
+        let (dry_run, prefixes): (Vec<_>, Vec<_>) = (2..argc)
+            .filter_map(|arg_num| main_engine.cmd_argv(arg_num))
+            .partition(|arg| arg.eq_ignore_ascii_case("dryrun"));
+        let dry_run = !dry_run.is_empty();
+        let prefix = prefixes
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| DEFAULT_TRANSFER_PREFIX.to_string());
+
+        if !pyshinqlx_is_initialized() {
+            cold_path();
+            main_engine.com_printf("Python is not initialized. The database is not available.\n");
+            return;
+        }
+
+        let result = Python::attach(|py| {
+            let snapshot = py.detach(|| read_export_file(Path::new(&path)))?;
+            let database = configured_database(py)?;
+            import_database(&database, &snapshot, &prefix, dry_run)
+        });
+
+        match result {
+            Ok((imported, migrated)) if dry_run => {
+                main_engine.com_printf(&format!(
+                    "Dry run: would import {imported} keys from {path} ({migrated} migrated).\n"
+                ));
+            }
+            Ok((imported, migrated)) => {
+                main_engine.com_printf(&format!(
+                    "Imported {imported} keys from {path} ({migrated} migrated).\n"
+                ));
+            }
+            Err(err) => {
+                cold_path();
+                main_engine.com_printf(&format!("Database import failed: {err}\n"));
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 mod commands_tests {
+    use core::borrow::BorrowMut;
+
     use mockall::predicate;
     use pyo3::{intern, types::PyBool};
     use rstest::rstest;
 
     use super::{
-        cmd_center_print, cmd_py_command, cmd_py_rcon, cmd_regular_print, cmd_restart_python,
-        cmd_send_server_command, cmd_slap, cmd_slay,
+        cmd_center_print, cmd_db_export, cmd_db_import, cmd_list_python_commands, cmd_py_command,
+        cmd_py_rcon, cmd_regular_print, cmd_restart_python, cmd_send_server_command, cmd_slap,
+        cmd_slay,
     };
     use crate::{
         ffi::{
             c::prelude::*,
             python::{
+                COMMANDS,
                 prelude::*,
                 pyshinqlx_test_support::{
                     python_function_raising_exception, python_function_returning,
@@ -992,6 +1122,36 @@ def handler(params):
             });
     }
 
+    #[test]
+    #[serial]
+    fn cmd_list_python_commands_with_no_main_engine() {
+        cmd_list_python_commands();
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn cmd_list_python_commands_with_no_registered_commands(_pyshinqlx_setup: ()) {
+        COMMANDS.store(None);
+
+        let prefix = c"!";
+        let mut raw_cmdprefix_cvar = CVarBuilder::default()
+            .string(prefix.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        MockEngineBuilder::default()
+            .with_find_cvar(
+                |cmd| cmd == "qlx_commandPrefix",
+                move |_| CVar::try_from(raw_cmdprefix_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1,
+            )
+            .with_com_printf(predicate::eq("0 qlx commands\n"), 1)
+            .run(|| {
+                cmd_list_python_commands();
+            });
+    }
+
     #[test]
     #[serial]
     fn cmd_restart_python_with_no_main_engine() {
@@ -1092,4 +1252,87 @@ def handler(params):
                 cmd_restart_python();
             });
     }
+
+    #[test]
+    #[serial]
+    fn cmd_db_export_with_no_main_engine() {
+        cmd_db_export();
+    }
+
+    #[test]
+    #[serial]
+    fn cmd_db_export_with_too_few_args() {
+        MockEngineBuilder::default()
+            .with_com_printf(predicate::eq("Usage: qlx_dbexport <file> [prefix]\n"), 1)
+            .with_argc(1)
+            .with_argv(predicate::eq(0), Some("qlx_dbexport"), 1)
+            .run(|| {
+                cmd_db_export();
+            });
+    }
+
+    #[test]
+    #[serial]
+    fn cmd_db_export_when_python_not_initialized() {
+        let pyshinqlx_is_initialized_ctx = pyshinqlx_is_initialized_context();
+        pyshinqlx_is_initialized_ctx
+            .expect()
+            .return_const(false)
+            .times(1);
+
+        MockEngineBuilder::default()
+            .with_com_printf(
+                predicate::eq("Python is not initialized. The database is not available.\n"),
+                1,
+            )
+            .with_argc(2)
+            .with_argv(predicate::eq(1), Some("export.json"), 1)
+            .with_argv(predicate::eq(2), None::<&str>, 1)
+            .run(|| {
+                cmd_db_export();
+            });
+    }
+
+    #[test]
+    #[serial]
+    fn cmd_db_import_with_no_main_engine() {
+        cmd_db_import();
+    }
+
+    #[test]
+    #[serial]
+    fn cmd_db_import_with_too_few_args() {
+        MockEngineBuilder::default()
+            .with_com_printf(
+                predicate::eq("Usage: qlx_dbimport <file> [prefix] [dryrun]\n"),
+                1,
+            )
+            .with_argc(1)
+            .with_argv(predicate::eq(0), Some("qlx_dbimport"), 1)
+            .run(|| {
+                cmd_db_import();
+            });
+    }
+
+    #[test]
+    #[serial]
+    fn cmd_db_import_when_python_not_initialized() {
+        let pyshinqlx_is_initialized_ctx = pyshinqlx_is_initialized_context();
+        pyshinqlx_is_initialized_ctx
+            .expect()
+            .return_const(false)
+            .times(1);
+
+        MockEngineBuilder::default()
+            .with_com_printf(
+                predicate::eq("Python is not initialized. The database is not available.\n"),
+                1,
+            )
+            .with_argc(3)
+            .with_argv(predicate::eq(1), Some("export.json"), 1)
+            .with_argv(predicate::eq(2), Some("dryrun"), 1)
+            .run(|| {
+                cmd_db_import();
+            });
+    }
 }
@@ -0,0 +1,585 @@
+use core::{
+    hint::cold_path,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use std::{
+    collections::VecDeque,
+    io::Read,
+    sync::{
+        Arc, LazyLock,
+        mpsc::{self, SyncSender},
+    },
+    thread,
+};
+
+use chrono::Utc;
+use pyo3::{
+    intern,
+    types::{PyDict, PyInt},
+};
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use super::{Teams, console_command, event_publisher::py_to_json, mute, prelude::*, put};
+use crate::{MAIN_ENGINE, prelude::*, quake_live_engine::FindCVar};
+
+/// How many chat messages ``/chat`` returns at most.
+const RECENT_CHAT_CAPACITY: usize = 50;
+/// The largest request body accepted.
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+/// How long a request waits for the game thread to answer it.
+const GAME_THREAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The attributes of :class:`Game` returned by ``/game``.
+const GAME_ATTRIBUTES: [&str; 17] = [
+    "gametype",
+    "type_short",
+    "map",
+    "map_title",
+    "red_score",
+    "blue_score",
+    "state",
+    "factory",
+    "hostname",
+    "maxclients",
+    "timelimit",
+    "fraglimit",
+    "roundlimit",
+    "scorelimit",
+    "capturelimit",
+    "teamsize",
+    "instagib",
+];
+
+/// Suffixes of cvars ``/cvars`` does not return, compared ignoring case, since they hold secrets.
+const SECRET_CVAR_SUFFIXES: [&str; 2] = ["password", "token"];
+/// Prefixes of cvars ``/cvars`` does not return, compared ignoring case.
+const SECRET_CVAR_PREFIXES: [&str; 1] = ["rcon_"];
+
+static ADMIN_API_ENABLED: AtomicBool = AtomicBool::new(false);
+
+static RECENT_CHAT: LazyLock<parking_lot::Mutex<VecDeque<Value>>> =
+    LazyLock::new(|| parking_lot::Mutex::new(VecDeque::with_capacity(RECENT_CHAT_CAPACITY)));
+
+/// The answer to a request of the admin API, with its HTTP status code.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AdminApiResponse {
+    pub(crate) status: u16,
+    pub(crate) body: Value,
+}
+
+impl AdminApiResponse {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({"error": message}),
+        }
+    }
+}
+
+/// A request received by the admin API, waiting to be answered on the game thread.
+#[pyclass(module = "_admin_api", name = "AdminApiRequest", frozen)]
+pub(crate) struct AdminApiRequest {
+    method: String,
+    path: String,
+    body: String,
+    /// Taken by whichever thread gets to it first: the game thread to carry out the request, or
+    /// the admin API thread once the client got a timeout, so the request is not carried out
+    /// anymore.
+    responder: Arc<parking_lot::Mutex<Option<SyncSender<AdminApiResponse>>>>,
+}
+
+/// Answers a request of the admin API. Scheduled as a frame task by the admin API thread.
+#[pyfunction]
+#[pyo3(name = "handle_admin_api_request")]
+pub(crate) fn pyshinqlx_handle_admin_api_request(
+    py: Python<'_>,
+    request: &Bound<'_, AdminApiRequest>,
+) {
+    let request = request.get();
+    let Some(responder) = request.responder.lock().take() else {
+        cold_path();
+        return;
+    };
+    let response = route(py, &request.method, &request.path, &request.body);
+    let _ = responder.send(response);
+}
+
+/// Records a chat message for ``/chat``, if the admin API is running.
+pub(crate) fn record_chat_message(
+    player: &Bound<'_, Player>,
+    msg: &str,
+    channel: &Bound<'_, PyAny>,
+) {
+    if !ADMIN_API_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let message = json!({
+        "time": Utc::now().timestamp(),
+        "player": py_to_json(player.as_any()),
+        "channel": channel.to_string(),
+        "message": msg,
+    });
+    let mut recent_chat = RECENT_CHAT.lock();
+    if recent_chat.len() == RECENT_CHAT_CAPACITY {
+        recent_chat.pop_front();
+    }
+    recent_chat.push_back(message);
+}
+
+/// Whether the value of an ``Authorization`` header carries *token*.
+pub(crate) fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided_token| {
+            provided_token.len() == token.len()
+                && provided_token
+                    .bytes()
+                    .zip(token.bytes())
+                    .fold(0, |acc, (left, right)| acc | (left ^ right))
+                    == 0
+        })
+}
+
+fn player_id(body: &Value) -> Result<i32, AdminApiResponse> {
+    body["id"]
+        .as_i64()
+        .and_then(|id| i32::try_from(id).ok())
+        .ok_or_else(|| AdminApiResponse::error(400, "missing player id"))
+}
+
+fn string_field<'a>(body: &'a Value, field: &str) -> Result<&'a str, AdminApiResponse> {
+    body[field]
+        .as_str()
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| AdminApiResponse::error(400, &format!("missing {field}")))
+}
+
+fn action_result(result: PyResult<()>) -> AdminApiResponse {
+    match result {
+        Ok(()) => AdminApiResponse::ok(json!({"ok": true})),
+        Err(err) => {
+            cold_path();
+            AdminApiResponse::error(400, &err.to_string())
+        }
+    }
+}
+
+fn game_state(py: Python<'_>) -> AdminApiResponse {
+    let Ok(game) = Game::py_new(py, true).and_then(|game| Bound::new(py, game)) else {
+        return AdminApiResponse::error(503, "no game running");
+    };
+
+    let state = GAME_ATTRIBUTES
+        .iter()
+        .map(|&attribute| {
+            let value = game
+                .getattr(attribute)
+                .map(|value| py_to_json(&value))
+                .unwrap_or_default();
+            (attribute.to_string(), value)
+        })
+        .collect();
+    AdminApiResponse::ok(Value::Object(state))
+}
+
+fn players(py: Python<'_>) -> PyResult<AdminApiResponse> {
+    let players = pyshinqlx_players_info(py)?
+        .into_iter()
+        .flatten()
+        .map(|player_info| {
+            let stats = pyshinqlx_player_stats(py, player_info.client_id)
+                .ok()
+                .flatten()
+                .map(|stats| {
+                    json!({
+                        "score": stats.score,
+                        "kills": stats.kills,
+                        "deaths": stats.deaths,
+                        "damage_dealt": stats.damage_dealt,
+                        "damage_taken": stats.damage_taken,
+                        "time": stats.time,
+                        "ping": stats.ping,
+                    })
+                });
+            json!({
+                "id": player_info.client_id,
+                "name": player_info.name,
+                "clean_name": clean_text(&player_info.name),
+                "steam_id": player_info.steam_id,
+                "team": Teams::from(player_info.team).to_string(),
+                "privileges": player_info.privileges,
+                "connection_state": player_info.connection_state,
+                "stats": stats,
+            })
+        })
+        .collect();
+    Ok(AdminApiResponse::ok(Value::Array(players)))
+}
+
+fn loaded_plugins(py: Python<'_>) -> PyResult<AdminApiResponse> {
+    let plugins = py
+        .get_type::<Plugin>()
+        .getattr(intern!(py, "_loaded_plugins"))?
+        .cast_into::<PyDict>()?
+        .keys()
+        .iter()
+        .map(|plugin| plugin.to_string())
+        .collect::<Vec<_>>();
+    Ok(AdminApiResponse::ok(json!(plugins)))
+}
+
+fn action(py: Python<'_>, action: &str, body: &str) -> AdminApiResponse {
+    let Ok(body) = serde_json::from_str::<Value>(body) else {
+        return AdminApiResponse::error(400, "request body is not valid json");
+    };
+
+    let result = match action {
+        "kick" => player_id(&body)
+            .map(|id| action_result(pyshinqlx_kick(py, id, body["reason"].as_str()))),
+        "put" => player_id(&body).and_then(|id| {
+            string_field(&body, "team")
+                .map(|team| action_result(put(py, PyInt::new(py, id).as_any(), team)))
+        }),
+        "mute" => player_id(&body).map(|id| action_result(mute(py, PyInt::new(py, id).as_any()))),
+        "change_map" => string_field(&body, "map").map(|new_map| {
+            let map_command = match body["factory"].as_str() {
+                None => format!("map {new_map}"),
+                Some(factory) => format!("map {new_map} {factory}"),
+            };
+            action_result(py.detach(|| console_command(&map_command)))
+        }),
+        "console" => string_field(&body, "command")
+            .map(|command| action_result(py.detach(|| console_command(command)))),
+        _ => Err(AdminApiResponse::error(404, "unknown action")),
+    };
+    result.unwrap_or_else(|response| response)
+}
+
+/// Answers the request *method* *path* with *body* on the game thread.
+pub(crate) fn route(py: Python<'_>, method: &str, path: &str, body: &str) -> AdminApiResponse {
+    let result = match (method, path.trim_end_matches('/')) {
+        ("GET", "/game") => Ok(game_state(py)),
+        ("GET", "/players") => players(py),
+        ("GET", "/plugins") => loaded_plugins(py),
+        ("GET", "/chat") => Ok(AdminApiResponse::ok(json!(
+            RECENT_CHAT.lock().iter().collect::<Vec<_>>()
+        ))),
+        ("GET", cvar_path) if cvar_path.starts_with("/cvars/") => {
+            let cvar_name = &cvar_path["/cvars/".len()..];
+            if is_secret_cvar(cvar_name) {
+                return AdminApiResponse::error(403, "secret cvars cannot be read");
+            }
+            pyshinqlx_get_cvar(py, cvar_name).map(|value| match value {
+                None => AdminApiResponse::error(404, "unknown cvar"),
+                Some(cvar_value) => {
+                    AdminApiResponse::ok(json!({"name": cvar_name, "value": cvar_value}))
+                }
+            })
+        }
+        ("POST", action_path) if action_path.starts_with("/actions/") => {
+            Ok(action(py, &action_path["/actions/".len()..], body))
+        }
+        _ => Ok(AdminApiResponse::error(404, "not found")),
+    };
+
+    result.unwrap_or_else(|err| {
+        cold_path();
+        AdminApiResponse::error(500, &err.to_string())
+    })
+}
+
+/// Whether the cvar *cvar_name* holds a secret, like ``sv_privatePassword`` or
+/// ``qlx_adminApiToken``.
+fn is_secret_cvar(cvar_name: &str) -> bool {
+    let cvar_name = cvar_name.to_ascii_lowercase();
+    SECRET_CVAR_SUFFIXES
+        .iter()
+        .any(|suffix| cvar_name.ends_with(suffix))
+        || SECRET_CVAR_PREFIXES
+            .iter()
+            .any(|prefix| cvar_name.starts_with(prefix))
+}
+
+fn schedule_on_game_thread(request: AdminApiRequest) -> PyResult<()> {
+    Python::attach(|py| {
+        let handler = wrap_pyfunction!(pyshinqlx_handle_admin_api_request, py)?;
+        py.import(intern!(py, "shinqlx"))?
+            .getattr(intern!(py, "next_frame_tasks"))?
+            .call_method1(
+                intern!(py, "put_nowait"),
+                ((&handler, (Bound::new(py, request)?,), PyDict::new(py)),),
+            )
+            .map(|_| ())
+    })
+}
+
+fn answer(request: &mut Request, token: &str) -> AdminApiResponse {
+    let authorization = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .map(|header| header.value.to_string());
+    if !is_authorized(authorization.as_deref(), token) {
+        return AdminApiResponse::error(401, "unauthorized");
+    }
+
+    let mut body = String::new();
+    if request
+        .as_reader()
+        .take(MAX_BODY_SIZE)
+        .read_to_string(&mut body)
+        .is_err()
+    {
+        return AdminApiResponse::error(400, "could not read request body");
+    }
+
+    let method = match request.method() {
+        Method::Get => "GET",
+        Method::Post => "POST",
+        _ => return AdminApiResponse::error(405, "method not allowed"),
+    };
+    let path = request.url().split('?').next().unwrap_or_default();
+
+    let (sender, receiver) = mpsc::sync_channel(1);
+    let responder = Arc::new(parking_lot::Mutex::new(Some(sender)));
+    let admin_request = AdminApiRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        body,
+        responder: responder.clone(),
+    };
+    if let Err(err) = schedule_on_game_thread(admin_request) {
+        cold_path();
+        return AdminApiResponse::error(500, &err.to_string());
+    }
+
+    if let Ok(response) = receiver.recv_timeout(GAME_THREAD_TIMEOUT) {
+        return response;
+    }
+
+    cold_path();
+    if responder.lock().take().is_some() {
+        return AdminApiResponse::error(504, "the game thread did not answer");
+    }
+    // the game thread is carrying out the request already
+    receiver.recv().unwrap_or_else(|_| {
+        cold_path();
+        AdminApiResponse::error(500, "the game thread could not answer")
+    })
+}
+
+fn run_server(server: Server, token: String) {
+    for mut request in server.incoming_requests() {
+        let AdminApiResponse { status, body } = answer(&mut request, &token);
+        let mut response = Response::from_string(body.to_string()).with_status_code(status);
+        if let Ok(content_type) = Header::from_bytes("Content-Type", "application/json") {
+            response.add_header(content_type);
+        }
+        if let Err(err) = request.respond(response) {
+            cold_path();
+            debug!(target: "shinqlx", "could not answer admin api request: {err}");
+        }
+    }
+}
+
+/// Starts the admin API if ``qlx_adminApiAddress`` and ``qlx_adminApiToken`` are set.
+pub(crate) fn initialize_admin_api() {
+    let Some((address, token)) = MAIN_ENGINE.load().as_ref().and_then(|main_engine| {
+        let address = main_engine
+            .find_cvar("qlx_adminApiAddress")
+            .map(|cvar| cvar.get_string())
+            .filter(|address| !address.is_empty())?;
+        let token = main_engine
+            .find_cvar("qlx_adminApiToken")
+            .map(|cvar| cvar.get_string())
+            .unwrap_or_default();
+        Some((address, token))
+    }) else {
+        return;
+    };
+
+    if token.is_empty() {
+        cold_path();
+        error!(target: "shinqlx", "qlx_adminApiToken needs to be set to use the admin api.");
+        return;
+    }
+
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(err) => {
+            cold_path();
+            error!(target: "shinqlx", "could not start the admin api on {address}: {err}");
+            return;
+        }
+    };
+    if let Err(err) = thread::Builder::new()
+        .name("shinqlx-admin-api".to_string())
+        .spawn(move || run_server(server, token))
+    {
+        cold_path();
+        error!(target: "shinqlx", "could not start the admin api thread: {err}");
+        return;
+    }
+    ADMIN_API_ENABLED.store(true, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod admin_api_tests {
+    use std::sync::{
+        Arc,
+        mpsc::{self, Receiver},
+    };
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::{
+        AdminApiRequest, AdminApiResponse, is_authorized, is_secret_cvar,
+        pyshinqlx_handle_admin_api_request, route,
+    };
+    use crate::{ffi::python::prelude::*, prelude::*};
+
+    fn admin_api_request(
+        method: &str,
+        path: &str,
+        body: &str,
+        cancelled: bool,
+    ) -> (AdminApiRequest, Receiver<AdminApiResponse>) {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let request = AdminApiRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.to_string(),
+            responder: Arc::new(parking_lot::Mutex::new((!cancelled).then_some(sender))),
+        };
+        (request, receiver)
+    }
+
+    #[rstest]
+    #[case(Some("Bearer secret"), true)]
+    #[case(Some("Bearer wrong!"), false)]
+    #[case(Some("Bearer secret2"), false)]
+    #[case(Some("secret"), false)]
+    #[case(None, false)]
+    fn authorization_needs_bearer_token(
+        #[case] authorization: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(is_authorized(authorization, "secret"), expected);
+    }
+
+    #[rstest]
+    #[case("sv_privatePassword", true)]
+    #[case("g_password", true)]
+    #[case("zmq_rcon_password", true)]
+    #[case("qlx_adminApiToken", true)]
+    #[case("rcon_password", true)]
+    #[case("RCON_ADDRESS", true)]
+    #[case("sv_hostname", false)]
+    #[case("qlx_passwordHint", false)]
+    fn secret_cvars_are_recognized(#[case] cvar_name: &str, #[case] expected: bool) {
+        assert_eq!(is_secret_cvar(cvar_name), expected);
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn secret_cvars_are_not_returned(_pyshinqlx_setup: ()) {
+        MockEngineBuilder::default()
+            .with_find_cvar(|_| true, |_| None, 0)
+            .run(|| {
+                Python::attach(|py| {
+                    let response = route(py, "GET", "/cvars/sv_privatePassword", "");
+                    assert_eq!(response.status, 403);
+                });
+            });
+    }
+
+    #[rstest]
+    #[case("GET", "/nothing")]
+    #[case("POST", "/game")]
+    #[case("POST", "/actions/explode")]
+    #[cfg_attr(miri, ignore)]
+    fn unknown_routes_are_not_found(
+        _pyshinqlx_setup: (),
+        #[case] method: &str,
+        #[case] path: &str,
+    ) {
+        Python::attach(|py| {
+            let response = route(py, method, path, "{}");
+            assert_eq!(response.status, 404);
+        });
+    }
+
+    #[rstest]
+    #[case("/actions/kick", "no json")]
+    #[case("/actions/kick", r#"{"reason": "missing id"}"#)]
+    #[case("/actions/put", r#"{"id": 2}"#)]
+    #[case("/actions/change_map", r#"{"factory": "ca"}"#)]
+    #[case("/actions/console", r#"{"command": ""}"#)]
+    #[cfg_attr(miri, ignore)]
+    fn malformed_actions_are_rejected(
+        _pyshinqlx_setup: (),
+        #[case] path: &str,
+        #[case] body: &str,
+    ) {
+        Python::attach(|py| {
+            let response = route(py, "POST", path, body);
+            assert_eq!(response.status, 400);
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn chat_is_empty_without_messages(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            assert_eq!(
+                route(py, "GET", "/chat", ""),
+                AdminApiResponse::ok(json!([]))
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn requests_are_answered(_pyshinqlx_setup: ()) {
+        let (request, receiver) = admin_api_request("GET", "/chat", "", false);
+
+        Python::attach(|py| {
+            let request = Bound::new(py, request).expect("this should not happen");
+            pyshinqlx_handle_admin_api_request(py, &request);
+        });
+
+        assert_eq!(
+            receiver.try_recv().ok(),
+            Some(AdminApiResponse::ok(json!([])))
+        );
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn cancelled_requests_are_not_carried_out(_pyshinqlx_setup: ()) {
+        let (request, receiver) =
+            admin_api_request("POST", "/actions/console", r#"{"command": "quit"}"#, true);
+
+        MockEngineBuilder::default()
+            .with_execute_console_command("quit", 0)
+            .run(|| {
+                Python::attach(|py| {
+                    let request = Bound::new(py, request).expect("this should not happen");
+                    pyshinqlx_handle_admin_api_request(py, &request);
+                });
+            });
+
+        assert!(receiver.try_recv().is_err());
+    }
+}
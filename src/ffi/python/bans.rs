@@ -0,0 +1,1006 @@
+use core::{hint::cold_path, str::FromStr};
+use std::{net::IpAddr, sync::LazyLock};
+
+use arc_swap::{ArcSwap, ArcSwapOption};
+use chrono::{DateTime, Utc};
+use pyo3::{
+    intern,
+    types::{IntoPyDict, PyDict, PyString},
+};
+use serde_json::json;
+
+use super::{
+    PythonReturnCodes, commands::CommandPriorities, database_configured,
+    event_publisher::publish_event, log_exception, owner, prelude::*,
+};
+use crate::prelude::*;
+
+/// The sorted set of all bans issued through the ban system, scored by their expiry.
+const BANS_KEY: &str = "minqlx:bans";
+/// The sorted set of all bans that include an IP address or range, scored by their expiry.
+const IP_BANS_KEY: &str = "minqlx:bans:ips";
+/// The counter used for the ids of new bans.
+const BAN_ID_KEY: &str = "minqlx:bans:next_id";
+
+/// The expiry of permanent bans, 9999-12-31 23:59:59 UTC.
+const PERMANENT_BAN_EXPIRY: i64 = 253_402_300_799;
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// How often expired bans are removed from the database, in seconds.
+const EXPIRY_CLEANUP_INTERVAL: f64 = 60.0;
+
+/// The plugin instance owning the ban commands, and the ban store in its database. Bans are only
+/// enforced once it has been initialized.
+static BAN_SYSTEM: LazyLock<ArcSwapOption<Py<Bans>>> = LazyLock::new(ArcSwapOption::empty);
+
+/// The bans that include an IP address or range, so that connecting players can be checked
+/// against them without going to the database. Refreshed whenever bans are added or removed.
+static IP_BANS: LazyLock<ArcSwap<Vec<BanEntry>>> = LazyLock::new(|| ArcSwap::from_pointee(vec![]));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BanDuration {
+    Seconds(i64),
+    Permanent,
+}
+
+impl FromStr for BanDuration {
+    type Err = ();
+
+    /// Parses durations like ``30s``, ``15m``, ``2h``, ``7d``, ``2w``, ``1y``, or ``perm``.
+    fn from_str(duration: &str) -> Result<Self, Self::Err> {
+        let lowercase_duration = duration.to_lowercase();
+        if ["perm", "permanent", "forever"].contains(&lowercase_duration.as_str()) {
+            return Ok(Self::Permanent);
+        }
+
+        let (amount, unit) = lowercase_duration.split_at(
+            lowercase_duration
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(lowercase_duration.len()),
+        );
+        let unit_seconds = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            "y" => 365 * 24 * 60 * 60,
+            _ => return Err(()),
+        };
+        amount
+            .parse::<i64>()
+            .ok()
+            .filter(|&value| value > 0)
+            .and_then(|value| value.checked_mul(unit_seconds))
+            .map(Self::Seconds)
+            .ok_or(())
+    }
+}
+
+/// Checks whether *ip* is the IP address *range*, or within it if *range* is in CIDR notation,
+/// e.g. ``10.0.0.0/8``.
+pub(crate) fn ip_in_range(range: &str, ip: &str) -> bool {
+    let Ok(address) = ip.parse::<IpAddr>() else {
+        return false;
+    };
+    let (network, prefix_len) = match range.split_once('/') {
+        None => (range, None),
+        Some((network, prefix_len)) => match prefix_len.parse::<u32>() {
+            Ok(value) => (network, Some(value)),
+            Err(_) => return false,
+        },
+    };
+
+    match (network.parse::<IpAddr>(), address) {
+        (Ok(IpAddr::V4(network)), IpAddr::V4(address)) => {
+            let prefix_len = prefix_len.unwrap_or(32);
+            prefix_len <= 32 && {
+                let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+                u32::from(network) & mask == u32::from(address) & mask
+            }
+        }
+        (Ok(IpAddr::V6(network)), IpAddr::V6(address)) => {
+            let prefix_len = prefix_len.unwrap_or(128);
+            prefix_len <= 128 && {
+                let mask = u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0);
+                u128::from(network) & mask == u128::from(address) & mask
+            }
+        }
+        _ => false,
+    }
+}
+
+fn is_valid_ip_range(range: &str) -> bool {
+    let network = range.split_once('/').map_or(range, |(network, _)| network);
+    network
+        .parse::<IpAddr>()
+        .is_ok_and(|address| ip_in_range(range, &address.to_string()))
+}
+
+pub(crate) fn format_timestamp(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .map(|datetime| datetime.format(TIMESTAMP_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+/// The sorted set of the bans of *steam_id*. Kept apart from ``minqlx:players:<steam_id>:bans`` of
+/// the ``ban`` plugin, which numbers the bans of a player on its own.
+fn player_bans_key(steam_id: i64) -> String {
+    format!("minqlx:bans:players:{steam_id}")
+}
+
+fn ban_key(steam_id: i64, ban_id: i64) -> String {
+    format!("minqlx:bans:players:{steam_id}:{ban_id}")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BanEntry {
+    pub(crate) steam_id: i64,
+    pub(crate) ban_id: i64,
+    pub(crate) expires: i64,
+    pub(crate) reason: String,
+    pub(crate) issued_by: String,
+    pub(crate) ip: Option<String>,
+}
+
+impl BanEntry {
+    fn is_permanent(&self) -> bool {
+        self.expires >= PERMANENT_BAN_EXPIRY
+    }
+
+    fn index_member(&self) -> String {
+        format!("{}:{}", self.steam_id, self.ban_id)
+    }
+
+    fn expiry_description(&self) -> String {
+        if self.is_permanent() {
+            "permanently".to_string()
+        } else {
+            format!("until {} UTC", format_timestamp(self.expires))
+        }
+    }
+
+    fn reason_description(&self) -> &str {
+        if self.reason.is_empty() {
+            "No reason given."
+        } else {
+            &self.reason
+        }
+    }
+
+    /// The message shown to the banned player when they try to connect.
+    pub(crate) fn reject_message(&self) -> String {
+        format!(
+            "You are banned {}: {}",
+            self.expiry_description(),
+            self.reason_description()
+        )
+    }
+
+    fn description(&self) -> String {
+        let ip_description = self
+            .ip
+            .as_ref()
+            .map(|ip| format!(" (IP {ip})"))
+            .unwrap_or_default();
+        format!(
+            "{}{ip_description} banned {} by {}: {}",
+            self.steam_id,
+            self.expiry_description(),
+            self.issued_by,
+            self.reason_description()
+        )
+    }
+}
+
+fn read_ban(
+    database: &Bound<'_, PyAny>,
+    steam_id: i64,
+    ban_id: i64,
+    expires: i64,
+) -> PyResult<Option<BanEntry>> {
+    let py = database.py();
+    let fields = database.call_method1(intern!(py, "hgetall"), (ban_key(steam_id, ban_id),))?;
+    let fields = fields.cast::<PyDict>()?;
+    if fields.is_empty() {
+        return Ok(None);
+    }
+
+    let field = |name: &str| {
+        fields
+            .get_item(name)
+            .ok()
+            .flatten()
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+    };
+    Ok(Some(BanEntry {
+        steam_id,
+        ban_id,
+        expires,
+        reason: field("reason"),
+        issued_by: field("issued_by"),
+        ip: Some(field("ip")).filter(|ip| !ip.is_empty()),
+    }))
+}
+
+fn scored_members(
+    database: &Bound<'_, PyAny>,
+    key: &str,
+    min: &str,
+    max: &str,
+) -> PyResult<Vec<(String, i64)>> {
+    let py = database.py();
+    database
+        .call_method(
+            intern!(py, "zrangebyscore"),
+            (key, min, max),
+            Some(&[(intern!(py, "withscores"), true)].into_py_dict(py)?),
+        )?
+        .extract::<Vec<(String, f64)>>()
+        .map(|members| {
+            members
+                .into_iter()
+                .map(|(member, score)| (member, score as i64))
+                .collect()
+        })
+}
+
+fn parse_index_member(member: &str) -> Option<(i64, i64)> {
+    let (steam_id, ban_id) = member.split_once(':')?;
+    Some((steam_id.parse().ok()?, ban_id.parse().ok()?))
+}
+
+fn delete_ban(database: &Bound<'_, PyAny>, steam_id: i64, ban_id: i64) -> PyResult<()> {
+    let py = database.py();
+    let index_member = format!("{steam_id}:{ban_id}");
+    database.call_method1(intern!(py, "zrem"), (BANS_KEY, &index_member))?;
+    database.call_method1(intern!(py, "zrem"), (IP_BANS_KEY, &index_member))?;
+    database.call_method1(
+        intern!(py, "zrem"),
+        (player_bans_key(steam_id), ban_id.to_string()),
+    )?;
+    database.call_method1(intern!(py, "delete"), (ban_key(steam_id, ban_id),))?;
+    Ok(())
+}
+
+/// Stores a new ban for *steam_id*, and optionally an IP address or range, in *database*.
+pub(crate) fn add_ban(
+    database: &Bound<'_, PyAny>,
+    steam_id: i64,
+    duration: BanDuration,
+    reason: &str,
+    issued_by: &str,
+    ip: Option<&str>,
+) -> PyResult<BanEntry> {
+    let py = database.py();
+    let now = Utc::now().timestamp();
+    let expires = match duration {
+        BanDuration::Seconds(seconds) => now.saturating_add(seconds).min(PERMANENT_BAN_EXPIRY),
+        BanDuration::Permanent => PERMANENT_BAN_EXPIRY,
+    };
+    let ban_id = database
+        .call_method1(intern!(py, "incr"), (BAN_ID_KEY,))?
+        .extract::<i64>()?;
+    let ban = BanEntry {
+        steam_id,
+        ban_id,
+        expires,
+        reason: reason.to_string(),
+        issued_by: issued_by.to_string(),
+        ip: ip.map(str::to_string),
+    };
+
+    let mapping = PyDict::new(py);
+    mapping.set_item(intern!(py, "expires"), format_timestamp(expires))?;
+    mapping.set_item(intern!(py, "reason"), reason)?;
+    mapping.set_item(intern!(py, "issued"), format_timestamp(now))?;
+    mapping.set_item(intern!(py, "issued_by"), issued_by)?;
+    if let Some(ip_range) = ip {
+        mapping.set_item(intern!(py, "ip"), ip_range)?;
+    }
+    let hash_key = ban_key(steam_id, ban_id);
+    database.call_method(
+        intern!(py, "hset"),
+        (&hash_key,),
+        Some(&[(intern!(py, "mapping"), mapping)].into_py_dict(py)?),
+    )?;
+    if !ban.is_permanent() {
+        database.call_method1(intern!(py, "expire"), (&hash_key, expires - now))?;
+    }
+
+    database.call_method1(
+        intern!(py, "zadd"),
+        (
+            player_bans_key(steam_id),
+            [(ban_id.to_string(), expires)].into_py_dict(py)?,
+        ),
+    )?;
+    let index_entry = [(ban.index_member(), expires)].into_py_dict(py)?;
+    database.call_method1(intern!(py, "zadd"), (BANS_KEY, &index_entry))?;
+    if ban.ip.is_some() {
+        database.call_method1(intern!(py, "zadd"), (IP_BANS_KEY, &index_entry))?;
+        refresh_ip_bans(database)?;
+    }
+
+    Ok(ban)
+}
+
+/// Removes all bans issued through the ban system that expired. Returns the number of removed
+/// bans.
+pub(crate) fn remove_expired_bans(database: &Bound<'_, PyAny>) -> PyResult<usize> {
+    let now = Utc::now().timestamp();
+    let expired_bans = scored_members(database, BANS_KEY, "-inf", &now.to_string())?;
+    expired_bans
+        .iter()
+        .filter_map(|(member, _)| parse_index_member(member))
+        .try_for_each(|(steam_id, ban_id)| delete_ban(database, steam_id, ban_id))?;
+    if !expired_bans.is_empty() {
+        refresh_ip_bans(database)?;
+    }
+    Ok(expired_bans.len())
+}
+
+/// Returns the active bans of *steam_id*.
+pub(crate) fn active_bans(database: &Bound<'_, PyAny>, steam_id: i64) -> PyResult<Vec<BanEntry>> {
+    let now = Utc::now().timestamp();
+    scored_members(
+        database,
+        &player_bans_key(steam_id),
+        &format!("({now}"),
+        "+inf",
+    )?
+    .into_iter()
+    .filter_map(|(ban_id, expires)| {
+        ban_id
+            .parse::<i64>()
+            .ok()
+            .map(|ban_id| read_ban(database, steam_id, ban_id, expires))
+    })
+    .filter_map(Result::transpose)
+    .collect()
+}
+
+fn active_indexed_bans(database: &Bound<'_, PyAny>, index_key: &str) -> PyResult<Vec<BanEntry>> {
+    let now = Utc::now().timestamp();
+    scored_members(database, index_key, &format!("({now}"), "+inf")?
+        .into_iter()
+        .filter_map(|(member, expires)| {
+            parse_index_member(&member)
+                .map(|(steam_id, ban_id)| read_ban(database, steam_id, ban_id, expires))
+        })
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+/// Returns all active bans issued through the ban system.
+pub(crate) fn all_active_bans(database: &Bound<'_, PyAny>) -> PyResult<Vec<BanEntry>> {
+    active_indexed_bans(database, BANS_KEY)
+}
+
+/// Reloads the cached bans that include an IP address or range from *database*.
+pub(crate) fn refresh_ip_bans(database: &Bound<'_, PyAny>) -> PyResult<()> {
+    let ip_bans = active_indexed_bans(database, IP_BANS_KEY)?;
+    IP_BANS.store(ip_bans.into());
+    Ok(())
+}
+
+/// Returns the longest active ban of *steam_id*, or of any IP range containing *ip*. IP bans are
+/// looked up in the cached IP bans.
+pub(crate) fn find_ban(
+    database: &Bound<'_, PyAny>,
+    steam_id: i64,
+    ip: &str,
+) -> PyResult<Option<BanEntry>> {
+    let steam_id_ban = active_bans(database, steam_id)?
+        .into_iter()
+        .max_by_key(|ban| ban.expires);
+    if steam_id_ban.is_some() || ip.is_empty() {
+        return Ok(steam_id_ban);
+    }
+
+    let now = Utc::now().timestamp();
+    Ok(IP_BANS
+        .load()
+        .iter()
+        .filter(|ban| {
+            ban.expires > now
+                && ban
+                    .ip
+                    .as_ref()
+                    .is_some_and(|ip_range| ip_in_range(ip_range, ip))
+        })
+        .max_by_key(|ban| ban.expires)
+        .cloned())
+}
+
+/// Lifts all active bans of *steam_id*. Returns the number of lifted bans.
+pub(crate) fn remove_bans(database: &Bound<'_, PyAny>, steam_id: i64) -> PyResult<usize> {
+    let bans = active_bans(database, steam_id)?;
+    bans.iter()
+        .try_for_each(|ban| delete_ban(database, ban.steam_id, ban.ban_id))?;
+    if bans.iter().any(|ban| ban.ip.is_some()) {
+        refresh_ip_bans(database)?;
+    }
+    Ok(bans.len())
+}
+
+/// Returns the message to reject a connecting player with, if the ban system is running and the
+/// player is banned. Errors are logged, and let the player connect.
+pub(crate) fn ban_reject_message(py: Python<'_>, player: &Bound<'_, Player>) -> Option<String> {
+    let ban_system = BAN_SYSTEM.load();
+    let ban_plugin = ban_system.as_ref()?;
+
+    ban_plugin
+        .bind(py)
+        .as_super()
+        .get_db()
+        .and_then(|database| find_ban(&database, player.get().steam_id, &player.get_ip()))
+        .map(|ban| ban.map(|active_ban| active_ban.reject_message()))
+        .unwrap_or_else(|err| {
+            cold_path();
+            log_exception(py, &err);
+            None
+        })
+}
+
+pub(crate) enum CommandTarget<'py> {
+    Online(Bound<'py, Player>),
+    Offline(i64),
+}
+
+impl CommandTarget<'_> {
+    pub(crate) fn steam_id(&self) -> i64 {
+        match self {
+            Self::Online(player) => player.get().steam_id,
+            Self::Offline(steam_id) => *steam_id,
+        }
+    }
+
+    pub(crate) fn name(&self) -> String {
+        match self {
+            Self::Online(player) => player.get_name(),
+            Self::Offline(steam_id) => steam_id.to_string(),
+        }
+    }
+}
+
+/// Resolves a client id, a Steam ID, or part of a player name to a command target. Steam IDs
+/// of players that are not on the server resolve to offline targets.
+pub(crate) fn command_target<'py>(
+    py: Python<'py>,
+    identifier: &str,
+) -> PyResult<Option<CommandTarget<'py>>> {
+    match identifier.parse::<i64>() {
+        Ok(client_id) if (0..64).contains(&client_id) => Player::py_new(client_id as i32, None)
+            .and_then(|player| Bound::new(py, player))
+            .map(|player| Some(CommandTarget::Online(player)))
+            .or(Ok(None)),
+        Ok(steam_id) => {
+            let online_player = Player::all_players(&py.get_type::<Player>())?
+                .into_iter()
+                .find(|player| player.steam_id == steam_id);
+            match online_player {
+                Some(player) => Ok(Some(CommandTarget::Online(Bound::new(py, player)?))),
+                None => Ok(Some(CommandTarget::Offline(steam_id))),
+            }
+        }
+        Err(_) => {
+            let found_player = py
+                .get_type::<Plugin>()
+                .call_method1(intern!(py, "player"), (identifier,))?;
+            if found_player.is_none() {
+                return Ok(None);
+            }
+            Ok(Some(CommandTarget::Online(
+                found_player.cast_into::<Player>()?,
+            )))
+        }
+    }
+}
+
+pub(crate) fn reply(channel: &Bound<'_, PyAny>, msg: &str) -> PyResult<()> {
+    channel
+        .call_method1(intern!(channel.py(), "reply"), (msg,))
+        .map(|_| ())
+}
+
+/// The built-in ban system. Bans are kept in the configured database and enforced when a player
+/// connects, so they survive server restarts, unlike the engine's own bans.
+#[pyclass(name = "bans", module = "_bans", extends = Plugin, frozen)]
+pub(crate) struct Bans {}
+
+#[pymethods]
+impl Bans {
+    /// ``!ban <player> <duration> [ip=<address or range>] [reason]``
+    fn cmd_ban(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        msg: Vec<String>,
+        channel: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<i32>> {
+        let py = slf.py();
+        if msg.len() < 3 {
+            return Ok(Some(PythonReturnCodes::RET_USAGE as i32));
+        }
+
+        let Some(target) = command_target(py, &msg[1])? else {
+            reply(channel, "Invalid client ID, Steam ID, or player name.")?;
+            return Ok(None);
+        };
+        let Ok(duration) = msg[2].parse::<BanDuration>() else {
+            reply(
+                channel,
+                "Invalid duration. Use e.g. ^630m^7, ^62h^7, ^67d^7, ^62w^7, or ^6perm^7.",
+            )?;
+            return Ok(None);
+        };
+        let (ip, reason_start) = match msg.get(3).and_then(|arg| arg.strip_prefix("ip=")) {
+            Some(ip_range) if !is_valid_ip_range(ip_range) => {
+                reply(
+                    channel,
+                    &format!("Invalid IP address or range: ^6{ip_range}^7"),
+                )?;
+                return Ok(None);
+            }
+            Some(ip_range) => (Some(ip_range), 4),
+            None => (None, 3),
+        };
+        let reason = msg.get(reason_start..).unwrap_or_default().join(" ");
+
+        let steam_id = target.steam_id();
+        if owner()? == Some(steam_id) {
+            reply(channel, "The owner cannot be banned.")?;
+            return Ok(None);
+        }
+
+        let database = slf.as_super().get_db()?;
+        if database
+            .call_method1(intern!(py, "has_permission"), (steam_id, 5))?
+            .is_truthy()?
+        {
+            reply(channel, "Players with permission level 5 cannot be banned.")?;
+            return Ok(None);
+        }
+
+        let issued_by = player
+            .getattr(intern!(py, "steam_id"))
+            .map(|issuer| issuer.to_string())
+            .unwrap_or_default();
+        let ban = add_ban(&database, steam_id, duration, &reason, &issued_by, ip)?;
+        publish_event(
+            "ban",
+            json!({
+                "steam_id": ban.steam_id,
+                "name": target.name(),
+                "ban_id": ban.ban_id,
+                "expires": format_timestamp(ban.expires),
+                "reason": ban.reason,
+                "issued_by": ban.issued_by,
+                "ip": ban.ip,
+            }),
+        );
+        reply(
+            channel,
+            &format!(
+                "{}^7 has been banned {}.",
+                target.name(),
+                ban.expiry_description()
+            ),
+        )?;
+
+        if let CommandTarget::Online(banned_player) = target {
+            banned_player.kick(&ban.reject_message())?;
+        }
+        Ok(None)
+    }
+
+    /// ``!unban <player or Steam ID>``
+    fn cmd_unban(
+        slf: &Bound<'_, Self>,
+        _player: &Bound<'_, PyAny>,
+        msg: Vec<String>,
+        channel: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<i32>> {
+        if msg.len() < 2 {
+            return Ok(Some(PythonReturnCodes::RET_USAGE as i32));
+        }
+
+        let Some(target) = command_target(slf.py(), &msg[1])? else {
+            reply(channel, "Invalid client ID, Steam ID, or player name.")?;
+            return Ok(None);
+        };
+
+        let database = slf.as_super().get_db()?;
+        let lifted_bans = remove_bans(&database, target.steam_id())?;
+        if lifted_bans == 0 {
+            reply(channel, &format!("{}^7 is not banned.", target.name()))?;
+        } else {
+            publish_event(
+                "unban",
+                json!({"steam_id": target.steam_id(), "name": target.name()}),
+            );
+            reply(channel, &format!("{}^7 has been unbanned.", target.name()))?;
+        }
+        Ok(None)
+    }
+
+    /// Removes the expired bans, and schedules itself to run again after
+    /// ``EXPIRY_CLEANUP_INTERVAL`` seconds.
+    fn remove_expired(slf: &Bound<'_, Self>) -> PyResult<()> {
+        let py = slf.py();
+        if let Err(err) = slf
+            .as_super()
+            .get_db()
+            .and_then(|database| remove_expired_bans(&database))
+        {
+            cold_path();
+            log_exception(py, &err);
+        }
+        schedule_expiry_cleanup(slf)
+    }
+
+    /// ``!bans [player or Steam ID]``
+    fn cmd_bans(
+        slf: &Bound<'_, Self>,
+        _player: &Bound<'_, PyAny>,
+        msg: Vec<String>,
+        channel: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<i32>> {
+        let database = slf.as_super().get_db()?;
+        let bans = match msg.get(1) {
+            None => all_active_bans(&database)?,
+            Some(identifier) => {
+                let Some(target) = command_target(slf.py(), identifier)? else {
+                    reply(channel, "Invalid client ID, Steam ID, or player name.")?;
+                    return Ok(None);
+                };
+                active_bans(&database, target.steam_id())?
+            }
+        };
+
+        if bans.is_empty() {
+            reply(channel, "There are no active bans.")?;
+            return Ok(None);
+        }
+        bans.iter()
+            .try_for_each(|ban| reply(channel, &ban.description()))?;
+        Ok(None)
+    }
+}
+
+fn schedule_expiry_cleanup(bans: &Bound<'_, Bans>) -> PyResult<()> {
+    let py = bans.py();
+    py.import(intern!(py, "shinqlx"))?
+        .getattr(intern!(py, "frame_tasks"))?
+        .call_method1(
+            intern!(py, "enter"),
+            (
+                EXPIRY_CLEANUP_INTERVAL,
+                1,
+                bans.getattr(intern!(py, "remove_expired"))?,
+                (),
+                PyDict::new(py),
+            ),
+        )
+        .map(|_| ())
+}
+
+/// Starts the ban system. The ``!ban``, ``!unban``, and ``!bans`` commands are only added if the
+/// ``ban`` plugin is not loaded, since it brings commands with the same names.
+pub(crate) fn initialize_bans(py: Python<'_>) -> PyResult<()> {
+    if !database_configured(py) {
+        cold_path();
+        warn!(target: "shinqlx", "No database configured. Bans will not be enforced.");
+        return Ok(());
+    }
+
+    let bans = Bound::new(
+        py,
+        PyClassInitializer::from(Plugin::py_new(py)).add_subclass(Bans {}),
+    )?;
+
+    let ban_plugin_loaded = py
+        .get_type::<Plugin>()
+        .getattr(intern!(py, "_loaded_plugins"))
+        .and_then(|loaded_plugins| loaded_plugins.contains(intern!(py, "ban")))
+        .unwrap_or(false);
+    if !ban_plugin_loaded {
+        [
+            (
+                "ban",
+                "cmd_ban",
+                "<player> <duration> [ip=<address or range>] [reason]",
+            ),
+            ("unban", "cmd_unban", "<player>"),
+            ("bans", "cmd_bans", "[player]"),
+        ]
+        .into_iter()
+        .try_for_each(|(name, handler, usage)| {
+            bans.as_super().add_command(
+                PyString::new(py, name).as_any(),
+                &bans.getattr(handler)?,
+                2,
+                None,
+                None,
+                CommandPriorities::PRI_NORMAL as u32,
+                false,
+                5,
+                true,
+                usage,
+            )
+        })?;
+    }
+
+    let database = bans.as_super().get_db()?;
+    remove_expired_bans(&database)?;
+    refresh_ip_bans(&database)?;
+    schedule_expiry_cleanup(&bans)?;
+
+    BAN_SYSTEM.store(Some(bans.unbind().into()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod bans_tests {
+    use pretty_assertions::assert_eq;
+    use pyo3::{prelude::*, types::IntoPyDict};
+    use rstest::rstest;
+
+    use super::{
+        super::{
+            database::{InMemory, InMemoryMethods},
+            prelude::*,
+        },
+        BanDuration, PERMANENT_BAN_EXPIRY, active_bans, add_ban, all_active_bans, find_ban,
+        ip_in_range, is_valid_ip_range, refresh_ip_bans, remove_bans, remove_expired_bans,
+    };
+    use crate::prelude::*;
+
+    fn flushed_in_memory(py: Python<'_>) -> Bound<'_, PyAny> {
+        let in_memory = Bound::new(py, InMemory::py_new(py, py.None().bind(py)))
+            .expect("this should not happen");
+        in_memory
+            .connect_with_snapshot(None)
+            .expect("this should not happen");
+        in_memory
+            .call_method0("flushdb")
+            .expect("this should not happen");
+        let database = in_memory.into_any();
+        refresh_ip_bans(&database).expect("this should not happen");
+        database
+    }
+
+    #[rstest]
+    #[case("30s", Ok(BanDuration::Seconds(30)))]
+    #[case("15m", Ok(BanDuration::Seconds(15 * 60)))]
+    #[case("2H", Ok(BanDuration::Seconds(2 * 60 * 60)))]
+    #[case("7d", Ok(BanDuration::Seconds(7 * 24 * 60 * 60)))]
+    #[case("2w", Ok(BanDuration::Seconds(14 * 24 * 60 * 60)))]
+    #[case("1y", Ok(BanDuration::Seconds(365 * 24 * 60 * 60)))]
+    #[case("perm", Ok(BanDuration::Permanent))]
+    #[case("Forever", Ok(BanDuration::Permanent))]
+    #[case("0m", Err(()))]
+    #[case("15", Err(()))]
+    #[case("m", Err(()))]
+    #[case("15 minutes", Err(()))]
+    fn parse_ban_duration(#[case] duration: &str, #[case] expected: Result<BanDuration, ()>) {
+        assert_eq!(duration.parse::<BanDuration>(), expected);
+    }
+
+    #[rstest]
+    #[case("10.0.0.1", "10.0.0.1", true)]
+    #[case("10.0.0.1", "10.0.0.2", false)]
+    #[case("10.0.0.0/8", "10.255.1.2", true)]
+    #[case("10.0.0.0/8", "11.0.0.1", false)]
+    #[case("0.0.0.0/0", "192.168.0.1", true)]
+    #[case("2001:db8::/32", "2001:db8::1", true)]
+    #[case("2001:db8::/32", "2001:db9::1", false)]
+    #[case("10.0.0.0/8", "2001:db8::1", false)]
+    #[case("10.0.0.0/33", "10.0.0.1", false)]
+    #[case("10.0.0.0/8", "not an ip", false)]
+    fn ip_in_range_matches(#[case] range: &str, #[case] ip: &str, #[case] expected: bool) {
+        assert_eq!(ip_in_range(range, ip), expected);
+    }
+
+    #[rstest]
+    #[case("10.0.0.1", true)]
+    #[case("10.0.0.0/8", true)]
+    #[case("10.0.0.0/40", false)]
+    #[case("10.0.0", false)]
+    fn validates_ip_ranges(#[case] range: &str, #[case] expected: bool) {
+        assert_eq!(is_valid_ip_range(range), expected);
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn added_ban_is_found_until_lifted(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+
+            let ban = add_ban(
+                &database,
+                1234,
+                BanDuration::Seconds(3600),
+                "cheating",
+                "5678",
+                None,
+            )
+            .expect("this should not happen");
+
+            assert_eq!(
+                find_ban(&database, 1234, "10.0.0.1").expect("this should not happen"),
+                Some(ban.clone())
+            );
+            assert!(ban.reject_message().starts_with("You are banned until "));
+            assert_eq!(
+                all_active_bans(&database).expect("this should not happen"),
+                vec![ban]
+            );
+
+            assert_eq!(
+                remove_bans(&database, 1234).expect("this should not happen"),
+                1
+            );
+            assert_eq!(
+                find_ban(&database, 1234, "10.0.0.1").expect("this should not happen"),
+                None
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn ip_range_ban_applies_to_other_steam_ids(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+
+            let ban = add_ban(
+                &database,
+                1234,
+                BanDuration::Permanent,
+                "",
+                "5678",
+                Some("10.0.0.0/24"),
+            )
+            .expect("this should not happen");
+
+            assert_eq!(ban.expires, PERMANENT_BAN_EXPIRY);
+            assert_eq!(
+                ban.reject_message(),
+                "You are banned permanently: No reason given."
+            );
+            assert_eq!(
+                find_ban(&database, 4321, "10.0.0.42").expect("this should not happen"),
+                Some(ban)
+            );
+            assert_eq!(
+                find_ban(&database, 4321, "10.0.1.42").expect("this should not happen"),
+                None
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn ip_bans_are_looked_up_in_memory(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+            let ban = add_ban(
+                &database,
+                1234,
+                BanDuration::Seconds(3600),
+                "",
+                "5678",
+                Some("10.0.0.1"),
+            )
+            .expect("this should not happen");
+
+            database
+                .call_method0("flushdb")
+                .expect("this should not happen");
+            assert_eq!(
+                find_ban(&database, 4321, "10.0.0.1").expect("this should not happen"),
+                Some(ban)
+            );
+
+            refresh_ip_bans(&database).expect("this should not happen");
+            assert_eq!(
+                find_ban(&database, 4321, "10.0.0.1").expect("this should not happen"),
+                None
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn expired_bans_are_cleaned_up(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+            let ban = add_ban(
+                &database,
+                1234,
+                BanDuration::Seconds(3600),
+                "",
+                "5678",
+                None,
+            )
+            .expect("this should not happen");
+            database
+                .call_method1(
+                    "zadd",
+                    (
+                        "minqlx:bans",
+                        [(format!("1234:{}", ban.ban_id), 1)]
+                            .into_py_dict(py)
+                            .expect("this should not happen"),
+                    ),
+                )
+                .expect("this should not happen");
+
+            assert_eq!(
+                remove_expired_bans(&database).expect("this should not happen"),
+                1
+            );
+            assert_eq!(
+                active_bans(&database, 1234).expect("this should not happen"),
+                vec![]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn bans_of_the_ban_plugin_are_left_alone(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+            database
+                .call_method1(
+                    "zadd",
+                    (
+                        "minqlx:players:1234:bans",
+                        [("0", PERMANENT_BAN_EXPIRY), ("1", PERMANENT_BAN_EXPIRY)]
+                            .into_py_dict(py)
+                            .expect("this should not happen"),
+                    ),
+                )
+                .expect("this should not happen");
+            database
+                .call_method1(
+                    "hset",
+                    ("minqlx:players:1234:bans:1", "reason", "plugin ban"),
+                )
+                .expect("this should not happen");
+
+            add_ban(
+                &database,
+                1234,
+                BanDuration::Permanent,
+                "ban system ban",
+                "5678",
+                None,
+            )
+            .expect("this should not happen");
+            remove_bans(&database, 1234).expect("this should not happen");
+
+            assert_eq!(
+                database
+                    .call_method1("zcard", ("minqlx:players:1234:bans",))
+                    .and_then(|count| count.extract::<i64>())
+                    .ok(),
+                Some(2)
+            );
+            assert_eq!(
+                database
+                    .call_method1("hget", ("minqlx:players:1234:bans:1", "reason"))
+                    .and_then(|reason| reason.extract::<String>())
+                    .ok(),
+                Some("plugin ban".to_string())
+            );
+        });
+    }
+}
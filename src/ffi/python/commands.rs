@@ -11,7 +11,8 @@ use rayon::prelude::*;
 use tap::TapOptional;
 
 use super::{
-    EVENT_DISPATCHERS, PythonReturnCodes, get_cvar, owner, prelude::*, pyshinqlx_get_logger,
+    COMMANDS, EVENT_DISPATCHERS, PythonReturnCodes, get_cvar, owner, prelude::*,
+    pyshinqlx_get_logger,
 };
 use crate::{MAIN_ENGINE, quake_live_engine::FindCVar};
 
@@ -2105,6 +2106,34 @@ def remove_command(cmd):
     }
 }
 
+/// The sorted names and aliases of all registered commands, with *prefix* in front of the ones
+/// that need it.
+pub(crate) fn registered_command_names(py: Python<'_>, prefix: &str) -> Vec<String> {
+    let mut names = COMMANDS
+        .load()
+        .as_ref()
+        .map(|commands| {
+            commands
+                .bind(py)
+                .get_commands()
+                .iter()
+                .flat_map(|command| {
+                    command.name.iter().map(|name| {
+                        if command.prefix {
+                            format!("{prefix}{name}")
+                        } else {
+                            name.to_owned()
+                        }
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.dedup();
+    names
+}
+
 #[cfg(test)]
 mod command_invoker_tests {
     use core::borrow::BorrowMut;
@@ -2123,7 +2152,7 @@ mod command_invoker_tests {
         ffi::{
             c::prelude::{CVar, CVarBuilder, cvar_t},
             python::{
-                EVENT_DISPATCHERS, PythonReturnCodes,
+                COMMANDS, EVENT_DISPATCHERS, PythonReturnCodes,
                 prelude::*,
                 pyshinqlx_test_support::{
                     capturing_hook, default_command, default_test_player,
@@ -2844,4 +2873,54 @@ mod command_invoker_tests {
                 });
             });
     }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn registered_command_names_without_commands(_pyshinqlx_setup: ()) {
+        COMMANDS.store(None);
+
+        Python::attach(|py| {
+            assert!(registered_command_names(py, "!").is_empty());
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn registered_command_names_lists_prefixed_and_unprefixed_commands(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let command_invoker =
+                Bound::new(py, CommandInvoker::py_new()).expect("this should not happen");
+            [
+                Command {
+                    name: vec!["kick".into(), "k".into()],
+                    ..default_command(py)
+                },
+                Command {
+                    name: vec!["elo".into()],
+                    prefix: false,
+                    ..default_command(py)
+                },
+                Command {
+                    name: vec!["kick".into()],
+                    handler: py.None(),
+                    ..default_command(py)
+                },
+            ]
+            .into_iter()
+            .for_each(|command| {
+                let py_command = Bound::new(py, command).expect("this should not happen");
+                command_invoker
+                    .add_command(&py_command, CommandPriorities::PRI_NORMAL as usize)
+                    .expect("this should not happen");
+            });
+            COMMANDS.store(Some(command_invoker.unbind().into()));
+
+            assert_eq!(
+                registered_command_names(py, "!"),
+                vec!["!k".to_string(), "!kick".to_string(), "elo".to_string()]
+            );
+        });
+    }
 }
@@ -0,0 +1,1580 @@
+use alloc::collections::VecDeque;
+use core::{
+    cmp::max,
+    hint::cold_path,
+    ops::{Deref, DerefMut},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::Instant,
+};
+
+use chrono::Utc;
+use derive_more::Display;
+use parking_lot::{RwLock, RwLockWriteGuard};
+use pyo3::{
+    IntoPyObjectExt, create_exception,
+    exceptions::{PyException, PyKeyError, PyValueError},
+    intern,
+    prelude::*,
+    types::{PyDict, PyInt, PyTuple},
+};
+use ql_dbformat::{SnapshotError, StoredEntry, StoredValue, entries_from_json, entries_to_json};
+use serde_json::{Map, Value};
+
+use super::{
+    super::{metrics::record_in_memory_call, owner},
+    AbstractDatabase, AbstractDatabaseMethods, database_value, database_values, flag_key,
+    glob_matches, index_range, mset_mapping, permission_key, player_steam_id, score_boundary,
+    time_in_seconds, zadd_arguments,
+};
+use crate::{MAIN_ENGINE, prelude::*, quake_live_engine::FindCVar};
+
+create_exception!(pyshinqlx_module, InMemoryError, PyException);
+
+/// The store shared by all plugins using the in-memory database.
+static STORE: LazyLock<RwLock<InMemoryStore>> = LazyLock::new(Default::default);
+
+/// Write access to the [`STORE`], recording how long the call held it once it is released.
+pub(crate) struct TimedStore {
+    store: RwLockWriteGuard<'static, InMemoryStore>,
+    call_start: Instant,
+}
+
+impl Deref for TimedStore {
+    type Target = InMemoryStore;
+
+    fn deref(&self) -> &Self::Target {
+        &self.store
+    }
+}
+
+impl DerefMut for TimedStore {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.store
+    }
+}
+
+impl Drop for TimedStore {
+    fn drop(&mut self) {
+        record_in_memory_call(self.call_start.elapsed());
+    }
+}
+
+#[derive(Debug, Display, Clone, PartialEq)]
+pub(crate) enum InMemoryStoreError {
+    #[display("WRONGTYPE Operation against a key holding the wrong kind of value")]
+    WrongType,
+    #[display("value is not an integer or out of range")]
+    NotAnInteger,
+    #[display("{_0}")]
+    Snapshot(String),
+}
+
+impl From<InMemoryStoreError> for PyErr {
+    fn from(err: InMemoryStoreError) -> PyErr {
+        let error_msg = format!("{err}");
+        InMemoryError::new_err(error_msg)
+    }
+}
+
+impl From<SnapshotError> for InMemoryStoreError {
+    fn from(err: SnapshotError) -> Self {
+        Self::Snapshot(format!("{err}"))
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct InMemoryStore {
+    entries: HashMap<String, StoredEntry>,
+    snapshot_path: Option<PathBuf>,
+    connected: bool,
+}
+
+impl InMemoryStore {
+    fn live_entry(&mut self, key: &str) -> Option<&mut StoredEntry> {
+        let now = Utc::now().timestamp();
+        if self
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.is_expired(now))
+        {
+            self.entries.remove(key);
+        }
+        self.entries.get_mut(key)
+    }
+
+    fn typed<T>(
+        &mut self,
+        key: &str,
+        extract: fn(&mut StoredValue) -> Option<&mut T>,
+    ) -> Result<Option<&mut T>, InMemoryStoreError> {
+        match self.live_entry(key) {
+            None => Ok(None),
+            Some(entry) => extract(&mut entry.value)
+                .map(Some)
+                .ok_or(InMemoryStoreError::WrongType),
+        }
+    }
+
+    fn typed_or_insert<T>(
+        &mut self,
+        key: &str,
+        empty: fn() -> StoredValue,
+        extract: fn(&mut StoredValue) -> Option<&mut T>,
+    ) -> Result<&mut T, InMemoryStoreError> {
+        if self.live_entry(key).is_none() {
+            self.entries.insert(key.to_string(), empty().into());
+        }
+        self.entries
+            .get_mut(key)
+            .and_then(|entry| extract(&mut entry.value))
+            .ok_or(InMemoryStoreError::WrongType)
+    }
+
+    /// Redis removes collections once their last element is gone.
+    fn remove_if_empty(&mut self, key: &str) {
+        if self
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.value.is_empty())
+        {
+            self.entries.remove(key);
+        }
+    }
+
+    pub(crate) fn live_entries(&self) -> impl Iterator<Item = (&String, &StoredEntry)> {
+        let now = Utc::now().timestamp();
+        self.entries
+            .iter()
+            .filter(move |(_, entry)| !entry.is_expired(now))
+    }
+
+    fn contains(&mut self, key: &str) -> bool {
+        self.live_entry(key).is_some()
+    }
+
+    fn delete(&mut self, key: &str) -> bool {
+        self.live_entry(key).is_some() && self.entries.remove(key).is_some()
+    }
+
+    fn get(&mut self, key: &str) -> Result<Option<String>, InMemoryStoreError> {
+        self.typed(key, StoredValue::as_string_mut)
+            .map(|value| value.cloned())
+    }
+
+    fn set(&mut self, key: &str, value: String, expires_in: Option<i64>) {
+        let expires_at = expires_in.map(|seconds| Utc::now().timestamp() + seconds);
+        self.entries.insert(
+            key.to_string(),
+            StoredEntry {
+                value: StoredValue::String(value),
+                expires_at,
+            },
+        );
+    }
+
+    fn increment(&mut self, key: &str, amount: i64) -> Result<i64, InMemoryStoreError> {
+        let value = self.typed_or_insert(
+            key,
+            || StoredValue::String("0".to_string()),
+            StoredValue::as_string_mut,
+        )?;
+        let new_value = value
+            .parse::<i64>()
+            .ok()
+            .and_then(|current| current.checked_add(amount))
+            .ok_or(InMemoryStoreError::NotAnInteger)?;
+        *value = new_value.to_string();
+        Ok(new_value)
+    }
+
+    fn expire(&mut self, key: &str, seconds: i64) -> bool {
+        let now = Utc::now().timestamp();
+        self.live_entry(key)
+            .map(|entry| entry.expires_at = Some(now + seconds))
+            .is_some()
+    }
+
+    fn ttl(&mut self, key: &str) -> i64 {
+        let now = Utc::now().timestamp();
+        self.live_entry(key).map_or(-2, |entry| {
+            entry.expires_at.map_or(-1, |expires_at| expires_at - now)
+        })
+    }
+
+    fn sorted_members(
+        &mut self,
+        key: &str,
+        desc: bool,
+    ) -> Result<Vec<(String, f64)>, InMemoryStoreError> {
+        let mut members = self
+            .typed(key, StoredValue::as_sorted_set_mut)?
+            .map(|sorted_set| {
+                sorted_set
+                    .iter()
+                    .map(|(member, score)| (member.clone(), *score))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        members.sort_by(|(left_member, left_score), (right_member, right_score)| {
+            left_score
+                .total_cmp(right_score)
+                .then_with(|| left_member.cmp(right_member))
+        });
+        if desc {
+            members.reverse();
+        }
+        Ok(members)
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn snapshot(&self) -> Value {
+        entries_to_json(self.live_entries())
+    }
+}
+
+fn read_snapshot(path: &Path) -> Result<HashMap<String, StoredEntry>, InMemoryStoreError> {
+    let contents = fs::read_to_string(path).map_err(|err| {
+        InMemoryStoreError::Snapshot(format!("could not read {}: {err}", path.display()))
+    })?;
+    let snapshot = serde_json::from_str::<Value>(&contents).map_err(|err| {
+        InMemoryStoreError::Snapshot(format!("could not parse {}: {err}", path.display()))
+    })?;
+    entries_from_json(&snapshot).map_err(InMemoryStoreError::from)
+}
+
+fn write_snapshot(path: &Path, snapshot: &Value) -> Result<usize, InMemoryStoreError> {
+    let contents = serde_json::to_string_pretty(snapshot)
+        .map_err(|err| InMemoryStoreError::Snapshot(format!("{err}")))?;
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, contents)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|err| {
+            InMemoryStoreError::Snapshot(format!("could not write {}: {err}", path.display()))
+        })?;
+    Ok(snapshot["keys"].as_object().map_or(0, Map::len))
+}
+
+fn configured_snapshot_path() -> Option<PathBuf> {
+    MAIN_ENGINE.load().as_ref().and_then(|main_engine| {
+        main_engine
+            .find_cvar("qlx_inMemorySnapshot")
+            .map(|cvar| cvar.get_string().to_string())
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// Writes the snapshot of the in-memory database, if one is configured. Called when the game
+/// shuts down.
+pub(crate) fn save_in_memory_snapshot() {
+    let (snapshot_path, snapshot) = {
+        let store = STORE.read();
+        let Some(ref snapshot_path) = store.snapshot_path else {
+            return;
+        };
+        (snapshot_path.clone(), store.snapshot())
+    };
+
+    if let Err(err) = write_snapshot(&snapshot_path, &snapshot) {
+        cold_path();
+        error!(target: "shinqlx", "could not save in-memory database snapshot: {err}");
+    }
+}
+
+/// A subclass of :class:`shinqlx.AbstractDatabase` keeping everything in memory, for plugin
+/// tests and ephemeral servers. The data can optionally be loaded from and saved to a JSON
+/// snapshot file.
+#[pyclass(name = "InMemory", module = "database", extends = AbstractDatabase, dict, frozen)]
+pub(crate) struct InMemory {}
+
+#[pymethods]
+impl InMemory {
+    #[new]
+    pub(crate) fn py_new(py: Python<'_>, plugin: &Bound<'_, PyAny>) -> PyClassInitializer<Self> {
+        let in_memory_type = py.get_type::<Self>();
+        let counter = in_memory_type
+            .getattr(intern!(py, "_counter"))
+            .and_then(|py_counter| py_counter.extract::<i32>())
+            .unwrap_or(0);
+        let _ = in_memory_type.setattr(intern!(py, "_counter"), counter + 1);
+
+        PyClassInitializer::from(AbstractDatabase {
+            plugin: plugin.to_owned().unbind(),
+        })
+        .add_subclass(Self {})
+    }
+
+    fn __del__(slf_: &Bound<'_, Self>) -> PyResult<()> {
+        Self::close(slf_)?;
+        let in_memory_type = slf_.py().get_type::<InMemory>();
+        let counter = in_memory_type
+            .getattr(intern!(slf_.py(), "_counter"))
+            .and_then(|py_counter| py_counter.extract::<i32>())
+            .unwrap_or(0);
+        in_memory_type.setattr(intern!(slf_.py(), "_counter"), max(0, counter - 1))?;
+
+        Ok(())
+    }
+
+    fn __contains__(slf_: &Bound<'_, Self>, key: &str) -> PyResult<bool> {
+        Ok(slf_.store()?.contains(key))
+    }
+
+    fn __getitem__(slf_: &Bound<'_, Self>, key: &str) -> PyResult<String> {
+        slf_.store()?.get(key)?.ok_or_else(|| {
+            let error_msg = format!("The key '{key}' is not present in the database.");
+            PyKeyError::new_err(error_msg)
+        })
+    }
+
+    fn __setitem__(slf_: &Bound<'_, Self>, key: &str, item: &Bound<'_, PyAny>) -> PyResult<()> {
+        slf_.set_item(key, item)
+    }
+
+    fn __delitem__(slf_: &Bound<'_, Self>, key: &str) -> PyResult<()> {
+        if !slf_.store()?.delete(key) {
+            let error_msg = format!("The key '{key}' is not present in the database.");
+            return Err(PyKeyError::new_err(error_msg));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the permission of a player.
+    #[pyo3(name = "set_permission")]
+    fn set_permission(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        level: i32,
+    ) -> PyResult<()> {
+        slf.set_permission(player, level)
+    }
+
+    /// Gets the permission of a player.
+    fn get_permission(slf: &Bound<'_, Self>, player: &Bound<'_, PyAny>) -> PyResult<i32> {
+        slf.get_permission(player)
+    }
+
+    /// Checks if the player has higher than or equal to *level*.
+    #[pyo3(name = "has_permission", signature = (player, level = 5), text_signature = "(player, level=5)")]
+    fn has_permission(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        level: i32,
+    ) -> PyResult<bool> {
+        slf.has_permission(player, level)
+    }
+
+    /// Sets specified player flag
+    #[pyo3(name = "set_flag", signature = (player, flag, value = true), text_signature = "(player, flag, value = True)")]
+    fn set_flag(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        flag: &str,
+        value: bool,
+    ) -> PyResult<()> {
+        slf.set_flag(player, flag, value)
+    }
+
+    /// returns the specified player flag
+    #[pyo3(name = "get_flag", signature = (player, flag, default = false), text_signature = "(player, flag, default=False)")]
+    fn get_flag(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        flag: &str,
+        default: bool,
+    ) -> PyResult<bool> {
+        slf.get_flag(player, flag, default)
+    }
+
+    /// Prepares the in-memory database. The first call loads the snapshot file from *snapshot*,
+    /// or ``qlx_inMemorySnapshot`` if *snapshot* is None, if it exists. The data is shared
+    /// across all plugins using the in-memory database.
+    #[pyo3(name = "connect", signature = (snapshot = None), text_signature = "(snapshot = None)")]
+    fn connect<'py>(slf: &Bound<'py, Self>, snapshot: Option<&str>) -> PyResult<Bound<'py, PyAny>> {
+        match snapshot {
+            None => slf.connect(),
+            Some(snapshot_path) => slf.connect_with_snapshot(Some(PathBuf::from(snapshot_path))),
+        }
+    }
+
+    /// Saves the snapshot if this is the last plugin using the in-memory database.
+    fn close(slf: &Bound<'_, Self>) -> PyResult<()> {
+        slf.close()
+    }
+
+    /// Saves all data to *path*, or the configured snapshot file if *path* is None.
+    /// Returns the number of saved keys.
+    #[pyo3(signature = (path = None), text_signature = "(path = None)")]
+    fn save(slf: &Bound<'_, Self>, path: Option<&str>) -> PyResult<usize> {
+        let (snapshot_path, snapshot) = {
+            let store = slf.store()?;
+            let Some(snapshot_path) = path
+                .map(PathBuf::from)
+                .or_else(|| store.snapshot_path.clone())
+            else {
+                cold_path();
+                return Err(PyValueError::new_err("no snapshot file configured."));
+            };
+            (snapshot_path, store.snapshot())
+        };
+
+        slf.py()
+            .detach(|| write_snapshot(&snapshot_path, &snapshot))
+            .map_err(PyErr::from)
+    }
+
+    /// Replaces all data with the contents of *path*, or the configured snapshot file if
+    /// *path* is None. Returns the number of loaded keys.
+    #[pyo3(signature = (path = None), text_signature = "(path = None)")]
+    fn load(slf: &Bound<'_, Self>, path: Option<&str>) -> PyResult<usize> {
+        let Some(snapshot_path) = path
+            .map(PathBuf::from)
+            .or_else(|| slf.store().ok()?.snapshot_path.clone())
+        else {
+            cold_path();
+            return Err(PyValueError::new_err("no snapshot file configured."));
+        };
+
+        let entries = slf.py().detach(|| read_snapshot(&snapshot_path))?;
+        let loaded = entries.len();
+        slf.store()?.entries = entries;
+        Ok(loaded)
+    }
+
+    /// Removes all keys.
+    fn flushdb(slf: &Bound<'_, Self>) -> PyResult<bool> {
+        slf.store()?.clear();
+        Ok(true)
+    }
+
+    #[pyo3(name = "type")]
+    fn type_name(slf: &Bound<'_, Self>, name: &str) -> PyResult<&'static str> {
+        Ok(slf
+            .store()?
+            .live_entry(name)
+            .map_or("none", |entry| entry.value.type_name()))
+    }
+
+    fn get(slf: &Bound<'_, Self>, name: &str) -> PyResult<Option<String>> {
+        slf.store()?.get(name).map_err(PyErr::from)
+    }
+
+    #[pyo3(signature = (name, value, ex = None, px = None, nx = false, xx = false), text_signature = "(name, value, ex=None, px=None, nx=False, xx=False)")]
+    fn set(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        ex: Option<&Bound<'_, PyAny>>,
+        px: Option<i64>,
+        nx: bool,
+        xx: bool,
+    ) -> PyResult<Option<bool>> {
+        let db_value = database_value(value)?;
+        let expires_in = ex
+            .map(time_in_seconds)
+            .transpose()?
+            .or(px.map(|milliseconds| milliseconds / 1000));
+
+        let mut store = slf.store()?;
+        let exists = store.contains(name);
+        if (nx && exists) || (xx && !exists) {
+            return Ok(None);
+        }
+        store.set(name, db_value, expires_in);
+        Ok(Some(true))
+    }
+
+    #[pyo3(signature = (*names))]
+    fn delete(slf: &Bound<'_, Self>, names: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let keys = database_values(names.iter())?;
+        let mut store = slf.store()?;
+        Ok(keys.iter().filter(|key| store.delete(key)).count())
+    }
+
+    #[pyo3(signature = (*names))]
+    fn exists(slf: &Bound<'_, Self>, names: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let keys = database_values(names.iter())?;
+        let mut store = slf.store()?;
+        Ok(keys.iter().filter(|key| store.contains(key)).count())
+    }
+
+    #[pyo3(signature = (pattern = "*"), text_signature = "(pattern=\"*\")")]
+    fn keys(slf: &Bound<'_, Self>, pattern: &str) -> PyResult<Vec<String>> {
+        Ok(slf
+            .store()?
+            .live_entries()
+            .filter(|(key, _)| glob_matches(pattern, key))
+            .map(|(key, _)| key.clone())
+            .collect())
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn incr(slf: &Bound<'_, Self>, name: &str, amount: i64) -> PyResult<i64> {
+        slf.store()?.increment(name, amount).map_err(PyErr::from)
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn incrby(slf: &Bound<'_, Self>, name: &str, amount: i64) -> PyResult<i64> {
+        Self::incr(slf, name, amount)
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn decr(slf: &Bound<'_, Self>, name: &str, amount: i64) -> PyResult<i64> {
+        Self::incr(slf, name, -amount)
+    }
+
+    fn expire(slf: &Bound<'_, Self>, name: &str, time: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let seconds = time_in_seconds(time)?;
+        Ok(slf.store()?.expire(name, seconds))
+    }
+
+    fn ttl(slf: &Bound<'_, Self>, name: &str) -> PyResult<i64> {
+        Ok(slf.store()?.ttl(name))
+    }
+
+    fn hget(slf: &Bound<'_, Self>, name: &str, key: &str) -> PyResult<Option<String>> {
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_hash_mut)?
+            .and_then(|hash| hash.get(key).cloned()))
+    }
+
+    #[pyo3(signature = (name, key = None, value = None, mapping = None), text_signature = "(name, key=None, value=None, mapping=None)")]
+    fn hset(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        key: Option<&Bound<'_, PyAny>>,
+        value: Option<&Bound<'_, PyAny>>,
+        mapping: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<usize> {
+        let mut fields = vec![];
+        if let (Some(field), Some(field_value)) = (key, value) {
+            fields.push((database_value(field)?, database_value(field_value)?));
+        }
+        if let Some(field_mapping) = mapping {
+            field_mapping.iter().try_for_each(|(field, field_value)| {
+                fields.push((database_value(&field)?, database_value(&field_value)?));
+                Ok::<(), PyErr>(())
+            })?;
+        }
+
+        if fields.is_empty() {
+            cold_path();
+            return Err(PyValueError::new_err("'hset' with no key value pairs"));
+        }
+
+        let mut store = slf.store()?;
+        let hash = store.typed_or_insert(
+            name,
+            || StoredValue::Hash(Default::default()),
+            StoredValue::as_hash_mut,
+        )?;
+        Ok(fields
+            .into_iter()
+            .filter(|(field, field_value)| {
+                hash.insert(field.clone(), field_value.clone()).is_none()
+            })
+            .count())
+    }
+
+    fn hgetall(slf: &Bound<'_, Self>, name: &str) -> PyResult<HashMap<String, String>> {
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_hash_mut)?
+            .map(|hash| hash.clone().into_iter().collect())
+            .unwrap_or_default())
+    }
+
+    #[pyo3(signature = (name, *keys))]
+    fn hdel(slf: &Bound<'_, Self>, name: &str, keys: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let fields = database_values(keys.iter())?;
+        let mut store = slf.store()?;
+        let deleted = store
+            .typed(name, StoredValue::as_hash_mut)?
+            .map_or(0, |hash| {
+                fields
+                    .iter()
+                    .filter(|field| hash.remove(*field).is_some())
+                    .count()
+            });
+        store.remove_if_empty(name);
+        Ok(deleted)
+    }
+
+    fn hexists(slf: &Bound<'_, Self>, name: &str, key: &str) -> PyResult<bool> {
+        Self::hget(slf, name, key).map(|value| value.is_some())
+    }
+
+    #[pyo3(signature = (name, key, amount = 1), text_signature = "(name, key, amount=1)")]
+    fn hincrby(slf: &Bound<'_, Self>, name: &str, key: &str, amount: i64) -> PyResult<i64> {
+        let mut store = slf.store()?;
+        let hash = store.typed_or_insert(
+            name,
+            || StoredValue::Hash(Default::default()),
+            StoredValue::as_hash_mut,
+        )?;
+        let field_value = hash
+            .entry(key.to_string())
+            .or_insert_with(|| "0".to_string());
+        let new_value = field_value
+            .parse::<i64>()
+            .ok()
+            .and_then(|current| current.checked_add(amount))
+            .ok_or(InMemoryStoreError::NotAnInteger)?;
+        *field_value = new_value.to_string();
+        Ok(new_value)
+    }
+
+    fn hkeys(slf: &Bound<'_, Self>, name: &str) -> PyResult<Vec<String>> {
+        Self::hgetall(slf, name).map(|hash| hash.into_keys().collect())
+    }
+
+    fn hvals(slf: &Bound<'_, Self>, name: &str) -> PyResult<Vec<String>> {
+        Self::hgetall(slf, name).map(|hash| hash.into_values().collect())
+    }
+
+    fn hlen(slf: &Bound<'_, Self>, name: &str) -> PyResult<usize> {
+        Self::hgetall(slf, name).map(|hash| hash.len())
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn sadd(slf: &Bound<'_, Self>, name: &str, values: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let members = database_values(values.iter())?;
+        let mut store = slf.store()?;
+        let set = store.typed_or_insert(
+            name,
+            || StoredValue::Set(Default::default()),
+            StoredValue::as_set_mut,
+        )?;
+        Ok(members
+            .into_iter()
+            .filter(|member| set.insert(member.clone()))
+            .count())
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn srem(slf: &Bound<'_, Self>, name: &str, values: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let members = database_values(values.iter())?;
+        let mut store = slf.store()?;
+        let removed = store
+            .typed(name, StoredValue::as_set_mut)?
+            .map_or(0, |set| {
+                members.iter().filter(|member| set.remove(*member)).count()
+            });
+        store.remove_if_empty(name);
+        Ok(removed)
+    }
+
+    fn smembers(slf: &Bound<'_, Self>, name: &str) -> PyResult<HashSet<String>> {
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_set_mut)?
+            .map(|set| set.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn sismember(slf: &Bound<'_, Self>, name: &str, value: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let member = database_value(value)?;
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_set_mut)?
+            .is_some_and(|set| set.contains(&member)))
+    }
+
+    fn scard(slf: &Bound<'_, Self>, name: &str) -> PyResult<usize> {
+        Self::smembers(slf, name).map(|members| members.len())
+    }
+
+    fn zscore(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<f64>> {
+        let member = database_value(value)?;
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_sorted_set_mut)?
+            .and_then(|sorted_set| sorted_set.get(&member).copied()))
+    }
+
+    #[pyo3(signature = (name, start, end, desc = false, withscores = false), text_signature = "(name, start, end, desc=False, withscores=False)")]
+    fn zrange<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        start: i64,
+        end: i64,
+        desc: bool,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let members = slf.store()?.sorted_members(name, desc)?;
+
+        let selected = index_range(members.len(), start, end)
+            .map(|(first, last)| members[first..=last].to_vec())
+            .unwrap_or_default();
+        sorted_members_into_py(slf.py(), selected, withscores)
+    }
+
+    #[pyo3(signature = (name, start, end, withscores = false), text_signature = "(name, start, end, withscores=False)")]
+    fn zrevrange<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        start: i64,
+        end: i64,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        Self::zrange(slf, name, start, end, true, withscores)
+    }
+
+    #[pyo3(signature = (name, min, max, withscores = false), text_signature = "(name, min, max, withscores=False)")]
+    fn zrangebyscore<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        min: &Bound<'py, PyAny>,
+        max: &Bound<'py, PyAny>,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.sorted_members_by_score(name, min, max, false, withscores)
+    }
+
+    #[pyo3(signature = (name, max, min, withscores = false), text_signature = "(name, max, min, withscores=False)")]
+    fn zrevrangebyscore<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        max: &Bound<'py, PyAny>,
+        min: &Bound<'py, PyAny>,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.sorted_members_by_score(name, min, max, true, withscores)
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn zrem(slf: &Bound<'_, Self>, name: &str, values: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let members = database_values(values.iter())?;
+        let mut store = slf.store()?;
+        let removed = store
+            .typed(name, StoredValue::as_sorted_set_mut)?
+            .map_or(0, |sorted_set| {
+                members
+                    .iter()
+                    .filter(|member| sorted_set.remove(*member).is_some())
+                    .count()
+            });
+        store.remove_if_empty(name);
+        Ok(removed)
+    }
+
+    fn zcard(slf: &Bound<'_, Self>, name: &str) -> PyResult<usize> {
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_sorted_set_mut)?
+            .map_or(0, |sorted_set| sorted_set.len()))
+    }
+
+    fn zrank(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<usize>> {
+        let member = database_value(value)?;
+        let members = slf.store()?.sorted_members(name, false)?;
+        Ok(members
+            .iter()
+            .position(|(sorted_member, _)| *sorted_member == member))
+    }
+
+    fn zrevrank(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<usize>> {
+        let member = database_value(value)?;
+        let members = slf.store()?.sorted_members(name, true)?;
+        Ok(members
+            .iter()
+            .position(|(sorted_member, _)| *sorted_member == member))
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn lpush(slf: &Bound<'_, Self>, name: &str, values: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let items = database_values(values.iter())?;
+        let mut store = slf.store()?;
+        let list = store.typed_or_insert(
+            name,
+            || StoredValue::List(Default::default()),
+            StoredValue::as_list_mut,
+        )?;
+        items.into_iter().for_each(|item| list.push_front(item));
+        Ok(list.len())
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn rpush(slf: &Bound<'_, Self>, name: &str, values: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let items = database_values(values.iter())?;
+        let mut store = slf.store()?;
+        let list = store.typed_or_insert(
+            name,
+            || StoredValue::List(Default::default()),
+            StoredValue::as_list_mut,
+        )?;
+        list.extend(items);
+        Ok(list.len())
+    }
+
+    fn lpop(slf: &Bound<'_, Self>, name: &str) -> PyResult<Option<String>> {
+        let mut store = slf.store()?;
+        let popped = store
+            .typed(name, StoredValue::as_list_mut)?
+            .and_then(|list| list.pop_front());
+        store.remove_if_empty(name);
+        Ok(popped)
+    }
+
+    fn rpop(slf: &Bound<'_, Self>, name: &str) -> PyResult<Option<String>> {
+        let mut store = slf.store()?;
+        let popped = store
+            .typed(name, StoredValue::as_list_mut)?
+            .and_then(|list| list.pop_back());
+        store.remove_if_empty(name);
+        Ok(popped)
+    }
+
+    fn lrange(slf: &Bound<'_, Self>, name: &str, start: i64, end: i64) -> PyResult<Vec<String>> {
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_list_mut)?
+            .and_then(|list| {
+                index_range(list.len(), start, end)
+                    .map(|(first, last)| list.range(first..=last).cloned().collect())
+            })
+            .unwrap_or_default())
+    }
+
+    fn llen(slf: &Bound<'_, Self>, name: &str) -> PyResult<usize> {
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_list_mut)?
+            .map_or(0, |list| list.len()))
+    }
+
+    fn lindex(slf: &Bound<'_, Self>, name: &str, index: i64) -> PyResult<Option<String>> {
+        Ok(slf
+            .store()?
+            .typed(name, StoredValue::as_list_mut)?
+            .and_then(|list| {
+                index_range(list.len(), index, index)
+                    .and_then(|(first, _)| list.get(first).cloned())
+            }))
+    }
+
+    fn ltrim(slf: &Bound<'_, Self>, name: &str, start: i64, end: i64) -> PyResult<bool> {
+        let mut store = slf.store()?;
+        if let Some(list) = store.typed(name, StoredValue::as_list_mut)? {
+            *list = index_range(list.len(), start, end)
+                .map(|(first, last)| list.range(first..=last).cloned().collect())
+                .unwrap_or_default();
+        }
+        store.remove_if_empty(name);
+        Ok(true)
+    }
+
+    #[pyo3(name = "mset", signature = (*args, **kwargs))]
+    fn mset(
+        slf: &Bound<'_, Self>,
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<bool> {
+        slf.mset(args, kwargs)
+    }
+
+    #[pyo3(name = "msetnx", signature = (*args, **kwargs))]
+    fn msetnx(
+        slf: &Bound<'_, Self>,
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<bool> {
+        slf.msetnx(args, kwargs)
+    }
+
+    #[pyo3(name = "zadd", signature = (name, *args, **kwargs))]
+    fn zadd<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.zadd(name, args, kwargs)
+    }
+
+    #[pyo3(name = "zincrby", signature = (name, *, value, amount), text_signature = "(name, *, value, amount)")]
+    fn zincrby(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        amount: f64,
+    ) -> PyResult<f64> {
+        slf.zincrby(name, value, amount)
+    }
+
+    #[pyo3(name = "setex", signature = (name, *, value, time), text_signature = "(name, *, value, time)")]
+    fn setex(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        slf.setx(name, value, time)
+    }
+
+    #[pyo3(name = "setx", signature = (name, *, value, time), text_signature = "(name, *, value, time)")]
+    fn setx(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        slf.setx(name, value, time)
+    }
+
+    #[pyo3(name = "lrem", signature = (name, *, value, count), text_signature = "(name, *, value, count)")]
+    fn lrem(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        count: i64,
+    ) -> PyResult<usize> {
+        slf.lrem(name, value, count)
+    }
+}
+
+fn sorted_members_into_py<'py>(
+    py: Python<'py>,
+    members: Vec<(String, f64)>,
+    withscores: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    if withscores {
+        members.into_bound_py_any(py)
+    } else {
+        members
+            .into_iter()
+            .map(|(member, _)| member)
+            .collect::<Vec<_>>()
+            .into_bound_py_any(py)
+    }
+}
+
+impl<'py> AbstractDatabaseMethods<'py> for Bound<'py, InMemory> {
+    fn get_logger(&self) -> PyResult<Bound<'py, PyAny>> {
+        self.as_super().get_logger()
+    }
+
+    fn set_permission(&self, player: &Bound<'py, PyAny>, level: i32) -> PyResult<()> {
+        let key = permission_key(player)?;
+
+        self.set_item(&key, PyInt::new(self.py(), level).as_any())
+    }
+
+    fn get_permission(&self, player: &Bound<'py, PyAny>) -> PyResult<i32> {
+        let steam_id = player_steam_id(player)?;
+
+        if Some(steam_id) == owner()? {
+            return Ok(5);
+        }
+
+        let key = format!("minqlx:players:{steam_id}:permission");
+        match self.store()?.get(&key)? {
+            None => Ok(0),
+            Some(value) => value.parse::<i32>().map_err(|_| {
+                let error_msg = format!("invalid literal for int() with base 10: '{value}'");
+                PyValueError::new_err(error_msg)
+            }),
+        }
+    }
+
+    fn has_permission(&self, player: &Bound<'py, PyAny>, level: i32) -> PyResult<bool> {
+        self.get_permission(player).map(|value| value >= level)
+    }
+
+    fn set_flag(&self, player: &Bound<'py, PyAny>, flag: &str, value: bool) -> PyResult<()> {
+        let key = flag_key(player, flag)?;
+
+        let db_value = if value { 1i32 } else { 0i32 };
+
+        self.set_item(&key, PyInt::new(self.py(), db_value).as_any())
+    }
+
+    fn clear_flag(&self, player: &Bound<'py, PyAny>, flag: &str) -> PyResult<()> {
+        self.set_flag(player, flag, false)
+    }
+
+    fn get_flag(&self, player: &Bound<'py, PyAny>, flag: &str, default: bool) -> PyResult<bool> {
+        let key = flag_key(player, flag)?;
+
+        self.store()?
+            .get(&key)
+            .map(|value| value.map_or(default, |flag_value| flag_value != "0"))
+            .map_err(PyErr::from)
+    }
+
+    fn connect(&self) -> PyResult<Bound<'py, PyAny>> {
+        self.connect_with_snapshot(configured_snapshot_path())
+    }
+
+    fn close(&self) -> PyResult<()> {
+        let in_memory_type = self.py().get_type::<InMemory>();
+        let class_counter = in_memory_type
+            .getattr(intern!(self.py(), "_counter"))
+            .and_then(|value| value.extract::<i32>())
+            .unwrap_or(0);
+        if class_counter <= 1 {
+            self.py().detach(save_in_memory_snapshot);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) trait InMemoryMethods<'py> {
+    fn connect_with_snapshot(&self, snapshot_path: Option<PathBuf>) -> PyResult<Bound<'py, PyAny>>;
+    fn store(&self) -> PyResult<TimedStore>;
+    fn set_item(&self, key: &str, item: &Bound<'py, PyAny>) -> PyResult<()>;
+    fn sorted_members_by_score(
+        &self,
+        name: &str,
+        min: &Bound<'py, PyAny>,
+        max: &Bound<'py, PyAny>,
+        desc: bool,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn mset(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<bool>;
+    fn msetnx(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<bool>;
+    fn zadd(
+        &self,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn zincrby(&self, name: &str, value: &Bound<'py, PyAny>, amount: f64) -> PyResult<f64>;
+    fn setx(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        time: &Bound<'py, PyAny>,
+    ) -> PyResult<bool>;
+    fn lrem(&self, name: &str, value: &Bound<'py, PyAny>, count: i64) -> PyResult<usize>;
+}
+
+impl<'py> InMemoryMethods<'py> for Bound<'py, InMemory> {
+    fn connect_with_snapshot(&self, snapshot_path: Option<PathBuf>) -> PyResult<Bound<'py, PyAny>> {
+        if STORE.read().connected {
+            return Ok(self.to_owned().into_any());
+        }
+
+        let entries = match snapshot_path {
+            Some(ref path) if path.is_file() => Some(self.py().detach(|| read_snapshot(path))?),
+            _ => None,
+        };
+
+        let mut store = STORE.write();
+        if !store.connected {
+            if let Some(loaded_entries) = entries {
+                store.entries = loaded_entries;
+            }
+            store.snapshot_path = snapshot_path;
+            store.connected = true;
+        }
+
+        Ok(self.to_owned().into_any())
+    }
+
+    fn store(&self) -> PyResult<TimedStore> {
+        if !STORE.read().connected {
+            self.connect()?;
+        }
+        let call_start = Instant::now();
+        Ok(TimedStore {
+            store: STORE.write(),
+            call_start,
+        })
+    }
+
+    fn set_item(&self, key: &str, item: &Bound<'py, PyAny>) -> PyResult<()> {
+        let value = database_value(item)?;
+        self.store()?.set(key, value, None);
+        Ok(())
+    }
+
+    fn sorted_members_by_score(
+        &self,
+        name: &str,
+        min: &Bound<'py, PyAny>,
+        max: &Bound<'py, PyAny>,
+        desc: bool,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (min_score, min_exclusive) = score_boundary(min)?;
+        let (max_score, max_exclusive) = score_boundary(max)?;
+        let members = self.store()?.sorted_members(name, desc)?;
+
+        let selected = members
+            .into_iter()
+            .filter(|(_, score)| {
+                (if min_exclusive {
+                    *score > min_score
+                } else {
+                    *score >= min_score
+                }) && (if max_exclusive {
+                    *score < max_score
+                } else {
+                    *score <= max_score
+                })
+            })
+            .collect();
+        sorted_members_into_py(self.py(), selected, withscores)
+    }
+
+    fn mset(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<bool> {
+        let mapping = mset_mapping("MSET", args, kwargs)?;
+        let mut store = self.store()?;
+        mapping
+            .into_iter()
+            .for_each(|(key, value)| store.set(&key, value, None));
+        Ok(true)
+    }
+
+    fn msetnx(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<bool> {
+        let mapping = mset_mapping("MSETNX", args, kwargs)?;
+        let mut store = self.store()?;
+        if mapping.iter().any(|(key, _)| store.contains(key)) {
+            return Ok(false);
+        }
+        mapping
+            .into_iter()
+            .for_each(|(key, value)| store.set(&key, value, None));
+        Ok(true)
+    }
+
+    fn zadd(
+        &self,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (options, pieces) = zadd_arguments(args, kwargs)?;
+
+        let mut store = self.store()?;
+        let sorted_set = store.typed_or_insert(
+            name,
+            || StoredValue::SortedSet(Default::default()),
+            StoredValue::as_sorted_set_mut,
+        )?;
+        let mut changed = 0usize;
+        let mut incremented = None;
+        for (member, score) in pieces {
+            let existing = sorted_set.get(&member).copied();
+            let Some(new_score) = options.new_score(existing, score) else {
+                continue;
+            };
+
+            sorted_set.insert(member, new_score);
+            incremented = Some(new_score);
+            match existing {
+                None => changed += 1,
+                Some(old_score) if options.ch && old_score != new_score => changed += 1,
+                _ => (),
+            }
+        }
+        store.remove_if_empty(name);
+        drop(store);
+
+        if options.incr {
+            incremented.into_bound_py_any(self.py())
+        } else {
+            changed.into_bound_py_any(self.py())
+        }
+    }
+
+    fn zincrby(&self, name: &str, value: &Bound<'py, PyAny>, amount: f64) -> PyResult<f64> {
+        let member = database_value(value)?;
+        let mut store = self.store()?;
+        let sorted_set = store.typed_or_insert(
+            name,
+            || StoredValue::SortedSet(Default::default()),
+            StoredValue::as_sorted_set_mut,
+        )?;
+        let score = sorted_set.entry(member).or_default();
+        *score += amount;
+        Ok(*score)
+    }
+
+    fn setx(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        time: &Bound<'py, PyAny>,
+    ) -> PyResult<bool> {
+        let db_value = database_value(value)?;
+        let seconds = time_in_seconds(time)?;
+        self.store()?.set(name, db_value, Some(seconds));
+        Ok(true)
+    }
+
+    fn lrem(&self, name: &str, value: &Bound<'py, PyAny>, count: i64) -> PyResult<usize> {
+        let item = database_value(value)?;
+        let mut store = self.store()?;
+        let removed = store
+            .typed(name, StoredValue::as_list_mut)?
+            .map_or(0, |list| remove_list_items(list, &item, count));
+        store.remove_if_empty(name);
+        Ok(removed)
+    }
+}
+
+/// Removes *count* occurrences of *item* from the head of *list*, from the tail for negative
+/// counts, or all occurrences for a count of zero, just like ``LREM``.
+fn remove_list_items(list: &mut VecDeque<String>, item: &str, count: i64) -> usize {
+    let limit = if count == 0 {
+        usize::MAX
+    } else {
+        count.unsigned_abs() as usize
+    };
+
+    let positions: Vec<usize> = if count < 0 {
+        (0..list.len())
+            .rev()
+            .filter(|&index| list[index] == item)
+            .take(limit)
+            .collect()
+    } else {
+        (0..list.len())
+            .filter(|&index| list[index] == item)
+            .take(limit)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect()
+    };
+
+    positions.iter().for_each(|&index| {
+        list.remove(index);
+    });
+    positions.len()
+}
+
+#[cfg(test)]
+mod in_memory_tests {
+    use alloc::collections::VecDeque;
+
+    use pretty_assertions::assert_eq;
+    use pyo3::{
+        exceptions::{PyKeyError, PyValueError},
+        intern,
+        prelude::*,
+        types::{IntoPyDict, PyDict, PyTuple},
+    };
+    use rstest::rstest;
+    use tempfile::tempdir;
+
+    use super::{
+        super::super::{prelude::pyshinqlx_setup, pyshinqlx_test_support::default_test_player},
+        AbstractDatabaseMethods, InMemory, InMemoryError, InMemoryMethods, remove_list_items,
+    };
+    use crate::prelude::*;
+
+    fn flushed_in_memory(py: Python<'_>) -> Bound<'_, InMemory> {
+        let in_memory = Bound::new(py, InMemory::py_new(py, py.None().bind(py)))
+            .expect("this should not happen");
+        in_memory
+            .connect_with_snapshot(None)
+            .expect("this should not happen");
+        InMemory::flushdb(&in_memory).expect("this should not happen");
+        in_memory
+    }
+
+    #[rstest]
+    #[case(0, vec!["b", "c"])]
+    #[case(1, vec!["b", "a", "c", "a"])]
+    #[case(-1, vec!["a", "b", "a", "c"])]
+    #[case(-2, vec!["a", "b", "c"])]
+    fn remove_list_items_removes_occurrences(#[case] count: i64, #[case] expected: Vec<&str>) {
+        let mut list: VecDeque<String> = ["a", "b", "a", "c", "a"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        remove_list_items(&mut list, "a", count);
+
+        assert_eq!(list, expected);
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn setitem_and_getitem_roundtrip(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let in_memory = flushed_in_memory(py);
+
+            in_memory
+                .set_item("minqlx:test", 42i32.into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+
+            assert_eq!(
+                InMemory::__getitem__(&in_memory, "minqlx:test").expect("this should not happen"),
+                "42"
+            );
+            InMemory::__delitem__(&in_memory, "minqlx:test").expect("this should not happen");
+            let result = InMemory::__getitem__(&in_memory, "minqlx:test");
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyKeyError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn wrong_type_access_raises_in_memory_error(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let in_memory = flushed_in_memory(py);
+
+            InMemory::sadd(
+                &in_memory,
+                "minqlx:set",
+                &PyTuple::new(py, ["a"]).expect("this should not happen"),
+            )
+            .expect("this should not happen");
+
+            let result = InMemory::get(&in_memory, "minqlx:set");
+            assert!(result.is_err_and(|err| err.is_instance_of::<InMemoryError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn permissions_roundtrip(_pyshinqlx_setup: ()) {
+        MockEngineBuilder::default()
+            .with_find_cvar(|cmd| cmd == "qlx_owner", |_| None, 0..)
+            .run(|| {
+                Python::attach(|py| {
+                    let in_memory = flushed_in_memory(py);
+                    let player = Bound::new(py, default_test_player())
+                        .expect("this should not happen")
+                        .into_any();
+
+                    assert_eq!(
+                        in_memory
+                            .get_permission(&player)
+                            .expect("this should not happen"),
+                        0
+                    );
+
+                    in_memory
+                        .set_permission(&player, 2)
+                        .expect("this should not happen");
+
+                    assert!(
+                        in_memory
+                            .has_permission(&player, 2)
+                            .expect("this should not happen")
+                    );
+                    assert!(
+                        !in_memory
+                            .has_permission(&player, 3)
+                            .expect("this should not happen")
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn flags_roundtrip(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let in_memory = flushed_in_memory(py);
+            let player = Bound::new(py, default_test_player())
+                .expect("this should not happen")
+                .into_any();
+
+            in_memory
+                .set_flag(&player, "muted", true)
+                .expect("this should not happen");
+            assert!(
+                in_memory
+                    .get_flag(&player, "muted", false)
+                    .expect("this should not happen")
+            );
+
+            in_memory
+                .clear_flag(&player, "muted")
+                .expect("this should not happen");
+            assert!(
+                !in_memory
+                    .get_flag(&player, "muted", true)
+                    .expect("this should not happen")
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn sorted_sets_roundtrip(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let in_memory = flushed_in_memory(py);
+
+            let mapping = PyDict::new(py);
+            mapping
+                .set_item("player1", 10)
+                .expect("this should not happen");
+            mapping
+                .set_item("player2", 20)
+                .expect("this should not happen");
+            in_memory
+                .zadd(
+                    "minqlx:scores",
+                    &PyTuple::new(py, [mapping]).expect("this should not happen"),
+                    None,
+                )
+                .expect("this should not happen");
+            in_memory
+                .zincrby(
+                    "minqlx:scores",
+                    "player1".into_pyobject(py).unwrap().as_any(),
+                    15.0,
+                )
+                .expect("this should not happen");
+
+            let ranking = in_memory
+                .call_method(
+                    intern!(py, "zrevrange"),
+                    ("minqlx:scores", 0, -1),
+                    Some(
+                        &[("withscores", true)]
+                            .into_py_dict(py)
+                            .expect("this should not happen"),
+                    ),
+                )
+                .expect("this should not happen");
+            assert_eq!(
+                ranking
+                    .extract::<Vec<(String, f64)>>()
+                    .expect("this should not happen"),
+                vec![("player1".to_string(), 25.0), ("player2".to_string(), 20.0)]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn lists_and_lrem(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let in_memory = flushed_in_memory(py);
+
+            InMemory::rpush(
+                &in_memory,
+                "minqlx:list",
+                &PyTuple::new(py, ["a", "b", "a"]).expect("this should not happen"),
+            )
+            .expect("this should not happen");
+
+            let removed = in_memory
+                .lrem("minqlx:list", "a".into_pyobject(py).unwrap().as_any(), 0)
+                .expect("this should not happen");
+            assert_eq!(removed, 2);
+            assert_eq!(
+                InMemory::lrange(&in_memory, "minqlx:list", 0, -1).expect("this should not happen"),
+                vec!["b".to_string()]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn keys_match_glob_pattern(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let in_memory = flushed_in_memory(py);
+
+            in_memory
+                .set_item("minqlx:players:1", "a".into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+            in_memory
+                .set_item("minqlx:other", "b".into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+
+            assert_eq!(
+                InMemory::keys(&in_memory, "minqlx:players:*").expect("this should not happen"),
+                vec!["minqlx:players:1".to_string()]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn save_and_load_snapshot(_pyshinqlx_setup: ()) {
+        let snapshot_dir = tempdir().expect("this should not happen");
+        let snapshot_path = snapshot_dir.path().join("snapshot.json");
+        let snapshot_file = snapshot_path.to_string_lossy().to_string();
+
+        Python::attach(|py| {
+            let in_memory = flushed_in_memory(py);
+
+            in_memory
+                .set_item("minqlx:test", "asdf".into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+
+            assert_eq!(
+                InMemory::save(&in_memory, Some(&snapshot_file)).expect("this should not happen"),
+                1
+            );
+
+            InMemory::flushdb(&in_memory).expect("this should not happen");
+            assert_eq!(
+                InMemory::load(&in_memory, Some(&snapshot_file)).expect("this should not happen"),
+                1
+            );
+            assert_eq!(
+                InMemory::get(&in_memory, "minqlx:test").expect("this should not happen"),
+                Some("asdf".to_string())
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn save_without_configured_snapshot(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let in_memory = flushed_in_memory(py);
+
+            let result = InMemory::save(&in_memory, None);
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyValueError>(py)));
+        });
+    }
+}
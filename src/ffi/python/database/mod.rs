@@ -0,0 +1,839 @@
+mod in_memory;
+mod redis;
+mod sqlite;
+mod transfer;
+mod write_behind;
+
+use core::{cmp::max, hint::cold_path};
+
+use itertools::Itertools;
+use pyo3::{
+    PyTraverseError, PyVisit,
+    exceptions::{PyNotImplementedError, PyValueError},
+    intern,
+    prelude::*,
+    types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyString, PyTuple},
+};
+
+pub(crate) use self::{
+    in_memory::{InMemory, InMemoryError, InMemoryMethods, save_in_memory_snapshot},
+    redis::{Redis, RedisCommand, RedisError, RedisMethods, write_behind_enabled},
+    sqlite::{Sqlite, SqliteError, SqliteMethods},
+    transfer::{
+        DEFAULT_TRANSFER_PREFIX, configured_database, export_database, import_database,
+        pyshinqlx_export_database, pyshinqlx_import_database, pyshinqlx_register_migration,
+        read_export_file, write_export_file,
+    },
+    write_behind::{flush_write_behind_queue, write_queue_stats},
+};
+use super::{prelude::*, pyshinqlx_get_logger};
+
+#[pyclass(name = "AbstractDatabase", module = "database", subclass, frozen)]
+pub(crate) struct AbstractDatabase {
+    plugin: Py<PyAny>,
+}
+
+#[pymethods]
+impl AbstractDatabase {
+    #[new]
+    fn py_new(_py: Python<'_>, plugin: &Bound<'_, PyAny>) -> Self {
+        Self {
+            plugin: plugin.to_owned().unbind(),
+        }
+    }
+
+    fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+        visit.call(&self.plugin)?;
+        Ok(())
+    }
+
+    fn __clear__(&self) {}
+
+    #[getter(logger)]
+    fn get_logger<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.get_logger()
+    }
+
+    /// Abstract method. Should set the permission of a player.
+    fn set_permission(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        level: i32,
+    ) -> PyResult<()> {
+        slf.set_permission(player, level)
+    }
+
+    /// Abstract method. Should return the permission of a player.
+    fn get_permission(slf: &Bound<'_, Self>, player: &Bound<'_, PyAny>) -> PyResult<i32> {
+        slf.get_permission(player)
+    }
+
+    /// Abstract method. Should return whether or not a player has more than or equal
+    /// to a certain permission level. Should only take a value of 0 to 5, where 0 is
+    /// always True.
+    #[pyo3(signature = (player, level=5), text_signature = "(player, level=5)")]
+    fn has_permission(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        level: i32,
+    ) -> PyResult<bool> {
+        slf.has_permission(player, level)
+    }
+
+    /// Abstract method. Should set specified player flag to value.
+    #[pyo3(signature = (player, flag, value=true), text_signature = "(player, flag, value=true)")]
+    fn set_flag(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        flag: &str,
+        value: bool,
+    ) -> PyResult<()> {
+        slf.set_flag(player, flag, value)
+    }
+
+    /// Should clear specified player flag.
+    fn clear_flag(slf: &Bound<'_, Self>, player: &Bound<'_, PyAny>, flag: &str) -> PyResult<()> {
+        slf.clear_flag(player, flag)
+    }
+
+    /// Abstract method. Should return specified player flag
+    #[pyo3(signature = (player, flag, default=false), text_signature = "(player, flag, default=false)")]
+    fn get_flag(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        flag: &str,
+        default: bool,
+    ) -> PyResult<bool> {
+        slf.get_flag(player, flag, default)
+    }
+
+    /// Abstract method. Should return a connection to the database. Exactly what a
+    /// "connection" obviously depends on the database, so the specifics will be up
+    /// to the implementation.
+    ///
+    /// A :class:`shinqlx.Plugin` subclass can set
+    fn connect<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.connect()
+    }
+
+    /// Abstract method. If the database has a connection state, this method should
+    /// close the connection.
+    fn close(slf: &Bound<'_, Self>) -> PyResult<()> {
+        slf.close()
+    }
+}
+
+pub(crate) trait AbstractDatabaseMethods<'py> {
+    fn get_logger(&self) -> PyResult<Bound<'py, PyAny>>;
+    fn set_permission(&self, player: &Bound<'py, PyAny>, level: i32) -> PyResult<()>;
+    fn get_permission(&self, player: &Bound<'py, PyAny>) -> PyResult<i32>;
+    fn has_permission(&self, player: &Bound<'py, PyAny>, level: i32) -> PyResult<bool>;
+    fn set_flag(&self, player: &Bound<'py, PyAny>, flag: &str, value: bool) -> PyResult<()>;
+    fn clear_flag(&self, player: &Bound<'py, PyAny>, flag: &str) -> PyResult<()>;
+    fn get_flag(&self, player: &Bound<'py, PyAny>, flag: &str, default: bool) -> PyResult<bool>;
+    fn connect(&self) -> PyResult<Bound<'py, PyAny>>;
+    fn close(&self) -> PyResult<()>;
+}
+
+impl<'py> AbstractDatabaseMethods<'py> for Bound<'py, AbstractDatabase> {
+    fn get_logger(&self) -> PyResult<Bound<'py, PyAny>> {
+        let bound_plugin = self.get().plugin.bind(self.py()).to_owned();
+        pyshinqlx_get_logger(self.py(), Some(bound_plugin))
+    }
+
+    #[allow(unused_variables)]
+    fn set_permission(&self, player: &Bound<'_, PyAny>, level: i32) -> PyResult<()> {
+        Err(PyNotImplementedError::new_err(
+            "The abstract base database can't do database actions.",
+        ))
+    }
+
+    #[allow(unused_variables)]
+    fn get_permission(&self, player: &Bound<'_, PyAny>) -> PyResult<i32> {
+        Err(PyNotImplementedError::new_err(
+            "The abstract base database can't do database actions.",
+        ))
+    }
+
+    #[allow(unused_variables)]
+    fn has_permission(&self, player: &Bound<'_, PyAny>, level: i32) -> PyResult<bool> {
+        Err(PyNotImplementedError::new_err(
+            "The abstract base database can't do database actions.",
+        ))
+    }
+
+    #[allow(unused_variables)]
+    fn set_flag(&self, player: &Bound<'_, PyAny>, flag: &str, value: bool) -> PyResult<()> {
+        Err(PyNotImplementedError::new_err(
+            "The abstract base database can't do database actions.",
+        ))
+    }
+
+    fn clear_flag(&self, player: &Bound<'_, PyAny>, flag: &str) -> PyResult<()> {
+        self.set_flag(player, flag, false)
+    }
+
+    #[allow(unused_variables)]
+    fn get_flag(&self, player: &Bound<'_, PyAny>, flag: &str, default: bool) -> PyResult<bool> {
+        Err(PyNotImplementedError::new_err(
+            "The abstract base database can't do database actions.",
+        ))
+    }
+
+    fn connect(&self) -> PyResult<Bound<'py, PyAny>> {
+        Err(PyNotImplementedError::new_err(
+            "The abstract base database can't do database actions.",
+        ))
+    }
+
+    fn close(&self) -> PyResult<()> {
+        Err(PyNotImplementedError::new_err(
+            "The abstract base database can't do database actions.",
+        ))
+    }
+}
+
+/// The part of a database key identifying the given player. Players are identified by their
+/// SteamID64, anything else is taken by its string representation.
+pub(crate) fn player_key(player: &Bound<'_, PyAny>) -> PyResult<String> {
+    match player.extract::<Player>() {
+        Ok(rust_player) => Ok(rust_player.steam_id.to_string()),
+        _ => Ok(player.str()?.to_string()),
+    }
+}
+
+pub(crate) fn permission_key(player: &Bound<'_, PyAny>) -> PyResult<String> {
+    player_key(player).map(|player_key| format!("minqlx:players:{player_key}:permission"))
+}
+
+pub(crate) fn flag_key(player: &Bound<'_, PyAny>, flag: &str) -> PyResult<String> {
+    player_key(player).map(|player_key| format!("minqlx:players:{player_key}:flags:{flag}"))
+}
+
+/// Extracts the SteamID64 from either a :class:`shinqlx.Player` instance, an int, or a str.
+pub(crate) fn player_steam_id(player: &Bound<'_, PyAny>) -> PyResult<i64> {
+    match player.extract::<Player>() {
+        Ok(rust_player) => Ok(rust_player.steam_id),
+        _ => match player.extract::<i64>() {
+            Ok(steam_id) => Ok(steam_id),
+            _ => player
+                .extract::<String>()
+                .ok()
+                .and_then(|rust_str| rust_str.parse::<i64>().ok())
+                .ok_or_else(|| {
+                    PyValueError::new_err(
+                        "Invalid player. Use either a shinqlx.Player instance or a SteamID64.",
+                    )
+                }),
+        },
+    }
+}
+
+/// Converts a Python value into the text representation the embedded databases store, the
+/// same way the Redis database encodes values.
+pub(crate) fn database_value(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(py_string) = value.cast::<PyString>() {
+        return Ok(py_string.to_str()?.to_string());
+    }
+
+    if let Ok(py_bytes) = value.cast::<PyBytes>() {
+        return Ok(String::from_utf8_lossy(py_bytes.as_bytes()).to_string());
+    }
+
+    if !value.is_instance_of::<PyBool>()
+        && (value.is_instance_of::<PyInt>() || value.is_instance_of::<PyFloat>())
+    {
+        return Ok(value.repr()?.to_string());
+    }
+
+    cold_path();
+    let error_msg = format!(
+        "Invalid input of type: '{}'. Convert to a bytes, string, int or float first.",
+        value.get_type().name()?
+    );
+    Err(PyValueError::new_err(error_msg))
+}
+
+pub(crate) fn database_values<'py>(
+    values: impl IntoIterator<Item = Bound<'py, PyAny>>,
+) -> PyResult<Vec<String>> {
+    values
+        .into_iter()
+        .map(|value| database_value(&value))
+        .collect()
+}
+
+pub(crate) fn time_in_seconds(time: &Bound<'_, PyAny>) -> PyResult<i64> {
+    if let Ok(seconds) = time.extract::<i64>() {
+        return Ok(seconds);
+    }
+
+    time.call_method0(intern!(time.py(), "total_seconds"))
+        .and_then(|total_seconds| total_seconds.extract::<f64>())
+        .map(|total_seconds| total_seconds as i64)
+}
+
+/// Parses a score boundary as used by ``ZRANGEBYSCORE``, i.e. ``-inf``, ``+inf``, a number, or
+/// a number prefixed with ``(`` for an exclusive boundary.
+pub(crate) fn score_boundary(value: &Bound<'_, PyAny>) -> PyResult<(f64, bool)> {
+    let boundary = database_value(value)?;
+    let (exclusive, number) = boundary
+        .strip_prefix('(')
+        .map_or((false, boundary.as_str()), |number| (true, number));
+
+    number
+        .parse::<f64>()
+        .map(|score| (score, exclusive))
+        .map_err(|_| {
+            cold_path();
+            PyValueError::new_err("min or max is not a float")
+        })
+}
+
+/// Resolves Redis-style inclusive start and end indexes, which may be negative, for a
+/// collection with *len* elements.
+pub(crate) fn index_range(len: usize, start: i64, end: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let first = if start < 0 {
+        max(len + start, 0)
+    } else {
+        start
+    };
+    let last = if end < 0 { len + end } else { end.min(len - 1) };
+
+    if first > last || first >= len {
+        return None;
+    }
+
+    Some((first as usize, last as usize))
+}
+
+/// Matches *key* against a Redis-style glob *pattern* supporting ``*``, ``?``, ``[...]``
+/// character classes and ``\`` escapes.
+pub(crate) fn glob_matches(pattern: &str, key: &str) -> bool {
+    fn matches(pattern: &[char], key: &[char]) -> bool {
+        match pattern.split_first() {
+            None => key.is_empty(),
+            Some(('*', rest)) => (0..=key.len()).any(|skipped| matches(rest, &key[skipped..])),
+            Some(('?', rest)) => !key.is_empty() && matches(rest, &key[1..]),
+            Some(('[', rest)) => {
+                let Some(class_end) = rest.iter().skip(1).position(|&c| c == ']') else {
+                    return key.first() == Some(&'[') && matches(rest, &key[1..]);
+                };
+                let (class, remaining) = rest.split_at(class_end + 1);
+                let Some(&current) = key.first() else {
+                    return false;
+                };
+                let (negated, class) = match class.split_first() {
+                    Some(('^', negated_class)) => (true, negated_class),
+                    _ => (false, class),
+                };
+                let mut index = 0;
+                let mut found = false;
+                while index < class.len() {
+                    if index + 2 < class.len() && class[index + 1] == '-' {
+                        found |= (class[index]..=class[index + 2]).contains(&current);
+                        index += 3;
+                    } else {
+                        found |= class[index] == current;
+                        index += 1;
+                    }
+                }
+                found != negated && matches(&remaining[1..], &key[1..])
+            }
+            Some(('\\', rest)) if !rest.is_empty() => {
+                key.first() == Some(&rest[0]) && matches(&rest[1..], &key[1..])
+            }
+            Some((literal, rest)) => key.first() == Some(literal) && matches(rest, &key[1..]),
+        }
+    }
+
+    let pattern_chars = pattern.chars().collect::<Vec<_>>();
+    let key_chars = key.chars().collect::<Vec<_>>();
+    matches(&pattern_chars, &key_chars)
+}
+
+pub(crate) fn mset_mapping<'py>(
+    command: &str,
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Vec<(String, String)>> {
+    if args.len() > 1 {
+        cold_path();
+        let error_msg = format!("{command} requires **kwargs or a single dict arg");
+        return Err(PyValueError::new_err(error_msg));
+    }
+
+    let mapping = PyDict::new(args.py());
+    if let Ok(dict_arg) = args.get_item(0) {
+        mapping.update(dict_arg.cast::<PyDict>()?.as_mapping())?;
+    }
+
+    if let Some(kwargs_dict) = kwargs {
+        mapping.update(kwargs_dict.as_mapping())?;
+    }
+
+    mapping
+        .iter()
+        .map(|(key, value)| Ok((database_value(&key)?, database_value(&value)?)))
+        .collect()
+}
+
+const ZADD_OPTIONS: [&str; 6] = ["nx", "xx", "gt", "lt", "ch", "incr"];
+
+/// The options ``ZADD`` accepts as keyword arguments.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct ZaddOptions {
+    pub(crate) nx: bool,
+    pub(crate) xx: bool,
+    pub(crate) gt: bool,
+    pub(crate) lt: bool,
+    pub(crate) ch: bool,
+    pub(crate) incr: bool,
+}
+
+impl ZaddOptions {
+    /// Returns the score a member should be updated to, or None if these options prevent
+    /// the update.
+    pub(crate) fn new_score(&self, existing: Option<f64>, score: f64) -> Option<f64> {
+        if (self.nx && existing.is_some()) || (self.xx && existing.is_none()) {
+            return None;
+        }
+
+        let new_score = if self.incr {
+            existing.unwrap_or_default() + score
+        } else {
+            score
+        };
+
+        if existing.is_some_and(|old_score| {
+            (self.gt && new_score <= old_score) || (self.lt && new_score >= old_score)
+        }) {
+            return None;
+        }
+
+        Some(new_score)
+    }
+}
+
+/// Parses the arguments of ``ZADD``: either a single mapping of members to scores, or pairs of
+/// scores and members, with further members and scores passed as keyword arguments.
+pub(crate) fn zadd_arguments<'py>(
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<(ZaddOptions, Vec<(String, f64)>)> {
+    let option_enabled = |option: &str| {
+        kwargs
+            .and_then(|kwargs_dict| kwargs_dict.get_item(option).ok().flatten())
+            .is_some_and(|value| value.is_truthy().unwrap_or(false))
+    };
+    let [nx, xx, gt, lt, ch, incr] = ZADD_OPTIONS.map(option_enabled);
+    let options = ZaddOptions {
+        nx,
+        xx,
+        gt,
+        lt,
+        ch,
+        incr,
+    };
+
+    let mut pieces = vec![];
+    if args.len() == 1 && args.get_item(0)?.is_instance_of::<PyDict>() {
+        args.get_item(0)?
+            .cast::<PyDict>()?
+            .iter()
+            .try_for_each(|(member, score)| {
+                pieces.push((database_value(&member)?, score.extract::<f64>()?));
+                Ok::<(), PyErr>(())
+            })?;
+        return Ok((options, pieces));
+    }
+
+    if args.len() % 2 != 0 {
+        cold_path();
+        return Err(PyValueError::new_err(
+            "ZADD requires an equal number of values and scores",
+        ));
+    }
+    args.iter().tuples().try_for_each(|(score, member)| {
+        pieces.push((database_value(&member)?, score.extract::<f64>()?));
+        Ok::<(), PyErr>(())
+    })?;
+    if let Some(kwargs_dict) = kwargs {
+        kwargs_dict
+            .iter()
+            .filter(|(member, _)| {
+                member
+                    .extract::<String>()
+                    .is_ok_and(|member_name| !ZADD_OPTIONS.contains(&member_name.as_str()))
+            })
+            .try_for_each(|(member, score)| {
+                pieces.push((database_value(&member)?, score.extract::<f64>()?));
+                Ok::<(), PyErr>(())
+            })?;
+    }
+
+    Ok((options, pieces))
+}
+
+#[cfg(test)]
+mod abstract_database_tests {
+    use pyo3::{exceptions::PyNotImplementedError, intern, prelude::*};
+    use rstest::rstest;
+
+    use super::{
+        super::{
+            prelude::pyshinqlx_setup,
+            pyshinqlx_test_support::{default_test_player, test_plugin},
+        },
+        AbstractDatabase, AbstractDatabaseMethods,
+    };
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn get_logger_returns_logger_for_plugin(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let test_plugin = test_plugin(py).call0().expect("this should not happen");
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, test_plugin.as_any()))
+                    .expect("this should not happen");
+
+            let result = abstract_database.getattr(intern!(py, "logger"));
+            assert!(result.is_ok_and(|logger| {
+                logger
+                    .getattr(intern!(py, "name"))
+                    .expect("this should not happen")
+                    .to_string()
+                    == "shinqlx.test_plugin"
+            }));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn set_permission_returns_not_implemented(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, py.None().bind(py)))
+                    .expect("this should not happen");
+
+            let result = abstract_database.set_permission(
+                Bound::new(py, default_test_player())
+                    .expect("this should not happen")
+                    .as_any(),
+                42,
+            );
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)))
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn get_permission_returns_not_implemented(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, py.None().bind(py)))
+                    .expect("this should not happen");
+
+            let result = abstract_database.get_permission(
+                Bound::new(py, default_test_player())
+                    .expect("this should not happen")
+                    .as_any(),
+            );
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)))
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn has_permission_returns_not_implemented(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, py.None().bind(py)))
+                    .expect("this should not happen");
+
+            let result = abstract_database.has_permission(
+                Bound::new(py, default_test_player())
+                    .expect("this should not happen")
+                    .as_any(),
+                42,
+            );
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)))
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn set_flag_returns_not_implemented(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, py.None().bind(py)))
+                    .expect("this should not happen");
+
+            let result = abstract_database.set_flag(
+                Bound::new(py, default_test_player())
+                    .expect("this should not happen")
+                    .as_any(),
+                "asdf",
+                true,
+            );
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)))
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn clear_flag_returns_not_implemented(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, py.None().bind(py)))
+                    .expect("this should not happen");
+
+            let result = abstract_database.clear_flag(
+                Bound::new(py, default_test_player())
+                    .expect("this should not happen")
+                    .as_any(),
+                "asdf",
+            );
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)))
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn get_flag_returns_not_implemented(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, py.None().bind(py)))
+                    .expect("this should not happen");
+
+            let result = abstract_database.get_flag(
+                Bound::new(py, default_test_player())
+                    .expect("this should not happen")
+                    .as_any(),
+                "asdf",
+                true,
+            );
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)))
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn connect_returns_not_implemented(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, py.None().bind(py)))
+                    .expect("this should not happen");
+
+            let result = abstract_database.connect();
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)))
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn close_returns_not_implemented(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let abstract_database =
+                Bound::new(py, AbstractDatabase::py_new(py, py.None().bind(py)))
+                    .expect("this should not happen");
+
+            let result = abstract_database.close();
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)))
+        });
+    }
+
+    fn python_test_db(py: Python<'_>) -> Bound<'_, PyAny> {
+        PyModule::from_code(
+            py,
+            cr#"
+from shinqlx import Plugin
+from shinqlx.database import AbstractDatabase
+
+class test_plugin(Plugin):
+    pass
+
+db = AbstractDatabase(test_plugin())
+            "#,
+            c"",
+            c"",
+        )
+        .expect("this should not happen")
+        .getattr(intern!(py, "db"))
+        .expect("this should not happen")
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn get_logger_returns_logger_for_plugin_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.getattr(intern!(py, "logger"));
+            assert!(result.is_ok_and(|logger| {
+                logger
+                    .getattr(intern!(py, "name"))
+                    .expect("this should not happen")
+                    .to_string()
+                    == "shinqlx.test_plugin"
+            }));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn set_permission_returns_not_implemented_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.call_method1(intern!(py, "set_permission"), (py.None(), 0));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)));
+        })
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn get_permission_returns_not_implemented_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.call_method1(intern!(py, "get_permission"), (py.None(),));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn has_permission_returns_not_implemented_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.call_method1(intern!(py, "has_permission"), (py.None(),));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn set_flag_returns_not_implemented_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.call_method1(intern!(py, "set_flag"), (py.None(), "asdf"));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn clear_flag_returns_not_implemented_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.call_method1(intern!(py, "clear_flag"), (py.None(), "asdf"));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn get_flag_returns_not_implemented_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.call_method1(intern!(py, "get_flag"), (py.None(), "asdf"));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn connect_returns_not_implemented_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.call_method0(intern!(py, "connect"));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn close_returns_not_implemented_in_python(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let db = python_test_db(py);
+
+            let result = db.call_method0(intern!(py, "close"));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyNotImplementedError>(py)));
+        });
+    }
+}
+
+#[cfg(test)]
+mod database_helpers_tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{ZaddOptions, glob_matches, index_range};
+
+    #[rstest]
+    #[case(5, 0, -1, Some((0, 4)))]
+    #[case(5, 1, 2, Some((1, 2)))]
+    #[case(5, -2, -1, Some((3, 4)))]
+    #[case(5, 0, 10, Some((0, 4)))]
+    #[case(5, 3, 1, None)]
+    #[case(5, 7, 9, None)]
+    #[case(0, 0, -1, None)]
+    fn index_range_resolves_redis_style_indexes(
+        #[case] len: usize,
+        #[case] start: i64,
+        #[case] end: i64,
+        #[case] expected: Option<(usize, usize)>,
+    ) {
+        assert_eq!(index_range(len, start, end), expected);
+    }
+
+    #[rstest]
+    #[case("*", "minqlx:players:1", true)]
+    #[case("minqlx:players:*", "minqlx:players:1", true)]
+    #[case("minqlx:players:*", "minqlx:other", false)]
+    #[case("minqlx:players:?", "minqlx:players:12", false)]
+    #[case("minqlx:players:[0-9]", "minqlx:players:7", true)]
+    #[case("minqlx:players:[^0-9]", "minqlx:players:7", false)]
+    #[case("minqlx:\\*", "minqlx:*", true)]
+    #[case("minqlx:\\*", "minqlx:a", false)]
+    fn glob_matches_redis_style_patterns(
+        #[case] pattern: &str,
+        #[case] key: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(glob_matches(pattern, key), expected);
+    }
+
+    #[rstest]
+    #[case(ZaddOptions::default(), None, 5.0, Some(5.0))]
+    #[case(ZaddOptions::default(), Some(3.0), 5.0, Some(5.0))]
+    #[case(ZaddOptions { nx: true, ..Default::default() }, Some(3.0), 5.0, None)]
+    #[case(ZaddOptions { xx: true, ..Default::default() }, None, 5.0, None)]
+    #[case(ZaddOptions { incr: true, ..Default::default() }, Some(3.0), 5.0, Some(8.0))]
+    #[case(ZaddOptions { gt: true, ..Default::default() }, Some(7.0), 5.0, None)]
+    #[case(ZaddOptions { lt: true, ..Default::default() }, Some(3.0), 5.0, None)]
+    #[case(ZaddOptions { lt: true, ..Default::default() }, None, 5.0, Some(5.0))]
+    fn zadd_options_new_score(
+        #[case] options: ZaddOptions,
+        #[case] existing: Option<f64>,
+        #[case] score: f64,
+        #[case] expected: Option<f64>,
+    ) {
+        assert_eq!(options.new_score(existing, score), expected);
+    }
+}
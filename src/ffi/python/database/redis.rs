@@ -0,0 +1,2292 @@
+use alloc::sync::Arc;
+use core::{cmp::max, hint::cold_path, time::Duration};
+use std::{path::PathBuf, sync::LazyLock, time::Instant};
+
+use ::redis::{
+    Client, Connection, ConnectionAddr, ConnectionInfo, IntoConnectionInfo, RedisConnectionInfo,
+    Value, cmd,
+};
+use arc_swap::ArcSwapOption;
+use itertools::Itertools;
+use pyo3::{
+    PyTraverseError, PyVisit, create_exception,
+    exceptions::{
+        PyAttributeError, PyEnvironmentError, PyException, PyKeyError, PyRuntimeError, PyValueError,
+    },
+    intern,
+    marker::Ungil,
+    prelude::*,
+    types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyList, PySet, PyString, PyTuple},
+};
+
+use super::{
+    super::{metrics::record_redis_call, owner},
+    AbstractDatabase, AbstractDatabaseMethods, flag_key, mset_mapping, permission_key,
+    player_steam_id, time_in_seconds,
+    write_behind::{
+        CommandKeys, FLUSH_TIMEOUT, command_keys, enqueue_write, flush_pending_writes,
+        has_pending_writes_for, write_queue_stats,
+    },
+    zadd_arguments,
+};
+use crate::{MAIN_ENGINE, prelude::*, quake_live_engine::FindCVar};
+
+create_exception!(pyshinqlx_module, RedisError, PyException);
+
+/// A connection to the Redis server that can be used while the GIL is released.
+pub(crate) struct RedisConnection(parking_lot::Mutex<Connection>);
+
+unsafe impl Ungil for RedisConnection {}
+
+impl RedisConnection {
+    pub(crate) fn new(connection: Connection) -> Self {
+        Self(parking_lot::Mutex::new(connection))
+    }
+
+    pub(crate) fn lock(&self) -> parking_lot::MutexGuard<'_, Connection> {
+        self.0.lock()
+    }
+}
+
+/// The connection shared by all plugins using the default configuration from the
+/// ``qlx_redis*`` cvars.
+static DEFAULT_CONNECTION: LazyLock<ArcSwapOption<RedisConnection>> =
+    LazyLock::new(ArcSwapOption::empty);
+
+fn redis_error(err: ::redis::RedisError) -> PyErr {
+    let error_msg = format!("{err}");
+    RedisError::new_err(error_msg)
+}
+
+fn connection_info(
+    host: &str,
+    database: i64,
+    unix_socket: bool,
+    password: Option<&str>,
+) -> PyResult<ConnectionInfo> {
+    let address = if unix_socket {
+        ConnectionAddr::Unix(PathBuf::from(host))
+    } else {
+        let (redis_hostname, port) = host.split_once(':').unwrap_or((host, "6379"));
+        let redis_port = if port.is_empty() {
+            6379
+        } else {
+            port.parse::<u16>().map_err(|_| {
+                let error_msg = format!("invalid redis port: '{port}'");
+                PyValueError::new_err(error_msg)
+            })?
+        };
+        ConnectionAddr::Tcp(redis_hostname.to_string(), redis_port)
+    };
+
+    let mut redis_settings = RedisConnectionInfo::default()
+        .set_db(database)
+        .set_skip_set_lib_name();
+    if let Some(redis_password) = password.filter(|value| !value.is_empty()) {
+        redis_settings = redis_settings.set_password(redis_password);
+    }
+
+    address
+        .into_connection_info()
+        .map(|info| info.set_redis_settings(redis_settings))
+        .map_err(redis_error)
+}
+
+fn open_connection(py: Python<'_>, info: ConnectionInfo) -> PyResult<Arc<RedisConnection>> {
+    py.detach(|| {
+        Client::open(info)
+            .and_then(|client| client.get_connection())
+            .map(|connection| Arc::new(RedisConnection::new(connection)))
+            .map_err(|err| format!("{err}"))
+    })
+    .map_err(RedisError::new_err)
+}
+
+fn default_connection_info() -> PyResult<ConnectionInfo> {
+    let Some(ref main_engine) = *MAIN_ENGINE.load() else {
+        cold_path();
+        return Err(PyEnvironmentError::new_err(
+            "could not get access to main engine.",
+        ));
+    };
+
+    let Some(cvar_host) = main_engine.find_cvar("qlx_redisAddress") else {
+        cold_path();
+        return Err(PyValueError::new_err("cvar qlx_redisAddress misconfigured"));
+    };
+    let Some(redis_db_cvar) = main_engine
+        .find_cvar("qlx_redisDatabase")
+        .and_then(|cvar| cvar.get_string().parse::<i64>().ok())
+    else {
+        cold_path();
+        return Err(PyValueError::new_err(
+            "cvar qlx_redisDatabase misconfigured.",
+        ));
+    };
+    let Some(unix_socket_cvar) = main_engine.find_cvar("qlx_redisUnixSocket").map(|cvar| {
+        let cvar_string = cvar.get_string();
+        !cvar_string.is_empty() && cvar_string != "0"
+    }) else {
+        cold_path();
+        return Err(PyValueError::new_err(
+            "cvar qlx_redisUnixSocket misconfigured.",
+        ));
+    };
+    let Some(password_cvar) = main_engine.find_cvar("qlx_redisPassword") else {
+        cold_path();
+        return Err(PyValueError::new_err(
+            "cvar qlx_redisPassword misconfigured.",
+        ));
+    };
+
+    connection_info(
+        &cvar_host.get_string(),
+        redis_db_cvar,
+        unix_socket_cvar,
+        Some(&password_cvar.get_string()),
+    )
+}
+
+/// Whether writes to Redis are queued for the write-behind worker, as set by
+/// ``qlx_redisWriteBehind``.
+pub(crate) fn write_behind_enabled() -> bool {
+    MAIN_ENGINE.load().as_ref().is_some_and(|main_engine| {
+        main_engine
+            .find_cvar("qlx_redisWriteBehind")
+            .is_some_and(|cvar| {
+                let cvar_string = cvar.get_string();
+                !cvar_string.is_empty() && cvar_string != "0"
+            })
+    })
+}
+
+/// Encodes a Python value the same way the Python redis package does it.
+pub(crate) fn to_redis_arg(value: &Bound<'_, PyAny>) -> PyResult<Vec<u8>> {
+    if let Ok(py_bytes) = value.cast::<PyBytes>() {
+        return Ok(py_bytes.as_bytes().to_vec());
+    }
+
+    if let Ok(py_string) = value.cast::<PyString>() {
+        return Ok(py_string.to_str()?.as_bytes().to_vec());
+    }
+
+    if !value.is_instance_of::<PyBool>()
+        && (value.is_instance_of::<PyInt>() || value.is_instance_of::<PyFloat>())
+    {
+        return Ok(value.repr()?.to_string().into_bytes());
+    }
+
+    cold_path();
+    let error_msg = format!(
+        "Invalid input of type: '{}'. Convert to a bytes, string, int or float first.",
+        value.get_type().name()?
+    );
+    Err(RedisError::new_err(error_msg))
+}
+
+fn to_redis_args<'py>(
+    values: impl IntoIterator<Item = Bound<'py, PyAny>>,
+) -> PyResult<Vec<Vec<u8>>> {
+    values
+        .into_iter()
+        .map(|value| to_redis_arg(&value))
+        .collect()
+}
+
+/// Converts a Redis response into its Python counterpart with decoded responses.
+pub(crate) fn redis_value_to_py(py: Python<'_>, value: Value) -> PyResult<Bound<'_, PyAny>> {
+    match value {
+        Value::Nil => Ok(py.None().into_bound(py)),
+        Value::Int(int_value) => Ok(PyInt::new(py, int_value).into_any()),
+        Value::BulkString(bytes) => {
+            Ok(PyString::new(py, &String::from_utf8_lossy(&bytes)).into_any())
+        }
+        Value::SimpleString(string) => Ok(PyString::new(py, &string).into_any()),
+        Value::VerbatimString { text, .. } => Ok(PyString::new(py, &text).into_any()),
+        Value::Okay => Ok(PyBool::new(py, true).to_owned().into_any()),
+        Value::Boolean(bool_value) => Ok(PyBool::new(py, bool_value).to_owned().into_any()),
+        Value::Double(float_value) => Ok(PyFloat::new(py, float_value).into_any()),
+        Value::Array(values) | Value::Set(values) | Value::Push { data: values, .. } => values
+            .into_iter()
+            .map(|item| redis_value_to_py(py, item))
+            .collect::<PyResult<Vec<_>>>()
+            .and_then(|items| PyList::new(py, items))
+            .map(|list| list.into_any()),
+        Value::Map(pairs) => {
+            let dict = PyDict::new(py);
+            pairs.into_iter().try_for_each(|(key, item)| {
+                dict.set_item(redis_value_to_py(py, key)?, redis_value_to_py(py, item)?)
+            })?;
+            Ok(dict.into_any())
+        }
+        Value::Attribute { data, .. } => redis_value_to_py(py, *data),
+        Value::BigNumber(big_number) => {
+            Ok(PyString::new(py, &format!("{big_number:?}")).into_any())
+        }
+        Value::ServerError(server_error) => {
+            cold_path();
+            let error_msg = format!("{server_error:?}");
+            Err(RedisError::new_err(error_msg))
+        }
+        unsupported_value => {
+            cold_path();
+            let error_msg = format!("unsupported redis response: {unsupported_value:?}");
+            Err(RedisError::new_err(error_msg))
+        }
+    }
+}
+
+fn redis_value_to_bool(value: &Value) -> bool {
+    match value {
+        Value::Okay | Value::Boolean(true) => true,
+        Value::Int(int_value) => *int_value != 0,
+        Value::SimpleString(string) => string == "OK",
+        _ => false,
+    }
+}
+
+fn redis_value_to_float(py: Python<'_>, value: Value) -> PyResult<Bound<'_, PyAny>> {
+    match value {
+        Value::Nil => Ok(py.None().into_bound(py)),
+        Value::Double(float_value) => Ok(PyFloat::new(py, float_value).into_any()),
+        Value::Int(int_value) => Ok(PyFloat::new(py, int_value as f64).into_any()),
+        Value::BulkString(bytes) => String::from_utf8_lossy(&bytes)
+            .parse::<f64>()
+            .map(|float_value| PyFloat::new(py, float_value).into_any())
+            .map_err(|_| {
+                cold_path();
+                RedisError::new_err("could not convert redis response to float.")
+            }),
+        other => redis_value_to_py(py, other),
+    }
+}
+
+fn redis_value_to_set(py: Python<'_>, value: Value) -> PyResult<Bound<'_, PyAny>> {
+    match value {
+        Value::Array(values) | Value::Set(values) => values
+            .into_iter()
+            .map(|item| redis_value_to_py(py, item))
+            .collect::<PyResult<Vec<_>>>()
+            .and_then(|items| PySet::new(py, items))
+            .map(|set| set.into_any()),
+        other => redis_value_to_py(py, other),
+    }
+}
+
+fn redis_value_to_dict(py: Python<'_>, value: Value) -> PyResult<Bound<'_, PyAny>> {
+    match value {
+        Value::Array(values) => {
+            let dict = PyDict::new(py);
+            values.into_iter().tuples().try_for_each(|(key, item)| {
+                dict.set_item(redis_value_to_py(py, key)?, redis_value_to_py(py, item)?)
+            })?;
+            Ok(dict.into_any())
+        }
+        other => redis_value_to_py(py, other),
+    }
+}
+
+fn redis_value_to_score_pairs(py: Python<'_>, value: Value) -> PyResult<Bound<'_, PyAny>> {
+    match value {
+        Value::Array(values) => values
+            .into_iter()
+            .tuples()
+            .map(|(member, score)| {
+                PyTuple::new(
+                    py,
+                    [
+                        redis_value_to_py(py, member)?,
+                        redis_value_to_float(py, score)?,
+                    ],
+                )
+            })
+            .collect::<PyResult<Vec<_>>>()
+            .and_then(|pairs| PyList::new(py, pairs))
+            .map(|list| list.into_any()),
+        other => redis_value_to_py(py, other),
+    }
+}
+
+/// A subclass of :class:`shinqlx.AbstractDatabase` providing support for Redis.
+#[pyclass(name = "Redis", module = "database", extends = AbstractDatabase, dict, frozen)]
+pub(crate) struct Redis {
+    connection: ArcSwapOption<RedisConnection>,
+}
+
+#[pymethods]
+impl Redis {
+    #[new]
+    fn py_new(py: Python<'_>, plugin: &Bound<'_, PyAny>) -> PyClassInitializer<Self> {
+        let redis_type = py.get_type::<Self>();
+        let counter = redis_type
+            .getattr(intern!(py, "_counter"))
+            .and_then(|py_counter| py_counter.extract::<i32>())
+            .unwrap_or(0);
+        let _ = redis_type.setattr(intern!(py, "_counter"), counter + 1);
+
+        PyClassInitializer::from(AbstractDatabase {
+            plugin: plugin.to_owned().unbind(),
+        })
+        .add_subclass(Self {
+            connection: ArcSwapOption::empty(),
+        })
+    }
+
+    fn __del__(slf_: &Bound<'_, Self>) -> PyResult<()> {
+        Self::close(slf_)?;
+        let redis_type = slf_.py().get_type::<Redis>();
+        let counter = redis_type
+            .getattr(intern!(slf_.py(), "_counter"))
+            .and_then(|py_counter| py_counter.extract::<i32>())
+            .unwrap_or(0);
+        redis_type.setattr(intern!(slf_.py(), "_counter"), max(0, counter - 1))?;
+
+        Ok(())
+    }
+
+    /// Kept for compatibility with plugins that access the underlying redis connection
+    /// directly. Redis commands are implemented by the database itself.
+    #[getter(r)]
+    fn get_redis<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.get_redis()
+    }
+
+    fn __contains__(slf_: &Bound<'_, Self>, key: &str) -> PyResult<bool> {
+        slf_.execute_command("EXISTS", vec![key.into()])
+            .map(|value| redis_value_to_bool(&value))
+    }
+
+    fn __getitem__<'py>(slf_: &Bound<'py, Self>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        match slf_.execute_command("GET", vec![key.into()])? {
+            Value::Nil => {
+                let error_msg = format!("The key '{key}' is not present in the database.");
+                Err(PyKeyError::new_err(error_msg))
+            }
+            value => redis_value_to_py(slf_.py(), value),
+        }
+    }
+
+    fn __setitem__(slf_: &Bound<'_, Self>, key: &str, item: &Bound<'_, PyAny>) -> PyResult<()> {
+        slf_.set_item(key, item)
+    }
+
+    fn __delitem__(slf_: &Bound<'_, Self>, key: &str) -> PyResult<()> {
+        let returned = slf_.execute_command("DEL", vec![key.into()])?;
+
+        if !redis_value_to_bool(&returned) {
+            let error_msg = format!("The key '{key}' is not present in the database.");
+            return Err(PyKeyError::new_err(error_msg));
+        }
+
+        Ok(())
+    }
+
+    /// Any Redis command without a dedicated method is sent to the server as is,
+    /// with the attribute name as the command name.
+    fn __getattr__(slf_: &Bound<'_, Self>, attr: &str) -> PyResult<RedisCommand> {
+        if attr.starts_with('_') {
+            cold_path();
+            let error_msg = format!("'Redis' object has no attribute '{attr}'");
+            return Err(PyAttributeError::new_err(error_msg));
+        }
+
+        Ok(RedisCommand {
+            database: slf_.to_owned().unbind(),
+            command: attr.to_uppercase(),
+        })
+    }
+
+    /// Sets the permission of a player.
+    #[pyo3(name = "set_permission")]
+    fn set_permission(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        level: i32,
+    ) -> PyResult<()> {
+        slf.set_permission(player, level)
+    }
+
+    /// Gets the permission of a player.
+    fn get_permission(slf: &Bound<'_, Self>, player: &Bound<'_, PyAny>) -> PyResult<i32> {
+        slf.get_permission(player)
+    }
+
+    /// Checks if the player has higher than or equal to *level*.
+    #[pyo3(name = "has_permission", signature = (player, level = 5), text_signature = "(player, level=5)")]
+    fn has_permission(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        level: i32,
+    ) -> PyResult<bool> {
+        slf.has_permission(player, level)
+    }
+
+    /// Sets specified player flag
+    #[pyo3(name = "set_flag", signature = (player, flag, value = true), text_signature = "(player, flag, value = True)")]
+    fn set_flag(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        flag: &str,
+        value: bool,
+    ) -> PyResult<()> {
+        slf.set_flag(player, flag, value)
+    }
+
+    /// returns the specified player flag
+    #[pyo3(name = "get_flag", signature = (player, flag, default = false), text_signature = "(player, flag, default=False)")]
+    fn get_flag(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        flag: &str,
+        default: bool,
+    ) -> PyResult<bool> {
+        slf.get_flag(player, flag, default)
+    }
+
+    /// Returns a connection to a Redis database. If *host* is None, it will
+    /// fall back to the settings in the config and ignore the rest of the arguments.
+    /// It will also share the connection across any plugins using the default
+    /// configuration. Passing *host* will make it connect to a specific database
+    /// that is not shared at all. Subsequent calls to this will return the connection
+    /// initialized the first call unless it has been closed.
+    #[pyo3(name = "connect", signature = (host = None, database = 0, unix_socket = false, password = None), text_signature = "(host = None, database = 0, unix_socket = false, password = None)")]
+    fn connect<'py>(
+        slf: &Bound<'py, Self>,
+        host: Option<&str>,
+        database: i64,
+        unix_socket: bool,
+        password: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        match host {
+            None => slf.connect(),
+            Some(hostname) => {
+                slf.connect_with_parameters(hostname, database, unix_socket, password)
+            }
+        }
+    }
+
+    /// Close the Redis connection if the config was overridden. Otherwise only do so
+    /// if this is the last plugin using the default connection.
+    fn close(slf: &Bound<'_, Self>) -> PyResult<()> {
+        slf.close()
+    }
+
+    fn get<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("GET", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, value, ex = None, px = None, nx = false, xx = false), text_signature = "(name, value, ex=None, px=None, nx=False, xx=False)")]
+    fn set<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        ex: Option<&Bound<'py, PyAny>>,
+        px: Option<i64>,
+        nx: bool,
+        xx: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut args = vec![name.into(), to_redis_arg(value)?];
+        if let Some(seconds) = ex {
+            args.extend([
+                "EX".into(),
+                time_in_seconds(seconds)?.to_string().into_bytes(),
+            ]);
+        }
+        if let Some(milliseconds) = px {
+            args.extend(["PX".into(), milliseconds.to_string().into_bytes()]);
+        }
+        if nx {
+            args.push("NX".into());
+        }
+        if xx {
+            args.push("XX".into());
+        }
+
+        match slf.execute_command("SET", args)? {
+            Value::Nil => Ok(slf.py().None().into_bound(slf.py())),
+            value => Ok(PyBool::new(slf.py(), redis_value_to_bool(&value))
+                .to_owned()
+                .into_any()),
+        }
+    }
+
+    #[pyo3(signature = (*names))]
+    fn delete<'py>(
+        slf: &Bound<'py, Self>,
+        names: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("DEL", to_redis_args(names.iter())?)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (*names))]
+    fn exists<'py>(
+        slf: &Bound<'py, Self>,
+        names: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("EXISTS", to_redis_args(names.iter())?)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (pattern = "*"), text_signature = "(pattern=\"*\")")]
+    fn keys<'py>(slf: &Bound<'py, Self>, pattern: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("KEYS", vec![pattern.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn incr<'py>(slf: &Bound<'py, Self>, name: &str, amount: i64) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("INCRBY", vec![name.into(), amount.to_string().into_bytes()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn incrby<'py>(slf: &Bound<'py, Self>, name: &str, amount: i64) -> PyResult<Bound<'py, PyAny>> {
+        Self::incr(slf, name, amount)
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn decr<'py>(slf: &Bound<'py, Self>, name: &str, amount: i64) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("DECRBY", vec![name.into(), amount.to_string().into_bytes()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn expire(slf: &Bound<'_, Self>, name: &str, time: &Bound<'_, PyAny>) -> PyResult<bool> {
+        slf.execute_command(
+            "EXPIRE",
+            vec![name.into(), time_in_seconds(time)?.to_string().into_bytes()],
+        )
+        .map(|value| redis_value_to_bool(&value))
+    }
+
+    fn ttl<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("TTL", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn hget<'py>(slf: &Bound<'py, Self>, name: &str, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("HGET", vec![name.into(), key.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, key = None, value = None, mapping = None), text_signature = "(name, key=None, value=None, mapping=None)")]
+    fn hset<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        key: Option<&Bound<'py, PyAny>>,
+        value: Option<&Bound<'py, PyAny>>,
+        mapping: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut args = vec![name.into()];
+        if let (Some(field), Some(field_value)) = (key, value) {
+            args.extend([to_redis_arg(field)?, to_redis_arg(field_value)?]);
+        }
+        if let Some(fields) = mapping {
+            fields.iter().try_for_each(|(field, field_value)| {
+                args.extend([to_redis_arg(&field)?, to_redis_arg(&field_value)?]);
+                Ok::<(), PyErr>(())
+            })?;
+        }
+
+        if args.len() == 1 {
+            cold_path();
+            return Err(RedisError::new_err("'hset' with no key value pairs"));
+        }
+
+        slf.execute_command("HSET", args)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn hgetall<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("HGETALL", vec![name.into()])
+            .and_then(|value| redis_value_to_dict(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, *keys))]
+    fn hdel<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        keys: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut args = vec![name.into()];
+        args.extend(to_redis_args(keys.iter())?);
+        slf.execute_command("HDEL", args)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn hexists(slf: &Bound<'_, Self>, name: &str, key: &str) -> PyResult<bool> {
+        slf.execute_command("HEXISTS", vec![name.into(), key.into()])
+            .map(|value| redis_value_to_bool(&value))
+    }
+
+    #[pyo3(signature = (name, key, amount = 1), text_signature = "(name, key, amount=1)")]
+    fn hincrby<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        key: &str,
+        amount: i64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command(
+            "HINCRBY",
+            vec![name.into(), key.into(), amount.to_string().into_bytes()],
+        )
+        .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn hkeys<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("HKEYS", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn hvals<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("HVALS", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn hlen<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("HLEN", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn sadd<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        values: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut args = vec![name.into()];
+        args.extend(to_redis_args(values.iter())?);
+        slf.execute_command("SADD", args)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn srem<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        values: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut args = vec![name.into()];
+        args.extend(to_redis_args(values.iter())?);
+        slf.execute_command("SREM", args)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn smembers<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("SMEMBERS", vec![name.into()])
+            .and_then(|value| redis_value_to_set(slf.py(), value))
+    }
+
+    fn sismember(slf: &Bound<'_, Self>, name: &str, value: &Bound<'_, PyAny>) -> PyResult<bool> {
+        slf.execute_command("SISMEMBER", vec![name.into(), to_redis_arg(value)?])
+            .map(|value| redis_value_to_bool(&value))
+    }
+
+    fn scard<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("SCARD", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn zscore<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("ZSCORE", vec![name.into(), to_redis_arg(value)?])
+            .and_then(|value| redis_value_to_float(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, start, end, desc = false, withscores = false), text_signature = "(name, start, end, desc=False, withscores=False)")]
+    fn zrange<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        start: i64,
+        end: i64,
+        desc: bool,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let command = if desc { "ZREVRANGE" } else { "ZRANGE" };
+        slf.zrange_command(
+            command,
+            vec![
+                name.into(),
+                start.to_string().into_bytes(),
+                end.to_string().into_bytes(),
+            ],
+            withscores,
+        )
+    }
+
+    #[pyo3(signature = (name, start, end, withscores = false), text_signature = "(name, start, end, withscores=False)")]
+    fn zrevrange<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        start: i64,
+        end: i64,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        Self::zrange(slf, name, start, end, true, withscores)
+    }
+
+    #[pyo3(signature = (name, min, max, withscores = false), text_signature = "(name, min, max, withscores=False)")]
+    fn zrangebyscore<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        min: &Bound<'py, PyAny>,
+        max: &Bound<'py, PyAny>,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.zrange_command(
+            "ZRANGEBYSCORE",
+            vec![name.into(), to_redis_arg(min)?, to_redis_arg(max)?],
+            withscores,
+        )
+    }
+
+    #[pyo3(signature = (name, max, min, withscores = false), text_signature = "(name, max, min, withscores=False)")]
+    fn zrevrangebyscore<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        max: &Bound<'py, PyAny>,
+        min: &Bound<'py, PyAny>,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.zrange_command(
+            "ZREVRANGEBYSCORE",
+            vec![name.into(), to_redis_arg(max)?, to_redis_arg(min)?],
+            withscores,
+        )
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn zrem<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        values: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut args = vec![name.into()];
+        args.extend(to_redis_args(values.iter())?);
+        slf.execute_command("ZREM", args)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn zcard<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("ZCARD", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn zrank<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("ZRANK", vec![name.into(), to_redis_arg(value)?])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn zrevrank<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("ZREVRANK", vec![name.into(), to_redis_arg(value)?])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn lpush<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        values: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut args = vec![name.into()];
+        args.extend(to_redis_args(values.iter())?);
+        slf.execute_command("LPUSH", args)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn rpush<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        values: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut args = vec![name.into()];
+        args.extend(to_redis_args(values.iter())?);
+        slf.execute_command("RPUSH", args)
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn lpop<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("LPOP", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn rpop<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("RPOP", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn lrange<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        start: i64,
+        end: i64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command(
+            "LRANGE",
+            vec![
+                name.into(),
+                start.to_string().into_bytes(),
+                end.to_string().into_bytes(),
+            ],
+        )
+        .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn llen<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("LLEN", vec![name.into()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn lindex<'py>(slf: &Bound<'py, Self>, name: &str, index: i64) -> PyResult<Bound<'py, PyAny>> {
+        slf.execute_command("LINDEX", vec![name.into(), index.to_string().into_bytes()])
+            .and_then(|value| redis_value_to_py(slf.py(), value))
+    }
+
+    fn ltrim(slf: &Bound<'_, Self>, name: &str, start: i64, end: i64) -> PyResult<bool> {
+        slf.execute_command(
+            "LTRIM",
+            vec![
+                name.into(),
+                start.to_string().into_bytes(),
+                end.to_string().into_bytes(),
+            ],
+        )
+        .map(|value| redis_value_to_bool(&value))
+    }
+
+    #[pyo3(name = "mset", signature = (*args, **kwargs))]
+    fn mset<'py>(
+        slf: &Bound<'py, Self>,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.mset(args, kwargs)
+    }
+
+    #[pyo3(name = "msetnx", signature = (*args, **kwargs))]
+    fn msetnx<'py>(
+        slf: &Bound<'py, Self>,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.msetnx(args, kwargs)
+    }
+
+    #[pyo3(name = "zadd", signature = (name, *args, **kwargs))]
+    fn zadd<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.zadd(name, args, kwargs)
+    }
+
+    #[pyo3(name = "zincrby", signature = (name, *, value, amount), text_signature = "(name, *, value, amount)")]
+    fn zincrby<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        amount: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.zincrby(name, value, amount)
+    }
+
+    #[pyo3(name = "setex", signature = (name, *, value, time), text_signature = "(name, *, value, time)")]
+    fn setex<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        time: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.setx(name, value, time)
+    }
+
+    #[pyo3(name = "setx", signature = (name, *, value, time), text_signature = "(name, *, value, time)")]
+    fn setx<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        time: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.setx(name, value, time)
+    }
+
+    #[pyo3(name = "lrem", signature = (name, *, value, count), text_signature = "(name, *, value, count)")]
+    fn lrem<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        count: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.lrem(name, value, count)
+    }
+
+    /// Queues a write command to be sent to the server by a background worker, without waiting
+    /// for the reply. Queued writes are sent in order, batched into pipelines, and always reach
+    /// the server before any later command on the same key.
+    #[pyo3(name = "enqueue", signature = (command, *args), text_signature = "(command, *args)")]
+    fn enqueue(slf: &Bound<'_, Self>, command: &str, args: &Bound<'_, PyTuple>) -> PyResult<()> {
+        let redis_args = to_redis_args(args.iter())?;
+        slf.queue_command(&command.to_uppercase(), redis_args)
+    }
+
+    /// Waits until all queued writes reached the server, at most *timeout* seconds. Returns
+    /// whether the queue was flushed completely.
+    #[pyo3(name = "flush", signature = (timeout = 5.0), text_signature = "(timeout=5.0)")]
+    fn flush(slf: &Bound<'_, Self>, timeout: f64) -> PyResult<bool> {
+        let flush_timeout = Duration::try_from_secs_f64(timeout).map_err(|_| {
+            let error_msg = format!("invalid timeout: {timeout}");
+            PyValueError::new_err(error_msg)
+        })?;
+        Ok(slf.py().detach(|| flush_pending_writes(flush_timeout)))
+    }
+
+    /// Returns the metrics of the write queue: the number of pending writes (``depth``), the
+    /// highest number of pending writes so far (``peak_depth``), and the number of ``written``
+    /// and ``failed`` writes.
+    #[pyo3(name = "write_queue_stats")]
+    fn get_write_queue_stats<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyDict>> {
+        let py = slf.py();
+        let stats = write_queue_stats();
+        let stats_dict = PyDict::new(py);
+        stats_dict.set_item(intern!(py, "depth"), stats.depth)?;
+        stats_dict.set_item(intern!(py, "peak_depth"), stats.peak_depth)?;
+        stats_dict.set_item(intern!(py, "written"), stats.written)?;
+        stats_dict.set_item(intern!(py, "failed"), stats.failed)?;
+        Ok(stats_dict)
+    }
+}
+
+impl<'py> AbstractDatabaseMethods<'py> for Bound<'py, Redis> {
+    fn get_logger(&self) -> PyResult<Bound<'py, PyAny>> {
+        self.as_super().get_logger()
+    }
+
+    fn set_permission(&self, player: &Bound<'py, PyAny>, level: i32) -> PyResult<()> {
+        let key = permission_key(player)?;
+
+        if write_behind_enabled() {
+            return self.queue_command("SET", vec![key.into(), level.to_string().into_bytes()]);
+        }
+        self.set_item(&key, PyInt::new(self.py(), level).as_any())
+    }
+
+    fn get_permission(&self, player: &Bound<'py, PyAny>) -> PyResult<i32> {
+        let steam_id = player_steam_id(player)?;
+
+        if Some(steam_id) == owner()? {
+            return Ok(5);
+        }
+
+        let key = format!("minqlx:players:{steam_id}:permission");
+        match self.execute_command("GET", vec![key.into()])? {
+            Value::Nil => Ok(0),
+            value => {
+                let value = redis_value_to_py(self.py(), value)?.to_string();
+                value.parse::<i32>().map_err(|_| {
+                    let error_msg = format!("invalid literal for int() with base 10: '{value}",);
+                    PyValueError::new_err(error_msg)
+                })
+            }
+        }
+    }
+
+    fn has_permission(&self, player: &Bound<'py, PyAny>, level: i32) -> PyResult<bool> {
+        self.get_permission(player).map(|value| value >= level)
+    }
+
+    fn set_flag(&self, player: &Bound<'py, PyAny>, flag: &str, value: bool) -> PyResult<()> {
+        let key = flag_key(player, flag)?;
+
+        let redis_value = if value { 1i32 } else { 0i32 };
+
+        if write_behind_enabled() {
+            return self.queue_command(
+                "SET",
+                vec![key.into(), redis_value.to_string().into_bytes()],
+            );
+        }
+        self.set_item(&key, PyInt::new(self.py(), redis_value).as_any())
+    }
+
+    fn clear_flag(&self, player: &Bound<'py, PyAny>, flag: &str) -> PyResult<()> {
+        self.set_flag(player, flag, false)
+    }
+
+    fn get_flag(&self, player: &Bound<'py, PyAny>, flag: &str, default: bool) -> PyResult<bool> {
+        let key = flag_key(player, flag)?;
+
+        match self.execute_command("GET", vec![key.into()])? {
+            Value::Nil => Ok(default),
+            value => {
+                redis_value_to_py(self.py(), value).map(|py_value| py_value.to_string() != "0")
+            }
+        }
+    }
+
+    fn connect(&self) -> PyResult<Bound<'py, PyAny>> {
+        if self.get().connection.load().is_none() && DEFAULT_CONNECTION.load().is_none() {
+            let connection = open_connection(self.py(), default_connection_info()?)?;
+            DEFAULT_CONNECTION.store(Some(connection));
+        }
+
+        Ok(self.to_owned().into_any())
+    }
+
+    fn close(&self) -> PyResult<()> {
+        if !self.py().detach(|| flush_pending_writes(FLUSH_TIMEOUT)) {
+            cold_path();
+            warn!(target: "shinqlx", "not all queued database writes reached the server.");
+        }
+        self.get().connection.store(None);
+
+        let redis_type = self.py().get_type::<Redis>();
+        let class_counter = redis_type
+            .getattr(intern!(self.py(), "_counter"))
+            .and_then(|value| value.extract::<i32>())
+            .unwrap_or(0);
+        if class_counter <= 1 {
+            DEFAULT_CONNECTION.store(None);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) trait RedisMethods<'py> {
+    fn get_redis(&self) -> PyResult<Bound<'py, PyAny>>;
+    fn connect_with_parameters(
+        &self,
+        host: &str,
+        database: i64,
+        unix_socket: bool,
+        password: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn execute_command(&self, command: &str, args: Vec<Vec<u8>>) -> PyResult<Value>;
+    fn queue_command(&self, command: &str, args: Vec<Vec<u8>>) -> PyResult<()>;
+    fn set_item(&self, key: &str, item: &Bound<'py, PyAny>) -> PyResult<()>;
+    fn zrange_command(
+        &self,
+        command: &str,
+        args: Vec<Vec<u8>>,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn mset(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn msetnx(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn zadd(
+        &self,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn zincrby(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        amount: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn setx(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        time: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn lrem(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        count: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+}
+
+/// Raises the ``ValueError``s of the argument parsing shared with the other databases as
+/// :class:`RedisError`, just like invalid arguments sent to the server.
+fn redis_argument_error(py: Python<'_>, err: PyErr) -> PyErr {
+    if err.is_instance_of::<PyValueError>(py) {
+        return RedisError::new_err(err.value(py).to_string());
+    }
+    err
+}
+
+fn mset_arguments<'py>(
+    command: &str,
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Vec<Vec<u8>>> {
+    mset_mapping(command, args, kwargs)
+        .map(|mapping| {
+            mapping
+                .into_iter()
+                .flat_map(|(key, value)| [key.into_bytes(), value.into_bytes()])
+                .collect()
+        })
+        .map_err(|err| redis_argument_error(args.py(), err))
+}
+
+impl<'py> RedisMethods<'py> for Bound<'py, Redis> {
+    fn get_redis(&self) -> PyResult<Bound<'py, PyAny>> {
+        self.connect()
+    }
+
+    fn connect_with_parameters(
+        &self,
+        host: &str,
+        database: i64,
+        unix_socket: bool,
+        password: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if self.get().connection.load().is_none() {
+            let info = connection_info(host, database, unix_socket, password)?;
+            let connection = open_connection(self.py(), info)?;
+            self.get().connection.store(Some(connection));
+        }
+
+        Ok(self.to_owned().into_any())
+    }
+
+    fn execute_command(&self, command: &str, args: Vec<Vec<u8>>) -> PyResult<Value> {
+        self.connect()?;
+        let Some(connection) = self
+            .get()
+            .connection
+            .load_full()
+            .or_else(|| DEFAULT_CONNECTION.load_full())
+        else {
+            cold_path();
+            return Err(RedisError::new_err("no connection to the redis database."));
+        };
+
+        self.py()
+            .detach(|| {
+                if has_pending_writes_for(command, &args) {
+                    flush_pending_writes(FLUSH_TIMEOUT);
+                }
+                let call_start = Instant::now();
+                let mut redis_cmd = cmd(command);
+                args.iter().for_each(|arg| {
+                    redis_cmd.arg(arg.as_slice());
+                });
+                let result = redis_cmd.query::<Value>(&mut *connection.lock());
+                record_redis_call(call_start.elapsed());
+                result.map_err(|err| format!("{err}"))
+            })
+            .map_err(RedisError::new_err)
+    }
+
+    fn queue_command(&self, command: &str, args: Vec<Vec<u8>>) -> PyResult<()> {
+        if command_keys(command, &args) == CommandKeys::Keyspace {
+            cold_path();
+            let error_msg = format!("{command} works on all keys and cannot be queued.");
+            return Err(PyValueError::new_err(error_msg));
+        }
+
+        self.connect()?;
+        let Some(connection) = self
+            .get()
+            .connection
+            .load_full()
+            .or_else(|| DEFAULT_CONNECTION.load_full())
+        else {
+            cold_path();
+            return Err(RedisError::new_err("no connection to the redis database."));
+        };
+
+        if !enqueue_write(connection, command, args) {
+            cold_path();
+            return Err(RedisError::new_err(
+                "the database write queue is not running.",
+            ));
+        }
+        Ok(())
+    }
+
+    fn set_item(&self, key: &str, item: &Bound<'py, PyAny>) -> PyResult<()> {
+        let returned = self.execute_command("SET", vec![key.into(), to_redis_arg(item)?])?;
+
+        if !redis_value_to_bool(&returned) {
+            cold_path();
+            return Err(PyRuntimeError::new_err("The database assignment failed."));
+        }
+
+        Ok(())
+    }
+
+    fn zrange_command(
+        &self,
+        command: &str,
+        mut args: Vec<Vec<u8>>,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if withscores {
+            args.push("WITHSCORES".into());
+        }
+
+        let returned = self.execute_command(command, args)?;
+        if withscores {
+            redis_value_to_score_pairs(self.py(), returned)
+        } else {
+            redis_value_to_py(self.py(), returned)
+        }
+    }
+
+    fn mset(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let redis_args = mset_arguments("MSET", args, kwargs)?;
+        self.execute_command("MSET", redis_args).map(|value| {
+            PyBool::new(self.py(), redis_value_to_bool(&value))
+                .to_owned()
+                .into_any()
+        })
+    }
+
+    fn msetnx(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let redis_args = mset_arguments("MSETNX", args, kwargs)?;
+        self.execute_command("MSETNX", redis_args).map(|value| {
+            PyBool::new(self.py(), redis_value_to_bool(&value))
+                .to_owned()
+                .into_any()
+        })
+    }
+
+    fn zadd(
+        &self,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (options, pieces) =
+            zadd_arguments(args, kwargs).map_err(|err| redis_argument_error(self.py(), err))?;
+
+        let mut redis_args = vec![name.into()];
+        redis_args.extend(
+            [
+                (options.nx, "NX"),
+                (options.xx, "XX"),
+                (options.gt, "GT"),
+                (options.lt, "LT"),
+                (options.ch, "CH"),
+                (options.incr, "INCR"),
+            ]
+            .into_iter()
+            .filter(|&(enabled, _)| enabled)
+            .map(|(_, option)| option.into()),
+        );
+        redis_args.extend(
+            pieces
+                .into_iter()
+                .flat_map(|(member, score)| [score.to_string().into_bytes(), member.into_bytes()]),
+        );
+
+        let returned = self.execute_command("ZADD", redis_args)?;
+        if options.incr {
+            redis_value_to_float(self.py(), returned)
+        } else {
+            redis_value_to_py(self.py(), returned)
+        }
+    }
+
+    fn zincrby(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        amount: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let args = vec![name.into(), to_redis_arg(amount)?, to_redis_arg(value)?];
+
+        self.execute_command("ZINCRBY", args)
+            .and_then(|returned| redis_value_to_float(self.py(), returned))
+    }
+
+    fn setx(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        time: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.execute_command(
+            "SETEX",
+            vec![
+                name.into(),
+                time_in_seconds(time)?.to_string().into_bytes(),
+                to_redis_arg(value)?,
+            ],
+        )
+        .map(|returned| {
+            PyBool::new(self.py(), redis_value_to_bool(&returned))
+                .to_owned()
+                .into_any()
+        })
+    }
+
+    fn lrem(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        count: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.execute_command(
+            "LREM",
+            vec![name.into(), to_redis_arg(count)?, to_redis_arg(value)?],
+        )
+        .and_then(|returned| redis_value_to_py(self.py(), returned))
+    }
+}
+
+/// A Redis command without a dedicated method on :class:`shinqlx.database.Redis`.
+/// Calling it sends the command with the given arguments to the server.
+#[pyclass(name = "RedisCommand", module = "database", frozen)]
+pub(crate) struct RedisCommand {
+    database: Py<Redis>,
+    #[pyo3(get)]
+    command: String,
+}
+
+#[pymethods]
+impl RedisCommand {
+    fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+        visit.call(&self.database)?;
+        Ok(())
+    }
+
+    fn __clear__(&self) {}
+
+    #[pyo3(signature = (*args))]
+    fn __call__<'py>(
+        &self,
+        py: Python<'py>,
+        args: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let redis_args = to_redis_args(args.iter())?;
+        self.database
+            .bind(py)
+            .execute_command(&self.command, redis_args)
+            .and_then(|value| redis_value_to_py(py, value))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod redis_stand_in {
+    use alloc::{collections::VecDeque, sync::Arc};
+    use core::time::Duration;
+    use std::{
+        collections::{BTreeMap, BTreeSet, HashMap},
+        io::{BufRead, BufReader, Write},
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    enum StandInValue {
+        String(String),
+        Hash(BTreeMap<String, String>),
+        Set(BTreeSet<String>),
+        SortedSet(Vec<(String, f64)>),
+        List(VecDeque<String>),
+    }
+
+    enum Reply {
+        Ok,
+        Nil,
+        Int(i64),
+        Bulk(String),
+        Array(Vec<Reply>),
+        Error(String),
+    }
+
+    impl Reply {
+        fn write_to(&self, out: &mut impl Write) -> std::io::Result<()> {
+            match self {
+                Reply::Ok => write!(out, "+OK\r\n"),
+                Reply::Nil => write!(out, "$-1\r\n"),
+                Reply::Int(value) => write!(out, ":{value}\r\n"),
+                Reply::Bulk(value) => write!(out, "${}\r\n{value}\r\n", value.len()),
+                Reply::Array(values) => {
+                    write!(out, "*{}\r\n", values.len())?;
+                    values.iter().try_for_each(|value| value.write_to(out))
+                }
+                Reply::Error(msg) => write!(out, "-ERR {msg}\r\n"),
+            }
+        }
+    }
+
+    type Store = Arc<parking_lot::Mutex<HashMap<String, StandInValue>>>;
+
+    /// A minimal redis-server stand-in speaking RESP2 on a random local port. It understands
+    /// just enough commands to exercise the database layer.
+    pub(crate) struct RedisStandIn {
+        pub(crate) address: String,
+    }
+
+    impl RedisStandIn {
+        pub(crate) fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("this should not happen");
+            let address = listener
+                .local_addr()
+                .expect("this should not happen")
+                .to_string();
+            let store: Store = Default::default();
+
+            thread::spawn(move || {
+                listener.incoming().flatten().for_each(|stream| {
+                    let connection_store = store.clone();
+                    thread::spawn(move || serve(stream, connection_store));
+                });
+            });
+
+            Self { address }
+        }
+    }
+
+    fn read_command(reader: &mut impl BufRead) -> Option<Vec<String>> {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok().filter(|&read| read > 0)?;
+        let count = line.trim_end().strip_prefix('*')?.parse::<usize>().ok()?;
+        (0..count)
+            .map(|_| {
+                let mut length_line = String::new();
+                reader.read_line(&mut length_line).ok()?;
+                let length = length_line
+                    .trim_end()
+                    .strip_prefix('$')?
+                    .parse::<usize>()
+                    .ok()?;
+                let mut data = vec![0u8; length + 2];
+                reader.read_exact(&mut data).ok()?;
+                data.truncate(length);
+                String::from_utf8(data).ok()
+            })
+            .collect()
+    }
+
+    fn serve(stream: TcpStream, store: Store) {
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+        let mut writer = stream.try_clone().expect("this should not happen");
+        let mut reader = BufReader::new(stream);
+        while let Some(command) = read_command(&mut reader) {
+            let reply = execute(&store, &command);
+            if reply.write_to(&mut writer).is_err() {
+                break;
+            }
+        }
+    }
+
+    fn format_score(score: f64) -> String {
+        if score.fract() == 0.0 {
+            format!("{}", score as i64)
+        } else {
+            format!("{score}")
+        }
+    }
+
+    fn execute(store: &Store, command: &[String]) -> Reply {
+        let mut data = store.lock();
+        let args = &command[1..];
+        match command[0].to_uppercase().as_str() {
+            "PING" | "SELECT" | "AUTH" => Reply::Ok,
+            "GET" => match data.get(&args[0]) {
+                Some(StandInValue::String(value)) => Reply::Bulk(value.clone()),
+                _ => Reply::Nil,
+            },
+            "SET" => {
+                data.insert(args[0].clone(), StandInValue::String(args[1].clone()));
+                Reply::Ok
+            }
+            "SETEX" => {
+                data.insert(args[0].clone(), StandInValue::String(args[2].clone()));
+                Reply::Ok
+            }
+            "MSET" => {
+                args.chunks(2).for_each(|pair| {
+                    data.insert(pair[0].clone(), StandInValue::String(pair[1].clone()));
+                });
+                Reply::Ok
+            }
+            "DEL" => Reply::Int(
+                args.iter()
+                    .filter(|key| data.remove(*key).is_some())
+                    .count() as i64,
+            ),
+            "EXISTS" => {
+                Reply::Int(args.iter().filter(|key| data.contains_key(*key)).count() as i64)
+            }
+            "INCRBY" => {
+                let current = match data.get(&args[0]) {
+                    Some(StandInValue::String(value)) => value.parse::<i64>().unwrap_or_default(),
+                    _ => 0,
+                };
+                let new_value = current + args[1].parse::<i64>().unwrap_or_default();
+                data.insert(args[0].clone(), StandInValue::String(new_value.to_string()));
+                Reply::Int(new_value)
+            }
+            "HSET" => {
+                let entry = data
+                    .entry(args[0].clone())
+                    .or_insert_with(|| StandInValue::Hash(BTreeMap::new()));
+                let StandInValue::Hash(hash) = entry else {
+                    return Reply::Error("WRONGTYPE".into());
+                };
+                Reply::Int(
+                    args[1..]
+                        .chunks(2)
+                        .filter(|pair| hash.insert(pair[0].clone(), pair[1].clone()).is_none())
+                        .count() as i64,
+                )
+            }
+            "HGET" => match data.get(&args[0]) {
+                Some(StandInValue::Hash(hash)) => hash
+                    .get(&args[1])
+                    .map_or(Reply::Nil, |value| Reply::Bulk(value.clone())),
+                _ => Reply::Nil,
+            },
+            "HGETALL" => match data.get(&args[0]) {
+                Some(StandInValue::Hash(hash)) => Reply::Array(
+                    hash.iter()
+                        .flat_map(|(key, value)| {
+                            [Reply::Bulk(key.clone()), Reply::Bulk(value.clone())]
+                        })
+                        .collect(),
+                ),
+                _ => Reply::Array(vec![]),
+            },
+            "SADD" => {
+                let entry = data
+                    .entry(args[0].clone())
+                    .or_insert_with(|| StandInValue::Set(BTreeSet::new()));
+                let StandInValue::Set(set) = entry else {
+                    return Reply::Error("WRONGTYPE".into());
+                };
+                Reply::Int(
+                    args[1..]
+                        .iter()
+                        .filter(|value| set.insert((*value).clone()))
+                        .count() as i64,
+                )
+            }
+            "SMEMBERS" => match data.get(&args[0]) {
+                Some(StandInValue::Set(set)) => {
+                    Reply::Array(set.iter().map(|value| Reply::Bulk(value.clone())).collect())
+                }
+                _ => Reply::Array(vec![]),
+            },
+            "SISMEMBER" => match data.get(&args[0]) {
+                Some(StandInValue::Set(set)) => Reply::Int(set.contains(&args[1]) as i64),
+                _ => Reply::Int(0),
+            },
+            "ZADD" => {
+                let entry = data
+                    .entry(args[0].clone())
+                    .or_insert_with(|| StandInValue::SortedSet(vec![]));
+                let StandInValue::SortedSet(sorted_set) = entry else {
+                    return Reply::Error("WRONGTYPE".into());
+                };
+                let mut added = 0;
+                args[1..].chunks(2).for_each(|pair| {
+                    let score = pair[0].parse::<f64>().unwrap_or_default();
+                    match sorted_set.iter_mut().find(|(member, _)| *member == pair[1]) {
+                        Some(existing) => existing.1 = score,
+                        None => {
+                            added += 1;
+                            sorted_set.push((pair[1].clone(), score));
+                        }
+                    }
+                });
+                Reply::Int(added)
+            }
+            "ZINCRBY" => {
+                let entry = data
+                    .entry(args[0].clone())
+                    .or_insert_with(|| StandInValue::SortedSet(vec![]));
+                let StandInValue::SortedSet(sorted_set) = entry else {
+                    return Reply::Error("WRONGTYPE".into());
+                };
+                let amount = args[1].parse::<f64>().unwrap_or_default();
+                let new_score = match sorted_set.iter_mut().find(|(member, _)| *member == args[2]) {
+                    Some(existing) => {
+                        existing.1 += amount;
+                        existing.1
+                    }
+                    None => {
+                        sorted_set.push((args[2].clone(), amount));
+                        amount
+                    }
+                };
+                Reply::Bulk(format_score(new_score))
+            }
+            "ZSCORE" => match data.get(&args[0]) {
+                Some(StandInValue::SortedSet(sorted_set)) => sorted_set
+                    .iter()
+                    .find(|(member, _)| *member == args[1])
+                    .map_or(Reply::Nil, |(_, score)| Reply::Bulk(format_score(*score))),
+                _ => Reply::Nil,
+            },
+            "ZRANGE" | "ZREVRANGE" => match data.get(&args[0]) {
+                Some(StandInValue::SortedSet(sorted_set)) => {
+                    let mut sorted = sorted_set.clone();
+                    sorted.sort_by(|left, right| left.1.total_cmp(&right.1));
+                    if command[0].eq_ignore_ascii_case("ZREVRANGE") {
+                        sorted.reverse();
+                    }
+                    let withscores = args
+                        .iter()
+                        .any(|arg| arg.eq_ignore_ascii_case("WITHSCORES"));
+                    Reply::Array(
+                        sorted
+                            .into_iter()
+                            .flat_map(|(member, score)| {
+                                let mut replies = vec![Reply::Bulk(member)];
+                                if withscores {
+                                    replies.push(Reply::Bulk(format_score(score)));
+                                }
+                                replies
+                            })
+                            .collect(),
+                    )
+                }
+                _ => Reply::Array(vec![]),
+            },
+            "RPUSH" | "LPUSH" => {
+                let entry = data
+                    .entry(args[0].clone())
+                    .or_insert_with(|| StandInValue::List(VecDeque::new()));
+                let StandInValue::List(list) = entry else {
+                    return Reply::Error("WRONGTYPE".into());
+                };
+                args[1..].iter().for_each(|value| {
+                    if command[0].eq_ignore_ascii_case("RPUSH") {
+                        list.push_back(value.clone());
+                    } else {
+                        list.push_front(value.clone());
+                    }
+                });
+                Reply::Int(list.len() as i64)
+            }
+            "LRANGE" => match data.get(&args[0]) {
+                Some(StandInValue::List(list)) => Reply::Array(
+                    list.iter()
+                        .map(|value| Reply::Bulk(value.clone()))
+                        .collect(),
+                ),
+                _ => Reply::Array(vec![]),
+            },
+            "LREM" => match data.get_mut(&args[0]) {
+                Some(StandInValue::List(list)) => {
+                    let before = list.len();
+                    list.retain(|value| *value != args[2]);
+                    Reply::Int((before - list.len()) as i64)
+                }
+                _ => Reply::Int(0),
+            },
+            other => Reply::Error(format!("unknown command '{other}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod redis_tests {
+    use core::borrow::BorrowMut;
+
+    use pretty_assertions::assert_eq;
+    use pyo3::{
+        exceptions::{PyKeyError, PyValueError},
+        intern,
+        prelude::*,
+        types::{IntoPyDict, PyDict, PyTuple},
+    };
+    use rstest::rstest;
+
+    use super::{
+        super::super::{prelude::pyshinqlx_setup, pyshinqlx_test_support::default_test_player},
+        AbstractDatabaseMethods, Redis, RedisError, RedisMethods,
+        redis_stand_in::RedisStandIn,
+        to_redis_arg, write_queue_stats,
+    };
+    use crate::{
+        ffi::c::prelude::{CVar, CVarBuilder, cvar_t},
+        prelude::*,
+    };
+
+    fn connected_redis<'py>(py: Python<'py>, stand_in: &RedisStandIn) -> Bound<'py, Redis> {
+        let redis =
+            Bound::new(py, Redis::py_new(py, py.None().bind(py))).expect("this should not happen");
+        redis
+            .connect_with_parameters(&stand_in.address, 0, false, None)
+            .expect("this should not happen");
+        redis
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn to_redis_arg_encodes_python_values(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            assert_eq!(
+                to_redis_arg(
+                    "asdf"
+                        .into_pyobject(py)
+                        .expect("this should not happen")
+                        .as_any()
+                )
+                .expect("this should not happen"),
+                b"asdf".to_vec()
+            );
+            assert_eq!(
+                to_redis_arg(
+                    42i32
+                        .into_pyobject(py)
+                        .expect("this should not happen")
+                        .as_any()
+                )
+                .expect("this should not happen"),
+                b"42".to_vec()
+            );
+            assert_eq!(
+                to_redis_arg(
+                    1.5f64
+                        .into_pyobject(py)
+                        .expect("this should not happen")
+                        .as_any()
+                )
+                .expect("this should not happen"),
+                b"1.5".to_vec()
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn to_redis_arg_rejects_booleans(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let result = to_redis_arg(
+                &true
+                    .into_pyobject(py)
+                    .expect("this should not happen")
+                    .to_owned()
+                    .into_any(),
+            );
+            assert!(result.is_err_and(|err| err.is_instance_of::<RedisError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn connect_with_parameters_to_unreachable_server(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let redis = Bound::new(py, Redis::py_new(py, py.None().bind(py)))
+                .expect("this should not happen");
+
+            let result = redis.connect_with_parameters("127.0.0.1:1", 0, false, None);
+            assert!(result.is_err_and(|err| err.is_instance_of::<RedisError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn connect_with_parameters_with_invalid_port(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let redis = Bound::new(py, Redis::py_new(py, py.None().bind(py)))
+                .expect("this should not happen");
+
+            let result = redis.connect_with_parameters("127.0.0.1:asdf", 0, false, None);
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyValueError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn setitem_and_getitem_roundtrip(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            redis
+                .set_item("minqlx:test", "asdf".into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+
+            let result = Redis::__getitem__(&redis, "minqlx:test");
+            assert!(result.is_ok_and(|value| value.to_string() == "asdf"));
+            assert!(Redis::__contains__(&redis, "minqlx:test").expect("this should not happen"));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn getitem_for_missing_key_raises_key_error(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let result = Redis::__getitem__(&redis, "minqlx:missing");
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyKeyError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn delitem_for_missing_key_raises_key_error(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let result = Redis::__delitem__(&redis, "minqlx:missing");
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyKeyError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn permissions_roundtrip(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        MockEngineBuilder::default()
+            .with_find_cvar(|cmd| cmd == "qlx_owner", |_| None, 0..)
+            .with_find_cvar(|cmd| cmd == "qlx_redisWriteBehind", |_| None, 0..)
+            .run(|| {
+                Python::attach(|py| {
+                    let redis = connected_redis(py, &stand_in);
+                    let player = Bound::new(py, default_test_player())
+                        .expect("this should not happen")
+                        .into_any();
+
+                    assert_eq!(
+                        redis
+                            .get_permission(&player)
+                            .expect("this should not happen"),
+                        0
+                    );
+
+                    redis
+                        .set_permission(&player, 3)
+                        .expect("this should not happen");
+
+                    assert_eq!(
+                        redis
+                            .get_permission(&player)
+                            .expect("this should not happen"),
+                        3
+                    );
+                    assert!(
+                        redis
+                            .has_permission(&player, 2)
+                            .expect("this should not happen")
+                    );
+                    assert!(
+                        !redis
+                            .has_permission(&player, 4)
+                            .expect("this should not happen")
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn permissions_with_write_behind_enabled(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+        let cvar_string = c"1";
+        let mut raw_write_behind_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        MockEngineBuilder::default()
+            .with_find_cvar(|cmd| cmd == "qlx_owner", |_| None, 0..)
+            .with_find_cvar(
+                |cmd| cmd == "qlx_redisWriteBehind",
+                move |_| CVar::try_from(raw_write_behind_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let redis = connected_redis(py, &stand_in);
+                    let player = Bound::new(py, default_test_player())
+                        .expect("this should not happen")
+                        .into_any();
+                    let written_before = write_queue_stats().written;
+
+                    redis
+                        .set_permission(&player, 3)
+                        .expect("this should not happen");
+
+                    assert_eq!(
+                        redis
+                            .get_permission(&player)
+                            .expect("this should not happen"),
+                        3
+                    );
+                    assert_eq!(write_queue_stats().written, written_before + 1);
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn zincrby_with_write_behind_enabled_returns_new_score(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+        let cvar_string = c"1";
+        let mut raw_write_behind_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        MockEngineBuilder::default()
+            .with_find_cvar(
+                |cmd| cmd == "qlx_redisWriteBehind",
+                move |_| CVar::try_from(raw_write_behind_cvar.borrow_mut() as *mut cvar_t).ok(),
+                0..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let redis = connected_redis(py, &stand_in);
+                    let written_before = write_queue_stats().written;
+
+                    let result = redis.zincrby(
+                        "minqlx:scores",
+                        "player1".into_pyobject(py).unwrap().as_any(),
+                        5i32.into_pyobject(py).unwrap().as_any(),
+                    );
+                    assert!(result.is_ok_and(|returned| {
+                        returned.extract::<f64>().expect("this should not happen") == 5.0
+                    }));
+                    assert_eq!(write_queue_stats().written, written_before);
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn enqueued_writes_are_visible_to_later_commands(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            Redis::enqueue(
+                &redis,
+                "set",
+                &PyTuple::new(py, ["minqlx:queued", "asdf"]).expect("this should not happen"),
+            )
+            .expect("this should not happen");
+
+            let result = Redis::get(&redis, "minqlx:queued");
+            assert!(result.is_ok_and(|value| value.to_string() == "asdf"));
+            assert!(Redis::flush(&redis, 1.0).expect("this should not happen"));
+            assert_eq!(
+                Redis::get_write_queue_stats(&redis)
+                    .expect("this should not happen")
+                    .get_item("depth")
+                    .expect("this should not happen")
+                    .and_then(|depth| depth.extract::<usize>().ok()),
+                Some(0)
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn enqueued_writes_to_several_keys_are_visible_to_later_commands(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            Redis::enqueue(
+                &redis,
+                "mset",
+                &PyTuple::new(py, ["minqlx:first", "1", "minqlx:second", "2"])
+                    .expect("this should not happen"),
+            )
+            .expect("this should not happen");
+
+            let result = Redis::get(&redis, "minqlx:second");
+            assert!(result.is_ok_and(|value| value.to_string() == "2"));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn enqueue_keyspace_command(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let result = Redis::enqueue(&redis, "flushdb", &PyTuple::empty(py));
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyValueError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn flush_with_invalid_timeout(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let result = Redis::flush(&redis, -1.0);
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyValueError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn get_permission_with_invalid_player(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let result = redis.get_permission(&"asdf".into_pyobject(py).unwrap().into_any());
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyValueError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn flags_roundtrip(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+            let player = Bound::new(py, default_test_player())
+                .expect("this should not happen")
+                .into_any();
+
+            assert!(
+                redis
+                    .get_flag(&player, "muted", true)
+                    .expect("this should not happen")
+            );
+
+            redis
+                .set_flag(&player, "muted", true)
+                .expect("this should not happen");
+            assert!(
+                redis
+                    .get_flag(&player, "muted", false)
+                    .expect("this should not happen")
+            );
+
+            redis
+                .clear_flag(&player, "muted")
+                .expect("this should not happen");
+            assert!(
+                !redis
+                    .get_flag(&player, "muted", true)
+                    .expect("this should not happen")
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn zadd_and_zincrby_update_sorted_set(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let mapping = PyDict::new(py);
+            mapping
+                .set_item("player1", 10)
+                .expect("this should not happen");
+            let added = redis
+                .zadd(
+                    "minqlx:scores",
+                    &PyTuple::new(py, [mapping]).expect("this should not happen"),
+                    None,
+                )
+                .expect("this should not happen");
+            assert_eq!(added.extract::<i64>().expect("this should not happen"), 1);
+
+            let result = redis.zincrby(
+                "minqlx:scores",
+                "player1".into_pyobject(py).unwrap().as_any(),
+                5i32.into_pyobject(py).unwrap().as_any(),
+            );
+            assert!(
+                result.is_ok_and(|score| score.extract::<f64>().is_ok_and(|value| value == 15.0))
+            );
+
+            let ranking = redis
+                .call_method(
+                    intern!(py, "zrange"),
+                    ("minqlx:scores", 0, -1),
+                    Some(
+                        &[("withscores", true)]
+                            .into_py_dict(py)
+                            .expect("this should not happen"),
+                    ),
+                )
+                .expect("this should not happen");
+            assert_eq!(
+                ranking
+                    .extract::<Vec<(String, f64)>>()
+                    .expect("this should not happen"),
+                vec![("player1".to_string(), 15.0)]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn zadd_with_uneven_pairs(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let result = redis.zadd(
+                "minqlx:scores",
+                &PyTuple::new(py, [1]).expect("this should not happen"),
+                None,
+            );
+            assert!(result.is_err_and(|err| err.is_instance_of::<RedisError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn lists_and_lrem(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            redis
+                .call_method1(intern!(py, "rpush"), ("minqlx:list", "a", "b", "a"))
+                .expect("this should not happen");
+            let removed = redis
+                .lrem(
+                    "minqlx:list",
+                    "a".into_pyobject(py).unwrap().as_any(),
+                    0i32.into_pyobject(py).unwrap().as_any(),
+                )
+                .expect("this should not happen");
+            assert_eq!(removed.extract::<i64>().expect("this should not happen"), 2);
+
+            let remaining = redis
+                .call_method1(intern!(py, "lrange"), ("minqlx:list", 0, -1))
+                .expect("this should not happen");
+            assert_eq!(
+                remaining
+                    .extract::<Vec<String>>()
+                    .expect("this should not happen"),
+                vec!["b".to_string()]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn hashes_and_sets(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            redis
+                .call_method(
+                    intern!(py, "hset"),
+                    ("minqlx:hash",),
+                    Some(
+                        &[("key", "field"), ("value", "value")]
+                            .into_py_dict(py)
+                            .expect("this should not happen"),
+                    ),
+                )
+                .expect("this should not happen");
+            let hash = redis
+                .call_method1(intern!(py, "hgetall"), ("minqlx:hash",))
+                .expect("this should not happen");
+            assert_eq!(
+                hash.get_item("field")
+                    .expect("this should not happen")
+                    .to_string(),
+                "value"
+            );
+
+            redis
+                .call_method1(intern!(py, "sadd"), ("minqlx:set", "a", "b"))
+                .expect("this should not happen");
+            let is_member = redis
+                .call_method1(intern!(py, "sismember"), ("minqlx:set", "b"))
+                .expect("this should not happen");
+            assert!(is_member.extract::<bool>().expect("this should not happen"));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn mset_with_too_many_args(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let result = redis.mset(
+                &PyTuple::new(py, [PyDict::new(py), PyDict::new(py)])
+                    .expect("this should not happen"),
+                None,
+            );
+            assert!(result.is_err_and(|err| err.is_instance_of::<RedisError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn unknown_attributes_are_sent_as_commands(_pyshinqlx_setup: ()) {
+        let stand_in = RedisStandIn::start();
+
+        Python::attach(|py| {
+            let redis = connected_redis(py, &stand_in);
+
+            let result = redis
+                .getattr(intern!(py, "ping"))
+                .and_then(|command| command.call0());
+            assert!(result.is_ok_and(|value| value.extract::<bool>().is_ok_and(|pong| pong)));
+
+            let unknown_command = redis
+                .getattr(intern!(py, "flushall"))
+                .and_then(|command| command.call0());
+            assert!(unknown_command.is_err_and(|err| err.is_instance_of::<RedisError>(py)));
+
+            let missing_private = redis.getattr(intern!(py, "_conn"));
+            assert!(missing_private.is_err());
+        });
+    }
+}
@@ -0,0 +1,1521 @@
+use alloc::sync::Arc;
+use core::{cmp::max, hint::cold_path};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+    time::Instant,
+};
+
+use arc_swap::ArcSwapOption;
+use itertools::Itertools;
+use pyo3::{
+    IntoPyObjectExt, create_exception,
+    exceptions::{PyEnvironmentError, PyException, PyKeyError, PyRuntimeError, PyValueError},
+    intern,
+    marker::Ungil,
+    prelude::*,
+    types::{PyDict, PyInt, PyTuple},
+};
+use ql_dbformat::SQLITE_SCHEMA;
+use rusqlite::{Connection, OptionalExtension, params};
+
+use super::{
+    super::{metrics::record_sqlite_call, owner},
+    AbstractDatabase, AbstractDatabaseMethods, database_value, database_values, flag_key,
+    index_range, mset_mapping, permission_key, player_steam_id, score_boundary, time_in_seconds,
+    zadd_arguments,
+};
+use crate::{MAIN_ENGINE, quake_live_engine::FindCVar};
+
+create_exception!(pyshinqlx_module, SqliteError, PyException);
+
+/// The connection shared by all plugins using the database file from the ``qlx_sqlitePath`` cvar.
+static DEFAULT_CONNECTION: LazyLock<ArcSwapOption<parking_lot::Mutex<Connection>>> =
+    LazyLock::new(ArcSwapOption::empty);
+
+/// Formats *err* inside of [`Python::detach`], since [`rusqlite::Error`] may hold boxed errors that
+/// cannot cross back to the interpreter thread.
+fn sqlite_error(err: rusqlite::Error) -> String {
+    format!("{err}")
+}
+
+fn not_an_integer() -> rusqlite::Error {
+    rusqlite::Error::ToSqlConversionFailure("value is not an integer or out of range".into())
+}
+
+fn open_connection(py: Python<'_>, path: &str) -> PyResult<Arc<parking_lot::Mutex<Connection>>> {
+    py.detach(|| {
+        Connection::open(path)
+            .and_then(|connection| {
+                connection
+                    .execute_batch("PRAGMA journal_mode = WAL;")
+                    .and_then(|_| connection.execute_batch(SQLITE_SCHEMA))
+                    .map(|_| Arc::new(parking_lot::Mutex::new(connection)))
+            })
+            .map_err(sqlite_error)
+    })
+    .map_err(SqliteError::new_err)
+}
+
+fn default_database_path() -> PyResult<String> {
+    let Some(ref main_engine) = *MAIN_ENGINE.load() else {
+        cold_path();
+        return Err(PyEnvironmentError::new_err(
+            "could not get access to main engine.",
+        ));
+    };
+
+    main_engine
+        .find_cvar("qlx_sqlitePath")
+        .map(|cvar| cvar.get_string().to_string())
+        .filter(|path| !path.is_empty())
+        .ok_or_else(|| {
+            cold_path();
+            PyValueError::new_err("cvar qlx_sqlitePath misconfigured.")
+        })
+}
+
+fn purge_expired(connection: &Connection) -> rusqlite::Result<usize> {
+    connection.execute(
+        "DELETE FROM kv WHERE expires_at IS NOT NULL AND expires_at <= unixepoch()",
+        [],
+    )
+}
+
+fn get_string(connection: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    connection
+        .query_row("SELECT value FROM kv WHERE key = ?1", [key], |row| {
+            row.get(0)
+        })
+        .optional()
+}
+
+fn set_string(
+    connection: &Connection,
+    key: &str,
+    value: &str,
+    expires_in: Option<i64>,
+) -> rusqlite::Result<usize> {
+    connection.execute(
+        "INSERT INTO kv (key, value, expires_at) VALUES (?1, ?2, unixepoch() + ?3) \
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value, expires_at = excluded.expires_at",
+        params![key, value, expires_in],
+    )
+}
+
+fn increment_string(connection: &Connection, key: &str, amount: i64) -> rusqlite::Result<i64> {
+    let current = get_string(connection, key)?
+        .map(|value| value.parse::<i64>().map_err(|_| not_an_integer()))
+        .transpose()?
+        .unwrap_or_default();
+    let new_value = current.checked_add(amount).ok_or_else(not_an_integer)?;
+    connection.execute(
+        "INSERT INTO kv (key, value) VALUES (?1, ?2) \
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        params![key, new_value.to_string()],
+    )?;
+    Ok(new_value)
+}
+
+fn key_exists(connection: &Connection, key: &str) -> rusqlite::Result<bool> {
+    connection.query_row(
+        "SELECT EXISTS (SELECT 1 FROM kv WHERE key = ?1) \
+         OR EXISTS (SELECT 1 FROM hashes WHERE key = ?1) \
+         OR EXISTS (SELECT 1 FROM sets WHERE key = ?1) \
+         OR EXISTS (SELECT 1 FROM sorted_sets WHERE key = ?1)",
+        [key],
+        |row| row.get(0),
+    )
+}
+
+fn delete_key(connection: &Connection, key: &str) -> rusqlite::Result<bool> {
+    ["kv", "hashes", "sets", "sorted_sets"]
+        .iter()
+        .map(|table| connection.execute(&format!("DELETE FROM {table} WHERE key = ?1"), [key]))
+        .fold_ok(0, |deleted, rows| deleted + rows)
+        .map(|deleted| deleted > 0)
+}
+
+fn sorted_members(
+    connection: &Connection,
+    key: &str,
+    desc: bool,
+) -> rusqlite::Result<Vec<(String, f64)>> {
+    let order = if desc { "DESC" } else { "ASC" };
+    connection
+        .prepare(&format!(
+            "SELECT member, score FROM sorted_sets WHERE key = ?1 ORDER BY score {order}, member {order}"
+        ))?
+        .query_map([key], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect()
+}
+
+fn sorted_member_score(
+    connection: &Connection,
+    key: &str,
+    member: &str,
+) -> rusqlite::Result<Option<f64>> {
+    connection
+        .query_row(
+            "SELECT score FROM sorted_sets WHERE key = ?1 AND member = ?2",
+            [key, member],
+            |row| row.get(0),
+        )
+        .optional()
+}
+
+fn upsert_sorted_member(
+    connection: &Connection,
+    key: &str,
+    member: &str,
+    score: f64,
+) -> rusqlite::Result<usize> {
+    connection.execute(
+        "INSERT INTO sorted_sets (key, member, score) VALUES (?1, ?2, ?3) \
+         ON CONFLICT (key, member) DO UPDATE SET score = excluded.score",
+        params![key, member, score],
+    )
+}
+
+fn sorted_members_into_py<'py>(
+    py: Python<'py>,
+    members: Vec<(String, f64)>,
+    withscores: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    if withscores {
+        members.into_bound_py_any(py)
+    } else {
+        members
+            .into_iter()
+            .map(|(member, _)| member)
+            .collect::<Vec<_>>()
+            .into_bound_py_any(py)
+    }
+}
+
+/// A subclass of :class:`shinqlx.AbstractDatabase` storing everything in a local SQLite database
+/// file, for servers that do not want to run a Redis server.
+#[pyclass(name = "Sqlite", module = "database", extends = AbstractDatabase, dict, frozen)]
+pub(crate) struct Sqlite {
+    connection: ArcSwapOption<parking_lot::Mutex<Connection>>,
+}
+
+#[pymethods]
+impl Sqlite {
+    #[new]
+    fn py_new(py: Python<'_>, plugin: &Bound<'_, PyAny>) -> PyClassInitializer<Self> {
+        let sqlite_type = py.get_type::<Self>();
+        let counter = sqlite_type
+            .getattr(intern!(py, "_counter"))
+            .and_then(|py_counter| py_counter.extract::<i32>())
+            .unwrap_or(0);
+        let _ = sqlite_type.setattr(intern!(py, "_counter"), counter + 1);
+
+        PyClassInitializer::from(AbstractDatabase {
+            plugin: plugin.to_owned().unbind(),
+        })
+        .add_subclass(Self {
+            connection: ArcSwapOption::empty(),
+        })
+    }
+
+    fn __del__(slf_: &Bound<'_, Self>) -> PyResult<()> {
+        Self::close(slf_)?;
+        let sqlite_type = slf_.py().get_type::<Sqlite>();
+        let counter = sqlite_type
+            .getattr(intern!(slf_.py(), "_counter"))
+            .and_then(|py_counter| py_counter.extract::<i32>())
+            .unwrap_or(0);
+        sqlite_type.setattr(intern!(slf_.py(), "_counter"), max(0, counter - 1))?;
+
+        Ok(())
+    }
+
+    fn __contains__(slf_: &Bound<'_, Self>, key: &str) -> PyResult<bool> {
+        let key = key.to_string();
+        slf_.with_connection(move |connection| key_exists(connection, &key))
+    }
+
+    fn __getitem__(slf_: &Bound<'_, Self>, key: &str) -> PyResult<String> {
+        let db_key = key.to_string();
+        slf_.with_connection(move |connection| get_string(connection, &db_key))?
+            .ok_or_else(|| {
+                let error_msg = format!("The key '{key}' is not present in the database.");
+                PyKeyError::new_err(error_msg)
+            })
+    }
+
+    fn __setitem__(slf_: &Bound<'_, Self>, key: &str, item: &Bound<'_, PyAny>) -> PyResult<()> {
+        slf_.set_item(key, item)
+    }
+
+    fn __delitem__(slf_: &Bound<'_, Self>, key: &str) -> PyResult<()> {
+        let db_key = key.to_string();
+        if !slf_.with_connection(move |connection| delete_key(connection, &db_key))? {
+            let error_msg = format!("The key '{key}' is not present in the database.");
+            return Err(PyKeyError::new_err(error_msg));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the permission of a player.
+    #[pyo3(name = "set_permission")]
+    fn set_permission(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        level: i32,
+    ) -> PyResult<()> {
+        slf.set_permission(player, level)
+    }
+
+    /// Gets the permission of a player.
+    fn get_permission(slf: &Bound<'_, Self>, player: &Bound<'_, PyAny>) -> PyResult<i32> {
+        slf.get_permission(player)
+    }
+
+    /// Checks if the player has higher than or equal to *level*.
+    #[pyo3(name = "has_permission", signature = (player, level = 5), text_signature = "(player, level=5)")]
+    fn has_permission(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        level: i32,
+    ) -> PyResult<bool> {
+        slf.has_permission(player, level)
+    }
+
+    /// Sets specified player flag
+    #[pyo3(name = "set_flag", signature = (player, flag, value = true), text_signature = "(player, flag, value = True)")]
+    fn set_flag(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        flag: &str,
+        value: bool,
+    ) -> PyResult<()> {
+        slf.set_flag(player, flag, value)
+    }
+
+    /// returns the specified player flag
+    #[pyo3(name = "get_flag", signature = (player, flag, default = false), text_signature = "(player, flag, default=False)")]
+    fn get_flag(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        flag: &str,
+        default: bool,
+    ) -> PyResult<bool> {
+        slf.get_flag(player, flag, default)
+    }
+
+    /// Opens the SQLite database. If *path* is None, it will fall back to the file configured
+    /// in ``qlx_sqlitePath`` and share the connection across any plugins using the default
+    /// configuration. Passing *path* will open a specific database file that is not shared at all.
+    #[pyo3(name = "connect", signature = (path = None), text_signature = "(path = None)")]
+    fn connect<'py>(slf: &Bound<'py, Self>, path: Option<&str>) -> PyResult<Bound<'py, PyAny>> {
+        match path {
+            None => slf.connect(),
+            Some(database_path) => slf.connect_with_path(database_path),
+        }
+    }
+
+    /// Close the SQLite connection if the path was overridden. Otherwise only do so
+    /// if this is the last plugin using the default connection.
+    fn close(slf: &Bound<'_, Self>) -> PyResult<()> {
+        slf.close()
+    }
+
+    fn get(slf: &Bound<'_, Self>, name: &str) -> PyResult<Option<String>> {
+        let key = name.to_string();
+        slf.with_connection(move |connection| get_string(connection, &key))
+    }
+
+    #[pyo3(signature = (name, value, ex = None, nx = false, xx = false), text_signature = "(name, value, ex=None, nx=False, xx=False)")]
+    fn set(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        ex: Option<&Bound<'_, PyAny>>,
+        nx: bool,
+        xx: bool,
+    ) -> PyResult<Option<bool>> {
+        let key = name.to_string();
+        let db_value = database_value(value)?;
+        let expires_in = ex.map(time_in_seconds).transpose()?;
+
+        slf.with_connection(move |connection| {
+            let exists = get_string(connection, &key)?.is_some();
+            if (nx && exists) || (xx && !exists) {
+                return Ok(None);
+            }
+            set_string(connection, &key, &db_value, expires_in).map(|_| Some(true))
+        })
+    }
+
+    #[pyo3(signature = (*names))]
+    fn delete(slf: &Bound<'_, Self>, names: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let keys = database_values(names.iter())?;
+        slf.with_connection(move |connection| {
+            keys.iter()
+                .map(|key| delete_key(connection, key))
+                .fold_ok(0, |deleted, was_deleted| deleted + was_deleted as usize)
+        })
+    }
+
+    #[pyo3(signature = (*names))]
+    fn exists(slf: &Bound<'_, Self>, names: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let keys = database_values(names.iter())?;
+        slf.with_connection(move |connection| {
+            keys.iter()
+                .map(|key| key_exists(connection, key))
+                .fold_ok(0, |existing, exists| existing + exists as usize)
+        })
+    }
+
+    #[pyo3(name = "type")]
+    fn type_name(slf: &Bound<'_, Self>, name: &str) -> PyResult<&'static str> {
+        let key = name.to_string();
+        slf.with_connection(move |connection| {
+            connection
+                .query_row(
+                    "SELECT 'string' FROM kv WHERE key = ?1 \
+                     UNION ALL SELECT 'hash' FROM hashes WHERE key = ?1 \
+                     UNION ALL SELECT 'set' FROM sets WHERE key = ?1 \
+                     UNION ALL SELECT 'zset' FROM sorted_sets WHERE key = ?1 \
+                     LIMIT 1",
+                    [&key],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()
+                .map(|type_name| match type_name.as_deref() {
+                    Some("string") => "string",
+                    Some("hash") => "hash",
+                    Some("set") => "set",
+                    Some("zset") => "zset",
+                    _ => "none",
+                })
+        })
+    }
+
+    #[pyo3(signature = (pattern = "*"), text_signature = "(pattern=\"*\")")]
+    fn keys(slf: &Bound<'_, Self>, pattern: &str) -> PyResult<Vec<String>> {
+        let glob = pattern.to_string();
+        slf.with_connection(move |connection| {
+            connection
+                .prepare(
+                    "SELECT key FROM kv WHERE key GLOB ?1 \
+                     UNION SELECT key FROM hashes WHERE key GLOB ?1 \
+                     UNION SELECT key FROM sets WHERE key GLOB ?1 \
+                     UNION SELECT key FROM sorted_sets WHERE key GLOB ?1",
+                )?
+                .query_map([glob], |row| row.get(0))?
+                .collect()
+        })
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn incr(slf: &Bound<'_, Self>, name: &str, amount: i64) -> PyResult<i64> {
+        let key = name.to_string();
+        slf.with_connection(move |connection| increment_string(connection, &key, amount))
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn incrby(slf: &Bound<'_, Self>, name: &str, amount: i64) -> PyResult<i64> {
+        Self::incr(slf, name, amount)
+    }
+
+    #[pyo3(signature = (name, amount = 1), text_signature = "(name, amount=1)")]
+    fn decr(slf: &Bound<'_, Self>, name: &str, amount: i64) -> PyResult<i64> {
+        Self::incr(slf, name, -amount)
+    }
+
+    fn expire(slf: &Bound<'_, Self>, name: &str, time: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let key = name.to_string();
+        let seconds = time_in_seconds(time)?;
+        slf.with_connection(move |connection| {
+            connection
+                .execute(
+                    "UPDATE kv SET expires_at = unixepoch() + ?2 WHERE key = ?1",
+                    params![key, seconds],
+                )
+                .map(|updated| updated > 0)
+        })
+    }
+
+    fn ttl(slf: &Bound<'_, Self>, name: &str) -> PyResult<i64> {
+        let key = name.to_string();
+        slf.with_connection(move |connection| {
+            if !key_exists(connection, &key)? {
+                return Ok(-2);
+            }
+            connection
+                .query_row(
+                    "SELECT expires_at - unixepoch() FROM kv WHERE key = ?1",
+                    [&key],
+                    |row| row.get::<_, Option<i64>>(0),
+                )
+                .optional()
+                .map(|remaining| remaining.flatten().unwrap_or(-1))
+        })
+    }
+
+    fn hget(slf: &Bound<'_, Self>, name: &str, key: &str) -> PyResult<Option<String>> {
+        let (hash_key, field) = (name.to_string(), key.to_string());
+        slf.with_connection(move |connection| {
+            connection
+                .query_row(
+                    "SELECT value FROM hashes WHERE key = ?1 AND field = ?2",
+                    [hash_key, field],
+                    |row| row.get(0),
+                )
+                .optional()
+        })
+    }
+
+    #[pyo3(signature = (name, key = None, value = None, mapping = None), text_signature = "(name, key=None, value=None, mapping=None)")]
+    fn hset(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        key: Option<&Bound<'_, PyAny>>,
+        value: Option<&Bound<'_, PyAny>>,
+        mapping: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<usize> {
+        let mut fields = vec![];
+        if let (Some(field), Some(field_value)) = (key, value) {
+            fields.push((database_value(field)?, database_value(field_value)?));
+        }
+        if let Some(field_mapping) = mapping {
+            field_mapping.iter().try_for_each(|(field, field_value)| {
+                fields.push((database_value(&field)?, database_value(&field_value)?));
+                Ok::<(), PyErr>(())
+            })?;
+        }
+
+        if fields.is_empty() {
+            cold_path();
+            return Err(PyValueError::new_err("'hset' with no key value pairs"));
+        }
+
+        let hash_key = name.to_string();
+        slf.with_connection(move |connection| {
+            let transaction = connection.transaction()?;
+            let added = fields
+                .iter()
+                .map(|(field, field_value)| {
+                    let existed = transaction
+                        .query_row(
+                            "SELECT 1 FROM hashes WHERE key = ?1 AND field = ?2",
+                            [&hash_key, field],
+                            |_| Ok(()),
+                        )
+                        .optional()?
+                        .is_some();
+                    transaction.execute(
+                        "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3) \
+                         ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                        [&hash_key, field, field_value],
+                    )?;
+                    Ok::<_, rusqlite::Error>(!existed as usize)
+                })
+                .fold_ok(0, |added, field_added| added + field_added)?;
+            transaction.commit().map(|_| added)
+        })
+    }
+
+    fn hgetall(slf: &Bound<'_, Self>, name: &str) -> PyResult<HashMap<String, String>> {
+        let hash_key = name.to_string();
+        slf.with_connection(move |connection| {
+            connection
+                .prepare("SELECT field, value FROM hashes WHERE key = ?1")?
+                .query_map([hash_key], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect()
+        })
+    }
+
+    #[pyo3(signature = (name, *keys))]
+    fn hdel(slf: &Bound<'_, Self>, name: &str, keys: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let hash_key = name.to_string();
+        let fields = database_values(keys.iter())?;
+        slf.with_connection(move |connection| {
+            fields
+                .iter()
+                .map(|field| {
+                    connection.execute(
+                        "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                        [&hash_key, field],
+                    )
+                })
+                .fold_ok(0, |deleted, rows| deleted + rows)
+        })
+    }
+
+    fn hexists(slf: &Bound<'_, Self>, name: &str, key: &str) -> PyResult<bool> {
+        Self::hget(slf, name, key).map(|value| value.is_some())
+    }
+
+    #[pyo3(signature = (name, key, amount = 1), text_signature = "(name, key, amount=1)")]
+    fn hincrby(slf: &Bound<'_, Self>, name: &str, key: &str, amount: i64) -> PyResult<i64> {
+        let (hash_key, field) = (name.to_string(), key.to_string());
+        slf.with_connection(move |connection| {
+            let current = connection
+                .query_row(
+                    "SELECT value FROM hashes WHERE key = ?1 AND field = ?2",
+                    [&hash_key, &field],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()?
+                .map(|value| value.parse::<i64>().map_err(|_| not_an_integer()))
+                .transpose()?
+                .unwrap_or_default();
+            let new_value = current.checked_add(amount).ok_or_else(not_an_integer)?;
+            connection.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3) \
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![hash_key, field, new_value.to_string()],
+            )?;
+            Ok(new_value)
+        })
+    }
+
+    fn hkeys(slf: &Bound<'_, Self>, name: &str) -> PyResult<Vec<String>> {
+        Self::hgetall(slf, name).map(|hash| hash.into_keys().collect())
+    }
+
+    fn hvals(slf: &Bound<'_, Self>, name: &str) -> PyResult<Vec<String>> {
+        Self::hgetall(slf, name).map(|hash| hash.into_values().collect())
+    }
+
+    fn hlen(slf: &Bound<'_, Self>, name: &str) -> PyResult<usize> {
+        Self::hgetall(slf, name).map(|hash| hash.len())
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn sadd(slf: &Bound<'_, Self>, name: &str, values: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let set_key = name.to_string();
+        let members = database_values(values.iter())?;
+        slf.with_connection(move |connection| {
+            members
+                .iter()
+                .map(|member| {
+                    connection.execute(
+                        "INSERT OR IGNORE INTO sets (key, member) VALUES (?1, ?2)",
+                        [&set_key, member],
+                    )
+                })
+                .fold_ok(0, |added, rows| added + rows)
+        })
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn srem(slf: &Bound<'_, Self>, name: &str, values: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let set_key = name.to_string();
+        let members = database_values(values.iter())?;
+        slf.with_connection(move |connection| {
+            members
+                .iter()
+                .map(|member| {
+                    connection.execute(
+                        "DELETE FROM sets WHERE key = ?1 AND member = ?2",
+                        [&set_key, member],
+                    )
+                })
+                .fold_ok(0, |removed, rows| removed + rows)
+        })
+    }
+
+    fn smembers(slf: &Bound<'_, Self>, name: &str) -> PyResult<HashSet<String>> {
+        let set_key = name.to_string();
+        slf.with_connection(move |connection| {
+            connection
+                .prepare("SELECT member FROM sets WHERE key = ?1")?
+                .query_map([set_key], |row| row.get(0))?
+                .collect()
+        })
+    }
+
+    fn sismember(slf: &Bound<'_, Self>, name: &str, value: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let set_key = name.to_string();
+        let member = database_value(value)?;
+        slf.with_connection(move |connection| {
+            connection.query_row(
+                "SELECT EXISTS (SELECT 1 FROM sets WHERE key = ?1 AND member = ?2)",
+                [set_key, member],
+                |row| row.get(0),
+            )
+        })
+    }
+
+    fn scard(slf: &Bound<'_, Self>, name: &str) -> PyResult<usize> {
+        Self::smembers(slf, name).map(|members| members.len())
+    }
+
+    fn zscore(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<f64>> {
+        let sorted_set_key = name.to_string();
+        let member = database_value(value)?;
+        slf.with_connection(move |connection| {
+            sorted_member_score(connection, &sorted_set_key, &member)
+        })
+    }
+
+    #[pyo3(signature = (name, start, end, desc = false, withscores = false), text_signature = "(name, start, end, desc=False, withscores=False)")]
+    fn zrange<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        start: i64,
+        end: i64,
+        desc: bool,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let sorted_set_key = name.to_string();
+        let members = slf
+            .with_connection(move |connection| sorted_members(connection, &sorted_set_key, desc))?;
+
+        let selected = index_range(members.len(), start, end)
+            .map(|(first, last)| members[first..=last].to_vec())
+            .unwrap_or_default();
+        sorted_members_into_py(slf.py(), selected, withscores)
+    }
+
+    #[pyo3(signature = (name, start, end, withscores = false), text_signature = "(name, start, end, withscores=False)")]
+    fn zrevrange<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        start: i64,
+        end: i64,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        Self::zrange(slf, name, start, end, true, withscores)
+    }
+
+    #[pyo3(signature = (name, min, max, withscores = false), text_signature = "(name, min, max, withscores=False)")]
+    fn zrangebyscore<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        min: &Bound<'py, PyAny>,
+        max: &Bound<'py, PyAny>,
+        withscores: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (min_score, min_exclusive) = score_boundary(min)?;
+        let (max_score, max_exclusive) = score_boundary(max)?;
+        let sorted_set_key = name.to_string();
+        let members = slf.with_connection(move |connection| {
+            sorted_members(connection, &sorted_set_key, false)
+        })?;
+
+        let selected = members
+            .into_iter()
+            .filter(|(_, score)| {
+                (if min_exclusive {
+                    *score > min_score
+                } else {
+                    *score >= min_score
+                }) && (if max_exclusive {
+                    *score < max_score
+                } else {
+                    *score <= max_score
+                })
+            })
+            .collect();
+        sorted_members_into_py(slf.py(), selected, withscores)
+    }
+
+    #[pyo3(signature = (name, *values))]
+    fn zrem(slf: &Bound<'_, Self>, name: &str, values: &Bound<'_, PyTuple>) -> PyResult<usize> {
+        let sorted_set_key = name.to_string();
+        let members = database_values(values.iter())?;
+        slf.with_connection(move |connection| {
+            members
+                .iter()
+                .map(|member| {
+                    connection.execute(
+                        "DELETE FROM sorted_sets WHERE key = ?1 AND member = ?2",
+                        [&sorted_set_key, member],
+                    )
+                })
+                .fold_ok(0, |removed, rows| removed + rows)
+        })
+    }
+
+    fn zcard(slf: &Bound<'_, Self>, name: &str) -> PyResult<usize> {
+        let sorted_set_key = name.to_string();
+        slf.with_connection(move |connection| {
+            connection.query_row(
+                "SELECT COUNT(*) FROM sorted_sets WHERE key = ?1",
+                [sorted_set_key],
+                |row| row.get::<_, i64>(0).map(|count| count as usize),
+            )
+        })
+    }
+
+    fn zrank(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<usize>> {
+        slf.sorted_rank(name, value, false)
+    }
+
+    fn zrevrank(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<usize>> {
+        slf.sorted_rank(name, value, true)
+    }
+
+    #[pyo3(name = "mset", signature = (*args, **kwargs))]
+    fn mset(
+        slf: &Bound<'_, Self>,
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<bool> {
+        slf.mset(args, kwargs)
+    }
+
+    #[pyo3(name = "msetnx", signature = (*args, **kwargs))]
+    fn msetnx(
+        slf: &Bound<'_, Self>,
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<bool> {
+        slf.msetnx(args, kwargs)
+    }
+
+    #[pyo3(name = "zadd", signature = (name, *args, **kwargs))]
+    fn zadd<'py>(
+        slf: &Bound<'py, Self>,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        slf.zadd(name, args, kwargs)
+    }
+
+    #[pyo3(name = "zincrby", signature = (name, *, value, amount), text_signature = "(name, *, value, amount)")]
+    fn zincrby(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        amount: f64,
+    ) -> PyResult<f64> {
+        slf.zincrby(name, value, amount)
+    }
+
+    #[pyo3(name = "setex", signature = (name, *, value, time), text_signature = "(name, *, value, time)")]
+    fn setex(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        slf.setx(name, value, time)
+    }
+
+    #[pyo3(name = "setx", signature = (name, *, value, time), text_signature = "(name, *, value, time)")]
+    fn setx(
+        slf: &Bound<'_, Self>,
+        name: &str,
+        value: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        slf.setx(name, value, time)
+    }
+}
+
+impl<'py> AbstractDatabaseMethods<'py> for Bound<'py, Sqlite> {
+    fn get_logger(&self) -> PyResult<Bound<'py, PyAny>> {
+        self.as_super().get_logger()
+    }
+
+    fn set_permission(&self, player: &Bound<'py, PyAny>, level: i32) -> PyResult<()> {
+        let key = permission_key(player)?;
+
+        self.set_item(&key, PyInt::new(self.py(), level).as_any())
+    }
+
+    fn get_permission(&self, player: &Bound<'py, PyAny>) -> PyResult<i32> {
+        let steam_id = player_steam_id(player)?;
+
+        if Some(steam_id) == owner()? {
+            return Ok(5);
+        }
+
+        let key = format!("minqlx:players:{steam_id}:permission");
+        match self.with_connection(move |connection| get_string(connection, &key))? {
+            None => Ok(0),
+            Some(value) => value.parse::<i32>().map_err(|_| {
+                let error_msg = format!("invalid literal for int() with base 10: '{value}'");
+                PyValueError::new_err(error_msg)
+            }),
+        }
+    }
+
+    fn has_permission(&self, player: &Bound<'py, PyAny>, level: i32) -> PyResult<bool> {
+        self.get_permission(player).map(|value| value >= level)
+    }
+
+    fn set_flag(&self, player: &Bound<'py, PyAny>, flag: &str, value: bool) -> PyResult<()> {
+        let key = flag_key(player, flag)?;
+
+        let db_value = if value { 1i32 } else { 0i32 };
+
+        self.set_item(&key, PyInt::new(self.py(), db_value).as_any())
+    }
+
+    fn clear_flag(&self, player: &Bound<'py, PyAny>, flag: &str) -> PyResult<()> {
+        self.set_flag(player, flag, false)
+    }
+
+    fn get_flag(&self, player: &Bound<'py, PyAny>, flag: &str, default: bool) -> PyResult<bool> {
+        let key = flag_key(player, flag)?;
+
+        self.with_connection(move |connection| get_string(connection, &key))
+            .map(|value| value.map_or(default, |flag_value| flag_value != "0"))
+    }
+
+    fn connect(&self) -> PyResult<Bound<'py, PyAny>> {
+        if self.get().connection.load().is_none() && DEFAULT_CONNECTION.load().is_none() {
+            let connection = open_connection(self.py(), &default_database_path()?)?;
+            DEFAULT_CONNECTION.store(Some(connection));
+        }
+
+        Ok(self.to_owned().into_any())
+    }
+
+    fn close(&self) -> PyResult<()> {
+        self.get().connection.store(None);
+
+        let sqlite_type = self.py().get_type::<Sqlite>();
+        let class_counter = sqlite_type
+            .getattr(intern!(self.py(), "_counter"))
+            .and_then(|value| value.extract::<i32>())
+            .unwrap_or(0);
+        if class_counter <= 1 {
+            DEFAULT_CONNECTION.store(None);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) trait SqliteMethods<'py> {
+    fn connect_with_path(&self, path: &str) -> PyResult<Bound<'py, PyAny>>;
+    fn with_connection<T, F>(&self, func: F) -> PyResult<T>
+    where
+        T: Ungil,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Ungil;
+    fn set_item(&self, key: &str, item: &Bound<'py, PyAny>) -> PyResult<()>;
+    fn sorted_rank(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        desc: bool,
+    ) -> PyResult<Option<usize>>;
+    fn mset(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<bool>;
+    fn msetnx(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<bool>;
+    fn zadd(
+        &self,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+    fn zincrby(&self, name: &str, value: &Bound<'py, PyAny>, amount: f64) -> PyResult<f64>;
+    fn setx(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        time: &Bound<'py, PyAny>,
+    ) -> PyResult<bool>;
+}
+
+impl<'py> SqliteMethods<'py> for Bound<'py, Sqlite> {
+    fn connect_with_path(&self, path: &str) -> PyResult<Bound<'py, PyAny>> {
+        if self.get().connection.load().is_none() {
+            let connection = open_connection(self.py(), path)?;
+            self.get().connection.store(Some(connection));
+        }
+
+        Ok(self.to_owned().into_any())
+    }
+
+    fn with_connection<T, F>(&self, func: F) -> PyResult<T>
+    where
+        T: Ungil,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Ungil,
+    {
+        self.connect()?;
+        let Some(connection) = self
+            .get()
+            .connection
+            .load_full()
+            .or_else(|| DEFAULT_CONNECTION.load_full())
+        else {
+            cold_path();
+            return Err(SqliteError::new_err(
+                "no connection to the sqlite database.",
+            ));
+        };
+
+        self.py()
+            .detach(|| {
+                let call_start = Instant::now();
+                let mut locked_connection = connection.lock();
+                purge_expired(&locked_connection).map_err(sqlite_error)?;
+                let result = func(&mut locked_connection);
+                record_sqlite_call(call_start.elapsed());
+                result.map_err(sqlite_error)
+            })
+            .map_err(SqliteError::new_err)
+    }
+
+    fn set_item(&self, key: &str, item: &Bound<'py, PyAny>) -> PyResult<()> {
+        let db_key = key.to_string();
+        let value = database_value(item)?;
+        let updated =
+            self.with_connection(move |connection| set_string(connection, &db_key, &value, None))?;
+
+        if updated == 0 {
+            cold_path();
+            return Err(PyRuntimeError::new_err("The database assignment failed."));
+        }
+
+        Ok(())
+    }
+
+    fn sorted_rank(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        desc: bool,
+    ) -> PyResult<Option<usize>> {
+        let sorted_set_key = name.to_string();
+        let member = database_value(value)?;
+        self.with_connection(move |connection| {
+            sorted_members(connection, &sorted_set_key, desc).map(|members| {
+                members
+                    .iter()
+                    .position(|(sorted_member, _)| *sorted_member == member)
+            })
+        })
+    }
+
+    fn mset(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<bool> {
+        let mapping = mset_mapping("MSET", args, kwargs)?;
+        self.with_connection(move |connection| {
+            let transaction = connection.transaction()?;
+            mapping.iter().try_for_each(|(key, value)| {
+                set_string(&transaction, key, value, None).map(|_| ())
+            })?;
+            transaction.commit().map(|_| true)
+        })
+    }
+
+    fn msetnx(
+        &self,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<bool> {
+        let mapping = mset_mapping("MSETNX", args, kwargs)?;
+        self.with_connection(move |connection| {
+            let transaction = connection.transaction()?;
+            for (key, _) in &mapping {
+                if key_exists(&transaction, key)? {
+                    return Ok(false);
+                }
+            }
+            mapping.iter().try_for_each(|(key, value)| {
+                set_string(&transaction, key, value, None).map(|_| ())
+            })?;
+            transaction.commit().map(|_| true)
+        })
+    }
+
+    fn zadd(
+        &self,
+        name: &str,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (options, pieces) = zadd_arguments(args, kwargs)?;
+
+        let sorted_set_key = name.to_string();
+        let (changed, incremented) = self.with_connection(move |connection| {
+            let transaction = connection.transaction()?;
+            let mut changed = 0usize;
+            let mut incremented = None;
+            for (member, score) in &pieces {
+                let existing = sorted_member_score(&transaction, &sorted_set_key, member)?;
+                let Some(new_score) = options.new_score(existing, *score) else {
+                    continue;
+                };
+
+                upsert_sorted_member(&transaction, &sorted_set_key, member, new_score)?;
+                incremented = Some(new_score);
+                match existing {
+                    None => changed += 1,
+                    Some(old_score) if options.ch && old_score != new_score => changed += 1,
+                    _ => (),
+                }
+            }
+            transaction.commit().map(|_| (changed, incremented))
+        })?;
+
+        if options.incr {
+            incremented.into_bound_py_any(self.py())
+        } else {
+            changed.into_bound_py_any(self.py())
+        }
+    }
+
+    fn zincrby(&self, name: &str, value: &Bound<'py, PyAny>, amount: f64) -> PyResult<f64> {
+        let sorted_set_key = name.to_string();
+        let member = database_value(value)?;
+        self.with_connection(move |connection| {
+            let new_score = sorted_member_score(connection, &sorted_set_key, &member)?
+                .unwrap_or_default()
+                + amount;
+            upsert_sorted_member(connection, &sorted_set_key, &member, new_score).map(|_| new_score)
+        })
+    }
+
+    fn setx(
+        &self,
+        name: &str,
+        value: &Bound<'py, PyAny>,
+        time: &Bound<'py, PyAny>,
+    ) -> PyResult<bool> {
+        let key = name.to_string();
+        let db_value = database_value(value)?;
+        let seconds = time_in_seconds(time)?;
+        self.with_connection(move |connection| {
+            set_string(connection, &key, &db_value, Some(seconds)).map(|updated| updated > 0)
+        })
+    }
+}
+
+#[cfg(test)]
+mod sqlite_tests {
+    use pretty_assertions::assert_eq;
+    use pyo3::{
+        exceptions::{PyEnvironmentError, PyKeyError, PyValueError},
+        intern,
+        prelude::*,
+        types::{IntoPyDict, PyDict, PyTuple},
+    };
+    use rstest::rstest;
+
+    use super::{
+        super::super::{prelude::pyshinqlx_setup, pyshinqlx_test_support::default_test_player},
+        AbstractDatabaseMethods, Sqlite, SqliteError, SqliteMethods,
+    };
+    use crate::prelude::*;
+
+    fn connected_sqlite(py: Python<'_>) -> Bound<'_, Sqlite> {
+        let sqlite =
+            Bound::new(py, Sqlite::py_new(py, py.None().bind(py))).expect("this should not happen");
+        sqlite
+            .connect_with_path(":memory:")
+            .expect("this should not happen");
+        sqlite
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn connect_with_no_main_engine(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = Bound::new(py, Sqlite::py_new(py, py.None().bind(py)))
+                .expect("this should not happen");
+
+            let result = sqlite.connect();
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyEnvironmentError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn setitem_and_getitem_roundtrip(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            sqlite
+                .set_item("minqlx:test", 42i32.into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+
+            assert_eq!(
+                Sqlite::__getitem__(&sqlite, "minqlx:test").expect("this should not happen"),
+                "42"
+            );
+            assert!(Sqlite::__contains__(&sqlite, "minqlx:test").expect("this should not happen"));
+
+            Sqlite::__delitem__(&sqlite, "minqlx:test").expect("this should not happen");
+            let result = Sqlite::__getitem__(&sqlite, "minqlx:test");
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyKeyError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn delitem_for_missing_key_raises_key_error(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            let result = Sqlite::__delitem__(&sqlite, "minqlx:missing");
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyKeyError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn setitem_with_boolean_value(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            let result = sqlite.set_item(
+                "minqlx:test",
+                true.into_pyobject(py).unwrap().to_owned().as_any(),
+            );
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyValueError>(py)));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn permissions_roundtrip(_pyshinqlx_setup: ()) {
+        MockEngineBuilder::default()
+            .with_find_cvar(|cmd| cmd == "qlx_owner", |_| None, 0..)
+            .run(|| {
+                Python::attach(|py| {
+                    let sqlite = connected_sqlite(py);
+                    let player = Bound::new(py, default_test_player())
+                        .expect("this should not happen")
+                        .into_any();
+
+                    assert_eq!(
+                        sqlite
+                            .get_permission(&player)
+                            .expect("this should not happen"),
+                        0
+                    );
+
+                    sqlite
+                        .set_permission(&player, 3)
+                        .expect("this should not happen");
+
+                    assert_eq!(
+                        sqlite
+                            .get_permission(&player)
+                            .expect("this should not happen"),
+                        3
+                    );
+                    assert!(
+                        sqlite
+                            .has_permission(&player, 3)
+                            .expect("this should not happen")
+                    );
+                    assert!(
+                        !sqlite
+                            .has_permission(&player, 4)
+                            .expect("this should not happen")
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn flags_roundtrip(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+            let player = Bound::new(py, default_test_player())
+                .expect("this should not happen")
+                .into_any();
+
+            assert!(
+                sqlite
+                    .get_flag(&player, "muted", true)
+                    .expect("this should not happen")
+            );
+
+            sqlite
+                .set_flag(&player, "muted", true)
+                .expect("this should not happen");
+            assert!(
+                sqlite
+                    .get_flag(&player, "muted", false)
+                    .expect("this should not happen")
+            );
+
+            sqlite
+                .clear_flag(&player, "muted")
+                .expect("this should not happen");
+            assert!(
+                !sqlite
+                    .get_flag(&player, "muted", true)
+                    .expect("this should not happen")
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn incr_on_non_integer_value(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            sqlite
+                .set_item("minqlx:test", "asdf".into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+
+            let result = Sqlite::incr(&sqlite, "minqlx:test", 1);
+            assert!(result.is_err_and(|err| err.is_instance_of::<SqliteError>(py)));
+
+            assert_eq!(
+                Sqlite::incr(&sqlite, "minqlx:counter", 3).expect("this should not happen"),
+                3
+            );
+            assert_eq!(
+                Sqlite::decr(&sqlite, "minqlx:counter", 1).expect("this should not happen"),
+                2
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn expired_keys_are_not_returned(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            sqlite
+                .setx(
+                    "minqlx:expired",
+                    "asdf".into_pyobject(py).unwrap().as_any(),
+                    (-1i32).into_pyobject(py).unwrap().as_any(),
+                )
+                .expect("this should not happen");
+            sqlite
+                .setx(
+                    "minqlx:fresh",
+                    "asdf".into_pyobject(py).unwrap().as_any(),
+                    60i32.into_pyobject(py).unwrap().as_any(),
+                )
+                .expect("this should not happen");
+
+            assert_eq!(
+                Sqlite::get(&sqlite, "minqlx:expired").expect("this should not happen"),
+                None
+            );
+            assert!(
+                Sqlite::ttl(&sqlite, "minqlx:fresh")
+                    .is_ok_and(|remaining| remaining > 0 && remaining <= 60)
+            );
+            assert_eq!(
+                Sqlite::ttl(&sqlite, "minqlx:missing").expect("this should not happen"),
+                -2
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn hashes_roundtrip(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            let mapping = [("field1", "value1"), ("field2", "value2")]
+                .into_py_dict(py)
+                .expect("this should not happen");
+            let added = Sqlite::hset(&sqlite, "minqlx:hash", None, None, Some(&mapping))
+                .expect("this should not happen");
+            assert_eq!(added, 2);
+
+            assert_eq!(
+                Sqlite::hget(&sqlite, "minqlx:hash", "field1").expect("this should not happen"),
+                Some("value1".to_string())
+            );
+            assert_eq!(
+                Sqlite::hincrby(&sqlite, "minqlx:hash", "count", 2)
+                    .expect("this should not happen"),
+                2
+            );
+            assert_eq!(
+                Sqlite::hlen(&sqlite, "minqlx:hash").expect("this should not happen"),
+                3
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn sorted_sets_roundtrip(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            let mapping = PyDict::new(py);
+            mapping
+                .set_item("player1", 10)
+                .expect("this should not happen");
+            mapping
+                .set_item("player2", 20)
+                .expect("this should not happen");
+            let added = sqlite
+                .zadd(
+                    "minqlx:scores",
+                    &PyTuple::new(py, [mapping]).expect("this should not happen"),
+                    None,
+                )
+                .expect("this should not happen");
+            assert_eq!(added.extract::<usize>().expect("this should not happen"), 2);
+
+            assert_eq!(
+                sqlite
+                    .zincrby(
+                        "minqlx:scores",
+                        "player1".into_pyobject(py).unwrap().as_any(),
+                        15.0
+                    )
+                    .expect("this should not happen"),
+                25.0
+            );
+
+            let ranking = sqlite
+                .call_method(
+                    intern!(py, "zrevrange"),
+                    ("minqlx:scores", 0, -1),
+                    Some(
+                        &[("withscores", true)]
+                            .into_py_dict(py)
+                            .expect("this should not happen"),
+                    ),
+                )
+                .expect("this should not happen");
+            assert_eq!(
+                ranking
+                    .extract::<Vec<(String, f64)>>()
+                    .expect("this should not happen"),
+                vec![("player1".to_string(), 25.0), ("player2".to_string(), 20.0)]
+            );
+            assert_eq!(
+                Sqlite::zrank(
+                    &sqlite,
+                    "minqlx:scores",
+                    "player1".into_pyobject(py).unwrap().as_any()
+                )
+                .expect("this should not happen"),
+                Some(1)
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn zadd_with_nx_keeps_existing_scores(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            sqlite
+                .zadd(
+                    "minqlx:scores",
+                    &(10, "player1")
+                        .into_pyobject(py)
+                        .expect("this should not happen"),
+                    None,
+                )
+                .expect("this should not happen");
+            sqlite
+                .zadd(
+                    "minqlx:scores",
+                    &(20, "player1")
+                        .into_pyobject(py)
+                        .expect("this should not happen"),
+                    Some(
+                        &[("nx", true)]
+                            .into_py_dict(py)
+                            .expect("this should not happen"),
+                    ),
+                )
+                .expect("this should not happen");
+
+            assert_eq!(
+                Sqlite::zscore(
+                    &sqlite,
+                    "minqlx:scores",
+                    "player1".into_pyobject(py).unwrap().as_any()
+                )
+                .expect("this should not happen"),
+                Some(10.0)
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn msetnx_with_existing_key_sets_nothing(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            sqlite
+                .set_item(
+                    "minqlx:existing",
+                    "asdf".into_pyobject(py).unwrap().as_any(),
+                )
+                .expect("this should not happen");
+
+            let mapping = [("minqlx:existing", "new"), ("minqlx:new", "new")]
+                .into_py_dict(py)
+                .expect("this should not happen");
+            let result = sqlite
+                .msetnx(
+                    &PyTuple::new(py, [mapping]).expect("this should not happen"),
+                    None,
+                )
+                .expect("this should not happen");
+            assert!(!result);
+            assert_eq!(
+                Sqlite::get(&sqlite, "minqlx:new").expect("this should not happen"),
+                None
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn keys_match_glob_pattern_across_types(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let sqlite = connected_sqlite(py);
+
+            sqlite
+                .set_item("minqlx:players:1", "a".into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+            Sqlite::sadd(
+                &sqlite,
+                "minqlx:players:2",
+                &PyTuple::new(py, ["b"]).expect("this should not happen"),
+            )
+            .expect("this should not happen");
+            sqlite
+                .set_item("minqlx:other", "c".into_pyobject(py).unwrap().as_any())
+                .expect("this should not happen");
+
+            let mut keys =
+                Sqlite::keys(&sqlite, "minqlx:players:*").expect("this should not happen");
+            keys.sort();
+            assert_eq!(keys, vec!["minqlx:players:1", "minqlx:players:2"]);
+        });
+    }
+}
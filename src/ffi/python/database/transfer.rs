@@ -0,0 +1,735 @@
+use core::hint::cold_path;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+    sync::LazyLock,
+};
+
+use chrono::Utc;
+use log::error;
+use parking_lot::RwLock;
+use pyo3::{
+    IntoPyObjectExt,
+    exceptions::{PyEnvironmentError, PyValueError},
+    intern,
+    prelude::*,
+    types::{IntoPyDict, PySet, PyTuple},
+};
+use ql_dbformat::{StoredEntry, StoredValue, entries_from_json, entries_to_json};
+use serde_json::{Map, Value, json};
+
+use super::{super::prelude::*, InMemoryError};
+
+/// The key prefix exported and imported when none is given.
+pub(crate) const DEFAULT_TRANSFER_PREFIX: &str = "minqlx:";
+
+struct Migration {
+    prefix: String,
+    version: u64,
+    migration: Py<PyAny>,
+}
+
+/// Migrations registered by plugins, applied when importing data exported with an older schema.
+static MIGRATIONS: LazyLock<RwLock<Vec<Migration>>> = LazyLock::new(Default::default);
+
+/// The schema version of every prefix that has migrations registered.
+fn schema_versions() -> BTreeMap<String, u64> {
+    MIGRATIONS
+        .read()
+        .iter()
+        .fold(BTreeMap::new(), |mut versions, migration| {
+            let version = versions.entry(migration.prefix.clone()).or_default();
+            *version = (*version).max(migration.version);
+            versions
+        })
+}
+
+fn stored_value_into_py<'py>(py: Python<'py>, value: &StoredValue) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        StoredValue::String(string) => string.into_bound_py_any(py),
+        StoredValue::Hash(hash) => hash.into_bound_py_any(py),
+        StoredValue::Set(set) => PySet::new(py, set).map(|py_set| py_set.into_any()),
+        StoredValue::SortedSet(sorted_set) => sorted_set.into_bound_py_any(py),
+        StoredValue::List(list) => list.iter().collect::<Vec<_>>().into_bound_py_any(py),
+    }
+}
+
+fn stored_value_from_py(type_name: &str, value: &Bound<'_, PyAny>) -> PyResult<StoredValue> {
+    match type_name {
+        "string" => value.extract::<String>().map(StoredValue::String),
+        "hash" => value
+            .extract::<BTreeMap<String, String>>()
+            .map(StoredValue::Hash),
+        "set" => value.extract::<BTreeSet<String>>().map(StoredValue::Set),
+        "zset" => value
+            .extract::<BTreeMap<String, f64>>()
+            .map(StoredValue::SortedSet),
+        "list" => value
+            .extract::<Vec<String>>()
+            .map(|items| StoredValue::List(items.into())),
+        _ => {
+            cold_path();
+            let error_msg = format!("unsupported value type: {type_name}");
+            Err(PyValueError::new_err(error_msg))
+        }
+    }
+}
+
+/// Reads a single key with all of its values from *database* through its Redis-like interface.
+fn export_entry(database: &Bound<'_, PyAny>, key: &str) -> PyResult<Option<StoredEntry>> {
+    let py = database.py();
+    let type_name = database
+        .call_method1(intern!(py, "type"), (key,))?
+        .extract::<String>()?;
+
+    let value = match type_name.as_str() {
+        "string" => {
+            let Some(string) = database
+                .call_method1(intern!(py, "get"), (key,))?
+                .extract::<Option<String>>()?
+            else {
+                return Ok(None);
+            };
+            StoredValue::String(string)
+        }
+        "hash" => stored_value_from_py(
+            &type_name,
+            &database.call_method1(intern!(py, "hgetall"), (key,))?,
+        )?,
+        "set" => stored_value_from_py(
+            &type_name,
+            &database.call_method1(intern!(py, "smembers"), (key,))?,
+        )?,
+        "zset" => StoredValue::SortedSet(
+            database
+                .call_method(
+                    intern!(py, "zrange"),
+                    (key, 0, -1),
+                    Some(&[("withscores", true)].into_py_dict(py)?),
+                )?
+                .extract::<Vec<(String, f64)>>()?
+                .into_iter()
+                .collect(),
+        ),
+        "list" => stored_value_from_py(
+            &type_name,
+            &database.call_method1(intern!(py, "lrange"), (key, 0, -1))?,
+        )?,
+        _ => return Ok(None),
+    };
+
+    let ttl = database
+        .call_method1(intern!(py, "ttl"), (key,))?
+        .extract::<i64>()?;
+    let expires_at = (ttl > 0).then(|| Utc::now().timestamp() + ttl);
+
+    Ok(Some(StoredEntry { value, expires_at }))
+}
+
+/// Exports all keys starting with *prefix* from *database* into the versioned JSON snapshot
+/// format, together with the schema versions of the registered migrations.
+pub(crate) fn export_database(database: &Bound<'_, PyAny>, prefix: &str) -> PyResult<Value> {
+    let py = database.py();
+    let keys = database
+        .call_method1(intern!(py, "keys"), (format!("{prefix}*"),))?
+        .extract::<Vec<String>>()?;
+
+    let mut entries = BTreeMap::new();
+    for key in keys.into_iter().filter(|key| key.starts_with(prefix)) {
+        if let Some(entry) = export_entry(database, &key)? {
+            entries.insert(key, entry);
+        }
+    }
+
+    let mut snapshot = entries_to_json(&entries);
+    snapshot["schema_versions"] = json!(schema_versions());
+    Ok(snapshot)
+}
+
+/// Runs the registered migrations newer than the schema versions recorded in *snapshot* over
+/// *entries*. Migrations may rename keys, transform values, or drop entries by returning None.
+fn apply_migrations(
+    py: Python<'_>,
+    snapshot: &Value,
+    mut entries: Vec<(String, StoredEntry)>,
+) -> PyResult<(Vec<(String, StoredEntry)>, usize)> {
+    let recorded_version = |prefix: &str| {
+        snapshot
+            .get("schema_versions")
+            .and_then(|versions| versions.get(prefix))
+            .and_then(Value::as_u64)
+            .unwrap_or_default()
+    };
+
+    let mut pending = MIGRATIONS
+        .read()
+        .iter()
+        .filter(|migration| migration.version > recorded_version(&migration.prefix))
+        .map(|migration| {
+            (
+                migration.prefix.clone(),
+                migration.version,
+                migration.migration.clone_ref(py),
+            )
+        })
+        .collect::<Vec<_>>();
+    pending.sort_by(
+        |(left_prefix, left_version, _), (right_prefix, right_version, _)| {
+            left_version
+                .cmp(right_version)
+                .then_with(|| left_prefix.cmp(right_prefix))
+        },
+    );
+
+    let mut migrated = 0usize;
+    for (prefix, _, migration) in pending {
+        let mut migrated_entries = Vec::with_capacity(entries.len());
+        for (key, entry) in entries {
+            if !key.starts_with(&prefix) {
+                migrated_entries.push((key, entry));
+                continue;
+            }
+
+            migrated += 1;
+            let result = migration.call1(py, (&key, stored_value_into_py(py, &entry.value)?))?;
+            let result = result.bind(py);
+            if result.is_none() {
+                continue;
+            }
+
+            let (new_key, new_value) = result.extract::<(String, Bound<'_, PyAny>)>()?;
+            let value = stored_value_from_py(entry.value.type_name(), &new_value)?;
+            migrated_entries.push((
+                new_key,
+                StoredEntry {
+                    value,
+                    expires_at: entry.expires_at,
+                },
+            ));
+        }
+        entries = migrated_entries;
+    }
+
+    Ok((entries, migrated))
+}
+
+/// The method of the Redis-like interface that stores values of the type of *value*.
+fn write_method(value: &StoredValue) -> &'static str {
+    match value {
+        StoredValue::String(_) => "set",
+        StoredValue::Hash(_) => "hset",
+        StoredValue::Set(_) => "sadd",
+        StoredValue::SortedSet(_) => "zadd",
+        StoredValue::List(_) => "rpush",
+    }
+}
+
+/// Writes a single key with all of its values to *database* through its Redis-like interface,
+/// replacing whatever was stored under it before.
+fn import_entry(database: &Bound<'_, PyAny>, key: &str, entry: &StoredEntry) -> PyResult<()> {
+    let py = database.py();
+    database.call_method1(intern!(py, "delete"), (key,))?;
+
+    match entry.value {
+        StoredValue::String(ref string) => {
+            database.call_method1(intern!(py, "set"), (key, string))?;
+        }
+        StoredValue::Hash(ref hash) => {
+            database.call_method(
+                intern!(py, "hset"),
+                (key,),
+                Some(&[("mapping", hash.into_py_dict(py)?)].into_py_dict(py)?),
+            )?;
+        }
+        StoredValue::Set(ref set) => {
+            let args = PyTuple::new(
+                py,
+                [key]
+                    .into_iter()
+                    .chain(set.iter().map(String::as_str))
+                    .collect::<Vec<_>>(),
+            )?;
+            database.call_method1(intern!(py, "sadd"), args)?;
+        }
+        StoredValue::SortedSet(ref sorted_set) => {
+            database.call_method1(intern!(py, "zadd"), (key, sorted_set.into_py_dict(py)?))?;
+        }
+        StoredValue::List(ref list) => {
+            let args = PyTuple::new(
+                py,
+                [key]
+                    .into_iter()
+                    .chain(list.iter().map(String::as_str))
+                    .collect::<Vec<_>>(),
+            )?;
+            database.call_method1(intern!(py, "rpush"), args)?;
+        }
+    }
+
+    if let Some(expires_at) = entry.expires_at {
+        database.call_method1(
+            intern!(py, "expire"),
+            (key, expires_at - Utc::now().timestamp()),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Imports all keys starting with *prefix* from *snapshot* into *database*, after applying the
+/// pending migrations. With *dry_run*, nothing is written. Returns the number of imported and
+/// migrated keys.
+pub(crate) fn import_database(
+    database: &Bound<'_, PyAny>,
+    snapshot: &Value,
+    prefix: &str,
+    dry_run: bool,
+) -> PyResult<(usize, usize)> {
+    let now = Utc::now().timestamp();
+    let mut entries = entries_from_json::<Vec<_>>(snapshot)
+        .map_err(|err| InMemoryError::new_err(format!("{err}")))?
+        .into_iter()
+        .filter(|(key, entry)| {
+            key.starts_with(prefix)
+                && !entry.value.is_empty()
+                && entry.expires_at.is_none_or(|expires_at| expires_at > now)
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|(left_key, _), (right_key, _)| left_key.cmp(right_key));
+
+    let (entries, migrated) = apply_migrations(database.py(), snapshot, entries)?;
+
+    if !dry_run {
+        import_entries(database, &entries)?;
+    }
+
+    Ok((entries.len(), migrated))
+}
+
+/// Writes all *entries* to *database*, or none of them. Values *database* cannot store are
+/// reported before anything is written, and the previous values of all keys are restored when a
+/// write fails halfway through.
+fn import_entries(database: &Bound<'_, PyAny>, entries: &[(String, StoredEntry)]) -> PyResult<()> {
+    let mut unsupported = Vec::new();
+    for (key, entry) in entries {
+        if !database.hasattr(write_method(&entry.value))? {
+            unsupported.push(format!("{key} ({})", entry.value.type_name()));
+        }
+    }
+    if !unsupported.is_empty() {
+        cold_path();
+        let error_msg = format!(
+            "the database does not support the values of these keys, nothing was imported: {}",
+            unsupported.join(", ")
+        );
+        return Err(PyValueError::new_err(error_msg));
+    }
+
+    let previous_entries = entries
+        .iter()
+        .map(|(key, _)| export_entry(database, key).map(|entry| (key, entry)))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let Err(err) = entries
+        .iter()
+        .try_for_each(|(key, entry)| import_entry(database, key, entry))
+    else {
+        return Ok(());
+    };
+
+    cold_path();
+    let py = database.py();
+    for (key, previous_entry) in previous_entries {
+        let restored = match previous_entry {
+            Some(ref entry) => import_entry(database, key, entry),
+            None => database
+                .call_method1(intern!(py, "delete"), (key,))
+                .map(|_| ()),
+        };
+        if let Err(restore_err) = restored {
+            error!(target: "shinqlx", "could not restore {key} after a failed import: {restore_err}");
+        }
+    }
+    Err(err)
+}
+
+pub(crate) fn read_export_file(path: &Path) -> PyResult<Value> {
+    let contents = fs::read_to_string(path).map_err(|err| {
+        cold_path();
+        let error_msg = format!("could not read {}: {err}", path.display());
+        PyEnvironmentError::new_err(error_msg)
+    })?;
+    serde_json::from_str::<Value>(&contents).map_err(|err| {
+        cold_path();
+        let error_msg = format!("could not parse {}: {err}", path.display());
+        InMemoryError::new_err(error_msg)
+    })
+}
+
+pub(crate) fn write_export_file(path: &Path, snapshot: &Value) -> PyResult<usize> {
+    let contents = serde_json::to_string_pretty(snapshot).map_err(|err| {
+        cold_path();
+        PyValueError::new_err(format!("{err}"))
+    })?;
+    fs::write(path, contents).map_err(|err| {
+        cold_path();
+        let error_msg = format!("could not write {}: {err}", path.display());
+        PyEnvironmentError::new_err(error_msg)
+    })?;
+    Ok(snapshot["keys"].as_object().map_or(0, Map::len))
+}
+
+/// An instance of the database class configured via ``qlx_database``, for console commands that
+/// are not tied to any plugin.
+pub(crate) fn configured_database(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    let database_class = py.get_type::<Plugin>().getattr(intern!(py, "database"))?;
+    if database_class.is_none() {
+        cold_path();
+        return Err(PyEnvironmentError::new_err(
+            "no database driver configured.",
+        ));
+    }
+
+    let database = database_class.call1((py.None(),))?;
+    database.call_method0(intern!(py, "connect"))?;
+    Ok(database)
+}
+
+/// Registers *migration* for all keys starting with *prefix*. When importing data exported before
+/// *version* was registered, it is called with the key and its value for every matching key, and
+/// must return a tuple of the new key and value, or None to drop the key.
+#[pyfunction]
+#[pyo3(name = "register_migration")]
+pub(crate) fn pyshinqlx_register_migration(
+    prefix: &str,
+    version: u64,
+    migration: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    if !migration.is_callable() {
+        cold_path();
+        return Err(PyValueError::new_err("The migration needs to be callable."));
+    }
+
+    let mut migrations = MIGRATIONS.write();
+    if migrations
+        .iter()
+        .any(|registered| registered.prefix == prefix && registered.version == version)
+    {
+        cold_path();
+        let error_msg =
+            format!("A migration for '{prefix}' with version {version} is already registered.");
+        return Err(PyValueError::new_err(error_msg));
+    }
+
+    migrations.push(Migration {
+        prefix: prefix.to_string(),
+        version,
+        migration: migration.to_owned().unbind(),
+    });
+    Ok(())
+}
+
+/// Exports all keys starting with *prefix* from *database* to the JSON file at *path*.
+/// Returns the number of exported keys.
+#[pyfunction]
+#[pyo3(name = "export_database", signature = (database, path, prefix = DEFAULT_TRANSFER_PREFIX), text_signature = "(database, path, prefix=\"minqlx:\")")]
+pub(crate) fn pyshinqlx_export_database(
+    database: &Bound<'_, PyAny>,
+    path: &str,
+    prefix: &str,
+) -> PyResult<usize> {
+    let snapshot = export_database(database, prefix)?;
+    database
+        .py()
+        .detach(|| write_export_file(Path::new(path), &snapshot))
+}
+
+/// Imports all keys starting with *prefix* from the JSON file at *path* into *database*, running
+/// pending migrations first. With *dry_run*, nothing is written. Returns the number of imported
+/// keys.
+#[pyfunction]
+#[pyo3(name = "import_database", signature = (database, path, prefix = DEFAULT_TRANSFER_PREFIX, dry_run = false), text_signature = "(database, path, prefix=\"minqlx:\", dry_run=False)")]
+pub(crate) fn pyshinqlx_import_database(
+    database: &Bound<'_, PyAny>,
+    path: &str,
+    prefix: &str,
+    dry_run: bool,
+) -> PyResult<usize> {
+    let snapshot = database.py().detach(|| read_export_file(Path::new(path)))?;
+    import_database(database, &snapshot, prefix, dry_run).map(|(imported, _)| imported)
+}
+
+#[cfg(test)]
+pub(crate) fn clear_migrations() {
+    MIGRATIONS.write().clear();
+}
+
+#[cfg(test)]
+mod transfer_tests {
+    use pretty_assertions::assert_eq;
+    use pyo3::{exceptions::PyValueError, prelude::*, types::IntoPyDict};
+    use rstest::rstest;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::{
+        super::super::prelude::pyshinqlx_setup, clear_migrations, export_database, import_database,
+        pyshinqlx_export_database, pyshinqlx_import_database, pyshinqlx_register_migration,
+    };
+    use crate::{
+        ffi::python::database::{InMemory, InMemoryMethods, Sqlite, SqliteMethods},
+        prelude::*,
+    };
+
+    fn flushed_in_memory(py: Python<'_>) -> Bound<'_, PyAny> {
+        let in_memory = Bound::new(py, InMemory::py_new(py, py.None().bind(py)))
+            .expect("this should not happen");
+        in_memory
+            .connect_with_snapshot(None)
+            .expect("this should not happen");
+        in_memory
+            .call_method0("flushdb")
+            .expect("this should not happen");
+        in_memory.into_any()
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn export_includes_all_types_below_prefix(_pyshinqlx_setup: ()) {
+        clear_migrations();
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+            database
+                .call_method1("set", ("minqlx:players:1234:permission", "3"))
+                .expect("this should not happen");
+            database
+                .call_method1("sadd", ("minqlx:players:1234", "name"))
+                .expect("this should not happen");
+            database
+                .call_method1("rpush", ("minqlx:queue", "a", "b"))
+                .expect("this should not happen");
+            database
+                .call_method1("set", ("other:key", "ignored"))
+                .expect("this should not happen");
+
+            let snapshot = export_database(&database, "minqlx:").expect("this should not happen");
+
+            assert_eq!(snapshot["version"], json!(1));
+            assert_eq!(
+                snapshot["keys"]["minqlx:players:1234:permission"],
+                json!({"type": "string", "value": "3"})
+            );
+            assert_eq!(
+                snapshot["keys"]["minqlx:players:1234"],
+                json!({"type": "set", "value": ["name"]})
+            );
+            assert_eq!(
+                snapshot["keys"]["minqlx:queue"],
+                json!({"type": "list", "value": ["a", "b"]})
+            );
+            assert!(snapshot["keys"].get("other:key").is_none());
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn import_with_dry_run_does_not_write(_pyshinqlx_setup: ()) {
+        clear_migrations();
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+            let snapshot = json!({
+                "version": 1,
+                "keys": {
+                    "minqlx:players:1234:permission": {"type": "string", "value": "3"},
+                    "other:key": {"type": "string", "value": "ignored"},
+                },
+            });
+
+            let result = import_database(&database, &snapshot, "minqlx:", true);
+
+            assert_eq!(result.expect("this should not happen"), (1, 0));
+            assert_eq!(
+                database
+                    .call_method1("exists", ("minqlx:players:1234:permission",))
+                    .and_then(|value| value.extract::<usize>())
+                    .expect("this should not happen"),
+                0
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn import_with_unsupported_lists_leaves_database_untouched(_pyshinqlx_setup: ()) {
+        clear_migrations();
+        Python::attach(|py| {
+            let database = py
+                .get_type::<Sqlite>()
+                .call1((py.None(),))
+                .expect("this should not happen");
+            database
+                .cast::<Sqlite>()
+                .expect("this should not happen")
+                .connect_with_path(":memory:")
+                .expect("this should not happen");
+            database
+                .call_method1("set", ("minqlx:players:1234:permission", "3"))
+                .expect("this should not happen");
+            let snapshot = json!({
+                "version": 1,
+                "keys": {
+                    "minqlx:players:1234:permission": {"type": "string", "value": "5"},
+                    "minqlx:players:5678:permission": {"type": "string", "value": "1"},
+                    "minqlx:queue": {"type": "list", "value": ["a", "b"]},
+                },
+            });
+
+            let result = import_database(&database, &snapshot, "minqlx:", false);
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyValueError>(py)));
+            assert_eq!(
+                database
+                    .call_method1("get", ("minqlx:players:1234:permission",))
+                    .and_then(|value| value.extract::<String>())
+                    .expect("this should not happen"),
+                "3"
+            );
+            assert_eq!(
+                database
+                    .call_method1("exists", ("minqlx:players:5678:permission",))
+                    .and_then(|value| value.extract::<usize>())
+                    .expect("this should not happen"),
+                0
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn import_applies_pending_migrations(_pyshinqlx_setup: ()) {
+        clear_migrations();
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+            let migration = py.eval(c"lambda key, value: (key.replace(':old', ':new'), {k: str(int(v) * 2) for k, v in value.items()})", None, None)
+                .expect("this should not happen");
+            pyshinqlx_register_migration("minqlx:plugin:", 2, &migration)
+                .expect("this should not happen");
+            let snapshot = json!({
+                "version": 1,
+                "schema_versions": {"minqlx:plugin:": 1},
+                "keys": {
+                    "minqlx:plugin:old": {"type": "hash", "value": {"score": "21"}},
+                },
+            });
+
+            let result = import_database(&database, &snapshot, "minqlx:", false);
+
+            assert_eq!(result.expect("this should not happen"), (1, 1));
+            assert_eq!(
+                database
+                    .call_method1("hget", ("minqlx:plugin:new", "score"))
+                    .and_then(|value| value.extract::<String>())
+                    .expect("this should not happen"),
+                "42"
+            );
+        });
+        clear_migrations();
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn import_skips_migrations_already_applied(_pyshinqlx_setup: ()) {
+        clear_migrations();
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+            let migration = py
+                .eval(c"lambda key, value: None", None, None)
+                .expect("this should not happen");
+            pyshinqlx_register_migration("minqlx:plugin:", 2, &migration)
+                .expect("this should not happen");
+            let snapshot = json!({
+                "version": 1,
+                "schema_versions": {"minqlx:plugin:": 2},
+                "keys": {
+                    "minqlx:plugin:key": {"type": "string", "value": "kept"},
+                },
+            });
+
+            let result = import_database(&database, &snapshot, "minqlx:", false);
+
+            assert_eq!(result.expect("this should not happen"), (1, 0));
+        });
+        clear_migrations();
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn register_migration_twice_for_same_version(_pyshinqlx_setup: ()) {
+        clear_migrations();
+        Python::attach(|py| {
+            let migration = py
+                .eval(c"lambda key, value: (key, value)", None, None)
+                .expect("this should not happen");
+            pyshinqlx_register_migration("minqlx:plugin:", 1, &migration)
+                .expect("this should not happen");
+
+            let result = pyshinqlx_register_migration("minqlx:plugin:", 1, &migration);
+
+            assert!(result.is_err_and(|err| err.is_instance_of::<PyValueError>(py)));
+        });
+        clear_migrations();
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn export_and_import_roundtrip_through_file(_pyshinqlx_setup: ()) {
+        clear_migrations();
+        let export_dir = tempdir().expect("this should not happen");
+        let export_path = export_dir.path().join("export.json");
+        let export_file = export_path.to_string_lossy().to_string();
+
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+            database
+                .call_method1(
+                    "zadd",
+                    (
+                        "minqlx:scores",
+                        [("player1", 10.0)].into_py_dict(py).unwrap(),
+                    ),
+                )
+                .expect("this should not happen");
+
+            assert_eq!(
+                pyshinqlx_export_database(&database, &export_file, "minqlx:")
+                    .expect("this should not happen"),
+                1
+            );
+
+            database
+                .call_method0("flushdb")
+                .expect("this should not happen");
+
+            assert_eq!(
+                pyshinqlx_import_database(&database, &export_file, "minqlx:", false)
+                    .expect("this should not happen"),
+                1
+            );
+            assert_eq!(
+                database
+                    .call_method1("zscore", ("minqlx:scores", "player1"))
+                    .and_then(|value| value.extract::<f64>())
+                    .expect("this should not happen"),
+                10.0
+            );
+        });
+    }
+}
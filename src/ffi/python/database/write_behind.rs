@@ -0,0 +1,432 @@
+use alloc::sync::Arc;
+use core::{
+    hint::cold_path,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        LazyLock,
+        mpsc::{self, Receiver, Sender, SyncSender},
+    },
+    thread,
+};
+
+use ::redis::pipe;
+
+use super::redis::RedisConnection;
+use crate::prelude::*;
+
+/// How many queued writes the worker sends to the server in a single pipeline at most.
+const MAX_BATCH_SIZE: usize = 128;
+
+/// How long flushing waits for the worker before giving up.
+pub(crate) const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+type SharedConnection = Arc<RedisConnection>;
+
+struct QueuedWrite {
+    connection: SharedConnection,
+    command: String,
+    args: Vec<Vec<u8>>,
+    /// All keys the write goes to.
+    keys: Vec<Vec<u8>>,
+}
+
+/// The keys a Redis command works on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CommandKeys<'a> {
+    /// The command only works on these keys.
+    Keys(Vec<&'a [u8]>),
+    /// The command works on the whole keyspace, like ``KEYS`` or ``FLUSHDB``.
+    Keyspace,
+}
+
+/// Looks up the keys in the arguments *args* of *command*.
+pub(crate) fn command_keys<'a>(command: &str, args: &'a [Vec<u8>]) -> CommandKeys<'a> {
+    // the keys following the argument at *count_index* telling how many there are, as with
+    // ZUNIONSTORE or EVAL
+    let counted_keys = |count_index: usize| {
+        let count = args
+            .get(count_index)
+            .and_then(|count| str::from_utf8(count).ok())
+            .and_then(|count| count.parse::<usize>().ok())
+            .unwrap_or_default();
+        args.iter().skip(count_index + 1).take(count)
+    };
+
+    let keys = match command.to_ascii_uppercase().as_str() {
+        "KEYS" | "SCAN" | "RANDOMKEY" | "DBSIZE" | "FLUSHDB" | "FLUSHALL" | "SWAPDB" => {
+            return CommandKeys::Keyspace;
+        }
+        "DEL" | "UNLINK" | "EXISTS" | "TOUCH" | "MGET" | "WATCH" | "SDIFF" | "SINTER"
+        | "SUNION" | "SDIFFSTORE" | "SINTERSTORE" | "SUNIONSTORE" | "PFCOUNT" | "PFMERGE" => {
+            args.iter().map(Vec::as_slice).collect()
+        }
+        "MSET" | "MSETNX" => args.iter().step_by(2).map(Vec::as_slice).collect(),
+        "RENAME" | "RENAMENX" | "SMOVE" | "RPOPLPUSH" | "LMOVE" | "COPY" => {
+            args.iter().take(2).map(Vec::as_slice).collect()
+        }
+        "ZUNIONSTORE" | "ZINTERSTORE" | "ZDIFFSTORE" => args
+            .iter()
+            .take(1)
+            .chain(counted_keys(1))
+            .map(Vec::as_slice)
+            .collect(),
+        "ZUNION" | "ZINTER" | "ZDIFF" => counted_keys(0).map(Vec::as_slice).collect(),
+        "EVAL" | "EVALSHA" => counted_keys(1).map(Vec::as_slice).collect(),
+        _ => args.iter().take(1).map(Vec::as_slice).collect(),
+    };
+    CommandKeys::Keys(keys)
+}
+
+enum QueueMessage {
+    Write(QueuedWrite),
+    Flush(SyncSender<()>),
+}
+
+struct WriteQueueCounters {
+    depth: AtomicUsize,
+    peak_depth: AtomicUsize,
+    written: AtomicU64,
+    failed: AtomicU64,
+}
+
+/// Kept apart from the queue, so that looking at the metrics does not start the worker.
+static COUNTERS: WriteQueueCounters = WriteQueueCounters {
+    depth: AtomicUsize::new(0),
+    peak_depth: AtomicUsize::new(0),
+    written: AtomicU64::new(0),
+    failed: AtomicU64::new(0),
+};
+
+/// How many queued writes go to each key, so that reads only wait for the writes they depend on.
+static PENDING_KEYS: LazyLock<parking_lot::Mutex<HashMap<Vec<u8>, usize>>> =
+    LazyLock::new(|| parking_lot::Mutex::new(HashMap::new()));
+
+/// The sending end of the write-behind queue. The worker thread is started on the first write.
+static WRITE_QUEUE: LazyLock<Sender<QueueMessage>> = LazyLock::new(|| {
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("shinqlx-db-writer".to_string())
+        .spawn(move || run_worker(receiver))
+        .expect("could not start the database writer thread");
+    sender
+});
+
+/// A snapshot of the write-behind queue metrics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct WriteQueueStats {
+    /// Writes queued, but not sent to the server yet.
+    pub(crate) depth: usize,
+    /// The highest depth the queue ever reached.
+    pub(crate) peak_depth: usize,
+    /// Writes the server accepted.
+    pub(crate) written: u64,
+    /// Writes lost due to errors from the server or the connection.
+    pub(crate) failed: u64,
+}
+
+pub(crate) fn write_queue_stats() -> WriteQueueStats {
+    WriteQueueStats {
+        depth: COUNTERS.depth.load(Ordering::Acquire),
+        peak_depth: COUNTERS.peak_depth.load(Ordering::Relaxed),
+        written: COUNTERS.written.load(Ordering::Relaxed),
+        failed: COUNTERS.failed.load(Ordering::Relaxed),
+    }
+}
+
+pub(crate) fn pending_writes() -> usize {
+    COUNTERS.depth.load(Ordering::Acquire)
+}
+
+/// Whether *command* with *args* has to wait for queued writes: keyspace-wide commands wait for
+/// all of them, all others only for writes to their keys.
+pub(crate) fn has_pending_writes_for(command: &str, args: &[Vec<u8>]) -> bool {
+    if pending_writes() == 0 {
+        return false;
+    }
+
+    match command_keys(command, args) {
+        CommandKeys::Keyspace => true,
+        CommandKeys::Keys(keys) => {
+            let pending_keys = PENDING_KEYS.lock();
+            keys.iter().any(|key| pending_keys.contains_key(*key))
+        }
+    }
+}
+
+fn track_pending_keys(keys: &[Vec<u8>]) {
+    let mut pending_keys = PENDING_KEYS.lock();
+    keys.iter().for_each(|key| {
+        *pending_keys.entry(key.clone()).or_default() += 1;
+    });
+}
+
+fn untrack_pending_keys(keys: &[Vec<u8>]) {
+    let mut pending_keys = PENDING_KEYS.lock();
+    keys.iter().for_each(|key| {
+        if let Some(pending) = pending_keys.get_mut(key) {
+            *pending -= 1;
+            if *pending == 0 {
+                pending_keys.remove(key);
+            }
+        }
+    });
+}
+
+/// Queues *command* to be sent over *connection* by the background worker. Returns false if the
+/// worker is gone. Keyspace-wide commands are not tracked, and must not be queued.
+pub(crate) fn enqueue_write(
+    connection: SharedConnection,
+    command: &str,
+    args: Vec<Vec<u8>>,
+) -> bool {
+    let depth = COUNTERS.depth.fetch_add(1, Ordering::AcqRel) + 1;
+    COUNTERS.peak_depth.fetch_max(depth, Ordering::Relaxed);
+
+    let keys = match command_keys(command, &args) {
+        CommandKeys::Keys(keys) => keys.into_iter().map(<[u8]>::to_vec).collect(),
+        CommandKeys::Keyspace => vec![],
+    };
+    let write = QueuedWrite {
+        connection,
+        command: command.to_string(),
+        args,
+        keys,
+    };
+    track_pending_keys(&write.keys);
+    if let Err(mpsc::SendError(QueueMessage::Write(write))) =
+        WRITE_QUEUE.send(QueueMessage::Write(write))
+    {
+        cold_path();
+        untrack_pending_keys(&write.keys);
+        COUNTERS.depth.fetch_sub(1, Ordering::AcqRel);
+        return false;
+    }
+    true
+}
+
+/// Blocks until all writes queued so far reached the server, or *timeout* passed. Returns
+/// whether the queue was flushed completely.
+pub(crate) fn flush_pending_writes(timeout: Duration) -> bool {
+    if pending_writes() == 0 {
+        return true;
+    }
+
+    let (flushed_sender, flushed_receiver) = mpsc::sync_channel(1);
+    if WRITE_QUEUE
+        .send(QueueMessage::Flush(flushed_sender))
+        .is_err()
+    {
+        cold_path();
+        return false;
+    }
+    flushed_receiver.recv_timeout(timeout).is_ok()
+}
+
+/// Flushes the write-behind queue. Called when the game shuts down.
+pub(crate) fn flush_write_behind_queue() {
+    if !flush_pending_writes(FLUSH_TIMEOUT) {
+        cold_path();
+        error!(
+            target: "shinqlx",
+            "{} database writes were still pending when the game shut down.",
+            pending_writes()
+        );
+    }
+}
+
+fn run_worker(receiver: Receiver<QueueMessage>) {
+    while let Ok(message) = receiver.recv() {
+        let mut writes = vec![];
+        let mut flushes = vec![];
+        let mut next_message = Some(message);
+        while let Some(message) = next_message.take() {
+            match message {
+                QueueMessage::Write(write) => writes.push(write),
+                QueueMessage::Flush(flushed_sender) => flushes.push(flushed_sender),
+            }
+            if writes.len() < MAX_BATCH_SIZE {
+                next_message = receiver.try_recv().ok();
+            }
+        }
+
+        writes
+            .chunk_by(|write, other| Arc::ptr_eq(&write.connection, &other.connection))
+            .for_each(write_batch);
+
+        flushes.into_iter().for_each(|flushed_sender| {
+            let _ = flushed_sender.send(());
+        });
+    }
+}
+
+fn write_batch(batch: &[QueuedWrite]) {
+    let mut pipeline = pipe();
+    batch.iter().for_each(|write| {
+        pipeline.cmd(&write.command);
+        write.args.iter().for_each(|arg| {
+            pipeline.arg(arg.as_slice());
+        });
+        pipeline.ignore();
+    });
+
+    let result = pipeline.query::<()>(&mut *batch[0].connection.lock());
+    let batch_size = batch.len();
+    match result {
+        Ok(()) => {
+            COUNTERS
+                .written
+                .fetch_add(batch_size as u64, Ordering::Relaxed);
+        }
+        Err(err) => {
+            cold_path();
+            COUNTERS
+                .failed
+                .fetch_add(batch_size as u64, Ordering::Relaxed);
+            error!(target: "shinqlx", "{batch_size} queued database writes failed: {err}");
+        }
+    }
+    batch
+        .iter()
+        .for_each(|write| untrack_pending_keys(&write.keys));
+    COUNTERS.depth.fetch_sub(batch_size, Ordering::AcqRel);
+}
+
+#[cfg(test)]
+mod write_behind_tests {
+    use alloc::sync::Arc;
+
+    use ::redis::{
+        Client, Commands, Connection, ConnectionAddr, IntoConnectionInfo, RedisConnectionInfo,
+    };
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{
+        super::redis::{RedisConnection, redis_stand_in::RedisStandIn},
+        CommandKeys, FLUSH_TIMEOUT, command_keys, enqueue_write, flush_pending_writes,
+        has_pending_writes_for, write_queue_stats,
+    };
+    use crate::prelude::*;
+
+    fn stand_in_connection(stand_in: &RedisStandIn) -> Connection {
+        let (host, port) = stand_in
+            .address
+            .split_once(':')
+            .expect("this should not happen");
+        ConnectionAddr::Tcp(
+            host.to_string(),
+            port.parse().expect("this should not happen"),
+        )
+        .into_connection_info()
+        .map(|info| info.set_redis_settings(RedisConnectionInfo::default().set_skip_set_lib_name()))
+        .and_then(Client::open)
+        .and_then(|client| client.get_connection())
+        .expect("this should not happen")
+    }
+
+    #[rstest]
+    #[case("GET", &["key"], &["key"])]
+    #[case("set", &["key", "value"], &["key"])]
+    #[case("ZINCRBY", &["key", "1", "member"], &["key"])]
+    #[case("DEL", &["key1", "key2"], &["key1", "key2"])]
+    #[case("MSET", &["key1", "value1", "key2", "value2"], &["key1", "key2"])]
+    #[case("RENAME", &["key1", "key2"], &["key1", "key2"])]
+    #[case(
+        "ZUNIONSTORE",
+        &["destination", "2", "key1", "key2", "WEIGHTS", "1", "2"],
+        &["destination", "key1", "key2"]
+    )]
+    #[case("EVAL", &["return 1", "1", "key", "arg"], &["key"])]
+    #[case("PING", &[], &[])]
+    fn command_keys_finds_all_keys(
+        #[case] command: &str,
+        #[case] args: &[&str],
+        #[case] expected_keys: &[&str],
+    ) {
+        let args = args
+            .iter()
+            .map(|arg| arg.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            command_keys(command, &args),
+            CommandKeys::Keys(expected_keys.iter().map(|key| key.as_bytes()).collect())
+        );
+    }
+
+    #[rstest]
+    #[case("KEYS", &["minqlx:*"])]
+    #[case("scan", &["0", "MATCH", "minqlx:*"])]
+    #[case("FLUSHDB", &[])]
+    fn command_keys_for_keyspace_commands(#[case] command: &str, #[case] args: &[&str]) {
+        let args = args
+            .iter()
+            .map(|arg| arg.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+
+        assert_eq!(command_keys(command, &args), CommandKeys::Keyspace);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn flush_without_pending_writes() {
+        assert!(flush_pending_writes(FLUSH_TIMEOUT));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn queued_writes_reach_the_server_after_flush() {
+        let stand_in = RedisStandIn::start();
+        let connection = Arc::new(RedisConnection::new(stand_in_connection(&stand_in)));
+        let stats_before = write_queue_stats();
+
+        (0..10).for_each(|value| {
+            assert!(enqueue_write(
+                connection.clone(),
+                "SET",
+                vec![
+                    format!("minqlx:queued:{value}").into_bytes(),
+                    value.to_string().into_bytes(),
+                ],
+            ));
+        });
+
+        assert!(flush_pending_writes(FLUSH_TIMEOUT));
+        assert!(!has_pending_writes_for(
+            "GET",
+            &[b"minqlx:queued:9".to_vec()]
+        ));
+        let stats = write_queue_stats();
+        assert_eq!(stats.depth, 0);
+        assert_eq!(stats.written, stats_before.written + 10);
+        assert!(stats.peak_depth >= 1);
+        assert_eq!(
+            stand_in_connection(&stand_in)
+                .get::<_, Option<String>>("minqlx:queued:9")
+                .expect("this should not happen"),
+            Some("9".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn failed_queued_writes_are_counted() {
+        let stand_in = RedisStandIn::start();
+        let connection = Arc::new(RedisConnection::new(stand_in_connection(&stand_in)));
+        let stats_before = write_queue_stats();
+
+        assert!(enqueue_write(connection, "BOGUS", vec![]));
+
+        assert!(flush_pending_writes(FLUSH_TIMEOUT));
+        let stats = write_queue_stats();
+        assert_eq!(stats.depth, 0);
+        assert_eq!(stats.failed, stats_before.failed + 1);
+    }
+}
@@ -1,10 +1,17 @@
 use core::hint::cold_path;
 
 use pyo3::exceptions::PyValueError;
+use serde_json::json;
 use tap::{TapOptional, TryConv};
 
 use super::validate_client_id;
-use crate::ffi::{c::prelude::*, python::prelude::*};
+use crate::ffi::{
+    c::prelude::*,
+    python::{
+        event_publisher::{event_publisher_enabled, publish_event},
+        prelude::*,
+    },
+};
 #[cfg(test)]
 use crate::hooks::mock_hooks::shinqlx_drop_client;
 #[cfg(not(test))]
@@ -26,6 +33,9 @@ pub(crate) fn pyshinqlx_kick(py: Python<'_>, client_id: i32, reason: Option<&str
                 let reason_str = reason
                     .filter(|rsn| !rsn.is_empty())
                     .unwrap_or("was kicked.");
+                if event_publisher_enabled() {
+                    publish_client_event("kick", client_id, client, Some(reason_str));
+                }
                 shinqlx_drop_client(client, reason_str);
             })
             .is_some()
@@ -40,6 +50,25 @@ pub(crate) fn pyshinqlx_kick(py: Python<'_>, client_id: i32, reason: Option<&str
     })
 }
 
+/// Publishes *event_name* for an action the engine takes on the player with *client_id*, like
+/// kicking or banning them.
+pub(crate) fn publish_client_event(
+    event_name: &str,
+    client_id: i32,
+    client: &Client,
+    reason: Option<&str>,
+) {
+    let mut data = json!({
+        "id": client_id,
+        "steam_id": client.get_steam_id(),
+        "name": client.get_name(),
+    });
+    if let Some(reason) = reason {
+        data["reason"] = reason.into();
+    }
+    publish_event(event_name, data);
+}
+
 #[cfg(test)]
 mod kick_tests {
     use mockall::predicate;
@@ -62,7 +62,7 @@ pub(crate) use get_configstring::pyshinqlx_get_configstring;
 pub(crate) use get_cvar::pyshinqlx_get_cvar;
 pub(crate) use get_targetting_entities::pyshinqlx_get_entity_targets;
 pub(crate) use get_userinfo::pyshinqlx_get_userinfo;
-pub(crate) use kick::pyshinqlx_kick;
+pub(crate) use kick::{publish_client_event, pyshinqlx_kick};
 pub(crate) use noclip::pyshinqlx_noclip;
 pub(crate) use player_info::pyshinqlx_player_info;
 pub(crate) use player_spawn::pyshinqlx_player_spawn;
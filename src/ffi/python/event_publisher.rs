@@ -0,0 +1,342 @@
+use core::{
+    hint::cold_path,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+use std::{
+    sync::{
+        LazyLock,
+        mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError},
+    },
+    thread,
+};
+
+use arc_swap::ArcSwapOption;
+use arzmq::prelude::{
+    ContextBuilder, Message, MultipartSender, PublishSocket, SendFlags, Socket, SocketBuilder,
+    ZmqResult,
+};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use ql_zap::{PlainCredentials, ZapHandler};
+use serde_json::{Map, Value, json};
+
+use super::prelude::*;
+use crate::{MAIN_ENGINE, prelude::*, quake_live_engine::FindCVar};
+
+/// The built-in events that are published. Events of dispatchers defined by plugins are always
+/// published.
+const PUBLISHED_EVENTS: [&str; 9] = [
+    "chat",
+    "command",
+    "vote_called",
+    "vote_started",
+    "vote",
+    "vote_ended",
+    "player_connect",
+    "player_disconnect",
+    "team_switch",
+];
+
+/// How many events may wait for the publisher thread before new ones are dropped.
+const QUEUE_CAPACITY: usize = 1024;
+
+const ZAP_DOMAIN: &str = "events";
+const ZAP_USERNAME: &str = "events";
+
+/// An event waiting to be sent, with the event name as topic frame and its JSON payload.
+struct PublishedEvent {
+    topic: String,
+    payload: String,
+}
+
+/// The queue to the publisher thread, if ``qlx_eventPublisherAddress`` was set when shinqlx
+/// started.
+static EVENT_QUEUE: LazyLock<ArcSwapOption<SyncSender<PublishedEvent>>> =
+    LazyLock::new(ArcSwapOption::empty);
+
+/// Events dropped because the publisher thread could not keep up.
+static DROPPED_EVENTS: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn event_publisher_enabled() -> bool {
+    EVENT_QUEUE.load().is_some()
+}
+
+/// Whether events of the dispatcher *dispatcher_name* are published. *builtin* tells whether the
+/// dispatcher is one of shinqlx' own.
+pub(crate) fn is_published_event(dispatcher_name: &str, builtin: bool) -> bool {
+    !builtin || PUBLISHED_EVENTS.contains(&dispatcher_name)
+}
+
+/// Converts the arguments of a dispatched event into JSON. Players are reduced to their id, Steam
+/// ID and name, anything that has no JSON representation is converted to its string.
+pub(crate) fn py_to_json(value: &Bound<'_, PyAny>) -> Value {
+    if value.is_none() {
+        Value::Null
+    } else if let Ok(py_bool) = value.cast::<PyBool>() {
+        py_bool.is_true().into()
+    } else if value.is_instance_of::<PyInt>() {
+        value
+            .extract::<i64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| value.to_string().into())
+    } else if value.is_instance_of::<PyFloat>() {
+        value.extract::<f64>().map(Value::from).unwrap_or_default()
+    } else if let Ok(py_string) = value.cast::<PyString>() {
+        py_string.to_string().into()
+    } else if let Ok(player) = value.cast::<Player>() {
+        json!({
+            "id": player.get().id,
+            "steam_id": player.get().steam_id,
+            "name": player.get_clean_name(),
+        })
+    } else if let Ok(py_tuple) = value.cast::<PyTuple>() {
+        py_tuple.iter().map(|item| py_to_json(&item)).collect()
+    } else if let Ok(py_list) = value.cast::<PyList>() {
+        py_list.iter().map(|item| py_to_json(&item)).collect()
+    } else if let Ok(py_dict) = value.cast::<PyDict>() {
+        py_dict
+            .iter()
+            .map(|(key, item)| (key.to_string(), py_to_json(&item)))
+            .collect::<Map<_, _>>()
+            .into()
+    } else {
+        value.to_string().into()
+    }
+}
+
+/// Queues *data* to be published under the topic *event_name*. Does nothing unless the event
+/// publisher is running.
+pub(crate) fn publish_event(event_name: &str, data: Value) {
+    let Some(ref queue) = *EVENT_QUEUE.load() else {
+        return;
+    };
+
+    let event = PublishedEvent {
+        topic: event_name.to_string(),
+        payload: json!({"TYPE": event_name, "DATA": data}).to_string(),
+    };
+    match queue.try_send(event) {
+        Ok(()) => (),
+        Err(TrySendError::Full(_)) => {
+            cold_path();
+            DROPPED_EVENTS.fetch_add(1, Ordering::Relaxed);
+        }
+        Err(TrySendError::Disconnected(_)) => {
+            cold_path();
+            EVENT_QUEUE.store(None);
+        }
+    }
+}
+
+/// Publishes a dispatched event with its arguments, if it is one that is published.
+pub(crate) fn publish_dispatched_event(
+    dispatcher: &Bound<'_, PyAny>,
+    dispatcher_name: &str,
+    args: &Bound<'_, PyTuple>,
+) {
+    if !event_publisher_enabled() {
+        return;
+    }
+
+    let builtin = dispatcher
+        .get_type()
+        .module()
+        .is_ok_and(|module| module == "_events");
+    if !is_published_event(dispatcher_name, builtin) {
+        return;
+    }
+
+    publish_event(dispatcher_name, py_to_json(args.as_any()));
+}
+
+/// Runs *test* with the event queue in place, returning the topics of the events it published.
+#[cfg(test)]
+pub(crate) fn published_topics(test: impl FnOnce()) -> Vec<String> {
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+    EVENT_QUEUE.store(Some(sender.into()));
+    test();
+    EVENT_QUEUE.store(None);
+    receiver.try_iter().map(|event| event.topic).collect()
+}
+
+/// Starts the event publisher if ``qlx_eventPublisherAddress`` is set.
+pub(crate) fn initialize_event_publisher() {
+    let Some((address, password)) = MAIN_ENGINE.load().as_ref().and_then(|main_engine| {
+        let address = main_engine
+            .find_cvar("qlx_eventPublisherAddress")
+            .map(|cvar| cvar.get_string())
+            .filter(|address| !address.is_empty())?;
+        let password = main_engine
+            .find_cvar("qlx_eventPublisherPassword")
+            .map(|cvar| cvar.get_string())
+            .unwrap_or_default();
+        Some((address, password))
+    }) else {
+        EVENT_QUEUE.store(None);
+        return;
+    };
+
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+    if let Err(err) = thread::Builder::new()
+        .name("shinqlx-event-publisher".to_string())
+        .spawn(move || run_publisher(&address, &password, receiver))
+    {
+        cold_path();
+        error!(target: "shinqlx", "could not start the event publisher thread: {err}");
+        return;
+    }
+    EVENT_QUEUE.store(Some(sender.into()));
+}
+
+fn run_publisher(address: &str, password: &str, receiver: Receiver<PublishedEvent>) {
+    let publisher = match EventPublisher::new(address, password) {
+        Ok(publisher) => publisher,
+        Err(err) => {
+            cold_path();
+            error!(target: "shinqlx", "could not start the event publisher on {address}: {err}");
+            EVENT_QUEUE.store(None);
+            return;
+        }
+    };
+    loop {
+        publisher.handle_authentication();
+
+        match receiver.recv_timeout(Duration::from_millis(50)) {
+            Ok(event) => {
+                publisher.publish(event);
+                receiver
+                    .try_iter()
+                    .for_each(|event| publisher.publish(event));
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// The publish socket, and the handler authenticating its subscribers if a password is set.
+struct EventPublisher {
+    publisher: PublishSocket,
+    authenticator: Option<ZapHandler>,
+}
+
+impl EventPublisher {
+    fn new(address: &str, password: &str) -> ZmqResult<Self> {
+        let context = ContextBuilder::default()
+            .blocky(false)
+            .max_sockets(10)
+            .io_threads(1)
+            .build()?;
+
+        let authenticator = if password.is_empty() {
+            None
+        } else {
+            Some(ZapHandler::bind(
+                &context,
+                PlainCredentials {
+                    domain: ZAP_DOMAIN.to_string(),
+                    username: ZAP_USERNAME.to_string(),
+                    password: password.to_string(),
+                },
+            )?)
+        };
+
+        let publisher = Socket::from_context(&context)?;
+        SocketBuilder::default()
+            .send_timeout(0)
+            .send_highwater_mark(QUEUE_CAPACITY as i32)
+            .apply(&publisher)?;
+        if let Some(ref authenticator) = authenticator {
+            authenticator.secure(&publisher)?;
+        }
+        publisher.bind(address)?;
+
+        Ok(Self {
+            publisher,
+            authenticator,
+        })
+    }
+
+    fn publish(&self, event: PublishedEvent) {
+        if let Err(err) = self.publisher.send_multipart(
+            vec![
+                Message::from(event.topic.into_bytes()),
+                Message::from(event.payload.into_bytes()),
+            ],
+            SendFlags::DONT_WAIT,
+        ) {
+            cold_path();
+            DROPPED_EVENTS.fetch_add(1, Ordering::Relaxed);
+            debug!(target: "shinqlx", "could not publish event: {err}");
+        }
+    }
+
+    /// Answers the pending authentication requests of connecting subscribers.
+    fn handle_authentication(&self) {
+        if let Some(ref authenticator) = self.authenticator {
+            authenticator.handle_requests();
+        }
+    }
+}
+
+#[cfg(test)]
+mod event_publisher_tests {
+    use pretty_assertions::assert_eq;
+    use pyo3::types::{PyBool, PyDict, PyString, PyTuple};
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::{is_published_event, py_to_json};
+    use crate::ffi::python::{prelude::*, pyshinqlx_test_support::default_test_player};
+
+    #[rstest]
+    #[case("chat", true, true)]
+    #[case("team_switch", true, true)]
+    #[case("frame", true, false)]
+    #[case("damage", true, false)]
+    #[case("custom_event", false, true)]
+    fn published_events(#[case] name: &str, #[case] builtin: bool, #[case] expected: bool) {
+        assert_eq!(is_published_event(name, builtin), expected);
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn dispatched_arguments_are_converted_to_json(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let player = Bound::new(py, default_test_player()).expect("this should not happen");
+            let details = PyDict::new(py);
+            details
+                .set_item("votes", vec![1, 2])
+                .expect("this should not happen");
+            details
+                .set_item("ratio", 0.5)
+                .expect("this should not happen");
+            let args = PyTuple::new(
+                py,
+                [
+                    player.as_any(),
+                    PyString::new(py, "hi there").as_any(),
+                    PyBool::new(py, true).as_any(),
+                    details.as_any(),
+                    py.None().bind(py),
+                ],
+            )
+            .expect("this should not happen");
+
+            assert_eq!(
+                py_to_json(args.as_any()),
+                json!([
+                    {
+                        "id": player.get().id,
+                        "steam_id": player.get().steam_id,
+                        "name": player.get_clean_name(),
+                    },
+                    "hi there",
+                    true,
+                    {"votes": [1, 2], "ratio": 0.5},
+                    null,
+                ])
+            );
+        });
+    }
+}
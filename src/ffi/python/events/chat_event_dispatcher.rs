@@ -6,7 +6,7 @@ use pyo3::{
 };
 
 use super::{
-    super::{COMMANDS, CommandInvokerMethods, Player},
+    super::{COMMANDS, CommandInvokerMethods, Player, admin_api::record_chat_message},
     prelude::*,
 };
 
@@ -55,6 +55,8 @@ impl<'py> ChatEventDispatcherMethods<'py> for Bound<'py, ChatEventDispatcher> {
         msg: &str,
         channel: &Bound<'py, PyAny>,
     ) -> PyResult<Bound<'py, PyAny>> {
+        record_chat_message(player, msg, channel);
+
         match try_handle_input(self.py(), player, msg, channel) {
             Err(e) => {
                 cold_path();
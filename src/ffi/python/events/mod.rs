@@ -15,6 +15,7 @@ mod map_dispatcher;
 mod new_game_dispatcher;
 mod player_connect_dispatcher;
 mod player_disconnect_dispatcher;
+mod player_event_dispatchers;
 mod player_loaded_dispatcher;
 mod player_spawn_dispatcher;
 mod round_countdown_dispatcher;
@@ -42,6 +43,7 @@ mod prelude {
 }
 
 use core::hint::cold_path;
+use std::time::Instant;
 
 pub(crate) use chat_event_dispatcher::{ChatEventDispatcher, ChatEventDispatcherMethods};
 pub(crate) use client_command_dispatcher::{
@@ -75,6 +77,11 @@ pub(crate) use player_connect_dispatcher::{
 pub(crate) use player_disconnect_dispatcher::{
     PlayerDisconnectDispatcher, PlayerDisconnectDispatcherMethods,
 };
+#[allow(unused_imports)]
+pub(crate) use player_event_dispatchers::{
+    MedalDispatcher, PlayerEventDispatcherMethods, PlayerStatsDispatcher,
+    StatsPlayerConnectDispatcher, StatsPlayerDisconnectDispatcher,
+};
 pub(crate) use player_loaded_dispatcher::{PlayerLoadedDispatcher, PlayerLoadedDispatcherMethods};
 pub(crate) use player_spawn_dispatcher::{PlayerSpawnDispatcher, PlayerSpawnDispatcherMethods};
 use prelude::*;
@@ -111,7 +118,12 @@ pub(crate) use vote_dispatcher::{VoteDispatcher, VoteDispatcherMethods};
 pub(crate) use vote_ended_dispatcher::{VoteEndedDispatcher, VoteEndedDispatcherMethods};
 pub(crate) use vote_started_dispatcher::{VoteStartedDispatcher, VoteStartedDispatcherMethods};
 
-use super::{commands::CommandPriorities, get_cvar};
+use super::{
+    commands::CommandPriorities,
+    event_publisher::publish_dispatched_event,
+    get_cvar,
+    metrics::{record_dispatch, record_handler_call},
+};
 
 fn try_dispatcher_debug_log(py: Python<'_>, debug_str: &str) -> PyResult<()> {
     pyshinqlx_get_logger(py, None).and_then(|logger| {
@@ -383,53 +395,17 @@ impl<'py> EventDispatcherMethods<'py> for Bound<'py, EventDispatcher> {
             let dbgstr = format!("{dispatcher_name}{args}");
             dispatcher_debug_log(self.py(), &dbgstr);
         }
+        record_dispatch(&dispatcher_name);
 
-        let mut return_value = PyBool::new(self.py(), true).to_owned().into_any().unbind();
-
-        let plugins = self.get().plugins.read();
-        for handler in (0..5).flat_map(|i| {
-            plugins.iter().flat_map(move |(_, handlers)| {
-                handlers[i]
-                    .iter()
-                    .map(|handler| handler.clone_ref(self.py()))
-            })
-        }) {
-            match handler.call1(self.py(), args) {
-                Err(e) => {
-                    cold_path();
-                    log_exception(self.py(), &e);
-                }
-                Ok(res) => match res.extract::<PythonReturnCodes>(self.py()) {
-                    Ok(PythonReturnCodes::RET_NONE) => (),
-                    Ok(PythonReturnCodes::RET_STOP) => {
-                        return PyBool::new(self.py(), true).to_owned().into_any();
-                    }
-                    Ok(PythonReturnCodes::RET_STOP_EVENT) => {
-                        return_value = PyBool::new(self.py(), false).to_owned().into_any().unbind();
-                    }
-                    Ok(PythonReturnCodes::RET_STOP_ALL) => {
-                        return PyBool::new(self.py(), false).to_owned().into_any();
-                    }
-                    _ => {
-                        match self.call_method1(
-                            intern!(self.py(), "handle_return"),
-                            (handler.bind(self.py()), res),
-                        ) {
-                            Err(e) => {
-                                cold_path();
-                                log_exception(self.py(), &e);
-                            }
-                            Ok(return_handler) if !return_handler.is_none() => {
-                                return return_handler;
-                            }
-                            _ => (),
-                        }
-                    }
-                },
-            }
+        let return_value = run_handlers(self, &dispatcher_name, args);
+        // Events cancelled by a handler did not take place, so they are not published.
+        if !return_value
+            .cast::<PyBool>()
+            .is_ok_and(|py_bool| !py_bool.is_true())
+        {
+            publish_dispatched_event(self.as_any(), &dispatcher_name, args);
         }
-
-        return_value.bind(self.py()).to_owned()
+        return_value
     }
 
     fn handle_return(
@@ -592,10 +568,67 @@ def remove_hook(event, plugin, handler, priority):
     }
 }
 
+/// Calls the handlers of *dispatcher* in the order of their priorities, returning whether the
+/// event should go on.
+fn run_handlers<'py>(
+    dispatcher: &Bound<'py, EventDispatcher>,
+    dispatcher_name: &str,
+    args: &Bound<'py, PyTuple>,
+) -> Bound<'py, PyAny> {
+    let py = dispatcher.py();
+    let mut return_value = PyBool::new(py, true).to_owned().into_any().unbind();
+
+    let plugins = dispatcher.get().plugins.read();
+    for handler in (0..5).flat_map(|i| {
+        plugins.iter().flat_map(move |(_, handlers)| {
+            handlers[i].iter().map(move |handler| handler.clone_ref(py))
+        })
+    }) {
+        let handler_start = Instant::now();
+        let handler_result = handler.call1(py, args);
+        record_handler_call(dispatcher_name, handler_start.elapsed());
+        match handler_result {
+            Err(e) => {
+                cold_path();
+                log_exception(py, &e);
+            }
+            Ok(res) => match res.extract::<PythonReturnCodes>(py) {
+                Ok(PythonReturnCodes::RET_NONE) => (),
+                Ok(PythonReturnCodes::RET_STOP) => {
+                    return PyBool::new(py, true).to_owned().into_any();
+                }
+                Ok(PythonReturnCodes::RET_STOP_EVENT) => {
+                    return_value = PyBool::new(py, false).to_owned().into_any().unbind();
+                }
+                Ok(PythonReturnCodes::RET_STOP_ALL) => {
+                    return PyBool::new(py, false).to_owned().into_any();
+                }
+                _ => {
+                    match dispatcher
+                        .call_method1(intern!(py, "handle_return"), (handler.bind(py), res))
+                    {
+                        Err(e) => {
+                            cold_path();
+                            log_exception(py, &e);
+                        }
+                        Ok(return_handler) if !return_handler.is_none() => {
+                            return return_handler;
+                        }
+                        _ => (),
+                    }
+                }
+            },
+        }
+    }
+
+    return_value.bind(py).to_owned()
+}
+
 #[cfg(test)]
 mod event_dispatcher_tests {
     use core::borrow::BorrowMut;
 
+    use pretty_assertions::assert_eq;
     use pyo3::{
         exceptions::{PyAssertionError, PyAttributeError, PyValueError},
         intern,
@@ -610,6 +643,7 @@ mod event_dispatcher_tests {
             python::{
                 PythonReturnCodes,
                 commands::CommandPriorities,
+                event_publisher::published_topics,
                 pyshinqlx_setup_fixture::*,
                 pyshinqlx_test_support::{
                     python_function_raising_exception, python_function_returning,
@@ -971,6 +1005,56 @@ class CustomDispatcher(shinqlx.EventDispatcher):
             });
     }
 
+    #[rstest]
+    #[case::ret_none(PythonReturnCodes::RET_NONE, vec!["custom_event"])]
+    #[case::ret_stop(PythonReturnCodes::RET_STOP, vec!["custom_event"])]
+    #[case::ret_stop_event(PythonReturnCodes::RET_STOP_EVENT, vec![])]
+    #[case::ret_stop_all(PythonReturnCodes::RET_STOP_ALL, vec![])]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn dispatch_publishes_events_not_cancelled_by_handlers(
+        _pyshinqlx_setup: (),
+        #[case] returned: PythonReturnCodes,
+        #[case] expected_topics: Vec<&str>,
+    ) {
+        let cvar_string = c"0";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        MockEngineBuilder::default()
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let dispatcher = custom_dispatcher(py);
+
+                    let returning_hook = python_function_returning(py, &(returned as i32));
+                    dispatcher
+                        .call_method1(
+                            intern!(py, "add_hook"),
+                            (
+                                "test_plugin",
+                                returning_hook.unbind(),
+                                CommandPriorities::PRI_NORMAL as i32,
+                            ),
+                        )
+                        .expect("this should not happen");
+
+                    let topics = published_topics(|| {
+                        dispatcher
+                            .call_method1(intern!(py, "dispatch"), PyTuple::empty(py))
+                            .expect("this should not happen");
+                    });
+                    assert_eq!(topics, expected_topics);
+                });
+            });
+    }
+
     #[rstest]
     #[cfg_attr(miri, ignore)]
     #[serial]
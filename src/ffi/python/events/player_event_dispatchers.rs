@@ -0,0 +1,233 @@
+use pyo3::types::PyTuple;
+
+use super::prelude::*;
+use crate::ffi::python::Player;
+
+/// Dispatches ZMQ stats events about a single player, resolved to the [`Player`] on the server.
+pub(crate) trait PlayerEventDispatcherMethods<'py> {
+    fn dispatch(
+        &self,
+        player: &Bound<'py, Player>,
+        data: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+}
+
+macro_rules! player_event_dispatcher {
+    ($(#[$doc:meta])* $dispatcher:ident, $name:literal) => {
+        $(#[$doc])*
+        #[pyclass(module = "_events", extends = EventDispatcher, frozen)]
+        pub(crate) struct $dispatcher {}
+
+        #[pymethods]
+        impl $dispatcher {
+            #[classattr]
+            #[allow(non_upper_case_globals)]
+            const name: &'static str = $name;
+            #[classattr]
+            #[allow(non_upper_case_globals)]
+            const need_zmq_stats_enabled: bool = true;
+
+            #[new]
+            fn py_new(_py: Python<'_>) -> PyClassInitializer<Self> {
+                PyClassInitializer::from(EventDispatcher::default()).add_subclass(Self {})
+            }
+
+            fn dispatch<'py>(
+                slf: &Bound<'py, Self>,
+                player: &Bound<'py, Player>,
+                data: &Bound<'py, PyAny>,
+            ) -> PyResult<Bound<'py, PyAny>> {
+                PlayerEventDispatcherMethods::dispatch(slf, player, data)
+            }
+        }
+
+        impl<'py> PlayerEventDispatcherMethods<'py> for Bound<'py, $dispatcher> {
+            fn dispatch(
+                &self,
+                player: &Bound<'py, Player>,
+                data: &Bound<'py, PyAny>,
+            ) -> PyResult<Bound<'py, PyAny>> {
+                let args_tuple = PyTuple::new(self.py(), [player.as_any(), data])?;
+                Ok(self.as_super().dispatch(&args_tuple))
+            }
+        }
+    };
+}
+
+player_event_dispatcher!(
+    /// Event that goes off when a player earns a medal.
+    MedalDispatcher,
+    "medal"
+);
+
+player_event_dispatcher!(
+    /// Event that goes off when the stats of a player are reported, i.e. when they leave or the
+    /// match ends.
+    PlayerStatsDispatcher,
+    "player_stats"
+);
+
+player_event_dispatcher!(
+    /// Event that goes off when the stats of the server report a player connecting.
+    StatsPlayerConnectDispatcher,
+    "stats_player_connect"
+);
+
+player_event_dispatcher!(
+    /// Event that goes off when the stats of the server report a player disconnecting.
+    StatsPlayerDisconnectDispatcher,
+    "stats_player_disconnect"
+);
+
+#[cfg(test)]
+mod player_event_dispatchers_tests {
+    use core::{borrow::BorrowMut, fmt::Debug};
+
+    use pyo3::{
+        PyTypeInfo, intern,
+        prelude::*,
+        types::{PyBool, PyString, PyType},
+    };
+    use rstest::rstest;
+
+    use super::{
+        MedalDispatcher, PlayerStatsDispatcher, StatsPlayerConnectDispatcher,
+        StatsPlayerDisconnectDispatcher,
+    };
+    use crate::{
+        ffi::{
+            c::prelude::{CVar, CVarBuilder, cvar_t},
+            python::{
+                PythonReturnCodes,
+                commands::CommandPriorities,
+                pyshinqlx_setup,
+                pyshinqlx_test_support::{
+                    default_test_player, python_function_raising_exception,
+                    python_function_returning,
+                },
+            },
+        },
+        prelude::*,
+    };
+
+    fn dispatch_with_hook<'py>(
+        py: Python<'py>,
+        dispatcher_type: fn(Python<'_>) -> Bound<'_, PyType>,
+        hook: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let dispatcher = dispatcher_type(py).call0()?;
+        if let Some(hook) = hook {
+            dispatcher.call_method1(
+                intern!(py, "add_hook"),
+                ("test_plugin", hook, CommandPriorities::PRI_NORMAL as i32),
+            )?;
+        }
+
+        dispatcher.call_method1(
+            intern!(py, "dispatch"),
+            (
+                Bound::new(py, default_test_player())?,
+                PyString::intern(py, "asdf"),
+            ),
+        )
+    }
+
+    fn run_with_zmq_stats_enabled(test: impl FnOnce()) {
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+        MockEngineBuilder::default()
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(test);
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn dispatch_with_no_handlers_registered(
+        _pyshinqlx_setup: (),
+        #[values(
+            MedalDispatcher::type_object,
+            PlayerStatsDispatcher::type_object,
+            StatsPlayerConnectDispatcher::type_object,
+            StatsPlayerDisconnectDispatcher::type_object
+        )]
+        dispatcher_type: fn(Python<'_>) -> Bound<'_, PyType>,
+    ) {
+        Python::attach(|py| {
+            let result = dispatch_with_hook(py, dispatcher_type, None);
+            assert!(result.is_ok_and(|value| {
+                value
+                    .cast::<PyBool>()
+                    .is_ok_and(|bool_value| bool_value.is_true())
+            }));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn dispatch_when_handler_returns_exception(
+        _pyshinqlx_setup: (),
+        #[values(
+            MedalDispatcher::type_object,
+            PlayerStatsDispatcher::type_object,
+            StatsPlayerConnectDispatcher::type_object,
+            StatsPlayerDisconnectDispatcher::type_object
+        )]
+        dispatcher_type: fn(Python<'_>) -> Bound<'_, PyType>,
+    ) {
+        run_with_zmq_stats_enabled(|| {
+            Python::attach(|py| {
+                let throws_exception_hook = python_function_raising_exception(py);
+
+                let result = dispatch_with_hook(py, dispatcher_type, Some(throws_exception_hook));
+                assert!(result.is_ok_and(|value| {
+                    value
+                        .cast::<PyBool>()
+                        .is_ok_and(|bool_value| bool_value.is_true())
+                }));
+            });
+        });
+    }
+
+    #[rstest]
+    #[case::none(&None::<i32>, true)]
+    #[case::ret_none(&(PythonReturnCodes::RET_NONE as i32), true)]
+    #[case::ret_stop(&(PythonReturnCodes::RET_STOP as i32), true)]
+    #[case::ret_stop_event(&(PythonReturnCodes::RET_STOP_EVENT as i32), false)]
+    #[case::ret_stop_all(&(PythonReturnCodes::RET_STOP_ALL as i32), false)]
+    #[case::string(&"return string", true)]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn dispatch_when_handler_returns(
+        _pyshinqlx_setup: (),
+        #[values(
+            MedalDispatcher::type_object,
+            PlayerStatsDispatcher::type_object,
+            StatsPlayerConnectDispatcher::type_object,
+            StatsPlayerDisconnectDispatcher::type_object
+        )]
+        dispatcher_type: fn(Python<'_>) -> Bound<'_, PyType>,
+        #[case] returned: &dyn Debug,
+        #[case] expected_result: bool,
+    ) {
+        run_with_zmq_stats_enabled(|| {
+            Python::attach(|py| {
+                let returning_hook = python_function_returning(py, &returned);
+
+                let result = dispatch_with_hook(py, dispatcher_type, Some(returning_hook));
+                assert!(result.is_ok_and(|value| {
+                    value
+                        .cast::<PyBool>()
+                        .is_ok_and(|bool_value| bool_value.is_true() == expected_result)
+                }));
+            });
+        });
+    }
+}
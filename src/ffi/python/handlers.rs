@@ -3,7 +3,7 @@ use core::{
     hint::cold_path,
     sync::atomic::{AtomicBool, AtomicI32, Ordering},
 };
-use std::sync::LazyLock;
+use std::{sync::LazyLock, time::Instant};
 
 use arc_swap::ArcSwapOption;
 use itertools::Itertools;
@@ -21,7 +21,8 @@ use tap::{TapFallible, TapOptional};
 use super::{
     BLUE_TEAM_CHAT_CHANNEL, CHAT_CHANNEL, COMMANDS, CONSOLE_CHANNEL, EVENT_DISPATCHERS,
     FREE_CHAT_CHANNEL, RED_TEAM_CHAT_CHANNEL, SPECTATOR_CHAT_CHANNEL, get_cvar, is_vote_active,
-    late_init, log_exception, prelude::*, pyshinqlx_get_logger, set_map_subtitles,
+    late_init, log_exception, metrics::record_frame, prelude::*, pyshinqlx_get_logger,
+    set_map_subtitles,
 };
 use crate::{
     MAIN_ENGINE,
@@ -573,25 +574,30 @@ fn try_handle_client_command(py: Python<'_>, client_id: i32, cmd: &str) -> PyRes
                 },
             )?;
 
-        return match result.cast::<PyBool>() {
-            Ok(py_bool) if !py_bool.is_true() => Ok(PyBool::new(py, false).into_any().unbind()),
-            _ => match result.cast::<PyDict>() {
-                Ok(changed_values) => {
-                    let updated_info = new_info.into_py_dict(py)?;
-                    updated_info
-                        .update(changed_values.to_owned().as_mapping())
-                        .map(|_| {
-                            let formatted_key_values = updated_info
-                                .iter()
-                                .map(|(key, value)| format!(r"\{key}\{value}"))
-                                .join("");
-
-                            let new_command = format!(r#"userinfo "{formatted_key_values}""#);
-                            PyString::new(py, &new_command).into_any().unbind()
-                        })
-                }
-                _ => Ok(PyString::new(py, updated_cmd).into_any().unbind()),
-            },
+        if result
+            .cast::<PyBool>()
+            .is_ok_and(|py_bool| !py_bool.is_true())
+        {
+            return Ok(PyBool::new(py, false).into_any().unbind());
+        }
+        return match result.cast::<PyDict>() {
+            Ok(changed_values) => {
+                let updated_info = new_info.into_py_dict(py)?;
+                updated_info.update(changed_values.to_owned().as_mapping())?;
+                record_userinfo_change(py, player.steam_id, &updated_info);
+
+                let formatted_key_values = updated_info
+                    .iter()
+                    .map(|(key, value)| format!(r"\{key}\{value}"))
+                    .join("");
+
+                let new_command = format!(r#"userinfo "{formatted_key_values}""#);
+                Ok(PyString::new(py, &new_command).into_any().unbind())
+            }
+            _ => {
+                record_userinfo_change(py, player.steam_id, &new_info.into_py_dict(py)?);
+                Ok(PyString::new(py, updated_cmd).into_any().unbind())
+            }
         };
     }
 
@@ -3871,6 +3877,8 @@ def next_frame_tasks_runner():
 /// and have it be executed here.
 #[pyfunction]
 pub(crate) fn handle_frame(py: Python<'_>) -> Option<bool> {
+    let frame_start = Instant::now();
+
     while try_run_frame_tasks(py)
         .tap_err(|e| {
             log_exception(py, e);
@@ -3888,6 +3896,8 @@ pub(crate) fn handle_frame(py: Python<'_>) -> Option<bool> {
 
     transfer_next_frame_tasks(py);
 
+    record_frame(py, frame_start.elapsed());
+
     return_value
 }
 
@@ -4837,6 +4847,20 @@ mod handle_new_game_tests {
                         "qlx_redisDatabase",
                         "qlx_redisUnixSocket",
                         "qlx_redisPassword",
+                        "qlx_redisWriteBehind",
+                        "qlx_sqlitePath",
+                        "qlx_inMemorySnapshot",
+                        "qlx_bans",
+                        "qlx_identityHistory",
+                        "qlx_identityIpPermission",
+                        "qlx_matchArchivePath",
+                        "qlx_matchArchiveMaxFiles",
+                        "qlx_matchArchiveMaxAgeDays",
+                        "qlx_eventPublisherAddress",
+                        "qlx_eventPublisherPassword",
+                        "qlx_adminApiAddress",
+                        "qlx_adminApiToken",
+                        "qlx_metricsAddress",
                     ]
                     .contains(&name)
                 },
@@ -4859,6 +4883,20 @@ mod handle_new_game_tests {
                         "qlx_redisDatabase",
                         "qlx_redisUnixSocket",
                         "qlx_redisPassword",
+                        "qlx_redisWriteBehind",
+                        "qlx_sqlitePath",
+                        "qlx_inMemorySnapshot",
+                        "qlx_bans",
+                        "qlx_identityHistory",
+                        "qlx_identityIpPermission",
+                        "qlx_matchArchivePath",
+                        "qlx_matchArchiveMaxFiles",
+                        "qlx_matchArchiveMaxAgeDays",
+                        "qlx_eventPublisherAddress",
+                        "qlx_eventPublisherPassword",
+                        "qlx_adminApiAddress",
+                        "qlx_adminApiToken",
+                        "qlx_metricsAddress",
                     ]
                     .contains(&name)
                 });
@@ -4932,6 +4970,20 @@ mod handle_new_game_tests {
                         "qlx_redisDatabase",
                         "qlx_redisUnixSocket",
                         "qlx_redisPassword",
+                        "qlx_redisWriteBehind",
+                        "qlx_sqlitePath",
+                        "qlx_inMemorySnapshot",
+                        "qlx_bans",
+                        "qlx_identityHistory",
+                        "qlx_identityIpPermission",
+                        "qlx_matchArchivePath",
+                        "qlx_matchArchiveMaxFiles",
+                        "qlx_matchArchiveMaxAgeDays",
+                        "qlx_eventPublisherAddress",
+                        "qlx_eventPublisherPassword",
+                        "qlx_adminApiAddress",
+                        "qlx_adminApiToken",
+                        "qlx_metricsAddress",
                     ]
                     .contains(&name)
                 },
@@ -4954,6 +5006,20 @@ mod handle_new_game_tests {
                         "qlx_redisDatabase",
                         "qlx_redisUnixSocket",
                         "qlx_redisPassword",
+                        "qlx_redisWriteBehind",
+                        "qlx_sqlitePath",
+                        "qlx_inMemorySnapshot",
+                        "qlx_bans",
+                        "qlx_identityHistory",
+                        "qlx_identityIpPermission",
+                        "qlx_matchArchivePath",
+                        "qlx_matchArchiveMaxFiles",
+                        "qlx_matchArchiveMaxAgeDays",
+                        "qlx_eventPublisherAddress",
+                        "qlx_eventPublisherPassword",
+                        "qlx_adminApiAddress",
+                        "qlx_adminApiToken",
+                        "qlx_metricsAddress",
                     ]
                     .contains(&name)
                 });
@@ -5030,6 +5096,20 @@ mod handle_new_game_tests {
                         "qlx_redisDatabase",
                         "qlx_redisUnixSocket",
                         "qlx_redisPassword",
+                        "qlx_redisWriteBehind",
+                        "qlx_sqlitePath",
+                        "qlx_inMemorySnapshot",
+                        "qlx_bans",
+                        "qlx_identityHistory",
+                        "qlx_identityIpPermission",
+                        "qlx_matchArchivePath",
+                        "qlx_matchArchiveMaxFiles",
+                        "qlx_matchArchiveMaxAgeDays",
+                        "qlx_eventPublisherAddress",
+                        "qlx_eventPublisherPassword",
+                        "qlx_adminApiAddress",
+                        "qlx_adminApiToken",
+                        "qlx_metricsAddress",
                     ]
                     .contains(&name)
                 },
@@ -5052,6 +5132,20 @@ mod handle_new_game_tests {
                         "qlx_redisDatabase",
                         "qlx_redisUnixSocket",
                         "qlx_redisPassword",
+                        "qlx_redisWriteBehind",
+                        "qlx_sqlitePath",
+                        "qlx_inMemorySnapshot",
+                        "qlx_bans",
+                        "qlx_identityHistory",
+                        "qlx_identityIpPermission",
+                        "qlx_matchArchivePath",
+                        "qlx_matchArchiveMaxFiles",
+                        "qlx_matchArchiveMaxAgeDays",
+                        "qlx_eventPublisherAddress",
+                        "qlx_eventPublisherPassword",
+                        "qlx_adminApiAddress",
+                        "qlx_adminApiToken",
+                        "qlx_metricsAddress",
                     ]
                     .contains(&name)
                 });
@@ -6517,7 +6611,7 @@ mod handle_set_configstring_tests {
     }
 }
 
-fn try_handle_player_connect(py: Python<'_>, client_id: i32, _is_bot: bool) -> PyResult<Py<PyAny>> {
+fn try_handle_player_connect(py: Python<'_>, client_id: i32, is_bot: bool) -> PyResult<Py<PyAny>> {
     EVENT_DISPATCHERS
         .load()
         .as_ref()
@@ -6535,13 +6629,17 @@ fn try_handle_player_connect(py: Python<'_>, client_id: i32, _is_bot: bool) -> P
                 ))
             },
             |player_connect_dispatcher| {
-                let player = Player::py_new(client_id, None)?;
+                let player = Bound::new(py, Player::py_new(client_id, None)?)?;
 
-                PlayerConnectDispatcherMethods::dispatch(
-                    player_connect_dispatcher.cast()?,
-                    &Bound::new(py, player)?,
-                )
-                .map(|value| value.unbind())
+                if !is_bot {
+                    record_connecting_player(py, &player);
+                }
+                if !is_bot && let Some(reject_message) = ban_reject_message(py, &player) {
+                    return Ok(PyString::new(py, &reject_message).into_any().unbind());
+                }
+
+                PlayerConnectDispatcherMethods::dispatch(player_connect_dispatcher.cast()?, &player)
+                    .map(|value| value.unbind())
             },
         )
 }
@@ -0,0 +1,732 @@
+use core::hint::cold_path;
+use std::sync::LazyLock;
+
+use arc_swap::ArcSwapOption;
+use chrono::Utc;
+use itertools::Itertools;
+use pyo3::{
+    intern,
+    types::{IntoPyDict, PyDict, PyString},
+};
+
+use super::{
+    PythonReturnCodes,
+    bans::{CommandTarget, command_target, format_timestamp, reply},
+    clean_text,
+    commands::CommandPriorities,
+    database::write_behind_enabled,
+    database_configured, log_exception,
+    prelude::*,
+};
+use crate::{MAIN_ENGINE, prelude::*, quake_live_engine::FindCVar};
+
+/// The sorted set of all Steam IDs ever seen, scored by when they were last seen.
+const LAST_SEEN_KEY: &str = "minqlx:history:last_seen";
+/// The sorted set of all Steam IDs ever seen, scored by when they were first seen.
+const FIRST_SEEN_KEY: &str = "minqlx:history:first_seen";
+
+/// How many entries of each kind of identity ``!aliases`` lists at most.
+const MAX_LISTED_IDENTITIES: usize = 10;
+/// The permission level needed to see IPs in ``!aliases``, unless ``qlx_identityIpPermission``
+/// says otherwise.
+const DEFAULT_IP_PERMISSION: i32 = 5;
+
+/// The identity tracker instance, owning the ``!seen`` and ``!aliases`` commands. Identities are
+/// only recorded once it has been initialized.
+static IDENTITY_TRACKER: LazyLock<ArcSwapOption<Py<Identities>>> =
+    LazyLock::new(ArcSwapOption::empty);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IdentityKind {
+    Name,
+    Clan,
+    Ip,
+    Country,
+}
+
+impl IdentityKind {
+    fn key_part(&self) -> &'static str {
+        match self {
+            Self::Name => "names",
+            Self::Clan => "clans",
+            Self::Ip => "ips",
+            Self::Country => "countries",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Name => "Names",
+            Self::Clan => "Clan tags",
+            Self::Ip => "IPs",
+            Self::Country => "Countries",
+        }
+    }
+
+    /// Names and IPs are indexed across all Steam IDs, so players sharing them can be found.
+    fn is_indexed(&self) -> bool {
+        matches!(self, Self::Name | Self::Ip)
+    }
+
+    fn index_key(&self, value: &str) -> String {
+        let index_value = match self {
+            Self::Name => clean_text(&value).to_lowercase(),
+            _ => value.to_string(),
+        };
+        format!("minqlx:history:{}:{index_value}", self.key_part())
+    }
+}
+
+fn history_key(steam_id: i64, kind: IdentityKind) -> String {
+    format!("minqlx:players:{steam_id}:history:{}", kind.key_part())
+}
+
+fn first_seen_history_key(steam_id: i64, kind: IdentityKind) -> String {
+    format!("{}:first_seen", history_key(steam_id, kind))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IdentityRecord {
+    pub(crate) value: String,
+    pub(crate) first_seen: i64,
+    pub(crate) last_seen: i64,
+}
+
+/// Adds *member* to the sorted set *key*. With ``qlx_redisWriteBehind`` enabled, the write is
+/// queued, so that recording identities does not wait for the database on the game thread.
+fn zadd_timestamp(
+    database: &Bound<'_, PyAny>,
+    key: &str,
+    member: &str,
+    timestamp: i64,
+    only_new: bool,
+) -> PyResult<()> {
+    let py = database.py();
+    if write_behind_enabled() && database.hasattr(intern!(py, "enqueue"))? {
+        let enqueued = if only_new {
+            database.call_method1(
+                intern!(py, "enqueue"),
+                ("zadd", key, "NX", timestamp, member),
+            )
+        } else {
+            database.call_method1(intern!(py, "enqueue"), ("zadd", key, timestamp, member))
+        };
+        return enqueued.map(|_| ());
+    }
+
+    let kwargs = [(intern!(py, "nx"), only_new)].into_py_dict(py)?;
+    database.call_method(
+        intern!(py, "zadd"),
+        (key, [(member, timestamp)].into_py_dict(py)?),
+        Some(&kwargs),
+    )?;
+    Ok(())
+}
+
+fn record_seen(
+    database: &Bound<'_, PyAny>,
+    key: &str,
+    first_seen_key: &str,
+    member: &str,
+    timestamp: i64,
+) -> PyResult<()> {
+    zadd_timestamp(database, key, member, timestamp, false)?;
+    zadd_timestamp(database, first_seen_key, member, timestamp, true)
+}
+
+/// Records that *steam_id* was seen with *identities* at *timestamp*. Empty values are skipped.
+pub(crate) fn record_identities(
+    database: &Bound<'_, PyAny>,
+    steam_id: i64,
+    identities: &[(IdentityKind, String)],
+    timestamp: i64,
+) -> PyResult<()> {
+    let steam_id_member = steam_id.to_string();
+    record_seen(
+        database,
+        LAST_SEEN_KEY,
+        FIRST_SEEN_KEY,
+        &steam_id_member,
+        timestamp,
+    )?;
+
+    identities
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .try_for_each(|(kind, value)| {
+            record_seen(
+                database,
+                &history_key(steam_id, *kind),
+                &first_seen_history_key(steam_id, *kind),
+                value,
+                timestamp,
+            )?;
+            if kind.is_indexed() {
+                zadd_timestamp(
+                    database,
+                    &kind.index_key(value),
+                    &steam_id_member,
+                    timestamp,
+                    false,
+                )?;
+            }
+            Ok(())
+        })
+}
+
+fn score(database: &Bound<'_, PyAny>, key: &str, member: &str) -> PyResult<Option<i64>> {
+    database
+        .call_method1(intern!(database.py(), "zscore"), (key, member))?
+        .extract::<Option<f64>>()
+        .map(|opt_score| opt_score.map(|value| value as i64))
+}
+
+fn members_by_recency(database: &Bound<'_, PyAny>, key: &str) -> PyResult<Vec<(String, i64)>> {
+    let py = database.py();
+    database
+        .call_method(
+            intern!(py, "zrevrange"),
+            (key, 0, -1),
+            Some(&[(intern!(py, "withscores"), true)].into_py_dict(py)?),
+        )?
+        .extract::<Vec<(String, f64)>>()
+        .map(|members| {
+            members
+                .into_iter()
+                .map(|(member, timestamp)| (member, timestamp as i64))
+                .collect()
+        })
+}
+
+/// Returns when *steam_id* was first and last seen, if ever.
+pub(crate) fn seen(database: &Bound<'_, PyAny>, steam_id: i64) -> PyResult<Option<(i64, i64)>> {
+    let steam_id_member = steam_id.to_string();
+    let Some(last_seen) = score(database, LAST_SEEN_KEY, &steam_id_member)? else {
+        return Ok(None);
+    };
+    let first_seen = score(database, FIRST_SEEN_KEY, &steam_id_member)?.unwrap_or(last_seen);
+    Ok(Some((first_seen, last_seen)))
+}
+
+/// Returns the *kind* identities *steam_id* was seen with, the most recently seen first.
+pub(crate) fn identity_history(
+    database: &Bound<'_, PyAny>,
+    steam_id: i64,
+    kind: IdentityKind,
+) -> PyResult<Vec<IdentityRecord>> {
+    let first_seen_key = first_seen_history_key(steam_id, kind);
+    members_by_recency(database, &history_key(steam_id, kind))?
+        .into_iter()
+        .map(|(value, last_seen)| {
+            let first_seen = score(database, &first_seen_key, &value)?.unwrap_or(last_seen);
+            Ok(IdentityRecord {
+                value,
+                first_seen,
+                last_seen,
+            })
+        })
+        .collect()
+}
+
+/// Returns the Steam IDs that were seen with *value* as *kind*, the most recently seen first.
+pub(crate) fn steam_ids_with(
+    database: &Bound<'_, PyAny>,
+    kind: IdentityKind,
+    value: &str,
+) -> PyResult<Vec<i64>> {
+    Ok(members_by_recency(database, &kind.index_key(value))?
+        .into_iter()
+        .filter_map(|(steam_id, _)| steam_id.parse::<i64>().ok())
+        .collect())
+}
+
+/// Returns the other Steam IDs that were seen with any of the IPs of *steam_id*.
+pub(crate) fn accounts_sharing_ips(
+    database: &Bound<'_, PyAny>,
+    steam_id: i64,
+) -> PyResult<Vec<i64>> {
+    let ip_history = identity_history(database, steam_id, IdentityKind::Ip)?;
+    let mut accounts = vec![];
+    ip_history.iter().try_for_each(|record| {
+        accounts.extend(steam_ids_with(database, IdentityKind::Ip, &record.value)?);
+        Ok::<(), PyErr>(())
+    })?;
+    Ok(accounts
+        .into_iter()
+        .filter(|&other_steam_id| other_steam_id != steam_id)
+        .unique()
+        .collect())
+}
+
+fn last_known_name(database: &Bound<'_, PyAny>, steam_id: i64) -> PyResult<String> {
+    Ok(identity_history(database, steam_id, IdentityKind::Name)?
+        .into_iter()
+        .next()
+        .map(|record| format!("{}^7", record.value.trim_end_matches("^7")))
+        .unwrap_or_else(|| steam_id.to_string()))
+}
+
+fn with_identity_database<F>(py: Python<'_>, record: F)
+where
+    F: FnOnce(&Bound<'_, PyAny>) -> PyResult<()>,
+{
+    let identity_tracker = IDENTITY_TRACKER.load();
+    let Some(tracker) = identity_tracker.as_ref() else {
+        return;
+    };
+
+    if let Err(err) = tracker
+        .bind(py)
+        .as_super()
+        .get_db()
+        .and_then(|database| record(&database))
+    {
+        cold_path();
+        log_exception(py, &err);
+    }
+}
+
+/// Records the name, clan tag, IP, and country of a connecting player, if the identity tracker is
+/// running. Errors are logged.
+pub(crate) fn record_connecting_player(py: Python<'_>, player: &Bound<'_, Player>) {
+    with_identity_database(py, |database| {
+        let identities = [
+            (IdentityKind::Name, player.get().name.read().to_string()),
+            (IdentityKind::Clan, player.get_clan()),
+            (IdentityKind::Ip, player.get_ip()),
+            (
+                IdentityKind::Country,
+                player.get_country().unwrap_or_default(),
+            ),
+        ];
+        record_identities(
+            database,
+            player.get().steam_id,
+            &identities,
+            Utc::now().timestamp(),
+        )
+    });
+}
+
+fn userinfo_identities(userinfo: &Bound<'_, PyDict>) -> [(IdentityKind, String); 2] {
+    let value = |key: &str| {
+        userinfo
+            .get_item(key)
+            .ok()
+            .flatten()
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+    };
+    [
+        (IdentityKind::Name, value("name")),
+        (IdentityKind::Country, value("country")),
+    ]
+}
+
+/// Records the name and country from the changed *userinfo* of *steam_id*, as the server applies
+/// it after plugins modified it, if the identity tracker is running. Errors are logged.
+pub(crate) fn record_userinfo_change(py: Python<'_>, steam_id: i64, userinfo: &Bound<'_, PyDict>) {
+    with_identity_database(py, |database| {
+        record_identities(
+            database,
+            steam_id,
+            &userinfo_identities(userinfo),
+            Utc::now().timestamp(),
+        )
+    });
+}
+
+/// The permission level needed to see IPs, and the accounts sharing them, in ``!aliases``.
+fn ip_permission() -> i32 {
+    MAIN_ENGINE
+        .load()
+        .as_ref()
+        .and_then(|main_engine| main_engine.find_cvar("qlx_identityIpPermission"))
+        .and_then(|cvar| cvar.get_string().parse::<i32>().ok())
+        .unwrap_or(DEFAULT_IP_PERMISSION)
+}
+
+/// Resolves the targets of ``!seen`` and ``!aliases``. Names of players that are not on the server
+/// are looked up in the name history.
+fn resolve_steam_ids(
+    py: Python<'_>,
+    database: &Bound<'_, PyAny>,
+    identifier: &str,
+) -> PyResult<Vec<i64>> {
+    match command_target(py, identifier)? {
+        Some(target) => Ok(vec![target.steam_id()]),
+        None => steam_ids_with(database, IdentityKind::Name, identifier),
+    }
+}
+
+/// Tracks the names, clan tags, IPs, and countries of every Steam ID connecting to the server, to
+/// help moderators catch name changers and ban evaders.
+#[pyclass(name = "identities", module = "_identities", extends = Plugin, frozen)]
+pub(crate) struct Identities {}
+
+#[pymethods]
+impl Identities {
+    /// ``!seen <player>``
+    fn cmd_seen(
+        slf: &Bound<'_, Self>,
+        _player: &Bound<'_, PyAny>,
+        msg: Vec<String>,
+        channel: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<i32>> {
+        let py = slf.py();
+        if msg.len() < 2 {
+            return Ok(Some(PythonReturnCodes::RET_USAGE as i32));
+        }
+
+        let identifier = msg[1..].join(" ");
+        let database = slf.as_super().get_db()?;
+        let steam_ids = match command_target(py, &identifier)? {
+            Some(CommandTarget::Online(online_player)) => {
+                reply(
+                    channel,
+                    &format!("{} is on the server right now.", online_player.get_name()),
+                )?;
+                return Ok(None);
+            }
+            Some(target) => vec![target.steam_id()],
+            None => steam_ids_with(&database, IdentityKind::Name, &identifier)?,
+        };
+        if steam_ids.is_empty() {
+            reply(channel, &format!("^6{identifier}^7 has never been seen."))?;
+            return Ok(None);
+        }
+
+        steam_ids
+            .into_iter()
+            .take(MAX_LISTED_IDENTITIES)
+            .try_for_each(|steam_id| {
+                let message = match seen(&database, steam_id)? {
+                    None => format!("^6{steam_id}^7 has never been seen."),
+                    Some((first_seen, last_seen)) => format!(
+                        "{} ({steam_id}) was first seen {} UTC, and last seen {} UTC.",
+                        last_known_name(&database, steam_id)?,
+                        format_timestamp(first_seen),
+                        format_timestamp(last_seen)
+                    ),
+                };
+                reply(channel, &message)
+            })?;
+        Ok(None)
+    }
+
+    /// ``!aliases <player>``. IPs are only listed for players with the permission level of
+    /// ``qlx_identityIpPermission``.
+    fn cmd_aliases(
+        slf: &Bound<'_, Self>,
+        player: &Bound<'_, PyAny>,
+        msg: Vec<String>,
+        channel: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<i32>> {
+        let py = slf.py();
+        if msg.len() < 2 {
+            return Ok(Some(PythonReturnCodes::RET_USAGE as i32));
+        }
+
+        let identifier = msg[1..].join(" ");
+        let database = slf.as_super().get_db()?;
+        let Some(&steam_id) = resolve_steam_ids(py, &database, &identifier)?.first() else {
+            reply(channel, &format!("^6{identifier}^7 has never been seen."))?;
+            return Ok(None);
+        };
+        let show_ips = database
+            .call_method1(intern!(py, "has_permission"), (player, ip_permission()))?
+            .is_truthy()?;
+
+        reply(
+            channel,
+            &format!(
+                "Identities of {} ({steam_id}):",
+                last_known_name(&database, steam_id)?
+            ),
+        )?;
+        [
+            IdentityKind::Name,
+            IdentityKind::Clan,
+            IdentityKind::Ip,
+            IdentityKind::Country,
+        ]
+        .into_iter()
+        .filter(|&kind| show_ips || kind != IdentityKind::Ip)
+        .try_for_each(|kind| {
+            let history = identity_history(&database, steam_id, kind)?;
+            if history.is_empty() {
+                return Ok(());
+            }
+
+            let listed = history
+                .iter()
+                .take(MAX_LISTED_IDENTITIES)
+                .map(|record| {
+                    format!(
+                        "{}^7 ({} - {})",
+                        record.value,
+                        format_timestamp(record.first_seen),
+                        format_timestamp(record.last_seen)
+                    )
+                })
+                .join(", ");
+            let more = history.len().saturating_sub(MAX_LISTED_IDENTITIES);
+            if more > 0 {
+                reply(
+                    channel,
+                    &format!("{}: {listed}, and {more} more", kind.description()),
+                )
+            } else {
+                reply(channel, &format!("{}: {listed}", kind.description()))
+            }
+        })?;
+
+        if !show_ips {
+            return Ok(None);
+        }
+        let shared_accounts = accounts_sharing_ips(&database, steam_id)?;
+        if !shared_accounts.is_empty() {
+            let listed = shared_accounts
+                .iter()
+                .take(MAX_LISTED_IDENTITIES)
+                .map(|&other_steam_id| {
+                    last_known_name(&database, other_steam_id)
+                        .map(|name| format!("{name} ({other_steam_id})"))
+                })
+                .collect::<PyResult<Vec<_>>>()?
+                .join(", ");
+            reply(channel, &format!("Shares IPs with: {listed}"))?;
+        }
+        Ok(None)
+    }
+}
+
+/// Starts the identity tracker, and adds the ``!seen`` and ``!aliases`` commands.
+pub(crate) fn initialize_identities(py: Python<'_>) -> PyResult<()> {
+    if !database_configured(py) {
+        cold_path();
+        warn!(target: "shinqlx", "No database configured. Player identities will not be recorded.");
+        return Ok(());
+    }
+
+    let identities = Bound::new(
+        py,
+        PyClassInitializer::from(Plugin::py_new(py)).add_subclass(Identities {}),
+    )?;
+
+    [("seen", "cmd_seen"), ("aliases", "cmd_aliases")]
+        .into_iter()
+        .try_for_each(|(name, handler)| {
+            identities.as_super().add_command(
+                PyString::new(py, name).as_any(),
+                &identities.getattr(handler)?,
+                2,
+                None,
+                None,
+                CommandPriorities::PRI_NORMAL as u32,
+                false,
+                5,
+                true,
+                "<player>",
+            )
+        })?;
+
+    IDENTITY_TRACKER.store(Some(identities.unbind().into()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod identities_tests {
+    use pretty_assertions::assert_eq;
+    use pyo3::{prelude::*, types::IntoPyDict};
+    use rstest::rstest;
+
+    use super::{
+        super::{
+            database::{InMemory, InMemoryMethods},
+            prelude::*,
+        },
+        IdentityKind, IdentityRecord, accounts_sharing_ips, identity_history, record_identities,
+        seen, steam_ids_with, userinfo_identities,
+    };
+    use crate::prelude::*;
+
+    fn flushed_in_memory(py: Python<'_>) -> Bound<'_, PyAny> {
+        let in_memory = Bound::new(py, InMemory::py_new(py, py.None().bind(py)))
+            .expect("this should not happen");
+        in_memory
+            .connect_with_snapshot(None)
+            .expect("this should not happen");
+        in_memory
+            .call_method0("flushdb")
+            .expect("this should not happen");
+        in_memory.into_any()
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn seen_for_unknown_steam_id(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+
+            assert_eq!(seen(&database, 1234).expect("this should not happen"), None);
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn userinfo_identities_are_name_and_country(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let userinfo = [("name", "Modified Name"), ("rate", "25000")]
+                .into_py_dict(py)
+                .expect("this should not happen");
+
+            assert_eq!(
+                userinfo_identities(&userinfo),
+                [
+                    (IdentityKind::Name, "Modified Name".to_string()),
+                    (IdentityKind::Country, "".to_string()),
+                ]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn recorded_identities_keep_first_and_last_seen(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+
+            record_identities(
+                &database,
+                1234,
+                &[
+                    (IdentityKind::Name, "^1Old Name".to_string()),
+                    (IdentityKind::Clan, "".to_string()),
+                    (IdentityKind::Ip, "10.0.0.1".to_string()),
+                ],
+                100,
+            )
+            .expect("this should not happen");
+            record_identities(
+                &database,
+                1234,
+                &[
+                    (IdentityKind::Name, "New Name".to_string()),
+                    (IdentityKind::Ip, "10.0.0.1".to_string()),
+                ],
+                200,
+            )
+            .expect("this should not happen");
+
+            assert_eq!(
+                seen(&database, 1234).expect("this should not happen"),
+                Some((100, 200))
+            );
+            assert_eq!(
+                identity_history(&database, 1234, IdentityKind::Name)
+                    .expect("this should not happen"),
+                vec![
+                    IdentityRecord {
+                        value: "New Name".to_string(),
+                        first_seen: 200,
+                        last_seen: 200,
+                    },
+                    IdentityRecord {
+                        value: "^1Old Name".to_string(),
+                        first_seen: 100,
+                        last_seen: 100,
+                    },
+                ]
+            );
+            assert_eq!(
+                identity_history(&database, 1234, IdentityKind::Ip)
+                    .expect("this should not happen"),
+                vec![IdentityRecord {
+                    value: "10.0.0.1".to_string(),
+                    first_seen: 100,
+                    last_seen: 200,
+                }]
+            );
+            assert_eq!(
+                identity_history(&database, 1234, IdentityKind::Clan)
+                    .expect("this should not happen"),
+                vec![]
+            );
+            assert_eq!(
+                steam_ids_with(&database, IdentityKind::Name, "old name")
+                    .expect("this should not happen"),
+                vec![1234]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn accounts_sharing_ips_excludes_own_steam_id(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = flushed_in_memory(py);
+
+            [
+                (1234, "10.0.0.1", 100),
+                (5678, "10.0.0.1", 200),
+                (9012, "10.0.0.2", 300),
+            ]
+            .into_iter()
+            .for_each(|(steam_id, ip, timestamp)| {
+                record_identities(
+                    &database,
+                    steam_id,
+                    &[(IdentityKind::Ip, ip.to_string())],
+                    timestamp,
+                )
+                .expect("this should not happen");
+            });
+
+            assert_eq!(
+                accounts_sharing_ips(&database, 1234).expect("this should not happen"),
+                vec![5678]
+            );
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn identities_are_written_directly_without_write_behind(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let database = PyModule::from_code(
+                py,
+                cr#"
+class QueueingDatabase:
+    def __init__(self):
+        self.calls = []
+
+    def enqueue(self, command, *args):
+        self.calls.append("enqueue")
+
+    def zadd(self, name, mapping, nx=False):
+        self.calls.append("zadd")
+        "#,
+                c"",
+                c"",
+            )
+            .and_then(|module| module.getattr("QueueingDatabase"))
+            .and_then(|database_class| database_class.call0())
+            .expect("this should not happen");
+
+            record_identities(&database, 1234, &[], 42).expect("this should not happen");
+
+            assert_eq!(
+                database
+                    .getattr("calls")
+                    .and_then(|calls| calls.extract::<Vec<String>>())
+                    .expect("this should not happen"),
+                vec!["zadd", "zadd"]
+            );
+        });
+    }
+}
@@ -0,0 +1,466 @@
+use core::time::Duration;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::SystemTime,
+};
+
+use arc_swap::ArcSwapOption;
+use serde_json::{Map, Value};
+
+use crate::{MAIN_ENGINE, prelude::*, quake_live_engine::FindCVar};
+
+/// Where and how many archived matches to keep, read from the ``qlx_matchArchive*`` cvars.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ArchiveConfig {
+    pub(crate) directory: PathBuf,
+    /// The maximum number of archived matches to keep, or 0 to keep any number.
+    pub(crate) max_files: usize,
+    /// The maximum age of archived matches, or `None` to keep them forever.
+    pub(crate) max_age: Option<Duration>,
+}
+
+/// The stats messages collected for a match that has not ended yet.
+#[derive(Debug, Default)]
+struct MatchRecord {
+    started: Option<Value>,
+    player_stats: Vec<Value>,
+    kills: Vec<Value>,
+}
+
+impl MatchRecord {
+    fn into_document(self, match_guid: &str, report: Value) -> Value {
+        let mut document = Map::new();
+        document.insert("MATCH_GUID".to_string(), match_guid.into());
+        document.insert(
+            "MATCH_STARTED".to_string(),
+            self.started.unwrap_or_default(),
+        );
+        document.insert("MATCH_REPORT".to_string(), report);
+        document.insert("PLAYER_STATS".to_string(), self.player_stats.into());
+        document.insert("KILLS".to_string(), self.kills.into());
+        document.into()
+    }
+}
+
+/// Collects the stats messages of running matches until their ``MATCH_REPORT`` arrives.
+#[derive(Debug, Default)]
+pub(crate) struct MatchArchive {
+    matches: HashMap<String, MatchRecord>,
+}
+
+impl MatchArchive {
+    /// Records a stats message, returning the match guid and the complete document of the match
+    /// once its report arrived.
+    pub(crate) fn record(&mut self, stats: &Value) -> Option<(String, Value)> {
+        let data = &stats["DATA"];
+        let match_guid = data["MATCH_GUID"]
+            .as_str()
+            .filter(|guid| !guid.is_empty())?;
+
+        match stats["TYPE"].as_str() {
+            Some("MATCH_STARTED") => {
+                // a new match means any match without a report was abandoned.
+                self.matches.clear();
+                self.matches
+                    .entry(match_guid.to_string())
+                    .or_default()
+                    .started = Some(data.to_owned());
+            }
+            Some("PLAYER_STATS") if data["WARMUP"].as_bool() != Some(true) => {
+                self.matches
+                    .entry(match_guid.to_string())
+                    .or_default()
+                    .player_stats
+                    .push(data.to_owned());
+            }
+            // every frag is also reported as PLAYER_DEATH of the victim, so only the kills count.
+            Some("PLAYER_KILL") if data["WARMUP"].as_bool() != Some(true) => {
+                self.matches
+                    .entry(match_guid.to_string())
+                    .or_default()
+                    .kills
+                    .push(data.to_owned());
+            }
+            Some("MATCH_REPORT") => {
+                let record = self.matches.remove(match_guid).unwrap_or_default();
+                return Some((
+                    match_guid.to_string(),
+                    record.into_document(match_guid, data.to_owned()),
+                ));
+            }
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// The configuration and running matches of the match archive, if ``qlx_matchArchivePath`` was
+/// set when the stats listener started.
+static MATCH_ARCHIVE: LazyLock<ArcSwapOption<(ArchiveConfig, parking_lot::Mutex<MatchArchive>)>> =
+    LazyLock::new(ArcSwapOption::empty);
+
+fn archive_config_from_cvars() -> Option<ArchiveConfig> {
+    MAIN_ENGINE.load().as_ref().and_then(|main_engine| {
+        let directory = main_engine
+            .find_cvar("qlx_matchArchivePath")
+            .map(|cvar| cvar.get_string())
+            .filter(|path| !path.is_empty())?;
+        let max_files = main_engine
+            .find_cvar("qlx_matchArchiveMaxFiles")
+            .map(|cvar| cvar.get_integer().max(0) as usize)
+            .unwrap_or_default();
+        let max_age = main_engine
+            .find_cvar("qlx_matchArchiveMaxAgeDays")
+            .map(|cvar| cvar.get_integer())
+            .filter(|&days| days > 0)
+            .map(|days| Duration::from_secs(days as u64 * 24 * 60 * 60));
+
+        Some(ArchiveConfig {
+            directory: directory.into(),
+            max_files,
+            max_age,
+        })
+    })
+}
+
+/// Enables the match archive if ``qlx_matchArchivePath`` is set.
+pub(crate) fn initialize_match_archive() {
+    let archive = archive_config_from_cvars()
+        .map(|config| (config, parking_lot::Mutex::new(MatchArchive::default())));
+    MATCH_ARCHIVE.store(archive.map(Into::into));
+}
+
+fn archive_file_name(match_guid: &str) -> Option<String> {
+    if !match_guid.is_empty()
+        && match_guid
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Some(format!("{match_guid}.json"))
+    } else {
+        None
+    }
+}
+
+/// Writes the *document* of a match into *directory*, named by its guid.
+pub(crate) fn write_match(
+    directory: &Path,
+    match_guid: &str,
+    document: &Value,
+) -> io::Result<PathBuf> {
+    let Some(file_name) = archive_file_name(match_guid) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid match guid {match_guid:?}"),
+        ));
+    };
+
+    fs::create_dir_all(directory)?;
+    let path = directory.join(file_name);
+    let temp_path = path.with_extension("tmp");
+    let contents = serde_json::to_string_pretty(document).map_err(io::Error::other)?;
+    fs::write(&temp_path, contents).and_then(|_| fs::rename(&temp_path, &path))?;
+    Ok(path)
+}
+
+/// Removes the archived matches in *directory* exceeding the retention limits of *config*,
+/// oldest first. Only files named like :func:`write_match` names them are considered, anything
+/// else in *directory* is left alone. Returns the number of removed files.
+pub(crate) fn enforce_retention(config: &ArchiveConfig, now: SystemTime) -> io::Result<usize> {
+    let mut archived = fs::read_dir(&config.directory)?
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry.file_name().to_str().is_some_and(|file_name| {
+                file_name
+                    .strip_suffix(".json")
+                    .and_then(archive_file_name)
+                    .is_some_and(|archive_file_name| archive_file_name == file_name)
+            })
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|metadata| metadata.modified());
+            modified.ok().map(|modified| (modified, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    archived.sort();
+
+    let expired = config.max_age.map_or(0, |max_age| {
+        archived
+            .iter()
+            .take_while(|(modified, _)| {
+                now.duration_since(*modified).is_ok_and(|age| age > max_age)
+            })
+            .count()
+    });
+    let surplus = if config.max_files == 0 {
+        0
+    } else {
+        archived.len().saturating_sub(config.max_files)
+    };
+
+    archived
+        .iter()
+        .take(expired.max(surplus))
+        .try_for_each(|(_, path)| fs::remove_file(path))?;
+    Ok(expired.max(surplus))
+}
+
+/// Feeds a stats message into the match archive, writing the match to disk when it ended. Does
+/// nothing unless the match archive was initialized.
+pub(crate) fn archive_stats_message(stats: &Value) {
+    let Some(ref match_archive) = *MATCH_ARCHIVE.load() else {
+        return;
+    };
+    let (config, archive) = match_archive.as_ref();
+
+    let Some((match_guid, document)) = archive.lock().record(stats) else {
+        return;
+    };
+
+    match write_match(&config.directory, &match_guid, &document) {
+        Err(err) => {
+            warn!(target: "shinqlx", "could not archive match {match_guid}: {err}");
+        }
+        Ok(_) => {
+            if let Err(err) = enforce_retention(config, SystemTime::now()) {
+                warn!(target: "shinqlx", "could not clean up match archive: {err}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod match_archive_tests {
+    use core::time::Duration;
+    use std::{fs, time::SystemTime};
+
+    use pretty_assertions::assert_eq;
+    use serde_json::{Value, json};
+
+    use super::{
+        ArchiveConfig, MATCH_ARCHIVE, MatchArchive, enforce_retention, initialize_match_archive,
+        write_match,
+    };
+    use crate::{
+        ffi::c::prelude::{CVar, CVarBuilder, cvar_t},
+        prelude::*,
+    };
+
+    fn stats(message_type: &str, data: Value) -> Value {
+        json!({"TYPE": message_type, "DATA": data})
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn collects_a_match_until_its_report() {
+        let mut archive = MatchArchive::default();
+
+        assert!(
+            archive
+                .record(&stats(
+                    "MATCH_STARTED",
+                    json!({"MATCH_GUID": "asdf", "MAP": "campgrounds"})
+                ))
+                .is_none()
+        );
+        let kill = json!({"MATCH_GUID": "asdf", "MOD": "ROCKET", "WARMUP": false});
+        assert!(
+            archive
+                .record(&stats("PLAYER_KILL", kill.clone()))
+                .is_none()
+        );
+        assert!(
+            archive
+                .record(&stats(
+                    "PLAYER_KILL",
+                    json!({"MATCH_GUID": "asdf", "MOD": "ROCKET", "WARMUP": true})
+                ))
+                .is_none()
+        );
+        assert!(
+            archive
+                .record(&stats(
+                    "PLAYER_STATS",
+                    json!({"MATCH_GUID": "asdf", "NAME": "player1", "WARMUP": false})
+                ))
+                .is_none()
+        );
+
+        let result = archive.record(&stats(
+            "MATCH_REPORT",
+            json!({"MATCH_GUID": "asdf", "TSCORE0": 8}),
+        ));
+
+        assert_eq!(
+            result,
+            Some((
+                "asdf".to_string(),
+                json!({
+                    "MATCH_GUID": "asdf",
+                    "MATCH_STARTED": {"MATCH_GUID": "asdf", "MAP": "campgrounds"},
+                    "MATCH_REPORT": {"MATCH_GUID": "asdf", "TSCORE0": 8},
+                    "PLAYER_STATS": [{"MATCH_GUID": "asdf", "NAME": "player1", "WARMUP": false}],
+                    "KILLS": [kill],
+                })
+            ))
+        );
+        assert!(archive.matches.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn kills_are_recorded_once_even_without_match_start() {
+        let mut archive = MatchArchive::default();
+        let kill = json!({"MATCH_GUID": "asdf", "MOD": "ROCKET", "WARMUP": false});
+
+        archive.record(&stats("PLAYER_KILL", kill.clone()));
+        archive.record(&stats(
+            "PLAYER_DEATH",
+            json!({"MATCH_GUID": "asdf", "MOD": "ROCKET", "WARMUP": false}),
+        ));
+        let result = archive.record(&stats("MATCH_REPORT", json!({"MATCH_GUID": "asdf"})));
+
+        assert_eq!(
+            result.map(|(_, document)| document["KILLS"].to_owned()),
+            Some(json!([kill]))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn new_match_discards_abandoned_matches() {
+        let mut archive = MatchArchive::default();
+        archive.record(&stats("MATCH_STARTED", json!({"MATCH_GUID": "old"})));
+
+        archive.record(&stats("MATCH_STARTED", json!({"MATCH_GUID": "new"})));
+
+        assert_eq!(archive.matches.keys().collect::<Vec<_>>(), vec!["new"]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn write_match_rejects_unsafe_guids() {
+        let directory = tempfile::tempdir().expect("this should not happen");
+
+        assert!(write_match(directory.path(), "../escape", &json!({})).is_err());
+        assert!(
+            write_match(directory.path(), "1234-abcd", &json!({}))
+                .is_ok_and(|path| path == directory.path().join("1234-abcd.json"))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn retention_removes_surplus_and_expired_matches() {
+        let directory = tempfile::tempdir().expect("this should not happen");
+        for guid in ["a", "b", "c"] {
+            write_match(directory.path(), guid, &json!({})).expect("this should not happen");
+        }
+        let mut config = ArchiveConfig {
+            directory: directory.path().to_path_buf(),
+            max_files: 2,
+            max_age: None,
+        };
+
+        assert!(enforce_retention(&config, SystemTime::now()).is_ok_and(|removed| removed == 1));
+        assert_eq!(
+            fs::read_dir(directory.path())
+                .expect("this should not happen")
+                .count(),
+            2
+        );
+
+        config.max_files = 0;
+        config.max_age = Some(Duration::from_secs(60));
+        let later = SystemTime::now() + Duration::from_secs(3600);
+        assert!(enforce_retention(&config, later).is_ok_and(|removed| removed == 2));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn retention_leaves_other_files_alone() {
+        let directory = tempfile::tempdir().expect("this should not happen");
+        for file_name in [
+            "settings.v2.json",
+            "notes.txt",
+            "player stats.json",
+            ".json",
+        ] {
+            fs::write(directory.path().join(file_name), "{}").expect("this should not happen");
+        }
+        write_match(directory.path(), "1234-abcd", &json!({})).expect("this should not happen");
+        let config = ArchiveConfig {
+            directory: directory.path().to_path_buf(),
+            max_files: 0,
+            max_age: Some(Duration::from_secs(60)),
+        };
+
+        let later = SystemTime::now() + Duration::from_secs(3600);
+        assert!(enforce_retention(&config, later).is_ok_and(|removed| removed == 1));
+        assert_eq!(
+            fs::read_dir(directory.path())
+                .expect("this should not happen")
+                .count(),
+            4
+        );
+        assert!(!directory.path().join("1234-abcd.json").exists());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn initialize_match_archive_reads_cvars() {
+        let archive_path = c"/tmp/matches";
+        let mut raw_path_cvar = CVarBuilder::default()
+            .string(archive_path.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+        let mut raw_max_age_cvar = CVarBuilder::default()
+            .integer(7)
+            .build()
+            .expect("this should not happen");
+
+        MockEngineBuilder::default()
+            .with_find_cvar(
+                |cmd| cmd == "qlx_matchArchivePath",
+                move |_| CVar::try_from(&mut raw_path_cvar as *mut cvar_t).ok(),
+                1,
+            )
+            .with_find_cvar(|cmd| cmd == "qlx_matchArchiveMaxFiles", |_| None, 1)
+            .with_find_cvar(
+                |cmd| cmd == "qlx_matchArchiveMaxAgeDays",
+                move |_| CVar::try_from(&mut raw_max_age_cvar as *mut cvar_t).ok(),
+                1,
+            )
+            .run(|| {
+                initialize_match_archive();
+
+                assert!(MATCH_ARCHIVE.load().as_ref().is_some_and(|match_archive| {
+                    match_archive.0
+                        == ArchiveConfig {
+                            directory: "/tmp/matches".into(),
+                            max_files: 0,
+                            max_age: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+                        }
+                }));
+            });
+        MATCH_ARCHIVE.store(None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn initialize_match_archive_without_path_disables_archive() {
+        MockEngineBuilder::default()
+            .with_find_cvar(|cmd| cmd == "qlx_matchArchivePath", |_| None, 1)
+            .run(|| {
+                initialize_match_archive();
+
+                assert!(MATCH_ARCHIVE.load().is_none());
+            });
+    }
+}
@@ -0,0 +1,634 @@
+use core::{
+    fmt::Write,
+    hint::cold_path,
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+use std::{collections::BTreeMap, sync::LazyLock, thread, time::Instant};
+
+use tiny_http::{Header, Response, Server};
+
+use super::{Teams, database::write_queue_stats, prelude::*};
+use crate::{MAIN_ENGINE, prelude::*, quake_live_engine::FindCVar};
+
+/// How often the players per team are counted.
+const PLAYER_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+static METRICS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How many calls were made and how long they took in total.
+#[derive(Debug, Default)]
+pub(crate) struct Timing {
+    count: AtomicU64,
+    total_micros: AtomicU64,
+}
+
+impl Timing {
+    const fn new() -> Self {
+        Self {
+            count: AtomicU64::new(0),
+            total_micros: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, elapsed: Duration) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.total_micros.fetch_add(
+            u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn snapshot(&self) -> TimingSnapshot {
+        TimingSnapshot {
+            count: self.count.load(Ordering::Relaxed),
+            total_seconds: self.total_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct TimingSnapshot {
+    pub(crate) count: u64,
+    pub(crate) total_seconds: f64,
+}
+
+#[derive(Debug, Default)]
+struct DispatcherMetrics {
+    invocations: AtomicU64,
+    handlers: Timing,
+}
+
+static FRAMES: Timing = Timing::new();
+static PYTHON_EXCEPTIONS: AtomicU64 = AtomicU64::new(0);
+static REDIS_CALLS: Timing = Timing::new();
+static SQLITE_CALLS: Timing = Timing::new();
+static IN_MEMORY_CALLS: Timing = Timing::new();
+/// The players on the free, red, blue, and spectator team, as last counted.
+static TEAM_PLAYERS: [AtomicUsize; 4] = [const { AtomicUsize::new(0) }; 4];
+static LAST_PLAYER_SAMPLE: parking_lot::Mutex<Option<Instant>> = parking_lot::Mutex::new(None);
+
+static DISPATCHERS: LazyLock<parking_lot::RwLock<BTreeMap<String, DispatcherMetrics>>> =
+    LazyLock::new(Default::default);
+static STATS_MESSAGES: LazyLock<parking_lot::RwLock<BTreeMap<String, AtomicU64>>> =
+    LazyLock::new(Default::default);
+
+fn metrics_enabled() -> bool {
+    METRICS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Updates the metrics of *name* in *metrics*. The name is only copied the first time it is seen,
+/// every later update shares the read lock with the other threads.
+fn update_named<T: Default>(
+    metrics: &parking_lot::RwLock<BTreeMap<String, T>>,
+    name: &str,
+    update: impl FnOnce(&T),
+) {
+    if let Some(named_metrics) = metrics.read().get(name) {
+        update(named_metrics);
+        return;
+    }
+    update(metrics.write().entry(name.to_string()).or_default());
+}
+
+/// Records the time spent handling a server frame, and counts the players per team once a
+/// second.
+pub(crate) fn record_frame(py: Python<'_>, elapsed: Duration) {
+    if !metrics_enabled() {
+        return;
+    }
+    FRAMES.record(elapsed);
+
+    {
+        let mut last_sample = LAST_PLAYER_SAMPLE.lock();
+        if last_sample.is_some_and(|sampled| sampled.elapsed() < PLAYER_SAMPLE_INTERVAL) {
+            return;
+        }
+        *last_sample = Some(Instant::now());
+    }
+
+    let Ok(players_info) = pyshinqlx_players_info(py) else {
+        cold_path();
+        return;
+    };
+    let mut team_players = [0; 4];
+    players_info
+        .iter()
+        .flatten()
+        .for_each(|player_info| match Teams::from(player_info.team) {
+            Teams::Free => team_players[0] += 1,
+            Teams::Red => team_players[1] += 1,
+            Teams::Blue => team_players[2] += 1,
+            Teams::Spectator => team_players[3] += 1,
+            Teams::Invalid => (),
+        });
+    TEAM_PLAYERS
+        .iter()
+        .zip(team_players)
+        .for_each(|(gauge, players)| gauge.store(players, Ordering::Relaxed));
+}
+
+/// Counts a dispatched event.
+pub(crate) fn record_dispatch(dispatcher_name: &str) {
+    if !metrics_enabled() {
+        return;
+    }
+    update_named(&DISPATCHERS, dispatcher_name, |metrics| {
+        metrics.invocations.fetch_add(1, Ordering::Relaxed);
+    });
+}
+
+/// Records how long a handler of the event *dispatcher_name* took.
+pub(crate) fn record_handler_call(dispatcher_name: &str, elapsed: Duration) {
+    if !metrics_enabled() {
+        return;
+    }
+    update_named(&DISPATCHERS, dispatcher_name, |metrics| {
+        metrics.handlers.record(elapsed);
+    });
+}
+
+pub(crate) fn record_python_exception() {
+    if !metrics_enabled() {
+        return;
+    }
+    PYTHON_EXCEPTIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts a message received from the stats socket by its ``TYPE``.
+pub(crate) fn record_stats_message(message_type: &str) {
+    if !metrics_enabled() {
+        return;
+    }
+    update_named(&STATS_MESSAGES, message_type, |count| {
+        count.fetch_add(1, Ordering::Relaxed);
+    });
+}
+
+pub(crate) fn record_redis_call(elapsed: Duration) {
+    if !metrics_enabled() {
+        return;
+    }
+    REDIS_CALLS.record(elapsed);
+}
+
+pub(crate) fn record_sqlite_call(elapsed: Duration) {
+    if !metrics_enabled() {
+        return;
+    }
+    SQLITE_CALLS.record(elapsed);
+}
+
+pub(crate) fn record_in_memory_call(elapsed: Duration) {
+    if !metrics_enabled() {
+        return;
+    }
+    IN_MEMORY_CALLS.record(elapsed);
+}
+
+/// Statistics of the jemalloc allocator, in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct AllocatorStats {
+    pub(crate) allocated: usize,
+    pub(crate) active: usize,
+    pub(crate) resident: usize,
+    pub(crate) mapped: usize,
+    pub(crate) retained: usize,
+}
+
+#[cfg(all(feature = "alloc", not(windows), not(miri)))]
+fn allocator_stats() -> Option<AllocatorStats> {
+    use tikv_jemalloc_ctl::{epoch, stats};
+
+    epoch::advance().ok()?;
+    Some(AllocatorStats {
+        allocated: stats::allocated::read().ok()?,
+        active: stats::active::read().ok()?,
+        resident: stats::resident::read().ok()?,
+        mapped: stats::mapped::read().ok()?,
+        retained: stats::retained::read().ok()?,
+    })
+}
+
+#[cfg(not(all(feature = "alloc", not(windows), not(miri))))]
+fn allocator_stats() -> Option<AllocatorStats> {
+    None
+}
+
+/// Everything exposed on the metrics endpoint, at the time it was scraped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct MetricsSnapshot {
+    pub(crate) team_players: [(&'static str, usize); 4],
+    pub(crate) frames: TimingSnapshot,
+    pub(crate) dispatchers: Vec<(String, u64, TimingSnapshot)>,
+    pub(crate) python_exceptions: u64,
+    pub(crate) stats_messages: Vec<(String, u64)>,
+    pub(crate) database_calls: [(&'static str, TimingSnapshot); 3],
+    pub(crate) write_queue_depth: usize,
+    pub(crate) write_queue_failed: u64,
+    pub(crate) allocator: Option<AllocatorStats>,
+}
+
+fn snapshot() -> MetricsSnapshot {
+    let team_names = ["free", "red", "blue", "spectator"];
+    let write_queue = write_queue_stats();
+    MetricsSnapshot {
+        team_players: core::array::from_fn(|index| {
+            (
+                team_names[index],
+                TEAM_PLAYERS[index].load(Ordering::Relaxed),
+            )
+        }),
+        frames: FRAMES.snapshot(),
+        dispatchers: DISPATCHERS
+            .read()
+            .iter()
+            .map(|(name, metrics)| {
+                (
+                    name.to_owned(),
+                    metrics.invocations.load(Ordering::Relaxed),
+                    metrics.handlers.snapshot(),
+                )
+            })
+            .collect(),
+        python_exceptions: PYTHON_EXCEPTIONS.load(Ordering::Relaxed),
+        stats_messages: STATS_MESSAGES
+            .read()
+            .iter()
+            .map(|(message_type, count)| (message_type.to_owned(), count.load(Ordering::Relaxed)))
+            .collect(),
+        database_calls: [
+            ("redis", REDIS_CALLS.snapshot()),
+            ("sqlite", SQLITE_CALLS.snapshot()),
+            ("in_memory", IN_MEMORY_CALLS.snapshot()),
+        ],
+        write_queue_depth: write_queue.depth,
+        write_queue_failed: write_queue.failed,
+        allocator: allocator_stats(),
+    }
+}
+
+fn write_header(output: &mut String, name: &str, metric_type: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {name} {help}");
+    let _ = writeln!(output, "# TYPE {name} {metric_type}");
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Renders *metrics* in the Prometheus text exposition format.
+pub(crate) fn render_metrics(metrics: &MetricsSnapshot) -> String {
+    let mut output = String::new();
+
+    write_header(
+        &mut output,
+        "shinqlx_players",
+        "gauge",
+        "Connected players per team.",
+    );
+    metrics.team_players.iter().for_each(|(team, players)| {
+        let _ = writeln!(output, "shinqlx_players{{team=\"{team}\"}} {players}");
+    });
+
+    write_header(
+        &mut output,
+        "shinqlx_frame_seconds",
+        "summary",
+        "Time spent handling server frames in shinqlx.",
+    );
+    let _ = writeln!(
+        output,
+        "shinqlx_frame_seconds_sum {}",
+        metrics.frames.total_seconds
+    );
+    let _ = writeln!(
+        output,
+        "shinqlx_frame_seconds_count {}",
+        metrics.frames.count
+    );
+
+    write_header(
+        &mut output,
+        "shinqlx_dispatcher_invocations_total",
+        "counter",
+        "Events dispatched to plugins.",
+    );
+    metrics
+        .dispatchers
+        .iter()
+        .for_each(|(event, invocations, _)| {
+            let _ = writeln!(
+                output,
+                "shinqlx_dispatcher_invocations_total{{event=\"{}\"}} {invocations}",
+                escape_label(event)
+            );
+        });
+
+    write_header(
+        &mut output,
+        "shinqlx_handler_seconds",
+        "summary",
+        "Time spent in plugin event handlers.",
+    );
+    metrics.dispatchers.iter().for_each(|(event, _, handlers)| {
+        let event = escape_label(event);
+        let _ = writeln!(
+            output,
+            "shinqlx_handler_seconds_sum{{event=\"{event}\"}} {}",
+            handlers.total_seconds
+        );
+        let _ = writeln!(
+            output,
+            "shinqlx_handler_seconds_count{{event=\"{event}\"}} {}",
+            handlers.count
+        );
+    });
+
+    write_header(
+        &mut output,
+        "shinqlx_python_exceptions_total",
+        "counter",
+        "Python exceptions logged.",
+    );
+    let _ = writeln!(
+        output,
+        "shinqlx_python_exceptions_total {}",
+        metrics.python_exceptions
+    );
+
+    write_header(
+        &mut output,
+        "shinqlx_zmq_stats_messages_total",
+        "counter",
+        "Messages received from the stats socket.",
+    );
+    metrics
+        .stats_messages
+        .iter()
+        .for_each(|(message_type, count)| {
+            let _ = writeln!(
+                output,
+                "shinqlx_zmq_stats_messages_total{{type=\"{}\"}} {count}",
+                escape_label(message_type)
+            );
+        });
+
+    write_header(
+        &mut output,
+        "shinqlx_database_call_seconds",
+        "summary",
+        "Time spent in calls to the database.",
+    );
+    metrics.database_calls.iter().for_each(|(backend, calls)| {
+        let _ = writeln!(
+            output,
+            "shinqlx_database_call_seconds_sum{{backend=\"{backend}\"}} {}",
+            calls.total_seconds
+        );
+        let _ = writeln!(
+            output,
+            "shinqlx_database_call_seconds_count{{backend=\"{backend}\"}} {}",
+            calls.count
+        );
+    });
+
+    write_header(
+        &mut output,
+        "shinqlx_database_write_queue_depth",
+        "gauge",
+        "Database writes queued, but not written yet.",
+    );
+    let _ = writeln!(
+        output,
+        "shinqlx_database_write_queue_depth {}",
+        metrics.write_queue_depth
+    );
+    write_header(
+        &mut output,
+        "shinqlx_database_write_failures_total",
+        "counter",
+        "Queued database writes that failed.",
+    );
+    let _ = writeln!(
+        output,
+        "shinqlx_database_write_failures_total {}",
+        metrics.write_queue_failed
+    );
+
+    if let Some(allocator) = metrics.allocator {
+        [
+            (
+                "allocated",
+                "Bytes allocated by the application.",
+                allocator.allocated,
+            ),
+            ("active", "Bytes in active pages.", allocator.active),
+            (
+                "resident",
+                "Bytes in physically resident pages.",
+                allocator.resident,
+            ),
+            (
+                "mapped",
+                "Bytes in active extents mapped by the allocator.",
+                allocator.mapped,
+            ),
+            (
+                "retained",
+                "Bytes in virtual memory retained by the allocator.",
+                allocator.retained,
+            ),
+        ]
+        .iter()
+        .for_each(|(stat, help, bytes)| {
+            let name = format!("shinqlx_jemalloc_{stat}_bytes");
+            write_header(&mut output, &name, "gauge", help);
+            let _ = writeln!(output, "{name} {bytes}");
+        });
+    }
+
+    output
+}
+
+fn run_server(server: Server) {
+    for request in server.incoming_requests() {
+        let response = if request.url().split('?').next() == Some("/metrics") {
+            let mut response = Response::from_string(render_metrics(&snapshot()));
+            if let Ok(content_type) =
+                Header::from_bytes("Content-Type", "text/plain; version=0.0.4")
+            {
+                response.add_header(content_type);
+            }
+            response
+        } else {
+            Response::from_string("not found").with_status_code(404)
+        };
+        if let Err(err) = request.respond(response) {
+            cold_path();
+            debug!(target: "shinqlx", "could not answer metrics request: {err}");
+        }
+    }
+}
+
+/// Starts serving metrics if ``qlx_metricsAddress`` is set.
+pub(crate) fn initialize_metrics() {
+    let Some(address) = MAIN_ENGINE.load().as_ref().and_then(|main_engine| {
+        main_engine
+            .find_cvar("qlx_metricsAddress")
+            .map(|cvar| cvar.get_string())
+            .filter(|address| !address.is_empty())
+    }) else {
+        return;
+    };
+
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(err) => {
+            cold_path();
+            error!(target: "shinqlx", "could not serve metrics on {address}: {err}");
+            return;
+        }
+    };
+    if let Err(err) = thread::Builder::new()
+        .name("shinqlx-metrics".to_string())
+        .spawn(move || run_server(server))
+    {
+        cold_path();
+        error!(target: "shinqlx", "could not start the metrics thread: {err}");
+        return;
+    }
+    METRICS_ENABLED.store(true, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod metrics_tests {
+    use core::sync::atomic::{AtomicU64, Ordering};
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{AllocatorStats, MetricsSnapshot, TimingSnapshot, render_metrics, update_named};
+
+    #[test]
+    fn render_metrics_in_text_format() {
+        let metrics = MetricsSnapshot {
+            team_players: [("free", 0), ("red", 3), ("blue", 2), ("spectator", 1)],
+            frames: TimingSnapshot {
+                count: 40,
+                total_seconds: 0.5,
+            },
+            dispatchers: vec![(
+                "chat".to_string(),
+                7,
+                TimingSnapshot {
+                    count: 14,
+                    total_seconds: 0.25,
+                },
+            )],
+            python_exceptions: 2,
+            stats_messages: vec![("PLAYER_KILL".to_string(), 11)],
+            database_calls: [
+                (
+                    "redis",
+                    TimingSnapshot {
+                        count: 5,
+                        total_seconds: 0.125,
+                    },
+                ),
+                ("sqlite", TimingSnapshot::default()),
+                ("in_memory", TimingSnapshot::default()),
+            ],
+            write_queue_depth: 1,
+            write_queue_failed: 0,
+            allocator: None,
+        };
+
+        let rendered = render_metrics(&metrics);
+
+        [
+            "shinqlx_players{team=\"red\"} 3",
+            "shinqlx_frame_seconds_sum 0.5",
+            "shinqlx_frame_seconds_count 40",
+            "shinqlx_dispatcher_invocations_total{event=\"chat\"} 7",
+            "shinqlx_handler_seconds_sum{event=\"chat\"} 0.25",
+            "shinqlx_handler_seconds_count{event=\"chat\"} 14",
+            "shinqlx_python_exceptions_total 2",
+            "shinqlx_zmq_stats_messages_total{type=\"PLAYER_KILL\"} 11",
+            "shinqlx_database_call_seconds_count{backend=\"redis\"} 5",
+            "shinqlx_database_call_seconds_count{backend=\"sqlite\"} 0",
+            "shinqlx_database_call_seconds_count{backend=\"in_memory\"} 0",
+            "shinqlx_database_write_queue_depth 1",
+        ]
+        .iter()
+        .for_each(|line| {
+            assert!(
+                rendered.lines().any(|rendered_line| rendered_line == *line),
+                "{line}"
+            );
+        });
+        assert!(!rendered.contains("jemalloc"));
+    }
+
+    #[test]
+    fn render_allocator_stats() {
+        let metrics = MetricsSnapshot {
+            allocator: Some(AllocatorStats {
+                allocated: 1024,
+                ..AllocatorStats::default()
+            }),
+            ..MetricsSnapshot::default()
+        };
+
+        let rendered = render_metrics(&metrics);
+
+        assert!(
+            rendered
+                .lines()
+                .any(|line| line == "shinqlx_jemalloc_allocated_bytes 1024")
+        );
+        assert_eq!(
+            rendered
+                .lines()
+                .filter(|line| line.starts_with("# TYPE shinqlx_jemalloc_"))
+                .count(),
+            5
+        );
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        let metrics = MetricsSnapshot {
+            dispatchers: vec![("odd\"event".to_string(), 1, TimingSnapshot::default())],
+            ..MetricsSnapshot::default()
+        };
+
+        assert!(
+            render_metrics(&metrics)
+                .contains("shinqlx_dispatcher_invocations_total{event=\"odd\\\"event\"} 1")
+        );
+    }
+
+    #[test]
+    fn named_metrics_are_updated_in_place() {
+        let metrics = parking_lot::RwLock::new(BTreeMap::<String, AtomicU64>::new());
+
+        ["chat", "chat", "frame"].iter().for_each(|name| {
+            update_named(&metrics, name, |count| {
+                count.fetch_add(1, Ordering::Relaxed);
+            })
+        });
+
+        assert_eq!(
+            metrics
+                .read()
+                .iter()
+                .map(|(name, count)| (name.as_str(), count.load(Ordering::Relaxed)))
+                .collect::<Vec<_>>(),
+            vec![("chat", 2), ("frame", 1)]
+        );
+    }
+}
@@ -1,13 +1,19 @@
+mod admin_api;
+mod bans;
 mod channels;
 mod commands;
 mod database;
 mod dispatchers;
 mod embed;
+mod event_publisher;
 mod events;
 mod flight;
 mod game;
 mod handlers;
 mod holdable;
+mod identities;
+mod match_archive;
+mod metrics;
 mod player;
 mod player_info;
 mod player_state;
@@ -15,12 +21,15 @@ mod player_stats;
 mod plugin;
 mod powerups;
 mod stats_listener;
+mod stats_schema;
 mod vector3;
 mod weapons;
 
 pub(crate) mod prelude {
     pub(crate) use pyo3::prelude::*;
 
+    #[allow(unused_imports)]
+    pub(crate) use super::bans::{BanDuration, Bans, ban_reject_message, initialize_bans};
     #[allow(unused_imports)]
     pub(crate) use super::channels::{
         AbstractChannel, AbstractChannelMethods, ChatChannel, ChatChannelMethods,
@@ -29,11 +38,16 @@ pub(crate) mod prelude {
     };
     #[allow(unused_imports)]
     pub(crate) use super::commands::{
-        Command, CommandInvoker, CommandInvokerMethods, CommandMethods,
+        Command, CommandInvoker, CommandInvokerMethods, CommandMethods, registered_command_names,
     };
     #[allow(unused_imports)]
     pub(crate) use super::database::{
-        AbstractDatabase, AbstractDatabaseMethods, Redis, RedisMethods,
+        AbstractDatabase, AbstractDatabaseMethods, DEFAULT_TRANSFER_PREFIX, InMemory,
+        InMemoryError, InMemoryMethods, Redis, RedisCommand, RedisError, RedisMethods, Sqlite,
+        SqliteError, SqliteMethods, configured_database, export_database, flush_write_behind_queue,
+        import_database, pyshinqlx_export_database, pyshinqlx_import_database,
+        pyshinqlx_register_migration, read_export_file, save_in_memory_snapshot, write_export_file,
+        write_queue_stats,
     };
     #[cfg(not(test))]
     pub(crate) use super::dispatchers::{
@@ -70,6 +84,10 @@ pub(crate) mod prelude {
         handle_player_disconnect, handle_player_loaded, handle_player_spawn, handle_rcon,
         handle_server_command, handle_set_configstring, register_handlers,
     };
+    #[allow(unused_imports)]
+    pub(crate) use super::identities::{
+        Identities, initialize_identities, record_connecting_player, record_userinfo_change,
+    };
     #[cfg(test)]
     pub(crate) use super::mock_python_tests::{
         client_command_dispatcher, client_connect_dispatcher, client_disconnect_dispatcher,
@@ -102,11 +120,16 @@ pub(crate) mod prelude {
     #[cfg(test)]
     pub(crate) use super::pyshinqlx_setup_fixture::*;
     pub(crate) use super::{
-        ALLOW_FREE_CLIENT, CUSTOM_COMMAND_HANDLER, PythonInitializationError, clean_text,
+        ALLOW_FREE_CLIENT, CUSTOM_COMMAND_HANDLER, PythonInitializationError,
+        admin_api::initialize_admin_api,
+        clean_text,
         embed::*,
+        event_publisher::initialize_event_publisher,
         events::*,
         flight::Flight,
         holdable::Holdable,
+        match_archive::initialize_match_archive,
+        metrics::initialize_metrics,
         parse_variables,
         player_info::PlayerInfo,
         player_state::PlayerState,
@@ -114,6 +137,7 @@ pub(crate) mod prelude {
         plugin::{Plugin, PluginMethods},
         powerups::Powerups,
         stats_listener::{StatsListener, StatsListenerMethods},
+        stats_schema::pyshinqlx_parse_stats,
         vector3::Vector3,
         weapons::Weapons,
     };
@@ -136,6 +160,7 @@ use arc_swap::ArcSwapOption;
 use chrono::Utc;
 use commands::CommandPriorities;
 use derive_more::Display;
+use event_publisher::event_publisher_enabled;
 use itertools::Itertools;
 use log::*;
 use prelude::*;
@@ -910,6 +935,11 @@ fn tempban(py: Python<'_>, player: &Bound<'_, PyAny>) -> PyResult<()> {
         },
         |player_id| {
             py.detach(|| {
+                if event_publisher_enabled()
+                    && let Ok(client) = player_id.try_conv::<Client>()
+                {
+                    publish_client_event("tempban", player_id, &client, None);
+                }
                 let tempban_cmd = format!("tempban {player_id}");
                 console_command(&tempban_cmd)
             })
@@ -925,6 +955,11 @@ fn ban(py: Python<'_>, player: &Bound<'_, PyAny>) -> PyResult<()> {
         },
         |player_id| {
             py.detach(|| {
+                if event_publisher_enabled()
+                    && let Ok(client) = player_id.try_conv::<Client>()
+                {
+                    publish_client_event("ban", player_id, &client, None);
+                }
                 let ban_cmd = format!("ban {player_id}");
                 console_command(&ban_cmd)
             })
@@ -1587,6 +1622,7 @@ mod pyshinqlx_configure_logger_tests {
 #[pyo3(name = "log_exception")]
 #[pyo3(signature = (plugin = None), text_signature = "(plugin = None)")]
 fn pyshinqlx_log_exception(py: Python<'_>, plugin: Option<Bound<'_, PyAny>>) -> PyResult<()> {
+    metrics::record_python_exception();
     py.import(intern!(py, "sys")).and_then(|sys_module| {
         sys_module
             .call_method0(intern!(py, "exc_info"))
@@ -1661,6 +1697,7 @@ fn pyshinqlx_handle_exception(
     exc_value: Bound<'_, PyAny>,
     exc_traceback: Bound<'_, PyAny>,
 ) -> PyResult<()> {
+    metrics::record_python_exception();
     py.import(intern!(py, "traceback"))
         .and_then(|traceback_module| {
             traceback_module
@@ -1790,6 +1827,7 @@ fn try_log_exception(py: Python<'_>, exception: &PyErr) -> PyResult<()> {
 }
 
 pub(crate) fn log_exception(py: Python<'_>, exception: &PyErr) {
+    metrics::record_python_exception();
     let _ = try_log_exception(py, exception);
 }
 
@@ -2318,6 +2356,13 @@ fn pyshinqlx_owner(py: Python<'_>) -> PyResult<Option<i64>> {
     py.detach(owner)
 }
 
+/// Whether a database driver was configured via ``qlx_database``.
+fn database_configured(py: Python<'_>) -> bool {
+    py.get_type::<Plugin>()
+        .getattr(intern!(py, "database"))
+        .is_ok_and(|database_class| !database_class.is_none())
+}
+
 fn owner() -> PyResult<Option<i64>> {
     get_cvar("qlx_owner").map(|opt_value| {
         opt_value.and_then(|value| value.parse::<i64>().ok()).filter(|&int_value| int_value >= 0).tap_none(|| {
@@ -3846,6 +3891,85 @@ fn initialize_cvars(py: Python<'_>) -> PyResult<()> {
         "qlx_redisPassword",
         PyString::intern(py, "").as_any(),
         0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_redisWriteBehind",
+        PyString::intern(py, "0").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_sqlitePath",
+        PyString::intern(py, "shinqlx.sqlite").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_inMemorySnapshot",
+        PyString::intern(py, "").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(py, "qlx_bans", PyString::intern(py, "0").as_any(), 0)?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_identityHistory",
+        PyString::intern(py, "1").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_identityIpPermission",
+        PyString::intern(py, "5").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_matchArchivePath",
+        PyString::intern(py, "").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_matchArchiveMaxFiles",
+        PyString::intern(py, "1000").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_matchArchiveMaxAgeDays",
+        PyString::intern(py, "0").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_eventPublisherAddress",
+        PyString::intern(py, "").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_eventPublisherPassword",
+        PyString::intern(py, "").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_adminApiAddress",
+        PyString::intern(py, "").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_adminApiToken",
+        PyString::intern(py, "").as_any(),
+        0,
+    )?;
+    pyshinqlx_set_cvar_once(
+        py,
+        "qlx_metricsAddress",
+        PyString::intern(py, "").as_any(),
+        0,
     )
     .map(|_| ())
 }
@@ -3906,6 +4030,20 @@ mod initialize_cvars_tests {
     #[case("qlx_redisDatabase", "0")]
     #[case("qlx_redisUnixSocket", "0")]
     #[case("qlx_redisPassword", "")]
+    #[case("qlx_redisWriteBehind", "0")]
+    #[case("qlx_sqlitePath", "shinqlx.sqlite")]
+    #[case("qlx_inMemorySnapshot", "")]
+    #[case("qlx_bans", "0")]
+    #[case("qlx_identityHistory", "1")]
+    #[case("qlx_identityIpPermission", "5")]
+    #[case("qlx_matchArchivePath", "")]
+    #[case("qlx_matchArchiveMaxFiles", "1000")]
+    #[case("qlx_matchArchiveMaxAgeDays", "0")]
+    #[case("qlx_eventPublisherAddress", "")]
+    #[case("qlx_eventPublisherPassword", "")]
+    #[case("qlx_adminApiAddress", "")]
+    #[case("qlx_adminApiToken", "")]
+    #[case("qlx_metricsAddress", "")]
     #[cfg_attr(miri, ignore)]
     #[serial]
     fn initialize_cvars_initializes_cvar_if_not_set(
@@ -4076,13 +4214,20 @@ fn late_init(module: &Bound<'_, PyModule>) -> PyResult<()> {
             Err(PyEnvironmentError::new_err("no main engine found"))
         },
         |main_engine| {
-            let database_cvar = main_engine.find_cvar("qlx_database");
-            if database_cvar.is_some_and(|value| value.get_string().to_lowercase() == "redis") {
-                let redis_database_class = module.py().get_type::<Redis>();
+            let database_class =
+                main_engine.find_cvar("qlx_database").and_then(|value| {
+                    match value.get_string().to_lowercase().as_str() {
+                        "redis" => Some(module.py().get_type::<Redis>()),
+                        "sqlite" => Some(module.py().get_type::<Sqlite>()),
+                        "inmemory" => Some(module.py().get_type::<InMemory>()),
+                        _ => None,
+                    }
+                });
+            if let Some(database_class) = database_class {
                 module
                     .py()
                     .get_type::<Plugin>()
-                    .setattr(intern!(module.py(), "database"), &redis_database_class)?;
+                    .setattr(intern!(module.py(), "database"), &database_class)?;
             }
 
             let sys_module = module.py().import(intern!(module.py(), "sys"))?;
@@ -4153,8 +4298,24 @@ fn late_init(module: &Bound<'_, PyModule>) -> PyResult<()> {
 
             load_preset_plugins(module.py())?;
 
+            let bans_enable_cvar = main_engine.find_cvar("qlx_bans");
+            if bans_enable_cvar.is_some_and(|value| value.get_string() != "0") {
+                initialize_bans(module.py())?;
+            }
+
+            let identity_history_cvar = main_engine.find_cvar("qlx_identityHistory");
+            if identity_history_cvar.is_some_and(|value| value.get_string() != "0") {
+                initialize_identities(module.py())?;
+            }
+
+            initialize_event_publisher();
+            initialize_admin_api();
+            initialize_metrics();
+
             let stats_enable_cvar = main_engine.find_cvar("zmq_stats_enable");
             if stats_enable_cvar.is_some_and(|value| value.get_string() != "0") {
+                initialize_match_archive();
+
                 let stats_value = Bound::new(module.py(), StatsListener::py_new()?)?;
                 module.setattr(intern!(module.py(), "_stats"), &stats_value)?;
 
@@ -4242,6 +4403,7 @@ fn pyshinqlx_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     register_handlers_module(m)?;
     register_events_module(m)?;
     register_zmq_module(m)?;
+    register_stats_module(m)?;
     register_plugin_module(m)?;
     register_database_submodule(m)?;
 
@@ -4738,6 +4900,10 @@ fn register_events_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<NewGameDispatcher>()?;
     m.add_class::<KillDispatcher>()?;
     m.add_class::<DeathDispatcher>()?;
+    m.add_class::<MedalDispatcher>()?;
+    m.add_class::<PlayerStatsDispatcher>()?;
+    m.add_class::<StatsPlayerConnectDispatcher>()?;
+    m.add_class::<StatsPlayerDisconnectDispatcher>()?;
     m.add_class::<UserinfoDispatcher>()?;
     m.add_class::<KamikazeUseDispatcher>()?;
     m.add_class::<KamikazeExplodeDispatcher>()?;
@@ -4776,6 +4942,10 @@ fn register_events_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     event_dispatchers.add_dispatcher(&m.py().get_type::<NewGameDispatcher>())?;
     event_dispatchers.add_dispatcher(&m.py().get_type::<KillDispatcher>())?;
     event_dispatchers.add_dispatcher(&m.py().get_type::<DeathDispatcher>())?;
+    event_dispatchers.add_dispatcher(&m.py().get_type::<MedalDispatcher>())?;
+    event_dispatchers.add_dispatcher(&m.py().get_type::<PlayerStatsDispatcher>())?;
+    event_dispatchers.add_dispatcher(&m.py().get_type::<StatsPlayerConnectDispatcher>())?;
+    event_dispatchers.add_dispatcher(&m.py().get_type::<StatsPlayerDisconnectDispatcher>())?;
     event_dispatchers.add_dispatcher(&m.py().get_type::<UserinfoDispatcher>())?;
     event_dispatchers.add_dispatcher(&m.py().get_type::<DamageDispatcher>())?;
     EVENT_DISPATCHERS.store(Some(event_dispatchers.unbind().into()));
@@ -4796,6 +4966,26 @@ fn register_zmq_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     Ok(())
 }
 
+fn register_stats_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ql_stats_schema::PlayerSummary>()?;
+    m.add_class::<ql_stats_schema::MatchStarted>()?;
+    m.add_class::<ql_stats_schema::MatchReport>()?;
+    m.add_class::<ql_stats_schema::RoundOver>()?;
+    m.add_class::<ql_stats_schema::Coordinates>()?;
+    m.add_class::<ql_stats_schema::KillParticipant>()?;
+    m.add_class::<ql_stats_schema::PlayerDeath>()?;
+    m.add_class::<ql_stats_schema::PlayerMedal>()?;
+    m.add_class::<ql_stats_schema::PlayerConnection>()?;
+    m.add_class::<ql_stats_schema::TeamSwitchPlayer>()?;
+    m.add_class::<ql_stats_schema::TeamSwitch>()?;
+    m.add_class::<ql_stats_schema::DamageStats>()?;
+    m.add_class::<ql_stats_schema::WeaponStats>()?;
+    m.add_class::<ql_stats_schema::PlayerStatsReport>()?;
+    m.add_function(wrap_pyfunction!(pyshinqlx_parse_stats, m)?)?;
+
+    Ok(())
+}
+
 fn register_plugin_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.py()
         .get_type::<Plugin>()
@@ -4813,6 +5003,24 @@ fn register_database_submodule(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     database_module.add_class::<AbstractDatabase>()?;
     database_module.add_class::<Redis>()?;
+    database_module.add_class::<RedisCommand>()?;
+    database_module.add("RedisError", m.py().get_type::<RedisError>())?;
+    database_module.add_class::<Sqlite>()?;
+    database_module.add("SqliteError", m.py().get_type::<SqliteError>())?;
+    database_module.add_class::<InMemory>()?;
+    database_module.add("InMemoryError", m.py().get_type::<InMemoryError>())?;
+    database_module.add_function(wrap_pyfunction!(
+        pyshinqlx_register_migration,
+        &database_module
+    )?)?;
+    database_module.add_function(wrap_pyfunction!(
+        pyshinqlx_export_database,
+        &database_module
+    )?)?;
+    database_module.add_function(wrap_pyfunction!(
+        pyshinqlx_import_database,
+        &database_module
+    )?)?;
     m.add_submodule(&database_module)?;
 
     m.py()
@@ -57,7 +57,7 @@ pub(crate) struct Plugin {
 #[pymethods]
 impl Plugin {
     #[new]
-    fn py_new(py: Python<'_>) -> Self {
+    pub(crate) fn py_new(py: Python<'_>) -> Self {
         Self {
             hooks: vec![].into(),
             commands: vec![].into(),
@@ -1390,7 +1390,8 @@ mod plugin_tests {
             python::{
                 BLUE_TEAM_CHAT_CHANNEL, CHAT_CHANNEL, COMMANDS, CONSOLE_CHANNEL, EVENT_DISPATCHERS,
                 RED_TEAM_CHAT_CHANNEL, commands::CommandPriorities,
-                events::EventDispatcherManagerMethods, prelude::*, pyshinqlx_test_support::*,
+                event_publisher::published_topics, events::EventDispatcherManagerMethods,
+                prelude::*, pyshinqlx_test_support::*,
             },
         },
         hooks::mock_hooks::{
@@ -5635,6 +5636,40 @@ mod plugin_tests {
             });
     }
 
+    #[rstest]
+    #[case::tempban("tempban")]
+    #[case::ban("ban")]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn bans_are_published(_pyshinqlx_setup: (), #[case] command: &'static str) {
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_steam_id()
+                    .return_const(1234567890u64);
+                mock_client.expect_get_name().return_const("Banned Player");
+                mock_client
+            });
+
+        MockEngineBuilder::default()
+            .with_execute_console_command(format!("{command} 2"), 1)
+            .run(|| {
+                let topics = published_topics(|| {
+                    let result = Python::attach(|py| {
+                        py.get_type::<Plugin>()
+                            .call_method1(command, (2i32,))
+                            .map(|_| ())
+                    });
+                    assert!(result.is_ok());
+                });
+                assert_eq!(topics, vec![command]);
+            });
+    }
+
     #[rstest]
     #[cfg_attr(miri, ignore)]
     #[serial]
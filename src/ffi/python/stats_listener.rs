@@ -1,13 +1,14 @@
 use core::{
     hint::cold_path,
-    sync::atomic::{AtomicBool, Ordering},
+    sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering},
+    time::Duration,
 };
-use std::sync::LazyLock;
+use std::{sync::LazyLock, time::Instant};
 
 use arzmq::{
     prelude::{
-        ContextBuilder, Receiver, RecvFlags, SocketBuilder, SubscribeBuilder, SubscribeSocket,
-        ZmqError, ZmqResult,
+        ContextBuilder, MonitorFlags, MonitorReceiver, MonitorSocket, MonitorSocketEvent, Receiver,
+        RecvFlags, Socket, SocketBuilder, SubscribeBuilder, SubscribeSocket, ZmqError, ZmqResult,
     },
     security::SecurityMechanism,
     socket::PollEvents,
@@ -16,14 +17,18 @@ use pyo3::{
     exceptions::{PyEnvironmentError, PyIOError},
     intern,
 };
+use ql_stats_schema::{KillParticipant, PlayerDeath, SchemaError, StatsMessage};
 use rayon::prelude::*;
 use serde_json::{Value, from_str};
 use tap::TapFallible;
 
-use super::{EVENT_DISPATCHERS, log_exception, prelude::*};
-use crate::{MAIN_ENGINE, quake_live_engine::FindCVar};
+use super::{
+    EVENT_DISPATCHERS, log_exception, match_archive::archive_stats_message,
+    metrics::record_stats_message, prelude::*, stats_schema::typed_stats_data,
+};
+use crate::{MAIN_ENGINE, prelude::*, quake_live_engine::FindCVar};
 
-fn to_py_json_data<'py>(py: Python<'py>, json_str: &str) -> PyResult<Bound<'py, PyAny>> {
+pub(crate) fn to_py_json_data<'py>(py: Python<'py>, json_str: &str) -> PyResult<Bound<'py, PyAny>> {
     py.import(intern!(py, "json"))
         .and_then(|json_module| json_module.call_method1(intern!(py, "loads"), (json_str,)))
 }
@@ -176,7 +181,7 @@ fn handle_player_death_event(py: Python<'_>, stats: Value) -> PyResult<()> {
         )
     };
 
-    dispatch_player_death_events(
+    dispatch_player_death_event(
         py,
         opt_victim_steam_id,
         victim_name,
@@ -236,7 +241,7 @@ fn player_by_name(py: Python<'_>, name: &str) -> Option<Player> {
     })
 }
 
-fn dispatch_player_death_events(
+fn dispatch_player_death_event(
     py: Python<'_>,
     opt_victim_steam_id: Option<i64>,
     victim_name: &str,
@@ -275,46 +280,58 @@ fn dispatch_player_death_events(
                 ))
             },
             |death_dispatcher| {
-                thread_safe_dispatcher.call1((
-                    death_dispatcher,
-                    victim.to_owned(),
-                    opt_killer.to_owned(),
-                    json_data.to_owned(),
-                ))?;
+                thread_safe_dispatcher.call1((death_dispatcher, victim, opt_killer, json_data))?;
                 Ok(())
             },
-        )?;
+        )
+}
 
-    if opt_killer.is_some() {
-        EVENT_DISPATCHERS
-            .load()
-            .as_ref()
-            .and_then(|event_dispatchers| {
-                event_dispatchers
-                    .bind(py)
-                    .get_item(intern!(py, "kill"))
-                    .ok()
-            })
-            .map_or(
-                {
-                    cold_path();
-                    Err(PyEnvironmentError::new_err(
-                        "could not get access to kill dispatcher",
-                    ))
-                },
-                |kill_dispatcher| {
-                    thread_safe_dispatcher.call1((
-                        kill_dispatcher,
-                        victim,
-                        opt_killer,
-                        json_data,
-                    ))?;
-                    Ok(())
-                },
-            )?;
+/// The player on the server a ``VICTIM`` or ``KILLER`` of a stats event refers to, looked up by
+/// name for bots and other players without a steam id.
+fn player_from_participant(py: Python<'_>, participant: &KillParticipant) -> Option<Player> {
+    if participant.steam_id > 0 {
+        player_by_steam_id(py, &participant.steam_id)
+    } else {
+        player_by_name(py, &participant.name)
     }
+}
 
-    Ok(())
+fn handle_player_kill_event(py: Python<'_>, data: PlayerDeath) -> PyResult<()> {
+    let Some(victim) = player_from_participant(py, &data.victim) else {
+        return Ok(());
+    };
+    let Some(killer) = data
+        .killer
+        .as_ref()
+        .and_then(|killer| player_from_participant(py, killer))
+    else {
+        return Ok(());
+    };
+
+    let kill_data = Bound::new(py, data)?;
+    EVENT_DISPATCHERS
+        .load()
+        .as_ref()
+        .and_then(|event_dispatchers| {
+            event_dispatchers
+                .bind(py)
+                .get_item(intern!(py, "kill"))
+                .ok()
+        })
+        .map_or(
+            {
+                cold_path();
+                Err(PyEnvironmentError::new_err(
+                    "could not get access to kill dispatcher",
+                ))
+            },
+            |kill_dispatcher| {
+                dispatch_thread_safe(py).and_then(|thread_safe_dispatcher| {
+                    thread_safe_dispatcher.call1((kill_dispatcher, victim, killer, kill_data))
+                })?;
+                Ok(())
+            },
+        )
 }
 
 fn handle_team_switch_event(py: Python<'_>, stats: Value) -> PyResult<()> {
@@ -407,8 +424,175 @@ def thread_safe_team_switch_dispatch(dispatcher, player, old_team, new_team):
     Ok(())
 }
 
+fn handle_player_event(py: Python<'_>, stats: StatsMessage) -> PyResult<()> {
+    let (dispatcher_name, steam_id, name) = match &stats {
+        StatsMessage::PlayerMedal(data) => ("medal", data.steam_id, &data.name),
+        StatsMessage::PlayerStats(data) => ("player_stats", data.steam_id, &data.name),
+        StatsMessage::PlayerConnect(data) => ("stats_player_connect", data.steam_id, &data.name),
+        StatsMessage::PlayerDisconnect(data) => {
+            ("stats_player_disconnect", data.steam_id, &data.name)
+        }
+        _ => return Ok(()),
+    };
+
+    let Some(player) = (if steam_id > 0 {
+        player_by_steam_id(py, &steam_id)
+    } else {
+        player_by_name(py, name)
+    }) else {
+        return Ok(());
+    };
+
+    dispatch_player_event(py, player, dispatcher_name, stats)
+}
+
+fn dispatch_player_event(
+    py: Python<'_>,
+    player: Player,
+    dispatcher_name: &str,
+    stats: StatsMessage,
+) -> PyResult<()> {
+    let data = typed_stats_data(py, stats)?;
+    EVENT_DISPATCHERS
+        .load()
+        .as_ref()
+        .and_then(|event_dispatchers| event_dispatchers.bind(py).get_item(dispatcher_name).ok())
+        .map_or(
+            {
+                cold_path();
+                Err(PyEnvironmentError::new_err(format!(
+                    "could not get access to {dispatcher_name} dispatcher"
+                )))
+            },
+            |dispatcher| {
+                dispatch_thread_safe(py).and_then(|thread_safe_dispatcher| {
+                    thread_safe_dispatcher.call1((dispatcher, player, data))
+                })?;
+                Ok(())
+            },
+        )
+}
+
 static IN_PROGRESS: LazyLock<AtomicBool> = LazyLock::new(AtomicBool::default);
 
+/// How long to wait before the first attempt to reconnect to the stats socket.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// The longest time to wait between two attempts to reconnect to the stats socket.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// The state of the connection of a :class:`StatsListener` to the stats socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum ConnectionState {
+    Disconnected = 0,
+    Connecting = 1,
+    Connected = 2,
+    HandshakeFailed = 3,
+}
+
+impl From<u8> for ConnectionState {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Connecting,
+            2 => Self::Connected,
+            3 => Self::HandshakeFailed,
+            _ => Self::Disconnected,
+        }
+    }
+}
+
+impl ConnectionState {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Disconnected => "disconnected",
+            Self::Connecting => "connecting",
+            Self::Connected => "connected",
+            Self::HandshakeFailed => "handshake_failed",
+        }
+    }
+}
+
+/// Connection state and message counters of a :class:`StatsListener`.
+#[derive(Debug, Default)]
+pub(crate) struct StatsConnection {
+    state: AtomicU8,
+    messages_received: AtomicU64,
+    reconnects: AtomicU64,
+    handshake_failures: AtomicU64,
+}
+
+impl StatsConnection {
+    fn state(&self) -> ConnectionState {
+        self.state.load(Ordering::Acquire).into()
+    }
+
+    fn set_state(&self, state: ConnectionState) {
+        self.state.store(state as u8, Ordering::Release);
+    }
+}
+
+/// Doubles the delay between reconnection attempts up to [`MAX_RECONNECT_DELAY`], until a
+/// connection succeeds.
+#[derive(Debug)]
+struct ReconnectBackoff {
+    next_delay: Duration,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self {
+            next_delay: INITIAL_RECONNECT_DELAY,
+        }
+    }
+}
+
+impl ReconnectBackoff {
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.next_delay;
+        self.next_delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        delay
+    }
+
+    fn reset(&mut self) {
+        self.next_delay = INITIAL_RECONNECT_DELAY;
+    }
+}
+
+#[cfg(test)]
+mod reconnect_backoff_tests {
+    use core::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{INITIAL_RECONNECT_DELAY, MAX_RECONNECT_DELAY, ReconnectBackoff};
+
+    #[test]
+    fn delays_double_up_to_the_maximum() {
+        let mut backoff = ReconnectBackoff::default();
+
+        let delays = (0..8).map(|_| backoff.next_delay()).collect::<Vec<_>>();
+
+        assert_eq!(
+            delays,
+            [1, 2, 4, 8, 16, 32, 60, 60]
+                .map(Duration::from_secs)
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn reset_starts_over_with_the_initial_delay() {
+        let mut backoff = ReconnectBackoff::default();
+        backoff.next_delay();
+        backoff.next_delay();
+
+        backoff.reset();
+
+        assert_eq!(backoff.next_delay(), INITIAL_RECONNECT_DELAY);
+        assert!(backoff.next_delay() < MAX_RECONNECT_DELAY);
+    }
+}
+
 /// Subscribes to the ZMQ stats protocol and calls the stats event dispatcher when
 /// we get stats from it.
 #[pyclass(module = "_zmq", name = "StatsListener", frozen, eq)]
@@ -419,6 +603,7 @@ pub(crate) struct StatsListener {
     pub(crate) address: String,
     #[pyo3(name = "password", get)]
     password: String,
+    connection: StatsConnection,
 }
 
 impl PartialEq for StatsListener {
@@ -446,6 +631,7 @@ impl StatsListener {
                 done: true.into(),
                 address: Default::default(),
                 password: Default::default(),
+                connection: Default::default(),
             });
         }
 
@@ -477,6 +663,7 @@ impl StatsListener {
             done: false.into(),
             address,
             password,
+            connection: Default::default(),
         })
     }
 
@@ -489,6 +676,43 @@ impl StatsListener {
         slf.stop()
     }
 
+    /// The state of the connection to the stats socket: ``disconnected``, ``connecting``,
+    /// ``connected``, or ``handshake_failed``.
+    #[getter(state)]
+    fn get_state(slf: &Bound<'_, Self>) -> &'static str {
+        slf.get_state().as_str()
+    }
+
+    /// Whether the listener is connected to the stats socket.
+    #[getter(connected)]
+    fn get_connected(slf: &Bound<'_, Self>) -> bool {
+        slf.get_state() == ConnectionState::Connected
+    }
+
+    /// How many messages the listener received from the stats socket.
+    #[getter(messages_received)]
+    fn get_messages_received(slf: &Bound<'_, Self>) -> u64 {
+        slf.get()
+            .connection
+            .messages_received
+            .load(Ordering::Relaxed)
+    }
+
+    /// How many times the listener reconnected to the stats socket.
+    #[getter(reconnects)]
+    fn get_reconnects(slf: &Bound<'_, Self>) -> u64 {
+        slf.get().connection.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// How many times the handshake with the stats socket failed, e.g. due to a wrong password.
+    #[getter(handshake_failures)]
+    fn get_handshake_failures(slf: &Bound<'_, Self>) -> u64 {
+        slf.get()
+            .connection
+            .handshake_failures
+            .load(Ordering::Relaxed)
+    }
+
     /// Receives until 'self.done' is set to True.
     pub(crate) fn keep_receiving(slf: &Bound<'_, Self>) -> PyResult<()> {
         slf.keep_receiving()
@@ -503,6 +727,7 @@ impl StatsListener {
 pub(crate) trait StatsListenerMethods<'py> {
     fn get_done(&self) -> bool;
     fn stop(&self);
+    fn get_state(&self) -> ConnectionState;
     fn keep_receiving(&self) -> PyResult<()>;
     fn _poll_zmq(&self) -> PyResult<()>;
 }
@@ -516,6 +741,10 @@ impl<'py> StatsListenerMethods<'py> for Bound<'py, StatsListener> {
         self.get().done.store(true, Ordering::Release);
     }
 
+    fn get_state(&self) -> ConnectionState {
+        self.get().connection.state()
+    }
+
     fn keep_receiving(&self) -> PyResult<()> {
         PyModule::from_code(
             self.py(),
@@ -534,70 +763,216 @@ def run_zmq_thread(poller):
     }
 
     fn _poll_zmq(&self) -> PyResult<()> {
-        let socket = get_zmq_socket(&self.get().address, &self.get().password).map_err(
-            |err: ZmqError| {
+        let listener = self.get();
+        let subscriber = self
+            .py()
+            .detach(|| MonitoredSubscriber::new(&listener.address, &listener.password))
+            .map_err(|err: ZmqError| {
                 let error_msg = format!("zmq error: {err:?}");
                 PyIOError::new_err(error_msg)
-            },
-        )?;
-
-        loop {
-            if !self.py().detach(|| {
-                socket
-                    .poll(PollEvents::POLL_IN, 125)
-                    .is_ok_and(|value| value == PollEvents::POLL_IN)
-            }) {
-                continue;
+            })?;
+        listener.connection.set_state(ConnectionState::Connecting);
+
+        let mut backoff = ReconnectBackoff::default();
+        let mut reconnect_at = None;
+        while !self.get_done() {
+            while let Some(event) = self.py().detach(|| subscriber.next_monitor_event()) {
+                if let Some(delay) = handle_monitor_event(listener, &event, &mut backoff) {
+                    let _ = self.py().detach(|| subscriber.disconnect());
+                    reconnect_at = Some(Instant::now() + delay);
+                }
             }
 
-            let _ = self
-                .py()
-                .detach(|| socket.recv_msg(RecvFlags::DONT_WAIT))
-                .tap_ok(|zmq_msg| {
-                    handle_zmq_msg(self.py(), &zmq_msg.to_string());
+            if reconnect_at.is_some_and(|deadline| Instant::now() >= deadline) {
+                reconnect_at = None;
+                listener
+                    .connection
+                    .reconnects
+                    .fetch_add(1, Ordering::Relaxed);
+                listener.connection.set_state(ConnectionState::Connecting);
+                let _ = self.py().detach(|| subscriber.connect()).tap_err(|err| {
+                    cold_path();
+                    error!(target: "shinqlx", "error reconnecting to {}: {err:?}", listener.address);
                 });
+            }
+
+            let Some(zmq_msg) = self.py().detach(|| subscriber.recv_msg()) else {
+                continue;
+            };
+            listener
+                .connection
+                .messages_received
+                .fetch_add(1, Ordering::Relaxed);
+            handle_zmq_msg(self.py(), &zmq_msg);
         }
+
+        let _ = self.py().detach(|| subscriber.disconnect());
+        listener.connection.set_state(ConnectionState::Disconnected);
+        Ok(())
     }
 }
 
-fn get_zmq_socket(address: &str, password: &str) -> ZmqResult<SubscribeSocket> {
-    let context = ContextBuilder::default()
-        .blocky(false)
-        .max_sockets(10)
-        .io_threads(1)
-        .build()?;
-
-    let socket_builder = SocketBuilder::default()
-        .security_mechanism(SecurityMechanism::Plain {
-            username: "stats".into(),
-            password: password.into(),
-        })
-        .receive_timeout(0)
-        .receive_highwater_mark(0)
-        .send_timeout(0)
-        .receive_highwater_mark(0)
-        .zap_domain("stats");
+/// Updates the connection state of *listener* for a monitor *event*. Returns how long to wait
+/// before reconnecting, if the connection needs to be re-established.
+fn handle_monitor_event(
+    listener: &StatsListener,
+    event: &MonitorSocketEvent,
+    backoff: &mut ReconnectBackoff,
+) -> Option<Duration> {
+    match event {
+        MonitorSocketEvent::HandshakeSucceeded => {
+            if listener.connection.state() != ConnectionState::Connected {
+                debug!(target: "shinqlx", "Connected to the stats socket at {}.", listener.address);
+            }
+            listener.connection.set_state(ConnectionState::Connected);
+            backoff.reset();
+            None
+        }
+        MonitorSocketEvent::HandshakeFailedAuth(_)
+        | MonitorSocketEvent::HandshakeFailedProtocol(_)
+        | MonitorSocketEvent::HandshakeFailedNoDetail(_) => {
+            cold_path();
+            listener
+                .connection
+                .handshake_failures
+                .fetch_add(1, Ordering::Relaxed);
+            listener
+                .connection
+                .set_state(ConnectionState::HandshakeFailed);
+            let delay = backoff.next_delay();
+            error!(
+                target: "shinqlx",
+                "Handshake with the stats socket at {} failed: {event:?}. Check zmq_stats_password. Retrying in {}s.",
+                listener.address,
+                delay.as_secs()
+            );
+            Some(delay)
+        }
+        MonitorSocketEvent::Disconnected | MonitorSocketEvent::Closed => {
+            cold_path();
+            listener.connection.set_state(ConnectionState::Disconnected);
+            let delay = backoff.next_delay();
+            warn!(
+                target: "shinqlx",
+                "Lost the connection to the stats socket at {}. Reconnecting in {}s.",
+                listener.address,
+                delay.as_secs()
+            );
+            Some(delay)
+        }
+        MonitorSocketEvent::MonitorStopped => {
+            cold_path();
+            error!(target: "shinqlx", "Stopped monitoring the stats socket at {}.", listener.address);
+            None
+        }
+        _ => None,
+    }
+}
+
+/// A subscriber to the stats socket, with a monitor socket reporting handshakes and
+/// disconnects.
+struct MonitoredSubscriber {
+    address: String,
+    subscriber: SubscribeSocket,
+    monitor: MonitorSocket,
+}
 
-    let config = SubscribeBuilder::default()
-        .socket_builder(socket_builder)
-        .subscribe("");
+unsafe impl Send for MonitoredSubscriber {}
+unsafe impl Sync for MonitoredSubscriber {}
+
+impl MonitoredSubscriber {
+    fn new(address: &str, password: &str) -> ZmqResult<Self> {
+        let context = ContextBuilder::default()
+            .blocky(false)
+            .max_sockets(10)
+            .io_threads(1)
+            .build()?;
+
+        let subscriber = Socket::from_context(&context)?;
+        let monitor = subscriber.monitor(
+            MonitorFlags::HandshakeSucceeded
+                | MonitorFlags::HandshakeFailedAuth
+                | MonitorFlags::HandshakeFailedProtocol
+                | MonitorFlags::HandshakeFailedNoDetail
+                | MonitorFlags::MonitorStopped
+                | MonitorFlags::Disconnected
+                | MonitorFlags::Closed,
+        )?;
+
+        let socket_builder = SocketBuilder::default()
+            .security_mechanism(SecurityMechanism::Plain {
+                username: "stats".into(),
+                password: password.into(),
+            })
+            .receive_timeout(0)
+            .receive_highwater_mark(0)
+            .send_timeout(0)
+            .receive_highwater_mark(0)
+            .zap_domain("stats");
+
+        SubscribeBuilder::default()
+            .socket_builder(socket_builder)
+            .subscribe("")
+            .apply(&subscriber)?;
+
+        let monitored_subscriber = Self {
+            address: address.to_string(),
+            subscriber,
+            monitor,
+        };
+        monitored_subscriber.connect()?;
+
+        Ok(monitored_subscriber)
+    }
+
+    fn connect(&self) -> ZmqResult<()> {
+        self.subscriber.connect(&self.address)
+    }
 
-    let socket = config.build_from_context(&context)?;
+    fn disconnect(&self) -> ZmqResult<()> {
+        self.subscriber.disconnect(&self.address)
+    }
 
-    socket.connect(address)?;
+    fn next_monitor_event(&self) -> Option<MonitorSocketEvent> {
+        if !self
+            .monitor
+            .poll(PollEvents::POLL_IN, 0)
+            .is_ok_and(|value| value == PollEvents::POLL_IN)
+        {
+            return None;
+        }
+        self.monitor.recv_monitor_event().ok()
+    }
 
-    Ok(socket)
+    /// Waits up to 125 milliseconds for a message from the stats socket.
+    fn recv_msg(&self) -> Option<String> {
+        if !self
+            .subscriber
+            .poll(PollEvents::POLL_IN, 125)
+            .is_ok_and(|value| value == PollEvents::POLL_IN)
+        {
+            return None;
+        }
+        self.subscriber
+            .recv_msg(RecvFlags::DONT_WAIT)
+            .ok()
+            .map(|zmq_msg| zmq_msg.to_string())
+    }
 }
 
 #[cfg(test)]
 mod stats_listener_tests {
-    use core::borrow::BorrowMut;
+    use core::{borrow::BorrowMut, time::Duration};
 
+    use arzmq::prelude::MonitorSocketEvent;
     use pretty_assertions::assert_eq;
     use pyo3::{exceptions::PyEnvironmentError, prelude::*};
     use rstest::*;
 
-    use super::{StatsListener, StatsListenerMethods};
+    use super::{
+        ConnectionState, ReconnectBackoff, StatsListener, StatsListenerMethods,
+        handle_monitor_event,
+    };
     use crate::{
         ffi::{
             c::prelude::{CVar, CVarBuilder, cvar_t},
@@ -640,7 +1015,8 @@ mod stats_listener_tests {
                     StatsListener {
                         done: true.into(),
                         address: "".into(),
-                        password: "".into()
+                        password: "".into(),
+                        connection: Default::default(),
                     }
                 );
             });
@@ -699,7 +1075,8 @@ mod stats_listener_tests {
                     StatsListener {
                         done: false.into(),
                         address: "tcp://127.0.0.1:27960".into(),
-                        password: "".into()
+                        password: "".into(),
+                        connection: Default::default(),
                     }
                 );
             });
@@ -757,7 +1134,8 @@ mod stats_listener_tests {
                     StatsListener {
                         done: false.into(),
                         address: "tcp://192.168.0.1:28960".into(),
-                        password: "p4ssw0rd".into()
+                        password: "p4ssw0rd".into(),
+                        connection: Default::default(),
                     }
                 );
             });
@@ -773,6 +1151,7 @@ mod stats_listener_tests {
                     done: false.into(),
                     address: "".into(),
                     password: "".into(),
+                    connection: Default::default(),
                 },
             )
             .expect("this should not happen");
@@ -782,6 +1161,57 @@ mod stats_listener_tests {
             assert_eq!(listener.get_done(), true);
         });
     }
+
+    #[test]
+    fn new_listener_is_disconnected() {
+        let listener = StatsListener {
+            done: false.into(),
+            address: "".into(),
+            password: "".into(),
+            connection: Default::default(),
+        };
+
+        assert_eq!(listener.connection.state(), ConnectionState::Disconnected);
+        assert_eq!(ConnectionState::Disconnected.as_str(), "disconnected");
+    }
+
+    #[test]
+    fn disconnects_back_off_until_the_next_handshake() {
+        let listener = StatsListener {
+            done: false.into(),
+            address: "tcp://127.0.0.1:27960".into(),
+            password: "".into(),
+            connection: Default::default(),
+        };
+        let mut backoff = ReconnectBackoff::default();
+
+        let delays = (0..3)
+            .map(|_| {
+                handle_monitor_event(&listener, &MonitorSocketEvent::Disconnected, &mut backoff)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            [1, 2, 4]
+                .map(|secs| Some(Duration::from_secs(secs)))
+                .to_vec()
+        );
+        assert_eq!(listener.connection.state(), ConnectionState::Disconnected);
+
+        assert_eq!(
+            handle_monitor_event(
+                &listener,
+                &MonitorSocketEvent::HandshakeSucceeded,
+                &mut backoff
+            ),
+            None
+        );
+        assert_eq!(listener.connection.state(), ConnectionState::Connected);
+        assert_eq!(
+            handle_monitor_event(&listener, &MonitorSocketEvent::Closed, &mut backoff),
+            Some(Duration::from_secs(1))
+        );
+    }
 }
 
 fn try_handle_zmq_msg(py: Python<'_>, zmq_msg: &str) -> PyResult<()> {
@@ -792,6 +1222,18 @@ fn try_handle_zmq_msg(py: Python<'_>, zmq_msg: &str) -> PyResult<()> {
         })
     })?;
 
+    let typed_stats = StatsMessage::from_value_ref(&stats)
+        .tap_err(|err| {
+            if let SchemaError::InvalidData { .. } = err {
+                cold_path();
+                warn!(target: "shinqlx", "{err}");
+            }
+        })
+        .ok();
+
+    record_stats_message(stats["TYPE"].as_str().unwrap_or("UNKNOWN"));
+    py.detach(|| archive_stats_message(&stats));
+
     dispatch_stats_event(py, &stats.to_string())?;
     match stats["TYPE"].as_str() {
         Some("MATCH_STARTED") => {
@@ -806,8 +1248,15 @@ fn try_handle_zmq_msg(py: Python<'_>, zmq_msg: &str) -> PyResult<()> {
             IN_PROGRESS.store(false, Ordering::Release);
             Ok(())
         }
+        Some("PLAYER_KILL") => match typed_stats {
+            Some(StatsMessage::PlayerKill(data)) => handle_player_kill_event(py, data),
+            _ => Ok(()),
+        },
         Some("PLAYER_DEATH") => handle_player_death_event(py, stats),
         Some("PLAYER_SWITCHTEAM") => handle_team_switch_event(py, stats),
+        Some("PLAYER_MEDAL" | "PLAYER_STATS" | "PLAYER_CONNECT" | "PLAYER_DISCONNECT") => {
+            typed_stats.map_or(Ok(()), |typed_stats| handle_player_event(py, typed_stats))
+        }
         _ => Ok(()),
     }
 }
@@ -824,9 +1273,11 @@ mod handle_zmq_msg_tests {
 
     use mockall::predicate;
     use pyo3::{
+        PyTypeInfo,
         exceptions::{PyAssertionError, PyEnvironmentError, PyIOError},
         intern,
         prelude::*,
+        types::{PyString, PyType},
     };
     use rstest::*;
 
@@ -843,11 +1294,14 @@ mod handle_zmq_msg_tests {
                 commands::CommandPriorities,
                 events::{
                     DeathDispatcher, EventDispatcherManager, EventDispatcherManagerMethods,
-                    GameEndDispatcher, GameStartDispatcher, KillDispatcher, RoundEndDispatcher,
-                    StatsDispatcher, TeamSwitchDispatcher,
+                    GameEndDispatcher, GameStartDispatcher, KillDispatcher, MedalDispatcher,
+                    PlayerStatsDispatcher, RoundEndDispatcher, StatsDispatcher,
+                    StatsPlayerConnectDispatcher, StatsPlayerDisconnectDispatcher,
+                    TeamSwitchDispatcher,
                 },
                 pyshinqlx_setup_fixture::*,
                 pyshinqlx_test_support::{run_all_frame_tasks, *},
+                stats_schema::pyshinqlx_parse_stats,
             },
         },
         prelude::*,
@@ -2085,8 +2539,8 @@ mod handle_zmq_msg_tests {
                         .expect("could not add hook to death dispatcher");
                     event_dispatcher
                         .get_item(intern!(py, "kill"))
-                        .and_then(|death_dispatcher| {
-                            death_dispatcher.call_method1(
+                        .and_then(|kill_dispatcher| {
+                            kill_dispatcher.call_method1(
                                 intern!(py, "add_hook"),
                                 (
                                     "asdf",
@@ -2131,17 +2585,8 @@ mod handle_zmq_msg_tests {
                     );
                     assert!(
                         capturing_hook
-                            .call_method1(
-                                intern!(py, "assert_called_with"),
-                                (
-                                    "_",
-                                    "_",
-                                    expected_json_data
-                                        .get_item(intern!(py, "DATA"))
-                                        .expect("this should not happen"),
-                                )
-                            )
-                            .is_ok()
+                            .call_method1(intern!(py, "assert_called_with"), ("_", "_", "_"))
+                            .is_err()
                     );
                 });
             });
@@ -2304,8 +2749,8 @@ mod handle_zmq_msg_tests {
                         .expect("could not add hook to death dispatcher");
                     event_dispatcher
                         .get_item(intern!(py, "kill"))
-                        .and_then(|death_dispatcher| {
-                            death_dispatcher.call_method1(
+                        .and_then(|kill_dispatcher| {
+                            kill_dispatcher.call_method1(
                                 intern!(py, "add_hook"),
                                 (
                                     "asdf",
@@ -2350,17 +2795,8 @@ mod handle_zmq_msg_tests {
                     );
                     assert!(
                         capturing_hook
-                            .call_method1(
-                                intern!(py, "assert_called_with"),
-                                (
-                                    "_",
-                                    "_",
-                                    expected_json_data
-                                        .get_item(intern!(py, "DATA"))
-                                        .expect("this should not happen"),
-                                )
-                            )
-                            .is_ok()
+                            .call_method1(intern!(py, "assert_called_with"), ("_", "_", "_"))
+                            .is_err()
                     );
                 });
             });
@@ -2369,8 +2805,13 @@ mod handle_zmq_msg_tests {
     #[rstest]
     #[cfg_attr(miri, ignore)]
     #[serial]
-    fn try_handle_player_death_msg_from_other_player_with_no_dispatcher(_pyshinqlx_setup: ()) {
-        let player_death_data = r#"{"DATA": {"KILLER": {"NAME": "player2", "STEAM_ID": "5678"}, "MOD": "HURT", "VICTIM": {"NAME": "player1", "STEAM_ID": "1234"}}, "TYPE": "PLAYER_DEATH"}"#;
+    fn try_handle_player_kill_msg_with_steam_id(_pyshinqlx_setup: ()) {
+        let player_kill_data = r#"{"DATA": {"KILLER": {"AIRBORNE": false, "AMMO": 10, "ARMOR": 50, "BOT": false, "BOT_SKILL": null, "HEALTH": 100, "HOLDABLE": null, "NAME": "player2", "POSITION": {"x": 4.0, "y": 5.0, "z": 6.0}, "POWERUPS": null, "SPEED": 0.0, "STEAM_ID": "5678", "SUBMERGED": false, "TEAM": 2, "VIEW": {"x": 0.0, "y": 270.0, "z": 0.0}, "WEAPON": "ROCKET"}, "MATCH_GUID": "asdf", "MOD": "ROCKET_SPLASH", "OTHER_TEAM_ALIVE": 4, "OTHER_TEAM_DEAD": 0, "ROUND": 3, "SUICIDE": false, "TEAMKILL": false, "TEAM_ALIVE": 3, "TEAM_DEAD": 1, "TIME": 120, "VICTIM": {"AIRBORNE": false, "AMMO": 5, "ARMOR": 0, "BOT": false, "BOT_SKILL": null, "HEALTH": 0, "HOLDABLE": null, "NAME": "player1", "POSITION": {"x": 1.0, "y": 2.0, "z": 3.0}, "POWERUPS": null, "SPEED": 320.5, "STEAM_ID": "1234", "SUBMERGED": false, "TEAM": 1, "VIEW": {"x": 0.0, "y": 90.0, "z": 0.0}, "WEAPON": "ROCKET"}, "WARMUP": false}, "TYPE": "PLAYER_KILL"}"#;
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
 
         let client_try_from_ctx = MockClient::from_context();
         client_try_from_ctx
@@ -2465,54 +2906,230 @@ mod handle_zmq_msg_tests {
                 mock_game_entity
             });
 
-        MockEngineBuilder::default().with_max_clients(16).run(|| {
-            Python::attach(|py| {
-                let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
-                    .expect("this should not happen");
-                event_dispatcher
-                    .add_dispatcher(&py.get_type::<StatsDispatcher>())
-                    .expect("could not add stats dispatcher");
-                event_dispatcher
-                    .add_dispatcher(&py.get_type::<DeathDispatcher>())
-                    .expect("could not add death dispatcher");
-                EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
-
-                let result = try_handle_zmq_msg(py, player_death_data);
-                run_all_frame_tasks(py).expect("this should not happen");
-
-                assert!(result.is_err_and(|err| err.is_instance_of::<PyEnvironmentError>(py)));
-            });
-        });
-    }
-
-    #[rstest]
-    #[cfg_attr(miri, ignore)]
-    #[serial]
-    fn try_handle_team_switch_msg_matching_with_steam_id(_pyshinqlx_setup: ()) {
-        let player_teamswitch_data = r#"{"DATA": {"KILLER": {"NAME": "player1", "OLD_TEAM": "SPECTATOR", "STEAM_ID": "1234", "TEAM": "BLUE"}}, "TYPE": "PLAYER_SWITCHTEAM"}"#;
-        let cvar_string = c"1";
-        let mut raw_cvar = CVarBuilder::default()
-            .string(cvar_string.as_ptr().cast_mut())
-            .build()
-            .expect("this should not happen");
-
-        let client_try_from_ctx = MockClient::from_context();
-        client_try_from_ctx
-            .expect()
-            .with(predicate::eq(2))
-            .returning(|_client_id| {
-                let mut mock_client = MockClient::new();
-                mock_client
-                    .expect_get_state()
-                    .returning(|| clientState_t::CS_ACTIVE);
-                mock_client
-                    .expect_get_user_info()
-                    .returning(|| r"\name\player1".into());
-                mock_client.expect_get_steam_id().returning(|| 1234);
-                mock_client
-            });
-        client_try_from_ctx
-            .expect()
+        MockEngineBuilder::default()
+            .with_max_clients(16)
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                        .expect("this should not happen");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                        .expect("could not add stats dispatcher");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<KillDispatcher>())
+                        .expect("could not add kill dispatcher");
+                    let capturing_hook = capturing_hook(py);
+                    event_dispatcher
+                        .get_item(intern!(py, "stats"))
+                        .and_then(|stats_dispatcher| {
+                            stats_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to stats dispatcher");
+                    event_dispatcher
+                        .get_item(intern!(py, "kill"))
+                        .and_then(|kill_dispatcher| {
+                            kill_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to kill dispatcher");
+                    EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                    let result = try_handle_zmq_msg(py, player_kill_data);
+                    assert!(result.is_ok());
+
+                    run_all_frame_tasks(py).expect("this should not happen");
+
+                    let expected_json_data =
+                        to_py_json_data(py, player_kill_data).expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(
+                                intern!(py, "assert_called_with"),
+                                (expected_json_data.to_owned(),)
+                            )
+                            .is_ok()
+                    );
+                    let expected_event_data =
+                        pyshinqlx_parse_stats(py, PyString::new(py, player_kill_data).as_any())
+                            .expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(
+                                intern!(py, "assert_called_with"),
+                                ("_", "_", expected_event_data)
+                            )
+                            .is_ok()
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_player_kill_msg_with_no_dispatcher(_pyshinqlx_setup: ()) {
+        let player_kill_data = r#"{"DATA": {"KILLER": {"AIRBORNE": false, "AMMO": 10, "ARMOR": 50, "BOT": false, "BOT_SKILL": null, "HEALTH": 100, "HOLDABLE": null, "NAME": "player2", "POSITION": {"x": 4.0, "y": 5.0, "z": 6.0}, "POWERUPS": null, "SPEED": 0.0, "STEAM_ID": "5678", "SUBMERGED": false, "TEAM": 2, "VIEW": {"x": 0.0, "y": 270.0, "z": 0.0}, "WEAPON": "ROCKET"}, "MATCH_GUID": "asdf", "MOD": "ROCKET_SPLASH", "OTHER_TEAM_ALIVE": 4, "OTHER_TEAM_DEAD": 0, "ROUND": 3, "SUICIDE": false, "TEAMKILL": false, "TEAM_ALIVE": 3, "TEAM_DEAD": 1, "TIME": 120, "VICTIM": {"AIRBORNE": false, "AMMO": 5, "ARMOR": 0, "BOT": false, "BOT_SKILL": null, "HEALTH": 0, "HOLDABLE": null, "NAME": "player1", "POSITION": {"x": 1.0, "y": 2.0, "z": 3.0}, "POWERUPS": null, "SPEED": 320.5, "STEAM_ID": "1234", "SUBMERGED": false, "TEAM": 1, "VIEW": {"x": 0.0, "y": 90.0, "z": 0.0}, "WEAPON": "ROCKET"}, "WARMUP": false}, "TYPE": "PLAYER_KILL"}"#;
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player1".into());
+                mock_client.expect_get_steam_id().returning(|| 1234);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(4))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player2".into());
+                mock_client.expect_get_steam_id().returning(|| 5678);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
+            .withf(|client_id| ![2, 4].contains(client_id))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "player1".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_RED);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::eq(4))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "player2".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_BLUE);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+        game_entity_try_from_ctx
+            .expect()
+            .withf(|client_id| ![2, 4].contains(client_id))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_RED);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default().with_max_clients(16).run(|| {
+            Python::attach(|py| {
+                let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                    .expect("this should not happen");
+                event_dispatcher
+                    .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                    .expect("could not add stats dispatcher");
+                EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                let result = try_handle_zmq_msg(py, player_kill_data);
+                run_all_frame_tasks(py).expect("this should not happen");
+
+                assert!(result.is_err_and(|err| err.is_instance_of::<PyEnvironmentError>(py)));
+            });
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_team_switch_msg_matching_with_steam_id(_pyshinqlx_setup: ()) {
+        let player_teamswitch_data = r#"{"DATA": {"KILLER": {"NAME": "player1", "OLD_TEAM": "SPECTATOR", "STEAM_ID": "1234", "TEAM": "BLUE"}}, "TYPE": "PLAYER_SWITCHTEAM"}"#;
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player1".into());
+                mock_client.expect_get_steam_id().returning(|| 1234);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
             .with(predicate::ne(2))
             .returning(|_client_id| {
                 let mut mock_client = MockClient::new();
@@ -3563,4 +4180,979 @@ mod handle_zmq_msg_tests {
                 });
             });
     }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_medal_msg_with_steam_id(_pyshinqlx_setup: ()) {
+        let player_medal_data = r#"{"DATA": {"MATCH_GUID": "asdf", "MEDAL": "EXCELLENT", "NAME": "player1", "STEAM_ID": "1234", "TIME": 42, "TOTAL": 3, "WARMUP": false}, "TYPE": "PLAYER_MEDAL"}"#;
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player1".into());
+                mock_client.expect_get_steam_id().returning(|| 1234);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "player1".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_BLUE);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_SPECTATOR);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default()
+            .with_max_clients(16)
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                        .expect("this should not happen");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                        .expect("could not add stats dispatcher");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<MedalDispatcher>())
+                        .expect("could not add medal dispatcher");
+                    let capturing_hook = capturing_hook(py);
+                    event_dispatcher
+                        .get_item(intern!(py, "stats"))
+                        .and_then(|stats_dispatcher| {
+                            stats_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to stats dispatcher");
+                    event_dispatcher
+                        .get_item(intern!(py, "medal"))
+                        .and_then(|medal_dispatcher| {
+                            medal_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to medal dispatcher");
+                    EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                    let result = try_handle_zmq_msg(py, player_medal_data);
+                    assert!(result.is_ok());
+
+                    run_all_frame_tasks(py).expect("this should not happen");
+
+                    let expected_json_data =
+                        to_py_json_data(py, player_medal_data).expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(intern!(py, "assert_called_with"), (expected_json_data,))
+                            .is_ok()
+                    );
+                    let expected_event_data =
+                        pyshinqlx_parse_stats(py, PyString::new(py, player_medal_data).as_any())
+                            .expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(
+                                intern!(py, "assert_called_with"),
+                                ("_", expected_event_data)
+                            )
+                            .is_ok()
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_medal_msg_with_name_only(_pyshinqlx_setup: ()) {
+        let player_medal_data = r#"{"DATA": {"MATCH_GUID": "asdf", "MEDAL": "EXCELLENT", "NAME": "player1", "STEAM_ID": "-1", "TIME": 42, "TOTAL": 3, "WARMUP": false}, "TYPE": "PLAYER_MEDAL"}"#;
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player1".into());
+                mock_client.expect_get_steam_id().returning(|| 1234);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "player1".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_BLUE);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_SPECTATOR);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default()
+            .with_max_clients(16)
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                        .expect("this should not happen");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                        .expect("could not add stats dispatcher");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<MedalDispatcher>())
+                        .expect("could not add medal dispatcher");
+                    let capturing_hook = capturing_hook(py);
+                    event_dispatcher
+                        .get_item(intern!(py, "stats"))
+                        .and_then(|stats_dispatcher| {
+                            stats_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to stats dispatcher");
+                    event_dispatcher
+                        .get_item(intern!(py, "medal"))
+                        .and_then(|medal_dispatcher| {
+                            medal_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to medal dispatcher");
+                    EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                    let result = try_handle_zmq_msg(py, player_medal_data);
+                    assert!(result.is_ok());
+
+                    run_all_frame_tasks(py).expect("this should not happen");
+
+                    let expected_json_data =
+                        to_py_json_data(py, player_medal_data).expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(intern!(py, "assert_called_with"), (expected_json_data,))
+                            .is_ok()
+                    );
+                    let expected_event_data =
+                        pyshinqlx_parse_stats(py, PyString::new(py, player_medal_data).as_any())
+                            .expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(
+                                intern!(py, "assert_called_with"),
+                                ("_", expected_event_data)
+                            )
+                            .is_ok()
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_medal_msg_when_player_cannot_be_found(_pyshinqlx_setup: ()) {
+        let player_medal_data = r#"{"DATA": {"MATCH_GUID": "asdf", "MEDAL": "EXCELLENT", "NAME": "player1", "STEAM_ID": "1234", "TIME": 42, "TOTAL": 3, "WARMUP": false}, "TYPE": "PLAYER_MEDAL"}"#;
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::always())
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::always())
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_SPECTATOR);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default()
+            .with_max_clients(16)
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                        .expect("this should not happen");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                        .expect("could not add stats dispatcher");
+                    let capturing_hook = capturing_hook(py);
+                    event_dispatcher
+                        .get_item(intern!(py, "stats"))
+                        .and_then(|stats_dispatcher| {
+                            stats_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to stats dispatcher");
+                    EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                    let result = try_handle_zmq_msg(py, player_medal_data);
+                    assert!(result.is_ok());
+
+                    run_all_frame_tasks(py).expect("this should not happen");
+
+                    let expected_json_data =
+                        to_py_json_data(py, player_medal_data).expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(intern!(py, "assert_called_with"), (expected_json_data,))
+                            .is_ok()
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_medal_msg_with_no_dispatcher(_pyshinqlx_setup: ()) {
+        let player_medal_data = r#"{"DATA": {"MATCH_GUID": "asdf", "MEDAL": "EXCELLENT", "NAME": "player1", "STEAM_ID": "1234", "TIME": 42, "TOTAL": 3, "WARMUP": false}, "TYPE": "PLAYER_MEDAL"}"#;
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player1".into());
+                mock_client.expect_get_steam_id().returning(|| 1234);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "player1".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_BLUE);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_SPECTATOR);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default().with_max_clients(16).run(|| {
+            Python::attach(|py| {
+                let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                    .expect("this should not happen");
+                event_dispatcher
+                    .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                    .expect("could not add stats dispatcher");
+                EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                let result = try_handle_zmq_msg(py, player_medal_data);
+                run_all_frame_tasks(py).expect("this should not happen");
+
+                assert!(result.is_err_and(|err| err.is_instance_of::<PyEnvironmentError>(py)));
+            });
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_player_stats_msg_with_steam_id(_pyshinqlx_setup: ()) {
+        let player_stats_data = r#"{"DATA": {"ABORTED": false, "BLUE_FLAG_PICKUPS": 0, "DAMAGE": {"DEALT": 1500, "TAKEN": 900}, "DEATHS": 2, "HOLY_SHITS": 0, "KILLS": 5, "LOSE": 0, "MATCH_GUID": "asdf", "MAX_STREAK": 3, "MEDALS": {"EXCELLENT": 1}, "MODEL": "sarge", "NAME": "player1", "NEUTRAL_FLAG_PICKUPS": 0, "PICKUPS": {"MEGA_HEALTH": 2}, "PLAY_TIME": 590, "QUIT": 0, "RANK": 1, "RED_FLAG_PICKUPS": 0, "SCORE": 42, "STEAM_ID": "1234", "TEAM": 1, "TEAM_JOIN_TIME": 0, "TEAM_RANK": 1, "TIED_RANK": 0, "TIED_TEAM_RANK": 0, "WARMUP": false, "WEAPONS": {"ROCKET": {"D": 1, "DG": 800, "DR": 300, "H": 8, "K": 3, "P": 2, "S": 20, "T": 120}}, "WIN": 1}, "TYPE": "PLAYER_STATS"}"#;
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player1".into());
+                mock_client.expect_get_steam_id().returning(|| 1234);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "player1".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_BLUE);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_SPECTATOR);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default()
+            .with_max_clients(16)
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                        .expect("this should not happen");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                        .expect("could not add stats dispatcher");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<PlayerStatsDispatcher>())
+                        .expect("could not add player_stats dispatcher");
+                    let capturing_hook = capturing_hook(py);
+                    event_dispatcher
+                        .get_item(intern!(py, "stats"))
+                        .and_then(|stats_dispatcher| {
+                            stats_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to stats dispatcher");
+                    event_dispatcher
+                        .get_item(intern!(py, "player_stats"))
+                        .and_then(|player_stats_dispatcher| {
+                            player_stats_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to player_stats dispatcher");
+                    EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                    let result = try_handle_zmq_msg(py, player_stats_data);
+                    assert!(result.is_ok());
+
+                    run_all_frame_tasks(py).expect("this should not happen");
+
+                    let expected_json_data =
+                        to_py_json_data(py, player_stats_data).expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(intern!(py, "assert_called_with"), (expected_json_data,))
+                            .is_ok()
+                    );
+                    let expected_event_data =
+                        pyshinqlx_parse_stats(py, PyString::new(py, player_stats_data).as_any())
+                            .expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(
+                                intern!(py, "assert_called_with"),
+                                ("_", expected_event_data)
+                            )
+                            .is_ok()
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_player_stats_msg_when_player_cannot_be_found(_pyshinqlx_setup: ()) {
+        let player_stats_data = r#"{"DATA": {"ABORTED": false, "BLUE_FLAG_PICKUPS": 0, "DAMAGE": {"DEALT": 1500, "TAKEN": 900}, "DEATHS": 2, "HOLY_SHITS": 0, "KILLS": 5, "LOSE": 0, "MATCH_GUID": "asdf", "MAX_STREAK": 3, "MEDALS": {"EXCELLENT": 1}, "MODEL": "sarge", "NAME": "player1", "NEUTRAL_FLAG_PICKUPS": 0, "PICKUPS": {"MEGA_HEALTH": 2}, "PLAY_TIME": 590, "QUIT": 0, "RANK": 1, "RED_FLAG_PICKUPS": 0, "SCORE": 42, "STEAM_ID": "1234", "TEAM": 1, "TEAM_JOIN_TIME": 0, "TEAM_RANK": 1, "TIED_RANK": 0, "TIED_TEAM_RANK": 0, "WARMUP": false, "WEAPONS": {"ROCKET": {"D": 1, "DG": 800, "DR": 300, "H": 8, "K": 3, "P": 2, "S": 20, "T": 120}}, "WIN": 1}, "TYPE": "PLAYER_STATS"}"#;
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::always())
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::always())
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_SPECTATOR);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default()
+            .with_max_clients(16)
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                        .expect("this should not happen");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                        .expect("could not add stats dispatcher");
+                    let capturing_hook = capturing_hook(py);
+                    event_dispatcher
+                        .get_item(intern!(py, "stats"))
+                        .and_then(|stats_dispatcher| {
+                            stats_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to stats dispatcher");
+                    EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                    let result = try_handle_zmq_msg(py, player_stats_data);
+                    assert!(result.is_ok());
+
+                    run_all_frame_tasks(py).expect("this should not happen");
+
+                    let expected_json_data =
+                        to_py_json_data(py, player_stats_data).expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(intern!(py, "assert_called_with"), (expected_json_data,))
+                            .is_ok()
+                    );
+                });
+            });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_player_stats_msg_with_no_dispatcher(_pyshinqlx_setup: ()) {
+        let player_stats_data = r#"{"DATA": {"ABORTED": false, "BLUE_FLAG_PICKUPS": 0, "DAMAGE": {"DEALT": 1500, "TAKEN": 900}, "DEATHS": 2, "HOLY_SHITS": 0, "KILLS": 5, "LOSE": 0, "MATCH_GUID": "asdf", "MAX_STREAK": 3, "MEDALS": {"EXCELLENT": 1}, "MODEL": "sarge", "NAME": "player1", "NEUTRAL_FLAG_PICKUPS": 0, "PICKUPS": {"MEGA_HEALTH": 2}, "PLAY_TIME": 590, "QUIT": 0, "RANK": 1, "RED_FLAG_PICKUPS": 0, "SCORE": 42, "STEAM_ID": "1234", "TEAM": 1, "TEAM_JOIN_TIME": 0, "TEAM_RANK": 1, "TIED_RANK": 0, "TIED_TEAM_RANK": 0, "WARMUP": false, "WEAPONS": {"ROCKET": {"D": 1, "DG": 800, "DR": 300, "H": 8, "K": 3, "P": 2, "S": 20, "T": 120}}, "WIN": 1}, "TYPE": "PLAYER_STATS"}"#;
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player1".into());
+                mock_client.expect_get_steam_id().returning(|| 1234);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "player1".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_BLUE);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_SPECTATOR);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default().with_max_clients(16).run(|| {
+            Python::attach(|py| {
+                let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                    .expect("this should not happen");
+                event_dispatcher
+                    .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                    .expect("could not add stats dispatcher");
+                EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                let result = try_handle_zmq_msg(py, player_stats_data);
+                run_all_frame_tasks(py).expect("this should not happen");
+
+                assert!(result.is_err_and(|err| err.is_instance_of::<PyEnvironmentError>(py)));
+            });
+        });
+    }
+
+    #[rstest]
+    #[case::connect("PLAYER_CONNECT", StatsPlayerConnectDispatcher::type_object)]
+    #[case::disconnect("PLAYER_DISCONNECT", StatsPlayerDisconnectDispatcher::type_object)]
+    #[cfg_attr(miri, ignore)]
+    #[serial]
+    fn try_handle_player_connection_msg_with_steam_id(
+        _pyshinqlx_setup: (),
+        #[case] msg_type: &str,
+        #[case] dispatcher_type: fn(Python<'_>) -> Bound<'_, PyType>,
+    ) {
+        let player_connection_data = format!(
+            r#"{{"DATA": {{"MATCH_GUID": "asdf", "NAME": "player1", "STEAM_ID": "1234", "TIME": 42, "WARMUP": false}}, "TYPE": "{msg_type}"}}"#
+        );
+        let cvar_string = c"1";
+        let mut raw_cvar = CVarBuilder::default()
+            .string(cvar_string.as_ptr().cast_mut())
+            .build()
+            .expect("this should not happen");
+
+        let client_try_from_ctx = MockClient::from_context();
+        client_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\player1".into());
+                mock_client.expect_get_steam_id().returning(|| 1234);
+                mock_client
+            });
+        client_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_client = MockClient::new();
+                mock_client
+                    .expect_get_state()
+                    .returning(|| clientState_t::CS_ACTIVE);
+                mock_client
+                    .expect_get_user_info()
+                    .returning(|| r"\name\Mocked Player".into());
+                mock_client.expect_get_steam_id().returning(|| 1235);
+                mock_client
+            });
+        let game_entity_try_from_ctx = MockGameEntity::from_context();
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::eq(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "player1".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_BLUE);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+        game_entity_try_from_ctx
+            .expect()
+            .with(predicate::ne(2))
+            .returning(|_client_id| {
+                let mut mock_game_entity = MockGameEntity::new();
+                mock_game_entity
+                    .expect_get_player_name()
+                    .returning(|| "Mocked Player".to_string());
+                mock_game_entity
+                    .expect_get_team()
+                    .returning(|| team_t::TEAM_SPECTATOR);
+                mock_game_entity
+                    .expect_get_privileges()
+                    .returning(|| privileges_t::PRIV_NONE);
+                mock_game_entity
+            });
+
+        MockEngineBuilder::default()
+            .with_max_clients(16)
+            .with_find_cvar(
+                |cmd| cmd == "zmq_stats_enable",
+                move |_| CVar::try_from(raw_cvar.borrow_mut() as *mut cvar_t).ok(),
+                1..,
+            )
+            .run(|| {
+                Python::attach(|py| {
+                    let event_dispatcher = Bound::new(py, EventDispatcherManager::default())
+                        .expect("this should not happen");
+                    event_dispatcher
+                        .add_dispatcher(&py.get_type::<StatsDispatcher>())
+                        .expect("could not add stats dispatcher");
+                    let dispatcher_type = dispatcher_type(py);
+                    event_dispatcher
+                        .add_dispatcher(&dispatcher_type)
+                        .expect("could not add player connection dispatcher");
+                    let capturing_hook = capturing_hook(py);
+                    event_dispatcher
+                        .get_item(intern!(py, "stats"))
+                        .and_then(|stats_dispatcher| {
+                            stats_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to stats dispatcher");
+                    event_dispatcher
+                        .get_item(
+                            dispatcher_type
+                                .getattr(intern!(py, "name"))
+                                .expect("this should not happen"),
+                        )
+                        .and_then(|player_connection_dispatcher| {
+                            player_connection_dispatcher.call_method1(
+                                intern!(py, "add_hook"),
+                                (
+                                    "asdf",
+                                    capturing_hook
+                                        .getattr(intern!(py, "hook"))
+                                        .expect("could not get capturing hook"),
+                                    CommandPriorities::PRI_NORMAL as i32,
+                                ),
+                            )
+                        })
+                        .expect("could not add hook to player connection dispatcher");
+                    EVENT_DISPATCHERS.store(Some(event_dispatcher.unbind().into()));
+
+                    let result = try_handle_zmq_msg(py, &player_connection_data);
+                    assert!(result.is_ok());
+
+                    run_all_frame_tasks(py).expect("this should not happen");
+
+                    let expected_json_data = to_py_json_data(py, &player_connection_data)
+                        .expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(intern!(py, "assert_called_with"), (expected_json_data,))
+                            .is_ok()
+                    );
+                    let expected_event_data = pyshinqlx_parse_stats(
+                        py,
+                        PyString::new(py, &player_connection_data).as_any(),
+                    )
+                    .expect("this should not happen");
+                    assert!(
+                        capturing_hook
+                            .call_method1(
+                                intern!(py, "assert_called_with"),
+                                ("_", expected_event_data)
+                            )
+                            .is_ok()
+                    );
+                });
+            });
+    }
 }
@@ -0,0 +1,118 @@
+use core::hint::cold_path;
+
+use pyo3::{exceptions::PyValueError, intern, types::PyString};
+use ql_stats_schema::StatsMessage;
+
+use super::prelude::*;
+
+/// Converts a typed stats message into the Python object matching its payload.
+pub(crate) fn typed_stats_data<'py>(
+    py: Python<'py>,
+    stats: StatsMessage,
+) -> PyResult<Bound<'py, PyAny>> {
+    match stats {
+        StatsMessage::MatchStarted(data) => Bound::new(py, data).map(Bound::into_any),
+        StatsMessage::MatchReport(data) => Bound::new(py, data).map(Bound::into_any),
+        StatsMessage::RoundOver(data) => Bound::new(py, data).map(Bound::into_any),
+        StatsMessage::PlayerKill(data) | StatsMessage::PlayerDeath(data) => {
+            Bound::new(py, data).map(Bound::into_any)
+        }
+        StatsMessage::PlayerMedal(data) => Bound::new(py, data).map(Bound::into_any),
+        StatsMessage::PlayerStats(data) => Bound::new(py, data).map(Bound::into_any),
+        StatsMessage::PlayerConnect(data) | StatsMessage::PlayerDisconnect(data) => {
+            Bound::new(py, data).map(Bound::into_any)
+        }
+        StatsMessage::PlayerSwitchTeam(data) => Bound::new(py, data).map(Bound::into_any),
+    }
+}
+
+fn schema_error_to_py(error_msg: String) -> PyErr {
+    cold_path();
+    PyValueError::new_err(error_msg)
+}
+
+/// Parses a stats message, either as received from the stats socket or as passed to the
+/// ``stats`` event, into its typed representation.
+#[pyfunction]
+#[pyo3(name = "parse_stats")]
+pub(crate) fn pyshinqlx_parse_stats<'py>(
+    py: Python<'py>,
+    stats: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let json_str = match stats.cast::<PyString>() {
+        Ok(py_string) => py_string.to_string(),
+        Err(_) => py
+            .import(intern!(py, "json"))?
+            .call_method1(intern!(py, "dumps"), (stats,))?
+            .extract::<String>()?,
+    };
+
+    let message = py
+        .detach(|| {
+            json_str
+                .parse::<StatsMessage>()
+                .map_err(|err| err.to_string())
+        })
+        .map_err(schema_error_to_py)?;
+    typed_stats_data(py, message)
+}
+
+#[cfg(test)]
+mod stats_schema_tests {
+    use pretty_assertions::assert_eq;
+    use pyo3::{exceptions::PyValueError, types::PyString};
+    use ql_stats_schema::{PlayerMedal, RoundOver};
+    use rstest::rstest;
+
+    use super::pyshinqlx_parse_stats;
+    use crate::ffi::python::{prelude::*, stats_listener::to_py_json_data};
+
+    const ROUND_OVER: &str = r#"{"DATA": {"MATCH_GUID": "asdf", "ROUND": 10, "TEAM_WON": "RED", "TIME": 539, "WARMUP": false}, "TYPE": "ROUND_OVER"}"#;
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn parse_stats_from_string(_pyshinqlx_setup: ()) {
+        Python::attach(|py| {
+            let result = pyshinqlx_parse_stats(py, PyString::new(py, ROUND_OVER).as_any())
+                .expect("this should not happen");
+
+            let round_over = result
+                .cast::<RoundOver>()
+                .expect("this should not happen")
+                .get()
+                .clone();
+            assert_eq!(round_over.round, 10);
+            assert_eq!(round_over.team_won, "RED");
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn parse_stats_from_dict(_pyshinqlx_setup: ()) {
+        let medal = r#"{"DATA": {"MATCH_GUID": "asdf", "MEDAL": "EXCELLENT", "NAME": "player1", "STEAM_ID": "1234", "TIME": 42, "TOTAL": 3, "WARMUP": false}, "TYPE": "PLAYER_MEDAL"}"#;
+        Python::attach(|py| {
+            let stats_dict = to_py_json_data(py, medal).expect("this should not happen");
+
+            let result = pyshinqlx_parse_stats(py, &stats_dict).expect("this should not happen");
+
+            assert!(result.cast::<PlayerMedal>().is_ok_and(|medal| {
+                medal.get().steam_id == 1234 && medal.get().medal == "EXCELLENT"
+            }));
+        });
+    }
+
+    #[rstest]
+    #[cfg_attr(miri, ignore)]
+    fn parse_stats_with_malformed_data(_pyshinqlx_setup: ()) {
+        let round_over =
+            r#"{"DATA": {"MATCH_GUID": "asdf", "TEAM_WON": "RED"}, "TYPE": "ROUND_OVER"}"#;
+        Python::attach(|py| {
+            let result = pyshinqlx_parse_stats(py, PyString::new(py, round_over).as_any());
+
+            assert!(result.is_err_and(|err| {
+                err.is_instance_of::<PyValueError>(py)
+                    && err.to_string().contains("malformed ROUND_OVER DATA")
+            }));
+        });
+    }
+}
@@ -72,6 +72,9 @@ pub(crate) fn shinqlx_g_initgame(level_time: c_int, random_seed: c_int, restart:
 }
 
 pub(crate) fn shinqlx_g_shutdowngame(restart: c_int) {
+    save_in_memory_snapshot();
+    flush_write_behind_queue();
+
     MAIN_ENGINE.load().as_ref().tap_some(|&main_engine| {
         main_engine.unhook_vm(restart != 0);
         main_engine.shutdown_game(restart);
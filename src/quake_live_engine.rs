@@ -24,8 +24,9 @@ use crate::patches::patch_callvote_f;
 use crate::quake_live_functions::pattern_search_module;
 use crate::{
     commands::{
-        cmd_center_print, cmd_py_command, cmd_py_rcon, cmd_regular_print, cmd_restart_python,
-        cmd_send_server_command, cmd_slap, cmd_slay,
+        cmd_center_print, cmd_db_export, cmd_db_import, cmd_list_python_commands, cmd_py_command,
+        cmd_py_rcon, cmd_regular_print, cmd_restart_python, cmd_send_server_command, cmd_slap,
+        cmd_slay,
     },
     ffi::{c::prelude::*, python::prelude::*},
     hooks::{
@@ -1141,7 +1142,10 @@ impl QuakeLiveEngine {
         self.add_command("slay", cmd_slay);
         self.add_command("qlx", cmd_py_rcon);
         self.add_command("pycmd", cmd_py_command);
+        self.add_command("qlx_cmdlist", cmd_list_python_commands);
         self.add_command("pyrestart", cmd_restart_python);
+        self.add_command("qlx_dbexport", cmd_db_export);
+        self.add_command("qlx_dbimport", cmd_db_import);
 
         pyshinqlx_initialize().map_err(|err| {
             cold_path();
@@ -1805,7 +1809,8 @@ mod quake_live_engine_tests {
     };
     use crate::{
         commands::{
-            cmd_center_print, cmd_py_command, cmd_py_rcon, cmd_regular_print, cmd_restart_python,
+            cmd_center_print, cmd_db_export, cmd_db_import, cmd_list_python_commands,
+            cmd_py_command, cmd_py_rcon, cmd_regular_print, cmd_restart_python,
             cmd_send_server_command, cmd_slap, cmd_slay,
         },
         ffi::{
@@ -2071,6 +2076,14 @@ mod quake_live_engine_tests {
                     && ptr::fn_addr_eq(func, cmd_py_command as extern "C" fn())
             })
             .times(1);
+        add_cmd_ctx
+            .expect()
+            .withf(|&cmd, &func| {
+                !cmd.is_null()
+                    && unsafe { CStr::from_ptr(cmd) } == c"qlx_cmdlist"
+                    && ptr::fn_addr_eq(func, cmd_list_python_commands as extern "C" fn())
+            })
+            .times(1);
         add_cmd_ctx
             .expect()
             .withf(|&cmd, &func| {
@@ -2079,6 +2092,22 @@ mod quake_live_engine_tests {
                     && ptr::fn_addr_eq(func, cmd_restart_python as extern "C" fn())
             })
             .times(1);
+        add_cmd_ctx
+            .expect()
+            .withf(|&cmd, &func| {
+                !cmd.is_null()
+                    && unsafe { CStr::from_ptr(cmd) } == c"qlx_dbexport"
+                    && ptr::fn_addr_eq(func, cmd_db_export as extern "C" fn())
+            })
+            .times(1);
+        add_cmd_ctx
+            .expect()
+            .withf(|&cmd, &func| {
+                !cmd.is_null()
+                    && unsafe { CStr::from_ptr(cmd) } == c"qlx_dbimport"
+                    && ptr::fn_addr_eq(func, cmd_db_import as extern "C" fn())
+            })
+            .times(1);
 
         let pyshinqlx_init_ctx = pyshinqlx_initialize_context();
         pyshinqlx_init_ctx.expect().returning(|| Ok(())).times(1);